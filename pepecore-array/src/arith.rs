@@ -0,0 +1,185 @@
+//! `+ - * /` operators between two images and between an image and a scalar.
+//!
+//! Samples are combined in their native range through [`Pixel::to_f64`] and [`Pixel::from_f64`],
+//! so integer results saturate at the type bounds and float results are never clamped.
+//! Image operands must share pixel type, height and width; the right-hand side may have a
+//! single channel, which is then broadcast over every channel of the left-hand side.
+//!
+//! ```rust
+//! use pepecore_array::{ImgData, SVec, Shape};
+//!
+//! let img = SVec::new(Shape::new(1, 2, Some(3)), ImgData::from(vec![250u8, 10, 20, 30, 40, 50]));
+//! let mask = SVec::new(Shape::new(1, 2, None), ImgData::from(vec![10u8, 1]));
+//! let sum = (&img + &mask).unwrap();
+//! assert_eq!(sum.get_data::<u8>().unwrap(), &[255, 20, 30, 31, 41, 51]);
+//! assert_eq!((&img * 2.0).get_data::<u8>().unwrap(), &[255, 20, 40, 60, 80, 100]);
+//! ```
+use crate::error::Error;
+use crate::{ImgData, Layout, Pixel, SVec};
+use std::borrow::Cow;
+use std::ops::{Add, Div, Mul, Sub};
+
+fn zip_t<T: Pixel>(dst: &mut [T], dst_c: usize, src: &[T], src_c: usize, f: fn(f64, f64) -> f64) {
+    for (d, s) in dst.chunks_exact_mut(dst_c).zip(src.chunks_exact(src_c)) {
+        if src_c == dst_c {
+            for (d, s) in d.iter_mut().zip(s) {
+                *d = T::from_f64(f(d.to_f64(), s.to_f64()));
+            }
+        } else {
+            let s = s[0].to_f64();
+            for d in d.iter_mut() {
+                *d = T::from_f64(f(d.to_f64(), s));
+            }
+        }
+    }
+}
+
+fn scalar_t<T: Pixel>(dst: &mut [T], value: f64, f: fn(f64, f64) -> f64) {
+    for d in dst.iter_mut() {
+        *d = T::from_f64(f(d.to_f64(), value));
+    }
+}
+
+fn combine(lhs: &SVec, rhs: &SVec, f: fn(f64, f64) -> f64) -> Result<SVec, Error> {
+    let (h, w, c) = lhs.shape();
+    let (rh, rw, rc) = rhs.shape();
+    let dst_c = c.unwrap_or(1);
+    let src_c = rc.unwrap_or(1);
+    if h != rh || w != rw || (src_c != dst_c && src_c != 1) {
+        return Err(Error::ShapeMismatch {
+            expected: lhs.shape(),
+            actual: rhs.shape(),
+        });
+    }
+    let rhs = if rhs.shape.get_layout() == Layout::CHW {
+        let mut rhs = rhs.clone();
+        rhs.to_interleaved();
+        Cow::Owned(rhs)
+    } else {
+        Cow::Borrowed(rhs)
+    };
+    let mut out = lhs.clone();
+    out.with_interleaved(|out| {
+        match (&mut out.data, &rhs.data) {
+            (ImgData::U8(d), ImgData::U8(s)) => zip_t(d, dst_c, s, src_c, f),
+            (ImgData::U16(d), ImgData::U16(s)) => zip_t(d, dst_c, s, src_c, f),
+            (ImgData::I16(d), ImgData::I16(s)) => zip_t(d, dst_c, s, src_c, f),
+            (ImgData::U32(d), ImgData::U32(s)) => zip_t(d, dst_c, s, src_c, f),
+            (ImgData::F16(d), ImgData::F16(s)) => zip_t(d, dst_c, s, src_c, f),
+            (ImgData::F32(d), ImgData::F32(s)) => zip_t(d, dst_c, s, src_c, f),
+            (ImgData::F64(d), ImgData::F64(s)) => zip_t(d, dst_c, s, src_c, f),
+            (d, s) => {
+                return Err(Error::TypeMismatch {
+                    expected: d.pixel_type().type_name(),
+                    actual: s.pixel_type().type_name(),
+                });
+            }
+        }
+        Ok(())
+    })?;
+    Ok(out)
+}
+
+fn combine_scalar(lhs: &SVec, value: f64, f: fn(f64, f64) -> f64) -> SVec {
+    let mut out = lhs.clone();
+    match &mut out.data {
+        ImgData::U8(d) => scalar_t(d, value, f),
+        ImgData::U16(d) => scalar_t(d, value, f),
+        ImgData::I16(d) => scalar_t(d, value, f),
+        ImgData::U32(d) => scalar_t(d, value, f),
+        ImgData::F16(d) => scalar_t(d, value, f),
+        ImgData::F32(d) => scalar_t(d, value, f),
+        ImgData::F64(d) => scalar_t(d, value, f),
+    }
+    out
+}
+
+impl Add<&SVec> for &SVec {
+    type Output = Result<SVec, Error>;
+    fn add(self, rhs: &SVec) -> Self::Output {
+        combine(self, rhs, |a, b| a + b)
+    }
+}
+
+impl Sub<&SVec> for &SVec {
+    type Output = Result<SVec, Error>;
+    fn sub(self, rhs: &SVec) -> Self::Output {
+        combine(self, rhs, |a, b| a - b)
+    }
+}
+
+impl Mul<&SVec> for &SVec {
+    type Output = Result<SVec, Error>;
+    fn mul(self, rhs: &SVec) -> Self::Output {
+        combine(self, rhs, |a, b| a * b)
+    }
+}
+
+/// Integer division by zero saturates to the type maximum, `0 / 0` gives zero.
+impl Div<&SVec> for &SVec {
+    type Output = Result<SVec, Error>;
+    fn div(self, rhs: &SVec) -> Self::Output {
+        combine(self, rhs, |a, b| a / b)
+    }
+}
+
+impl Add<f64> for &SVec {
+    type Output = SVec;
+    fn add(self, rhs: f64) -> SVec {
+        combine_scalar(self, rhs, |a, b| a + b)
+    }
+}
+
+impl Sub<f64> for &SVec {
+    type Output = SVec;
+    fn sub(self, rhs: f64) -> SVec {
+        combine_scalar(self, rhs, |a, b| a - b)
+    }
+}
+
+impl Mul<f64> for &SVec {
+    type Output = SVec;
+    fn mul(self, rhs: f64) -> SVec {
+        combine_scalar(self, rhs, |a, b| a * b)
+    }
+}
+
+impl Div<f64> for &SVec {
+    type Output = SVec;
+    fn div(self, rhs: f64) -> SVec {
+        combine_scalar(self, rhs, |a, b| a / b)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{ImgData, SVec, Shape};
+
+    #[test]
+    fn saturating_and_broadcast() {
+        let a = SVec::new(Shape::new(1, 3, None), ImgData::from(vec![200u8, 5, 0]));
+        let b = SVec::new(Shape::new(1, 3, None), ImgData::from(vec![100u8, 10, 0]));
+        assert_eq!((&a + &b).unwrap().get_data::<u8>().unwrap(), &[255, 15, 0]);
+        assert_eq!((&a - &b).unwrap().get_data::<u8>().unwrap(), &[100, 0, 0]);
+        assert_eq!((&a / &b).unwrap().get_data::<u8>().unwrap(), &[2, 1, 0]);
+
+        let mut rgb = SVec::new(Shape::new(1, 2, Some(3)), ImgData::from(vec![1u8, 2, 3, 4, 5, 6]));
+        rgb.to_planar();
+        let mask = SVec::new(Shape::new(1, 2, None), ImgData::from(vec![2u8, 3]));
+        let mut out = (&rgb * &mask).unwrap();
+        out.to_interleaved();
+        assert_eq!(out.get_data::<u8>().unwrap(), &[2, 4, 6, 12, 15, 18]);
+    }
+
+    #[test]
+    fn scalar_and_mismatch() {
+        let img = SVec::new(Shape::new(1, 2, Some(2)), ImgData::from(vec![0.5f32, 1.0, 2.0, -1.0]));
+        assert_eq!((&img * 2.0).get_data::<f32>().unwrap(), &[1.0, 2.0, 4.0, -2.0]);
+        assert_eq!((&img - 1.0).get_data::<f32>().unwrap(), &[-0.5, 0.0, 1.0, -2.0]);
+
+        let wrong = SVec::new(Shape::new(2, 1, None), ImgData::from(vec![1.0f32, 1.0]));
+        assert!(matches!(&img + &wrong, Err(crate::error::Error::ShapeMismatch { .. })));
+        let wrong = SVec::new(Shape::new(1, 2, None), ImgData::from(vec![1u8, 1]));
+        assert!(matches!(&img + &wrong, Err(crate::error::Error::TypeMismatch { .. })));
+    }
+}
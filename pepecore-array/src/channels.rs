@@ -0,0 +1,232 @@
+//! Channel level operations: split, merge, selection/permutation and alpha helpers.
+use crate::error::Error;
+use crate::{ImgData, Layout, Pixel, SVec, Shape, Strides};
+
+/// Copy the channels listed in `order` out of `data`, keeping the layout of `shape`.
+fn pick<T: Copy>(data: &[T], shape: &Shape, order: &[usize]) -> Vec<T> {
+    let (h, w, c) = shape.get_shape();
+    let c = c.unwrap_or(1);
+    let mut out = Vec::with_capacity(h * w * order.len());
+    if shape.get_layout() == Layout::CHW {
+        for &ch in order {
+            out.extend_from_slice(&data[ch * h * w..(ch + 1) * h * w]);
+        }
+    } else {
+        for px in data.chunks_exact(c) {
+            out.extend(order.iter().map(|&ch| px[ch]));
+        }
+    }
+    out
+}
+
+/// Interleave all channels of `images` pixel by pixel, in order.
+fn stack<T: Copy + 'static>(images: &[&SVec], len: usize) -> Result<Vec<T>, Error> {
+    let planes = images
+        .iter()
+        .map(|img| {
+            Ok((
+                img.get_data::<T>()?,
+                Strides::for_shape(&img.shape),
+                img.shape.get_channels().unwrap_or(1),
+            ))
+        })
+        .collect::<Result<Vec<_>, Error>>()?;
+    let (h, w, _) = images[0].shape();
+    let mut out = Vec::with_capacity(len);
+    for y in 0..h {
+        for x in 0..w {
+            for (data, strides, c) in &planes {
+                out.extend((0..*c).map(|ch| data[strides.index(y, x, ch)]));
+            }
+        }
+    }
+    Ok(out)
+}
+
+fn merge_refs(images: &[&SVec]) -> Result<SVec, Error> {
+    let first = images.first().ok_or(Error::NoChannelsError)?;
+    let (h, w, _) = first.shape();
+    if let Some(img) = images
+        .iter()
+        .find(|img| img.shape.get_height() != h || img.shape.get_width() != w)
+    {
+        return Err(Error::ShapeMismatch {
+            expected: first.shape(),
+            actual: img.shape(),
+        });
+    }
+    let c: usize = images.iter().map(|img| img.shape.get_channels().unwrap_or(1)).sum();
+    let shape = Shape::try_new(h, w, Some(c))?;
+    let len = shape.checked_len()?;
+    let data = match &first.data {
+        ImgData::U8(_) => ImgData::U8(stack(images, len)?),
+        ImgData::U16(_) => ImgData::U16(stack(images, len)?),
+        ImgData::I16(_) => ImgData::I16(stack(images, len)?),
+        ImgData::U32(_) => ImgData::U32(stack(images, len)?),
+        ImgData::F16(_) => ImgData::F16(stack(images, len)?),
+        ImgData::F64(_) => ImgData::F64(stack(images, len)?),
+        ImgData::F32(_) => ImgData::F32(stack(images, len)?),
+    };
+    Ok(SVec::new(shape, data))
+}
+
+/// Single-channel plane of the same size and pixel type as `img`, filled with the opaque value.
+fn opaque_plane(img: &SVec) -> SVec {
+    fn fill<T: Pixel>(n: usize) -> Vec<T> {
+        vec![T::MAX_VALUE; n]
+    }
+    let (h, w, _) = img.shape();
+    let data = match &img.data {
+        ImgData::U8(_) => ImgData::U8(fill(h * w)),
+        ImgData::U16(_) => ImgData::U16(fill(h * w)),
+        ImgData::I16(_) => ImgData::I16(fill(h * w)),
+        ImgData::U32(_) => ImgData::U32(fill(h * w)),
+        ImgData::F16(_) => ImgData::F16(fill(h * w)),
+        ImgData::F64(_) => ImgData::F64(fill(h * w)),
+        ImgData::F32(_) => ImgData::F32(fill(h * w)),
+    };
+    SVec::new(Shape::new(h, w, None), data)
+}
+
+impl SVec {
+    /// Split the image into one single-channel image per channel.
+    pub fn split_channels(&self) -> Vec<SVec> {
+        let c = self.shape.get_channels().unwrap_or(1);
+        (0..c)
+            .map(|ch| {
+                let mut plane = self.view().channel(ch).expect("channel index in range").to_svec();
+                plane.shape = Shape::new(plane.shape.get_height(), plane.shape.get_width(), None);
+                plane
+            })
+            .collect()
+    }
+    /// Stack `images` along the channel axis into one interleaved image.
+    ///
+    /// All inputs must share height, width and pixel type; each may have any channel count
+    /// and layout.
+    pub fn merge_channels(images: &[SVec]) -> Result<SVec, Error> {
+        merge_refs(&images.iter().collect::<Vec<_>>())
+    }
+    /// New image whose channel `i` is channel `order[i]` of `self`.
+    ///
+    /// Channels may be repeated or left out, e.g. `[2, 1, 0, 3]` swaps RGBA to BGRA and
+    /// `[0, 1, 2]` drops the alpha channel. The layout of `self` is kept.
+    pub fn select_channels(&self, order: &[usize]) -> Result<SVec, Error> {
+        let c = self.shape.get_channels().unwrap_or(1);
+        if order.is_empty() {
+            return Err(Error::NoChannelsError);
+        }
+        if order.iter().any(|&ch| ch >= c) {
+            return Err(Error::OutOfBounds);
+        }
+        let (h, w, _) = self.shape();
+        let channels = if order.len() == 1 && self.shape.get_channels().is_none() {
+            None
+        } else {
+            Some(order.len())
+        };
+        let shape = Shape::try_new(h, w, channels)?.with_layout(self.shape.get_layout());
+        let data = match &self.data {
+            ImgData::U8(data) => ImgData::U8(pick(data, &self.shape, order)),
+            ImgData::U16(data) => ImgData::U16(pick(data, &self.shape, order)),
+            ImgData::I16(data) => ImgData::I16(pick(data, &self.shape, order)),
+            ImgData::U32(data) => ImgData::U32(pick(data, &self.shape, order)),
+            ImgData::F16(data) => ImgData::F16(pick(data, &self.shape, order)),
+            ImgData::F64(data) => ImgData::F64(pick(data, &self.shape, order)),
+            ImgData::F32(data) => ImgData::F32(pick(data, &self.shape, order)),
+        };
+        Ok(SVec::new(shape, data))
+    }
+    /// In-place variant of [`SVec::select_channels`].
+    pub fn reorder_channels(&mut self, order: &[usize]) -> Result<(), Error> {
+        *self = self.select_channels(order)?;
+        Ok(())
+    }
+    /// Append a fully opaque alpha channel: GRAY becomes GRAYA and RGB becomes RGBA.
+    pub fn add_alpha(&mut self) -> Result<(), Error> {
+        let c = self.shape.get_channels().unwrap_or(1);
+        if c != 1 && c != 3 {
+            return Err(Error::UnsupportedChannels(c));
+        }
+        let layout = self.shape.get_layout();
+        *self = merge_refs(&[self, &opaque_plane(self)])?;
+        if layout == Layout::CHW {
+            self.to_planar();
+        }
+        Ok(())
+    }
+    /// Drop the alpha channel: GRAYA becomes GRAY and RGBA becomes RGB.
+    pub fn drop_alpha(&mut self) -> Result<(), Error> {
+        match self.shape.get_channels() {
+            Some(2) => self.reorder_channels(&[0])?,
+            Some(4) => self.reorder_channels(&[0, 1, 2])?,
+            c => return Err(Error::UnsupportedChannels(c.unwrap_or(1))),
+        }
+        if self.shape.get_channels() == Some(1) {
+            self.shape = Shape::new(self.shape.get_height(), self.shape.get_width(), None).with_layout(self.shape.get_layout());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rgb() -> SVec {
+        SVec::new(Shape::new(1, 2, Some(3)), ImgData::from(vec![1u8, 2, 3, 4, 5, 6]))
+    }
+
+    #[test]
+    fn split_merge_roundtrip() {
+        let img = rgb();
+        let planes = img.split_channels();
+        assert_eq!(planes.len(), 3);
+        assert_eq!(planes[1].shape(), (1, 2, None));
+        assert_eq!(planes[1].get_data::<u8>().unwrap(), &[2, 5]);
+        let merged = SVec::merge_channels(&planes).unwrap();
+        assert_eq!(merged.get_data::<u8>().unwrap(), img.get_data::<u8>().unwrap());
+
+        let mut planar = img.clone();
+        planar.to_planar();
+        let merged = SVec::merge_channels(&[planar, planes[0].clone()]).unwrap();
+        assert_eq!(merged.get_data::<u8>().unwrap(), &[1, 2, 3, 1, 4, 5, 6, 4]);
+
+        let f = SVec::new(Shape::new(1, 2, None), ImgData::from(vec![0.0f32; 2]));
+        assert!(SVec::merge_channels(&[rgb(), f]).is_err());
+        let tall = SVec::new(Shape::new(2, 1, None), ImgData::from(vec![0u8; 2]));
+        assert!(matches!(
+            SVec::merge_channels(&[rgb(), tall]),
+            Err(Error::ShapeMismatch {
+                expected: (1, 2, Some(3)),
+                actual: (2, 1, None)
+            })
+        ));
+    }
+
+    #[test]
+    fn select_and_alpha() {
+        let mut img = rgb();
+        assert_eq!(
+            img.select_channels(&[2, 1, 0]).unwrap().get_data::<u8>().unwrap(),
+            &[3, 2, 1, 6, 5, 4]
+        );
+        assert!(img.select_channels(&[3]).is_err());
+
+        img.add_alpha().unwrap();
+        assert_eq!(img.get_data::<u8>().unwrap(), &[1, 2, 3, 255, 4, 5, 6, 255]);
+        img.reorder_channels(&[2, 1, 0, 3]).unwrap();
+        assert_eq!(img.get_data::<u8>().unwrap(), &[3, 2, 1, 255, 6, 5, 4, 255]);
+        img.drop_alpha().unwrap();
+        assert_eq!(img.get_data::<u8>().unwrap(), &[3, 2, 1, 6, 5, 4]);
+        assert!(img.drop_alpha().is_err());
+
+        let mut planar = rgb();
+        planar.to_planar();
+        planar.add_alpha().unwrap();
+        assert_eq!(planar.shape.get_layout(), Layout::CHW);
+        assert_eq!(planar.get_data::<u8>().unwrap(), &[1, 4, 2, 5, 3, 6, 255, 255]);
+        planar.drop_alpha().unwrap();
+        assert_eq!(planar.get_data::<u8>().unwrap(), &[1, 4, 2, 5, 3, 6]);
+    }
+}
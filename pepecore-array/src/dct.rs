@@ -0,0 +1,382 @@
+//! Orthonormal 2D DCT-II / DCT-III (inverse) over whole images or square blocks.
+//!
+//! The 1D transforms are computed through a real FFT of the even-reflected signal. [`DctPlan`]
+//! keeps the FFT plans, twiddles and scratch buffers per length so repeated calls on images of
+//! the same size do no planning at all.
+use crate::error::Error;
+use crate::{ImgData, Pixel, PixelType, SVec, SVecView, Shape, Strides, f16};
+use realfft::{ComplexToReal, RealFftPlanner, RealToComplex};
+use rustfft::num_complex::Complex;
+use std::collections::HashMap;
+use std::f32::consts::{FRAC_1_SQRT_2, PI};
+use std::sync::Arc;
+
+/// Region the transform is applied to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DctMode {
+    /// One transform over the whole image (per channel).
+    Full,
+    /// Independent `n x n` transforms, e.g. `Block(8)` as in JPEG. Edge blocks of images whose
+    /// size is not a multiple of `n` are transformed at their actual size.
+    Block(usize),
+}
+
+#[inline]
+fn precompute_twiddle_alpha(n: usize) -> (Vec<Complex<f32>>, Vec<f32>) {
+    let base = 0.5 * (2.0f32 / n as f32).sqrt();
+    let mut tw = Vec::with_capacity(n);
+    let mut alpha = Vec::with_capacity(n);
+    for k in 0..n {
+        let theta = PI * (k as f32) / (2.0 * n as f32);
+        let (s, c) = theta.sin_cos();
+        tw.push(Complex { re: c, im: -s }); // exp(-jθ)
+        alpha.push(if k == 0 { base * FRAC_1_SQRT_2 } else { base }); // 0.5*sqrt(2/N)*(k==0?1/√2:1)
+    }
+    (tw, alpha)
+}
+
+/// Plans, twiddles and scratch buffers for one transform length.
+struct DctKernel {
+    r2c: Arc<dyn RealToComplex<f32>>,
+    c2r: Arc<dyn ComplexToReal<f32>>,
+    tw: Vec<Complex<f32>>,
+    alpha: Vec<f32>,
+    vbuf: Vec<f32>,
+    spec: Vec<Complex<f32>>,
+    input: Vec<f32>,
+    output: Vec<f32>,
+}
+
+impl DctKernel {
+    fn new(planner: &mut RealFftPlanner<f32>, n: usize) -> Self {
+        let r2c = planner.plan_fft_forward(2 * n);
+        let c2r = planner.plan_fft_inverse(2 * n);
+        let (tw, alpha) = precompute_twiddle_alpha(n);
+        Self {
+            vbuf: r2c.make_input_vec(),
+            spec: r2c.make_output_vec(),
+            r2c,
+            c2r,
+            tw,
+            alpha,
+            input: vec![0.0; n],
+            output: vec![0.0; n],
+        }
+    }
+
+    /// DCT-II of `self.input` into `self.output`.
+    #[inline(always)]
+    fn forward(&mut self) {
+        let n = self.input.len();
+        // even-reflect до 2N
+        for i in 0..n {
+            let v = self.input[i];
+            self.vbuf[i] = v;
+            self.vbuf[2 * n - 1 - i] = v;
+        }
+        self.r2c.process(&mut self.vbuf, &mut self.spec).unwrap();
+        // DCT-II: X[k] = Re{ V[k]*exp(-jπk/2N) } * 0.5 * sqrt(2/N), k=0: *1/√2
+        for k in 0..n {
+            let z = self.spec[k];
+            let t = self.tw[k];
+            self.output[k] = (z.re * t.re - z.im * t.im) * self.alpha[k];
+        }
+    }
+
+    /// DCT-III (inverse of [`DctKernel::forward`]) of `self.input` into `self.output`.
+    #[inline(always)]
+    fn inverse(&mut self) {
+        let n = self.input.len();
+        // rebuild the spectrum of the even-reflected signal: V[k] = X[k] / alpha[k] * exp(+jπk/2N)
+        for k in 0..n {
+            let x = self.input[k] / self.alpha[k];
+            let t = self.tw[k];
+            self.spec[k] = Complex {
+                re: x * t.re,
+                im: -x * t.im,
+            };
+        }
+        self.spec[0].im = 0.0;
+        self.spec[n] = Complex { re: 0.0, im: 0.0 };
+        self.c2r.process(&mut self.spec, &mut self.vbuf).unwrap();
+        let scale = 1.0 / (2 * n) as f32;
+        for i in 0..n {
+            self.output[i] = self.vbuf[i] * scale;
+        }
+    }
+}
+
+/// Reusable 2D DCT plan.
+///
+/// ```rust
+/// use pepecore_array::dct::{DctMode, DctPlan};
+/// use pepecore_array::{ImgData, SVec, Shape};
+///
+/// let mut plan = DctPlan::new();
+/// let mut img = SVec::new(Shape::new(4, 4, None), ImgData::from((0..16).map(|v| v as f32).collect::<Vec<_>>()));
+/// plan.forward(&mut img, DctMode::Block(2)).unwrap();
+/// plan.inverse(&mut img, DctMode::Block(2)).unwrap();
+/// assert!((img.get_data::<f32>().unwrap()[5] - 5.0).abs() < 1e-4);
+/// ```
+pub struct DctPlan {
+    planner: RealFftPlanner<f32>,
+    kernels: HashMap<usize, DctKernel>,
+}
+
+impl Default for DctPlan {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DctPlan {
+    pub fn new() -> Self {
+        Self {
+            planner: RealFftPlanner::new(),
+            kernels: HashMap::new(),
+        }
+    }
+    /// Forward DCT-II of every channel. The image is converted to `f32` first.
+    pub fn forward(&mut self, img: &mut SVec, mode: DctMode) -> Result<(), Error> {
+        self.apply(img, None, mode, false)
+    }
+    /// Inverse DCT (DCT-III) of every channel, undoing [`DctPlan::forward`] with the same mode.
+    pub fn inverse(&mut self, img: &mut SVec, mode: DctMode) -> Result<(), Error> {
+        self.apply(img, None, mode, true)
+    }
+    /// Forward DCT-II of a single channel, other channels are left untouched.
+    pub fn forward_channel(&mut self, img: &mut SVec, channel: usize, mode: DctMode) -> Result<(), Error> {
+        self.apply(img, Some(channel), mode, false)
+    }
+    /// Inverse DCT of a single channel, other channels are left untouched.
+    pub fn inverse_channel(&mut self, img: &mut SVec, channel: usize, mode: DctMode) -> Result<(), Error> {
+        self.apply(img, Some(channel), mode, true)
+    }
+
+    fn kernel(&mut self, n: usize) -> &mut DctKernel {
+        let planner = &mut self.planner;
+        self.kernels.entry(n).or_insert_with(|| DctKernel::new(planner, n))
+    }
+
+    /// Forward DCT-II of every channel of a borrowed view into a new interleaved `f32` image.
+    ///
+    /// The source is read sample by sample into the output, so it is neither cloned nor
+    /// converted in place.
+    pub fn forward_view(&mut self, src: &SVecView, mode: DctMode) -> Result<SVec, Error> {
+        let (h, w, c) = src.shape();
+        let shape = Shape::try_new(h, w, c)?;
+        let len = shape.checked_len()?;
+        let c = c.unwrap_or(1);
+        let strides = src.strides();
+        let data = match src.pixel_type() {
+            PixelType::U8 => gather_f32(src.get_data::<u8>()?, strides, h, w, c, len),
+            PixelType::U16 => gather_f32(src.get_data::<u16>()?, strides, h, w, c, len),
+            PixelType::I16 => gather_f32(src.get_data::<i16>()?, strides, h, w, c, len),
+            PixelType::U32 => gather_f32(src.get_data::<u32>()?, strides, h, w, c, len),
+            PixelType::F16 => gather_f32(src.get_data::<f16>()?, strides, h, w, c, len),
+            PixelType::F32 => gather_f32(src.get_data::<f32>()?, strides, h, w, c, len),
+            PixelType::F64 => gather_f32(src.get_data::<f64>()?, strides, h, w, c, len),
+        };
+        let mut out = SVec::new(shape, ImgData::F32(data));
+        self.apply(&mut out, None, mode, false)?;
+        Ok(out)
+    }
+
+    fn apply(&mut self, img: &mut SVec, channel: Option<usize>, mode: DctMode, inverse: bool) -> Result<(), Error> {
+        let (h, w, c) = img.shape();
+        let c = c.unwrap_or(1);
+        let channels = match channel {
+            Some(ch) if ch >= c => return Err(Error::OutOfBounds),
+            Some(ch) => ch..ch + 1,
+            None => 0..c,
+        };
+        let (bh, bw) = match mode {
+            DctMode::Full => (h, w),
+            DctMode::Block(0) => return Err(Error::ZeroDimension),
+            DctMode::Block(n) => (n, n),
+        };
+        let strides = Strides::for_shape(&img.shape);
+        img.as_f32();
+        let buf = img.get_data_mut::<f32>()?;
+        // every index below is at most strides.index(h - 1, w - 1, c - 1) < h * w * c
+        let expected = h * w * c;
+        if buf.len() != expected {
+            return Err(Error::LengthMismatch {
+                expected,
+                actual: buf.len(),
+            });
+        }
+        for ch in channels {
+            for y0 in (0..h).step_by(bh) {
+                let rows = bh.min(h - y0);
+                for x0 in (0..w).step_by(bw) {
+                    let cols = bw.min(w - x0);
+                    // по строкам блока
+                    let kernel = self.kernel(cols);
+                    for y in y0..y0 + rows {
+                        let base = strides.index(y, x0, ch);
+                        unsafe {
+                            for x in 0..cols {
+                                *kernel.input.get_unchecked_mut(x) = *buf.get_unchecked(base + x * strides.col);
+                            }
+                            if inverse {
+                                kernel.inverse()
+                            } else {
+                                kernel.forward()
+                            }
+                            for x in 0..cols {
+                                *buf.get_unchecked_mut(base + x * strides.col) = *kernel.output.get_unchecked(x);
+                            }
+                        }
+                    }
+                    // по столбцам блока
+                    let kernel = self.kernel(rows);
+                    for x in x0..x0 + cols {
+                        let base = strides.index(y0, x, ch);
+                        unsafe {
+                            for y in 0..rows {
+                                *kernel.input.get_unchecked_mut(y) = *buf.get_unchecked(base + y * strides.row);
+                            }
+                            if inverse {
+                                kernel.inverse()
+                            } else {
+                                kernel.forward()
+                            }
+                            for y in 0..rows {
+                                *buf.get_unchecked_mut(base + y * strides.row) = *kernel.output.get_unchecked(y);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Copy a strided view into a dense interleaved `f32` buffer, normalizing every sample.
+fn gather_f32<T: Pixel>(data: &[T], strides: Strides, h: usize, w: usize, c: usize, len: usize) -> Vec<f32> {
+    let mut out = Vec::with_capacity(len);
+    for y in 0..h {
+        for x in 0..w {
+            let base = strides.index(y, x, 0);
+            out.extend((0..c).map(|ch| data[base + ch * strides.channel].to_f32_normalized()));
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ImgData, Shape};
+
+    fn ramp(h: usize, w: usize, c: usize) -> SVec {
+        let data = (0..h * w * c).map(|v| ((v * 37) % 101) as f32 / 101.0).collect::<Vec<_>>();
+        SVec::new(Shape::new(h, w, Some(c)), ImgData::from(data))
+    }
+
+    fn assert_close(a: &SVec, b: &SVec) {
+        for (x, y) in a.get_data::<f32>().unwrap().iter().zip(b.get_data::<f32>().unwrap()) {
+            assert!((x - y).abs() < 1e-4, "{x} != {y}");
+        }
+    }
+
+    #[test]
+    fn forward_matches_definition() {
+        // 1 x 4 row, orthonormal DCT-II computed directly
+        let x = [1.0f32, 2.0, 3.0, 4.0];
+        let mut img = SVec::new(Shape::new(1, 4, None), ImgData::from(x.to_vec()));
+        DctPlan::new().forward(&mut img, DctMode::Full).unwrap();
+        let out = img.get_data::<f32>().unwrap();
+        for (k, got) in out.iter().enumerate() {
+            let scale = if k == 0 {
+                (1.0f32 / 4.0).sqrt()
+            } else {
+                (2.0f32 / 4.0).sqrt()
+            };
+            let expected: f32 = x
+                .iter()
+                .enumerate()
+                .map(|(n, v)| v * (PI * k as f32 * (2 * n + 1) as f32 / 8.0).cos())
+                .sum::<f32>()
+                * scale;
+            assert!((got - expected).abs() < 1e-4, "{k}: {got} != {expected}");
+        }
+    }
+
+    #[test]
+    fn roundtrip_full_block_and_planar() {
+        let mut plan = DctPlan::new();
+        let src = ramp(13, 10, 3);
+        for mode in [DctMode::Full, DctMode::Block(8), DctMode::Block(3)] {
+            let mut img = src.clone();
+            plan.forward(&mut img, mode).unwrap();
+            plan.inverse(&mut img, mode).unwrap();
+            assert_close(&img, &src);
+        }
+
+        let mut planar = src.clone();
+        planar.to_planar();
+        plan.forward(&mut planar, DctMode::Block(8)).unwrap();
+        let mut interleaved = src.clone();
+        plan.forward(&mut interleaved, DctMode::Block(8)).unwrap();
+        planar.to_interleaved();
+        assert_close(&planar, &interleaved);
+    }
+
+    #[test]
+    fn svec_dct2_is_magnitude() {
+        let src = ramp(6, 5, 3);
+        let mut signed = src.clone();
+        signed.dct2_signed().unwrap();
+        assert!(signed.get_data::<f32>().unwrap().iter().any(|v| *v < 0.0));
+        let mut magnitude = src.clone();
+        magnitude.dct2().unwrap();
+        for (m, s) in magnitude
+            .get_data::<f32>()
+            .unwrap()
+            .iter()
+            .zip(signed.get_data::<f32>().unwrap())
+        {
+            assert_eq!(*m, s.abs());
+        }
+        signed.idct2().unwrap();
+        assert_close(&signed, &src);
+    }
+
+    #[test]
+    fn forward_view_matches_forward() {
+        let mut src = SVec::new(
+            Shape::new(9, 7, Some(3)),
+            ImgData::from((0..189).map(|v| (v * 13 % 256) as u8).collect::<Vec<_>>()),
+        );
+        let mut plan = DctPlan::new();
+        let cropped = plan
+            .forward_view(&src.view().crop(1, 2, 5, 6).unwrap(), DctMode::Full)
+            .unwrap();
+        let mut expected = src.view().crop(1, 2, 5, 6).unwrap().to_svec();
+        plan.forward(&mut expected, DctMode::Full).unwrap();
+        assert_close(&cropped, &expected);
+
+        src.to_planar();
+        let planar = plan.forward_view(&src.view(), DctMode::Block(4)).unwrap();
+        src.to_interleaved();
+        plan.forward(&mut src, DctMode::Block(4)).unwrap();
+        assert_eq!(planar.shape.get_layout(), crate::Layout::HWC);
+        assert_close(&planar, &src);
+    }
+
+    #[test]
+    fn single_channel_only() {
+        let src = ramp(8, 8, 3);
+        let mut img = src.clone();
+        let mut plan = DctPlan::new();
+        plan.forward_channel(&mut img, 1, DctMode::Full).unwrap();
+        let (a, b) = (img.get_data::<f32>().unwrap(), src.get_data::<f32>().unwrap());
+        assert_eq!(a[0], b[0]);
+        assert_ne!(a[1], b[1]);
+        assert!(plan.forward_channel(&mut img, 3, DctMode::Full).is_err());
+        assert!(plan.forward(&mut img, DctMode::Block(0)).is_err());
+    }
+}
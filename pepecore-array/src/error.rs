@@ -8,4 +8,19 @@ pub enum Error {
     NoChannelsError,
     #[error("Dimensions out of bounds")]
     OutOfBounds,
+    #[error("Data length mismatch: shape expects {expected} elements, found {actual}")]
+    LengthMismatch { expected: usize, actual: usize },
+    #[error("Shape mismatch: expected {expected:?}, found {actual:?}")]
+    ShapeMismatch {
+        expected: (usize, usize, Option<usize>),
+        actual: (usize, usize, Option<usize>),
+    },
+    #[error("Shape contains a zero dimension")]
+    ZeroDimension,
+    #[error("Shape element count overflows usize")]
+    Overflow,
+    #[error("Expected 2 or 3 dimensions, found {0}")]
+    InvalidDimensions(usize),
+    #[error("Unsupported channel count: {0}")]
+    UnsupportedChannels(usize),
 }
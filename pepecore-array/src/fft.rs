@@ -0,0 +1,217 @@
+//! 2D complex FFT of image channels and spectrum export.
+use crate::error::Error;
+use crate::{ImgData, SVec, Shape};
+use rustfft::num_complex::Complex;
+use rustfft::{Fft, FftPlanner};
+use std::sync::Arc;
+
+/// Unshifted 2D spectrum of one channel, stored row-major (`height x width`).
+#[derive(Clone, Debug)]
+pub struct Spectrum {
+    height: usize,
+    width: usize,
+    data: Vec<Complex<f32>>,
+}
+
+impl Spectrum {
+    pub fn height(&self) -> usize {
+        self.height
+    }
+    pub fn width(&self) -> usize {
+        self.width
+    }
+    pub fn data(&self) -> &[Complex<f32>] {
+        &self.data
+    }
+    pub fn data_mut(&mut self) -> &mut [Complex<f32>] {
+        &mut self.data
+    }
+    /// Signed frequency of bin `(y, x)` in cycles per pixel, each component in `-0.5..0.5`.
+    #[inline]
+    pub fn frequency(&self, y: usize, x: usize) -> (f32, f32) {
+        (signed_frequency(y, self.height), signed_frequency(x, self.width))
+    }
+    /// Multiply every bin by `gain(fy, fx)`, frequencies as in [`Spectrum::frequency`].
+    pub fn apply_gain(&mut self, gain: impl Fn(f32, f32) -> f32) {
+        for y in 0..self.height {
+            let fy = signed_frequency(y, self.height);
+            for x in 0..self.width {
+                let g = gain(fy, signed_frequency(x, self.width));
+                self.data[y * self.width + x] *= g;
+            }
+        }
+    }
+    /// `ln(1 + |F|)` with the zero frequency moved to the centre, as a single-channel `f32` image.
+    pub fn magnitude(&self) -> SVec {
+        self.shifted(|z| z.norm().ln_1p())
+    }
+    /// Phase in `-π..=π` with the zero frequency moved to the centre, as a single-channel `f32` image.
+    pub fn phase(&self) -> SVec {
+        self.shifted(|z| z.arg())
+    }
+    fn shifted(&self, f: impl Fn(Complex<f32>) -> f32) -> SVec {
+        let (h, w) = (self.height, self.width);
+        let mut out = vec![0.0f32; h * w];
+        for y in 0..h {
+            let sy = (y + h / 2) % h;
+            for x in 0..w {
+                let sx = (x + w / 2) % w;
+                out[sy * w + sx] = f(self.data[y * w + x]);
+            }
+        }
+        SVec::new(Shape::new(h, w, None), ImgData::from(out))
+    }
+}
+
+#[inline(always)]
+fn signed_frequency(i: usize, n: usize) -> f32 {
+    let i = if i > n / 2 { i as isize - n as isize } else { i as isize };
+    i as f32 / n as f32
+}
+
+/// Reusable 2D FFT plan; `rustfft` keeps one plan per length, so batches of equally sized
+/// images are planned once.
+pub struct FftPlan {
+    planner: FftPlanner<f32>,
+    scratch: Vec<Complex<f32>>,
+}
+
+impl Default for FftPlan {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FftPlan {
+    pub fn new() -> Self {
+        Self {
+            planner: FftPlanner::new(),
+            scratch: Vec::new(),
+        }
+    }
+    /// Forward FFT of every channel of `img`, one [`Spectrum`] per channel.
+    ///
+    /// Samples are taken in their normalized `f32` range (see [`crate::Pixel`]).
+    pub fn fft2(&mut self, img: &SVec) -> Result<Vec<Spectrum>, Error> {
+        let c = img.shape.get_channels().unwrap_or(1);
+        (0..c).map(|ch| self.fft2_channel(img, ch)).collect()
+    }
+    /// Forward FFT of channel `channel` of `img`.
+    pub fn fft2_channel(&mut self, img: &SVec, channel: usize) -> Result<Spectrum, Error> {
+        let mut plane = img.view().channel(channel)?.to_svec();
+        plane.as_f32();
+        let (h, w, _) = plane.shape();
+        let data = plane.get_data::<f32>()?.iter().map(|&re| Complex { re, im: 0.0 }).collect();
+        let mut spectrum = Spectrum {
+            height: h,
+            width: w,
+            data,
+        };
+        self.transform(&mut spectrum, false);
+        Ok(spectrum)
+    }
+    /// Inverse FFT of `spectra` into an interleaved `f32` image, one channel per spectrum.
+    ///
+    /// All spectra must have the same size. The imaginary part of the result is dropped.
+    pub fn ifft2(&mut self, spectra: &[Spectrum]) -> Result<SVec, Error> {
+        let first = spectra.first().ok_or(Error::NoChannelsError)?;
+        let (h, w) = (first.height, first.width);
+        if let Some(s) = spectra.iter().find(|s| s.height != h || s.width != w) {
+            return Err(Error::ShapeMismatch {
+                expected: (h, w, None),
+                actual: (s.height, s.width, None),
+            });
+        }
+        let c = spectra.len();
+        let scale = 1.0 / (h * w) as f32;
+        let mut out = vec![0.0f32; h * w * c];
+        for (ch, spectrum) in spectra.iter().enumerate() {
+            let mut spectrum = spectrum.clone();
+            self.transform(&mut spectrum, true);
+            for (i, z) in spectrum.data.iter().enumerate() {
+                out[i * c + ch] = z.re * scale;
+            }
+        }
+        let channels = if c == 1 { None } else { Some(c) };
+        SVec::try_new(Shape::try_new(h, w, channels)?, ImgData::from(out))
+    }
+
+    fn plan(&mut self, n: usize, inverse: bool) -> Arc<dyn Fft<f32>> {
+        let fft = if inverse {
+            self.planner.plan_fft_inverse(n)
+        } else {
+            self.planner.plan_fft_forward(n)
+        };
+        let len = fft.get_inplace_scratch_len();
+        if self.scratch.len() < len {
+            self.scratch.resize(len, Complex { re: 0.0, im: 0.0 });
+        }
+        fft
+    }
+
+    fn transform(&mut self, spectrum: &mut Spectrum, inverse: bool) {
+        let (h, w) = (spectrum.height, spectrum.width);
+        // строки: rustfft обрабатывает буфер кусками длины w
+        let row = self.plan(w, inverse);
+        let len = row.get_inplace_scratch_len();
+        row.process_with_scratch(&mut spectrum.data, &mut self.scratch[..len]);
+        // столбцы через транспонирование
+        let mut t = transpose(&spectrum.data, h, w);
+        let col = self.plan(h, inverse);
+        let len = col.get_inplace_scratch_len();
+        col.process_with_scratch(&mut t, &mut self.scratch[..len]);
+        spectrum.data = transpose(&t, w, h);
+    }
+}
+
+fn transpose(data: &[Complex<f32>], h: usize, w: usize) -> Vec<Complex<f32>> {
+    let mut out = Vec::with_capacity(h * w);
+    for x in 0..w {
+        out.extend((0..h).map(|y| data[y * w + x]));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn roundtrip_and_dc() {
+        let data: Vec<f32> = (0..6 * 5 * 2).map(|v| (v % 7) as f32 / 7.0).collect();
+        let img = SVec::new(Shape::new(6, 5, Some(2)), ImgData::from(data.clone()));
+        let mut plan = FftPlan::new();
+        let spectra = plan.fft2(&img).unwrap();
+        assert_eq!(spectra.len(), 2);
+        let dc: f32 = data.iter().step_by(2).sum();
+        assert!((spectra[0].data()[0].re - dc).abs() < 1e-4);
+
+        let back = plan.ifft2(&spectra).unwrap();
+        for (a, b) in back.get_data::<f32>().unwrap().iter().zip(&data) {
+            assert!((a - b).abs() < 1e-5);
+        }
+
+        let small = SVec::new(Shape::new(3, 5, None), ImgData::from(vec![0.0f32; 15]));
+        let mixed = [spectra[0].clone(), plan.fft2_channel(&small, 0).unwrap()];
+        assert!(matches!(
+            plan.ifft2(&mixed),
+            Err(Error::ShapeMismatch {
+                expected: (6, 5, None),
+                actual: (3, 5, None)
+            })
+        ));
+    }
+
+    #[test]
+    fn frequencies_and_shift() {
+        let img = SVec::new(Shape::new(4, 4, None), ImgData::from(vec![1.0f32; 16]));
+        let spectrum = FftPlan::new().fft2_channel(&img, 0).unwrap();
+        assert_eq!(spectrum.frequency(0, 1), (0.0, 0.25));
+        assert_eq!(spectrum.frequency(3, 2), (-0.25, 0.5));
+        // all energy in DC, which lands at the centre after the shift
+        let mag = spectrum.magnitude();
+        let mag = mag.get_data::<f32>().unwrap();
+        assert!((mag[2 * 4 + 2] - 16.0f32.ln_1p()).abs() < 1e-5);
+        assert_eq!(mag.iter().filter(|v| **v > 1e-5).count(), 1);
+    }
+}
@@ -1,95 +1,67 @@
+mod arith;
+mod channels;
+pub mod dct;
 pub mod error;
+pub mod fft;
+pub mod pixel;
 #[cfg(feature = "type-convert")]
 pub mod type_convert;
+pub mod view;
 #[cfg(not(feature = "type-convert"))]
 mod type_convert;
 
+use crate::dct::{DctMode, DctPlan};
 use crate::error::Error;
+pub use crate::pixel::Pixel;
+use crate::type_convert::convert_normalized;
+use crate::type_convert::normalized::{
+    convert_f16_to_f32_normalized, convert_f32_to_f16_normalized, convert_f32_to_f64_normalized,
+    convert_f32_to_i16_normalized, convert_f32_to_u32_normalized, convert_f64_to_f32_normalized,
+    convert_i16_to_f32_normalized, convert_u32_to_f32_normalized,
+};
 use crate::type_convert::f32_to::{convert_f32_to_u8_normalized, convert_f32_to_u16_normalized};
 use crate::type_convert::u8_to::{convert_u8_to_f32_normalized, convert_u8_to_u16_normalized};
 use crate::type_convert::u16_to::{convert_u16_to_f32_normalized, convert_u16_to_u8_normalized};
-use realfft::{RealFftPlanner, RealToComplex};
-use rustfft::num_complex::Complex;
+pub use half::f16;
 use std::any::TypeId;
-use std::f32::consts::{FRAC_1_SQRT_2, PI};
 use std::fmt;
 use std::ops::Range;
-
-#[inline]
-fn precompute_twiddle_alpha(n: usize) -> (Vec<Complex<f32>>, Vec<f32>) {
-    let base = 0.5 * (2.0f32 / n as f32).sqrt();
-    let mut tw = Vec::with_capacity(n);
-    let mut alpha = Vec::with_capacity(n);
-    for k in 0..n {
-        let theta = PI * (k as f32) / (2.0 * n as f32);
-        let (s, c) = theta.sin_cos();
-        tw.push(Complex { re: c, im: -s }); // exp(-jθ)
-        alpha.push(if k == 0 { base * FRAC_1_SQRT_2 } else { base }); // 0.5*sqrt(2/N)*(k==0?1/√2:1)
-    }
-    (tw, alpha)
-}
-
-#[inline(always)]
-fn dct1d_ortho_realfft_into(
-    input: &[f32],
-    output: &mut [f32],
-    r2c: &std::sync::Arc<dyn RealToComplex<f32>>,
-    vbuf: &mut [f32],
-    spec: &mut [Complex<f32>],
-    tw: &[Complex<f32>],
-    alpha: &[f32],
-) {
-    let n = input.len();
-    debug_assert_eq!(output.len(), n);
-    debug_assert_eq!(vbuf.len(), 2 * n);
-    debug_assert_eq!(spec.len(), n + 1);
-
-    // even-reflect до 2N
-    for i in 0..n {
-        let v = unsafe { *input.get_unchecked(i) };
-        unsafe {
-            *vbuf.get_unchecked_mut(i) = v;
-            *vbuf.get_unchecked_mut(2 * n - 1 - i) = v;
-        }
-    }
-
-    // Real FFT (2N) → spec
-    r2c.process(vbuf, spec).unwrap();
-
-    // DCT-II: X[k] = Re{ V[k]*exp(-jπk/2N) } * 0.5 * sqrt(2/N), k=0: *1/√2
-    for k in 0..n {
-        unsafe {
-            let z = *spec.get_unchecked(k);
-            let t = *tw.get_unchecked(k);
-            let re = z.re * t.re - z.im * t.im;
-            *output.get_unchecked_mut(k) = re * *alpha.get_unchecked(k);
-        }
-    }
+pub use view::{SVecView, SVecViewMut, Strides};
+
+/// Order of the samples in an `SVec` buffer.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Layout {
+    /// Interleaved: all channels of a pixel are stored next to each other.
+    #[default]
+    HWC,
+    /// Planar: every channel is stored as a separate `height * width` plane.
+    CHW,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct Shape {
     height: usize,
     width: usize,
     channels: Option<usize>,
+    layout: Layout,
 }
 
-impl From<Vec<usize>> for Shape {
-    fn from(vec: Vec<usize>) -> Self {
-        Self {
-            height: vec[0],
-            width: vec[1],
-            channels: vec.get(2).cloned(),
-        }
+impl TryFrom<Vec<usize>> for Shape {
+    type Error = Error;
+
+    fn try_from(vec: Vec<usize>) -> Result<Self, Self::Error> {
+        Shape::try_from(vec.as_slice())
     }
 }
 
-impl From<&[usize]> for Shape {
-    fn from(value: &[usize]) -> Self {
-        Self {
-            height: value[0],
-            width: value[1],
-            channels: value.get(2).cloned(),
+impl TryFrom<&[usize]> for Shape {
+    type Error = Error;
+
+    fn try_from(value: &[usize]) -> Result<Self, Self::Error> {
+        match *value {
+            [height, width] => Shape::try_new(height, width, None),
+            [height, width, channels] => Shape::try_new(height, width, Some(channels)),
+            _ => Err(Error::InvalidDimensions(value.len())),
         }
     }
 }
@@ -99,6 +71,25 @@ pub enum PixelType {
     U8,
     U16,
     F32,
+    I16,
+    U32,
+    F16,
+    F64,
+}
+
+impl PixelType {
+    /// Rust name of the sample type, as used in [`Error::TypeMismatch`].
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            PixelType::U8 => "u8",
+            PixelType::U16 => "u16",
+            PixelType::F32 => "f32",
+            PixelType::I16 => "i16",
+            PixelType::U32 => "u32",
+            PixelType::F16 => "f16",
+            PixelType::F64 => "f64",
+        }
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -106,6 +97,10 @@ pub enum ImgData {
     F32(Vec<f32>),
     U8(Vec<u8>),
     U16(Vec<u16>),
+    I16(Vec<i16>),
+    U32(Vec<u32>),
+    F16(Vec<f16>),
+    F64(Vec<f64>),
 }
 
 impl From<Vec<f32>> for ImgData {
@@ -126,14 +121,56 @@ impl From<Vec<u16>> for ImgData {
     }
 }
 
+impl From<Vec<i16>> for ImgData {
+    fn from(value: Vec<i16>) -> Self {
+        ImgData::I16(value)
+    }
+}
+
+impl From<Vec<u32>> for ImgData {
+    fn from(value: Vec<u32>) -> Self {
+        ImgData::U32(value)
+    }
+}
+
+impl From<Vec<f16>> for ImgData {
+    fn from(value: Vec<f16>) -> Self {
+        ImgData::F16(value)
+    }
+}
+
+impl From<Vec<f64>> for ImgData {
+    fn from(value: Vec<f64>) -> Self {
+        ImgData::F64(value)
+    }
+}
+
 impl ImgData {
     pub fn pixel_type(&self) -> PixelType {
         match self {
             ImgData::U8(_) => PixelType::U8,
             ImgData::U16(_) => PixelType::U16,
             ImgData::F32(_) => PixelType::F32,
+            ImgData::I16(_) => PixelType::I16,
+            ImgData::U32(_) => PixelType::U32,
+            ImgData::F16(_) => PixelType::F16,
+            ImgData::F64(_) => PixelType::F64,
         }
     }
+    pub fn len(&self) -> usize {
+        match self {
+            ImgData::U8(data) => data.len(),
+            ImgData::U16(data) => data.len(),
+            ImgData::F32(data) => data.len(),
+            ImgData::I16(data) => data.len(),
+            ImgData::U32(data) => data.len(),
+            ImgData::F16(data) => data.len(),
+            ImgData::F64(data) => data.len(),
+        }
+    }
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
 }
 
 #[derive(Clone)]
@@ -143,8 +180,30 @@ pub struct SVec {
 }
 
 impl Shape {
+    /// Build a shape without validation; prefer [`Shape::try_new`] for untrusted sizes.
     pub fn new(height: usize, width: usize, channels: Option<usize>) -> Self {
-        Self { height, width, channels }
+        Self {
+            height,
+            width,
+            channels,
+            layout: Layout::HWC,
+        }
+    }
+    /// Build a shape, rejecting zero dimensions and element counts that overflow `usize`.
+    pub fn try_new(height: usize, width: usize, channels: Option<usize>) -> Result<Self, Error> {
+        let shape = Self::new(height, width, channels);
+        shape.checked_len()?;
+        Ok(shape)
+    }
+    /// Number of elements described by the shape (`height * width * channels`).
+    pub fn checked_len(&self) -> Result<usize, Error> {
+        if self.height == 0 || self.width == 0 || self.channels == Some(0) {
+            return Err(Error::ZeroDimension);
+        }
+        self.height
+            .checked_mul(self.width)
+            .and_then(|len| len.checked_mul(self.channels.unwrap_or(1)))
+            .ok_or(Error::Overflow)
     }
     pub fn get_height(&self) -> usize {
         self.height
@@ -158,14 +217,39 @@ impl Shape {
     pub fn get_shape(&self) -> (usize, usize, Option<usize>) {
         (self.height, self.width, self.channels)
     }
+    /// Same dimensions with the sample order set to `layout`.
+    ///
+    /// Only describes the buffer, use [`SVec::to_planar`] / [`SVec::to_interleaved`] to reorder data.
+    pub fn with_layout(self, layout: Layout) -> Self {
+        Self { layout, ..self }
+    }
+    pub fn get_layout(&self) -> Layout {
+        self.layout
+    }
     pub fn get_ndims(&self) -> usize {
         if self.channels.is_some() { 3 } else { 2 }
     }
 }
 impl SVec {
+    /// Build an `SVec` without validation; prefer [`SVec::try_new`] when the data comes from outside.
     pub fn new(shape: Shape, data: ImgData) -> Self {
         SVec { shape, data }
     }
+    /// Build an `SVec`, checking that `data` holds exactly `height * width * channels` elements.
+    ///
+    /// # Errors
+    ///
+    /// - `ZeroDimension` if any dimension is zero.
+    /// - `Overflow` if the element count overflows `usize`.
+    /// - `LengthMismatch` if the data length differs from the element count.
+    pub fn try_new(shape: Shape, data: ImgData) -> Result<Self, Error> {
+        let expected = shape.checked_len()?;
+        let actual = data.len();
+        if expected != actual {
+            return Err(Error::LengthMismatch { expected, actual });
+        }
+        Ok(SVec { shape, data })
+    }
     pub fn shape(&self) -> (usize, usize, Option<usize>) {
         self.shape.get_shape()
     }
@@ -176,36 +260,13 @@ impl SVec {
 
     pub fn get_data<T: 'static>(&self) -> Result<&[T], Error> {
         match &self.data {
-            ImgData::U8(data) => {
-                if TypeId::of::<T>() == TypeId::of::<u8>() {
-                    Ok(unsafe { std::mem::transmute::<&[u8], &[T]>(data) })
-                } else {
-                    Err(Error::TypeMismatch {
-                        expected: "u8",
-                        actual: std::any::type_name::<T>(),
-                    })
-                }
-            }
-            ImgData::U16(data) => {
-                if TypeId::of::<T>() == TypeId::of::<u16>() {
-                    Ok(unsafe { std::mem::transmute::<&[u16], &[T]>(data) })
-                } else {
-                    Err(Error::TypeMismatch {
-                        expected: "u16",
-                        actual: std::any::type_name::<T>(),
-                    })
-                }
-            }
-            ImgData::F32(data) => {
-                if TypeId::of::<T>() == TypeId::of::<f32>() {
-                    Ok(unsafe { std::mem::transmute::<&[f32], &[T]>(data) })
-                } else {
-                    Err(Error::TypeMismatch {
-                        expected: "f32",
-                        actual: std::any::type_name::<T>(),
-                    })
-                }
-            }
+            ImgData::U8(data) => cast_slice(data),
+            ImgData::U16(data) => cast_slice(data),
+            ImgData::F32(data) => cast_slice(data),
+            ImgData::I16(data) => cast_slice(data),
+            ImgData::U32(data) => cast_slice(data),
+            ImgData::F16(data) => cast_slice(data),
+            ImgData::F64(data) => cast_slice(data),
         }
     }
     pub fn pixel_type(&self) -> PixelType {
@@ -213,36 +274,13 @@ impl SVec {
     }
     pub fn get_data_mut<T: 'static>(&mut self) -> Result<&mut [T], Error> {
         match &mut self.data {
-            ImgData::U8(data) => {
-                if TypeId::of::<T>() == TypeId::of::<u8>() {
-                    Ok(unsafe { std::mem::transmute::<&mut [u8], &mut [T]>(data) })
-                } else {
-                    Err(Error::TypeMismatch {
-                        expected: "u8",
-                        actual: std::any::type_name::<T>(),
-                    })
-                }
-            }
-            ImgData::U16(data) => {
-                if TypeId::of::<T>() == TypeId::of::<u16>() {
-                    Ok(unsafe { std::mem::transmute::<&mut [u16], &mut [T]>(data) })
-                } else {
-                    Err(Error::TypeMismatch {
-                        expected: "u16",
-                        actual: std::any::type_name::<T>(),
-                    })
-                }
-            }
-            ImgData::F32(data) => {
-                if TypeId::of::<T>() == TypeId::of::<f32>() {
-                    Ok(unsafe { std::mem::transmute::<&mut [f32], &mut [T]>(data) })
-                } else {
-                    Err(Error::TypeMismatch {
-                        expected: "f32",
-                        actual: std::any::type_name::<T>(),
-                    })
-                }
-            }
+            ImgData::U8(data) => cast_slice_mut(data),
+            ImgData::U16(data) => cast_slice_mut(data),
+            ImgData::F32(data) => cast_slice_mut(data),
+            ImgData::I16(data) => cast_slice_mut(data),
+            ImgData::U32(data) => cast_slice_mut(data),
+            ImgData::F16(data) => cast_slice_mut(data),
+            ImgData::F64(data) => cast_slice_mut(data),
         }
     }
     pub fn as_f32(&mut self) {
@@ -250,6 +288,10 @@ impl SVec {
             ImgData::U8(_) => convert_u8_to_f32_normalized(self),
             ImgData::U16(_) => convert_u16_to_f32_normalized(self),
             ImgData::F32(_) => {}
+            ImgData::I16(_) => convert_i16_to_f32_normalized(self),
+            ImgData::U32(_) => convert_u32_to_f32_normalized(self),
+            ImgData::F16(_) => convert_f16_to_f32_normalized(self),
+            ImgData::F64(_) => convert_f64_to_f32_normalized(self),
         }
     }
     pub fn as_u8(&mut self) {
@@ -257,6 +299,7 @@ impl SVec {
             ImgData::U8(_) => {}
             ImgData::U16(_) => convert_u16_to_u8_normalized(self),
             ImgData::F32(_) => convert_f32_to_u8_normalized(self),
+            _ => convert_normalized::<u8>(self),
         }
     }
     pub fn as_u16(&mut self) {
@@ -264,100 +307,76 @@ impl SVec {
             ImgData::U8(_) => convert_u8_to_u16_normalized(self),
             ImgData::U16(_) => {}
             ImgData::F32(_) => convert_f32_to_u16_normalized(self),
+            _ => convert_normalized::<u16>(self),
         }
     }
-    pub fn dct2(&mut self) -> Result<(), Error> {
-        let (h, w, c_opt) = self.shape();
-        let c = c_opt.unwrap_or(1);
-
-        self.as_f32();
-        let buf = self.get_data_mut::<f32>()?;
-
-        // планы real-FFT
-        let mut planner = RealFftPlanner::<f32>::new();
-        let r2c_row = planner.plan_fft_forward(2 * w);
-        let r2c_col = planner.plan_fft_forward(2 * h);
-
-        // рабочие буферы (reuse)
-        let mut v_row = r2c_row.make_input_vec(); // 2W
-        let mut v_col = r2c_col.make_input_vec(); // 2H
-        let mut spec_row = r2c_row.make_output_vec(); // W+1
-        let mut spec_col = r2c_col.make_output_vec(); // H+1
-
-        let (tw_row, alpha_row) = precompute_twiddle_alpha(w);
-        let (tw_col, alpha_col) = precompute_twiddle_alpha(h);
-
-        // РАЗДЕЛЬНЫЕ буферы ввода/вывода!
-        let mut row_in = vec![0.0f32; w];
-        let mut row_out = vec![0.0f32; w];
-        let mut col_in = vec![0.0f32; h];
-        let mut col_out = vec![0.0f32; h];
-
-        // --- DCT по строкам ---
-        for ch in 0..c {
-            for y in 0..h {
-                // gather строки
-                let base = y * w * c + ch;
-                let mut idx = base;
-                for x in 0..w {
-                    unsafe {
-                        *row_in.get_unchecked_mut(x) = *buf.get_unchecked(idx);
-                    }
-                    idx += c;
-                }
-
-                dct1d_ortho_realfft_into(
-                    &row_in,
-                    &mut row_out,
-                    &r2c_row,
-                    &mut v_row,
-                    &mut spec_row,
-                    &tw_row,
-                    &alpha_row,
-                );
-
-                // scatter обратно
-                let mut idx = base;
-                for x in 0..w {
-                    unsafe {
-                        *buf.get_unchecked_mut(idx) = *row_out.get_unchecked(x);
-                    }
-                    idx += c;
-                }
-            }
+    /// Convert to `pixel_type` with the matching `as_*` conversion.
+    pub fn as_pixel_type(&mut self, pixel_type: PixelType) {
+        match pixel_type {
+            PixelType::U8 => self.as_u8(),
+            PixelType::U16 => self.as_u16(),
+            PixelType::F32 => self.as_f32(),
+            PixelType::I16 => self.as_i16(),
+            PixelType::U32 => self.as_u32(),
+            PixelType::F16 => self.as_f16(),
+            PixelType::F64 => self.as_f64(),
         }
-
-        for ch in 0..c {
-            for x in 0..w {
-                let mut idx = x * c + ch;
-                for y in 0..h {
-                    unsafe {
-                        *col_in.get_unchecked_mut(y) = *buf.get_unchecked(idx);
-                    }
-                    idx += w * c;
-                }
-
-                dct1d_ortho_realfft_into(
-                    &col_in,
-                    &mut col_out,
-                    &r2c_col,
-                    &mut v_col,
-                    &mut spec_col,
-                    &tw_col,
-                    &alpha_col,
-                );
-
-                let mut idx = x * c + ch;
-                for y in 0..h {
-                    unsafe {
-                        *buf.get_unchecked_mut(idx) = (*col_out.get_unchecked(y)).abs();
-                    }
-                    idx += w * c;
-                }
-            }
+    }
+    /// Convert to `i16`, mapping the normalized range onto `-32767..=32767`.
+    pub fn as_i16(&mut self) {
+        match &mut self.data {
+            ImgData::F32(_) => convert_f32_to_i16_normalized(self),
+            _ => convert_normalized::<i16>(self),
+        }
+    }
+    pub fn as_u32(&mut self) {
+        match &mut self.data {
+            ImgData::F32(_) => convert_f32_to_u32_normalized(self),
+            _ => convert_normalized::<u32>(self),
+        }
+    }
+    pub fn as_f16(&mut self) {
+        match &mut self.data {
+            ImgData::F32(_) => convert_f32_to_f16_normalized(self),
+            _ => convert_normalized::<f16>(self),
+        }
+    }
+    pub fn as_f64(&mut self) {
+        match &mut self.data {
+            ImgData::F32(_) => convert_f32_to_f64_normalized(self),
+            _ => convert_normalized::<f64>(self),
+        }
+    }
+    /// Absolute values of the orthonormal DCT-II over the whole image, per channel. Converts the
+    /// data to `f32`.
+    ///
+    /// The signs are dropped, so the result can't be inverted; use [`SVec::dct2_signed`] for that.
+    pub fn dct2(&mut self) -> Result<(), Error> {
+        self.dct2_signed()?;
+        for v in self.get_data_mut::<f32>()? {
+            *v = v.abs();
         }
         Ok(())
     }
+    /// Orthonormal DCT-II over the whole image, per channel, keeping the signs. Converts the data
+    /// to `f32`.
+    ///
+    /// Use a [`DctPlan`] directly to reuse FFT plans between calls.
+    pub fn dct2_signed(&mut self) -> Result<(), Error> {
+        DctPlan::new().forward(self, DctMode::Full)
+    }
+    /// Inverse of [`SVec::dct2_signed`].
+    pub fn idct2(&mut self) -> Result<(), Error> {
+        DctPlan::new().inverse(self, DctMode::Full)
+    }
+    /// Orthonormal DCT-II of independent `block x block` tiles, per channel.
+    pub fn dct2_blocks(&mut self, block: usize) -> Result<(), Error> {
+        DctPlan::new().forward(self, DctMode::Block(block))
+    }
+    /// Inverse of [`SVec::dct2_blocks`] with the same block size.
+    pub fn idct2_blocks(&mut self, block: usize) -> Result<(), Error> {
+        DctPlan::new().inverse(self, DctMode::Block(block))
+    }
 
     pub fn truncate(&mut self, new_len: usize) -> Result<(), Error> {
         match &mut self.data {
@@ -373,6 +392,22 @@ impl SVec {
                 data.truncate(new_len);
                 Ok(())
             }
+            ImgData::I16(data) => {
+                data.truncate(new_len);
+                Ok(())
+            }
+            ImgData::U32(data) => {
+                data.truncate(new_len);
+                Ok(())
+            }
+            ImgData::F16(data) => {
+                data.truncate(new_len);
+                Ok(())
+            }
+            ImgData::F64(data) => {
+                data.truncate(new_len);
+                Ok(())
+            }
         }
     }
     pub fn drain(&mut self, new_len: Range<usize>) -> Result<(), Error> {
@@ -389,40 +424,33 @@ impl SVec {
                 data.drain(new_len);
                 Ok(())
             }
+            ImgData::I16(data) => {
+                data.drain(new_len);
+                Ok(())
+            }
+            ImgData::U32(data) => {
+                data.drain(new_len);
+                Ok(())
+            }
+            ImgData::F16(data) => {
+                data.drain(new_len);
+                Ok(())
+            }
+            ImgData::F64(data) => {
+                data.drain(new_len);
+                Ok(())
+            }
         }
     }
     pub fn get_mut_vec<T: 'static>(&mut self) -> Result<&mut Vec<T>, Error> {
         match &mut self.data {
-            ImgData::U8(data) => {
-                if TypeId::of::<T>() == TypeId::of::<u8>() {
-                    Ok(unsafe { std::mem::transmute::<&mut Vec<u8>, &mut Vec<T>>(data) })
-                } else {
-                    Err(Error::TypeMismatch {
-                        expected: "u8",
-                        actual: std::any::type_name::<T>(),
-                    })
-                }
-            }
-            ImgData::U16(data) => {
-                if TypeId::of::<T>() == TypeId::of::<u16>() {
-                    Ok(unsafe { std::mem::transmute::<&mut Vec<u16>, &mut Vec<T>>(data) })
-                } else {
-                    Err(Error::TypeMismatch {
-                        expected: "u16",
-                        actual: std::any::type_name::<T>(),
-                    })
-                }
-            }
-            ImgData::F32(data) => {
-                if TypeId::of::<T>() == TypeId::of::<f32>() {
-                    Ok(unsafe { std::mem::transmute::<&mut Vec<f32>, &mut Vec<T>>(data) })
-                } else {
-                    Err(Error::TypeMismatch {
-                        expected: "f32",
-                        actual: std::any::type_name::<T>(),
-                    })
-                }
-            }
+            ImgData::U8(data) => cast_vec_mut(data),
+            ImgData::U16(data) => cast_vec_mut(data),
+            ImgData::F32(data) => cast_vec_mut(data),
+            ImgData::I16(data) => cast_vec_mut(data),
+            ImgData::U32(data) => cast_vec_mut(data),
+            ImgData::F16(data) => cast_vec_mut(data),
+            ImgData::F64(data) => cast_vec_mut(data),
         }
     }
     pub fn get_mut_ptr<T: 'static>(&mut self) -> Result<*mut T, Error> {
@@ -431,6 +459,11 @@ impl SVec {
 }
 impl fmt::Debug for SVec {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.shape.get_layout() == Layout::CHW {
+            let mut img = self.clone();
+            img.to_interleaved();
+            return fmt::Debug::fmt(&img, f);
+        }
         let (w, h, c_opt) = self.shape();
         let c = c_opt.unwrap_or(1);
 
@@ -440,10 +473,50 @@ impl fmt::Debug for SVec {
             ImgData::U8(data) => fmt_data(f, data, w, h, c),
             ImgData::U16(data) => fmt_data(f, data, w, h, c),
             ImgData::F32(data) => fmt_data(f, data, w, h, c),
+            ImgData::I16(data) => fmt_data(f, data, w, h, c),
+            ImgData::U32(data) => fmt_data(f, data, w, h, c),
+            ImgData::F16(data) => fmt_data(f, data, w, h, c),
+            ImgData::F64(data) => fmt_data(f, data, w, h, c),
         }
     }
 }
 
+#[inline]
+pub(crate) fn cast_slice<S: Pixel, T: 'static>(data: &[S]) -> Result<&[T], Error> {
+    if TypeId::of::<T>() == TypeId::of::<S>() {
+        Ok(unsafe { std::slice::from_raw_parts(data.as_ptr() as *const T, data.len()) })
+    } else {
+        Err(Error::TypeMismatch {
+            expected: S::PIXEL_TYPE.type_name(),
+            actual: std::any::type_name::<T>(),
+        })
+    }
+}
+
+#[inline]
+pub(crate) fn cast_slice_mut<S: Pixel, T: 'static>(data: &mut [S]) -> Result<&mut [T], Error> {
+    if TypeId::of::<T>() == TypeId::of::<S>() {
+        Ok(unsafe { std::slice::from_raw_parts_mut(data.as_mut_ptr() as *mut T, data.len()) })
+    } else {
+        Err(Error::TypeMismatch {
+            expected: S::PIXEL_TYPE.type_name(),
+            actual: std::any::type_name::<T>(),
+        })
+    }
+}
+
+#[inline]
+fn cast_vec_mut<S: Pixel, T: 'static>(data: &mut Vec<S>) -> Result<&mut Vec<T>, Error> {
+    if TypeId::of::<T>() == TypeId::of::<S>() {
+        Ok(unsafe { &mut *(data as *mut Vec<S> as *mut Vec<T>) })
+    } else {
+        Err(Error::TypeMismatch {
+            expected: S::PIXEL_TYPE.type_name(),
+            actual: std::any::type_name::<T>(),
+        })
+    }
+}
+
 fn fmt_data<T: fmt::Debug>(f: &mut fmt::Formatter<'_>, data: &[T], w: usize, h: usize, c: usize) -> fmt::Result {
     for y in 0..h {
         for x in 0..w {
@@ -521,6 +594,31 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_try_new_validates() {
+        let svec = SVec::try_new(Shape::new(2, 2, Some(3)), ImgData::U8(vec![0; 12])).unwrap();
+        assert_eq!(svec.get_len(), 12);
+
+        assert!(matches!(
+            SVec::try_new(Shape::new(2, 2, Some(3)), ImgData::U8(vec![0; 11])),
+            Err(Error::LengthMismatch { expected: 12, actual: 11 })
+        ));
+        assert!(matches!(
+            SVec::try_new(Shape::new(0, 2, None), ImgData::U8(vec![])),
+            Err(Error::ZeroDimension)
+        ));
+        assert!(matches!(Shape::try_new(usize::MAX, 2, None), Err(Error::Overflow)));
+    }
+
+    #[test]
+    fn test_shape_try_from() {
+        assert_eq!(Shape::try_from(vec![4, 5]).unwrap().get_shape(), (4, 5, None));
+        assert_eq!(Shape::try_from(&[4usize, 5, 3][..]).unwrap().get_shape(), (4, 5, Some(3)));
+        assert!(matches!(Shape::try_from(vec![4]), Err(Error::InvalidDimensions(1))));
+        assert!(matches!(Shape::try_from(vec![1, 2, 3, 4]), Err(Error::InvalidDimensions(4))));
+        assert!(matches!(Shape::try_from(vec![4, 5, 0]), Err(Error::ZeroDimension)));
+    }
+
     #[test]
     fn test_get_data_mut() {
         let shape = Shape::new(2, 2, Some(1));
@@ -0,0 +1,208 @@
+use crate::PixelType;
+use half::f16;
+
+/// Scalar sample type that can be stored in an [`SVec`](crate::SVec).
+///
+/// Unsigned integer types map their full range onto `0.0..=1.0`, `i16` maps onto `-1.0..=1.0`
+/// and float types are passed through unchanged. [`Pixel::to_f64`] and [`Pixel::from_f64`] keep the
+/// native range instead, for arithmetic on the raw sample values.
+pub trait Pixel: Copy + PartialOrd + Send + Sync + 'static {
+    const PIXEL_TYPE: PixelType;
+    const MIN_VALUE: Self;
+    const MAX_VALUE: Self;
+
+    fn to_f32_normalized(self) -> f32;
+    fn from_f32_normalized(value: f32) -> Self;
+
+    /// Double-precision variant of [`Pixel::to_f32_normalized`], lossless for `u32` and `f64`.
+    #[inline(always)]
+    fn to_f64_normalized(self) -> f64 {
+        self.to_f32_normalized() as f64
+    }
+    #[inline(always)]
+    fn from_f64_normalized(value: f64) -> Self {
+        Self::from_f32_normalized(value as f32)
+    }
+
+    /// Sample in its native range widened to `f64` (`u8` stays `0..=255`), lossless for every type.
+    fn to_f64(self) -> f64;
+    /// Narrow a native-range value back, rounding integers and saturating them at the type bounds.
+    fn from_f64(value: f64) -> Self;
+}
+
+impl Pixel for u8 {
+    const PIXEL_TYPE: PixelType = PixelType::U8;
+    const MIN_VALUE: Self = u8::MIN;
+    const MAX_VALUE: Self = u8::MAX;
+
+    #[inline(always)]
+    fn to_f32_normalized(self) -> f32 {
+        self as f32 / 255.0
+    }
+    #[inline(always)]
+    fn from_f32_normalized(value: f32) -> Self {
+        (value * 255.0).round().clamp(0.0, 255.0) as u8
+    }
+    #[inline(always)]
+    fn to_f64(self) -> f64 {
+        self as f64
+    }
+    #[inline(always)]
+    fn from_f64(value: f64) -> Self {
+        value.round() as u8
+    }
+}
+
+impl Pixel for u16 {
+    const PIXEL_TYPE: PixelType = PixelType::U16;
+    const MIN_VALUE: Self = u16::MIN;
+    const MAX_VALUE: Self = u16::MAX;
+
+    #[inline(always)]
+    fn to_f32_normalized(self) -> f32 {
+        self as f32 / 65535.0
+    }
+    #[inline(always)]
+    fn from_f32_normalized(value: f32) -> Self {
+        (value * 65535.0).round().clamp(0.0, 65535.0) as u16
+    }
+    #[inline(always)]
+    fn to_f64(self) -> f64 {
+        self as f64
+    }
+    #[inline(always)]
+    fn from_f64(value: f64) -> Self {
+        value.round() as u16
+    }
+}
+
+impl Pixel for f32 {
+    const PIXEL_TYPE: PixelType = PixelType::F32;
+    const MIN_VALUE: Self = 0.0;
+    const MAX_VALUE: Self = 1.0;
+
+    #[inline(always)]
+    fn to_f32_normalized(self) -> f32 {
+        self
+    }
+    #[inline(always)]
+    fn from_f32_normalized(value: f32) -> Self {
+        value
+    }
+    #[inline(always)]
+    fn to_f64(self) -> f64 {
+        self as f64
+    }
+    #[inline(always)]
+    fn from_f64(value: f64) -> Self {
+        value as f32
+    }
+}
+
+impl Pixel for i16 {
+    const PIXEL_TYPE: PixelType = PixelType::I16;
+    const MIN_VALUE: Self = i16::MIN;
+    const MAX_VALUE: Self = i16::MAX;
+
+    #[inline(always)]
+    fn to_f32_normalized(self) -> f32 {
+        (self as f32 / 32767.0).max(-1.0)
+    }
+    #[inline(always)]
+    fn from_f32_normalized(value: f32) -> Self {
+        (value * 32767.0).round().clamp(-32767.0, 32767.0) as i16
+    }
+    #[inline(always)]
+    fn to_f64(self) -> f64 {
+        self as f64
+    }
+    #[inline(always)]
+    fn from_f64(value: f64) -> Self {
+        value.round() as i16
+    }
+}
+
+impl Pixel for u32 {
+    const PIXEL_TYPE: PixelType = PixelType::U32;
+    const MIN_VALUE: Self = u32::MIN;
+    const MAX_VALUE: Self = u32::MAX;
+
+    #[inline(always)]
+    fn to_f32_normalized(self) -> f32 {
+        self.to_f64_normalized() as f32
+    }
+    #[inline(always)]
+    fn from_f32_normalized(value: f32) -> Self {
+        Self::from_f64_normalized(value as f64)
+    }
+    #[inline(always)]
+    fn to_f64_normalized(self) -> f64 {
+        self as f64 / 4294967295.0
+    }
+    #[inline(always)]
+    fn from_f64_normalized(value: f64) -> Self {
+        (value * 4294967295.0).round().clamp(0.0, 4294967295.0) as u32
+    }
+    #[inline(always)]
+    fn to_f64(self) -> f64 {
+        self as f64
+    }
+    #[inline(always)]
+    fn from_f64(value: f64) -> Self {
+        value.round() as u32
+    }
+}
+
+impl Pixel for f16 {
+    const PIXEL_TYPE: PixelType = PixelType::F16;
+    const MIN_VALUE: Self = f16::ZERO;
+    const MAX_VALUE: Self = f16::ONE;
+
+    #[inline(always)]
+    fn to_f32_normalized(self) -> f32 {
+        self.to_f32()
+    }
+    #[inline(always)]
+    fn from_f32_normalized(value: f32) -> Self {
+        f16::from_f32(value)
+    }
+    #[inline(always)]
+    fn to_f64(self) -> f64 {
+        f16::to_f64(self)
+    }
+    #[inline(always)]
+    fn from_f64(value: f64) -> Self {
+        f16::from_f64(value)
+    }
+}
+
+impl Pixel for f64 {
+    const PIXEL_TYPE: PixelType = PixelType::F64;
+    const MIN_VALUE: Self = 0.0;
+    const MAX_VALUE: Self = 1.0;
+
+    #[inline(always)]
+    fn to_f32_normalized(self) -> f32 {
+        self as f32
+    }
+    #[inline(always)]
+    fn from_f32_normalized(value: f32) -> Self {
+        value as f64
+    }
+    #[inline(always)]
+    fn to_f64_normalized(self) -> f64 {
+        self
+    }
+    #[inline(always)]
+    fn from_f64_normalized(value: f64) -> Self {
+        value
+    }
+    #[inline(always)]
+    fn to_f64(self) -> f64 {
+        self
+    }
+    #[inline(always)]
+    fn from_f64(value: f64) -> Self {
+        value
+    }
+}
@@ -1,3 +1,6 @@
 pub mod f32_to;
+pub mod normalized;
 pub mod u16_to;
 pub mod u8_to;
+
+pub use normalized::convert_normalized;
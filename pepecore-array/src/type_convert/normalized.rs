@@ -0,0 +1,99 @@
+use crate::{ImgData, Pixel, SVec, f16};
+
+/// Convert every sample through the normalized range of [`Pixel`].
+pub fn convert_slice_normalized<S: Pixel, D: Pixel>(input: &[S]) -> Vec<D> {
+    input.iter().map(|&v| D::from_f64_normalized(v.to_f64_normalized())).collect()
+}
+
+/// Convert `input` to the pixel type `D`, whatever its current type is.
+///
+/// Does nothing if the data already has type `D`.
+pub fn convert_normalized<D: Pixel>(input: &mut SVec)
+where
+    ImgData: From<Vec<D>>,
+{
+    let out: Vec<D> = match &input.data {
+        _ if input.pixel_type() == D::PIXEL_TYPE => return,
+        ImgData::U8(data) => convert_slice_normalized(data),
+        ImgData::U16(data) => convert_slice_normalized(data),
+        ImgData::F32(data) => convert_slice_normalized(data),
+        ImgData::I16(data) => convert_slice_normalized(data),
+        ImgData::U32(data) => convert_slice_normalized(data),
+        ImgData::F16(data) => convert_slice_normalized(data),
+        ImgData::F64(data) => convert_slice_normalized(data),
+    };
+    input.data = ImgData::from(out);
+}
+
+pub fn convert_i16_to_f32_normalized(input: &mut SVec) {
+    input.data = ImgData::F32(convert_slice_normalized(input.get_data::<i16>().unwrap()));
+}
+
+pub fn convert_u32_to_f32_normalized(input: &mut SVec) {
+    input.data = ImgData::F32(convert_slice_normalized(input.get_data::<u32>().unwrap()));
+}
+
+pub fn convert_f16_to_f32_normalized(input: &mut SVec) {
+    input.data = ImgData::F32(convert_slice_normalized(input.get_data::<f16>().unwrap()));
+}
+
+pub fn convert_f64_to_f32_normalized(input: &mut SVec) {
+    input.data = ImgData::F32(convert_slice_normalized(input.get_data::<f64>().unwrap()));
+}
+
+pub fn convert_f32_to_i16_normalized(input: &mut SVec) {
+    input.data = ImgData::I16(convert_slice_normalized(input.get_data::<f32>().unwrap()));
+}
+
+pub fn convert_f32_to_u32_normalized(input: &mut SVec) {
+    input.data = ImgData::U32(convert_slice_normalized(input.get_data::<f32>().unwrap()));
+}
+
+pub fn convert_f32_to_f16_normalized(input: &mut SVec) {
+    input.data = ImgData::F16(convert_slice_normalized(input.get_data::<f32>().unwrap()));
+}
+
+pub fn convert_f32_to_f64_normalized(input: &mut SVec) {
+    input.data = ImgData::F64(convert_slice_normalized(input.get_data::<f32>().unwrap()));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Shape;
+
+    #[test]
+    fn i16_roundtrip_test() {
+        let mut img = SVec::new(Shape::new(2, 2, None), ImgData::I16(vec![-32768, -32767, 0, 32767]));
+        convert_i16_to_f32_normalized(&mut img);
+        assert_eq!(img.get_data::<f32>().unwrap().to_vec(), vec![-1.0, -1.0, 0.0, 1.0]);
+        convert_f32_to_i16_normalized(&mut img);
+        assert_eq!(img.get_data::<i16>().unwrap().to_vec(), vec![-32767, -32767, 0, 32767]);
+    }
+
+    #[test]
+    fn u32_to_u8_test() {
+        let mut img = SVec::new(Shape::new(1, 3, None), ImgData::U32(vec![0, u32::MAX / 2, u32::MAX]));
+        convert_normalized::<u8>(&mut img);
+        assert_eq!(img.get_data::<u8>().unwrap().to_vec(), vec![0, 128, 255]);
+    }
+
+    #[test]
+    fn u8_to_f64_test() {
+        let mut img = SVec::new(Shape::new(1, 2, None), ImgData::U8(vec![0, 255]));
+        convert_normalized::<f64>(&mut img);
+        assert_eq!(img.get_data::<f64>().unwrap().to_vec(), vec![0.0, 1.0]);
+        convert_normalized::<u32>(&mut img);
+        assert_eq!(img.get_data::<u32>().unwrap().to_vec(), vec![0, u32::MAX]);
+    }
+
+    #[test]
+    fn f16_to_f32_test() {
+        let mut img = SVec::new(
+            Shape::new(1, 3, None),
+            ImgData::F16(vec![f16::ZERO, f16::from_f32(0.5), f16::ONE]),
+        );
+        convert_f16_to_f32_normalized(&mut img);
+        assert_eq!(img.get_data::<f32>().unwrap().to_vec(), vec![0.0, 0.5, 1.0]);
+    }
+}
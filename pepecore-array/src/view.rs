@@ -0,0 +1,580 @@
+//! Borrowed, strided views over `SVec` data.
+//!
+//! A view addresses a rectangular region (and optionally a single channel) of an image through an
+//! element offset and per-axis strides. Crops, channels, row ranges and tiles can therefore be read
+//! or modified in place without copying the underlying buffer.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use pepecore_array::{ImgData, SVec, Shape};
+//!
+//! let mut img = SVec::new(Shape::new(4, 4, Some(3)), ImgData::U8(vec![0; 48]));
+//! {
+//!     // Green channel of the 2x2 block in the bottom-right corner
+//!     let mut green = img.view_mut().crop(2, 2, 2, 2).unwrap().channel(1).unwrap();
+//!     let strides = green.strides();
+//!     let data = green.get_data_mut::<u8>().unwrap();
+//!     data[strides.index(0, 0, 0)] = 255;
+//! }
+//! assert_eq!(img.get_data::<u8>().unwrap()[(2 * 4 + 2) * 3 + 1], 255);
+//! ```
+use crate::error::Error;
+use crate::{ImgData, Layout, PixelType, SVec, Shape, cast_slice, cast_slice_mut, f16};
+
+/// Element offset and strides (in elements, not bytes) describing how a view maps
+/// `(y, x, c)` coordinates onto its backing buffer.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Strides {
+    pub offset: usize,
+    pub row: usize,
+    pub col: usize,
+    pub channel: usize,
+}
+
+impl Strides {
+    /// Strides of a densely packed interleaved (HWC) image.
+    pub fn interleaved(width: usize, channels: usize) -> Self {
+        Self {
+            offset: 0,
+            row: width * channels,
+            col: channels,
+            channel: 1,
+        }
+    }
+    /// Strides of a densely packed planar (CHW) image.
+    pub fn planar(height: usize, width: usize) -> Self {
+        Self {
+            offset: 0,
+            row: width,
+            col: 1,
+            channel: height * width,
+        }
+    }
+    /// Strides of a densely packed image with the given shape, following its layout.
+    pub fn for_shape(shape: &Shape) -> Self {
+        let (h, w, c) = shape.get_shape();
+        match shape.get_layout() {
+            Layout::HWC => Self::interleaved(w, c.unwrap_or(1)),
+            Layout::CHW => Self::planar(h, w),
+        }
+    }
+    #[inline(always)]
+    pub fn index(&self, y: usize, x: usize, c: usize) -> usize {
+        self.offset + y * self.row + x * self.col + c * self.channel
+    }
+}
+
+#[derive(Clone, Copy)]
+pub enum ImgDataRef<'a> {
+    F32(&'a [f32]),
+    U8(&'a [u8]),
+    U16(&'a [u16]),
+    I16(&'a [i16]),
+    U32(&'a [u32]),
+    F16(&'a [f16]),
+    F64(&'a [f64]),
+}
+
+pub enum ImgDataMut<'a> {
+    F32(&'a mut [f32]),
+    U8(&'a mut [u8]),
+    U16(&'a mut [u16]),
+    I16(&'a mut [i16]),
+    U32(&'a mut [u32]),
+    F16(&'a mut [f16]),
+    F64(&'a mut [f64]),
+}
+
+impl ImgDataRef<'_> {
+    pub fn pixel_type(&self) -> PixelType {
+        match self {
+            ImgDataRef::U8(_) => PixelType::U8,
+            ImgDataRef::U16(_) => PixelType::U16,
+            ImgDataRef::I16(_) => PixelType::I16,
+            ImgDataRef::U32(_) => PixelType::U32,
+            ImgDataRef::F16(_) => PixelType::F16,
+            ImgDataRef::F64(_) => PixelType::F64,
+            ImgDataRef::F32(_) => PixelType::F32,
+        }
+    }
+}
+
+impl ImgDataMut<'_> {
+    pub fn pixel_type(&self) -> PixelType {
+        match self {
+            ImgDataMut::U8(_) => PixelType::U8,
+            ImgDataMut::U16(_) => PixelType::U16,
+            ImgDataMut::I16(_) => PixelType::I16,
+            ImgDataMut::U32(_) => PixelType::U32,
+            ImgDataMut::F16(_) => PixelType::F16,
+            ImgDataMut::F64(_) => PixelType::F64,
+            ImgDataMut::F32(_) => PixelType::F32,
+        }
+    }
+    fn as_ref(&self) -> ImgDataRef<'_> {
+        match self {
+            ImgDataMut::U8(data) => ImgDataRef::U8(data),
+            ImgDataMut::U16(data) => ImgDataRef::U16(data),
+            ImgDataMut::I16(data) => ImgDataRef::I16(data),
+            ImgDataMut::U32(data) => ImgDataRef::U32(data),
+            ImgDataMut::F16(data) => ImgDataRef::F16(data),
+            ImgDataMut::F64(data) => ImgDataRef::F64(data),
+            ImgDataMut::F32(data) => ImgDataRef::F32(data),
+        }
+    }
+    fn reborrow(&mut self) -> ImgDataMut<'_> {
+        match self {
+            ImgDataMut::U8(data) => ImgDataMut::U8(data),
+            ImgDataMut::U16(data) => ImgDataMut::U16(data),
+            ImgDataMut::I16(data) => ImgDataMut::I16(data),
+            ImgDataMut::U32(data) => ImgDataMut::U32(data),
+            ImgDataMut::F16(data) => ImgDataMut::F16(data),
+            ImgDataMut::F64(data) => ImgDataMut::F64(data),
+            ImgDataMut::F32(data) => ImgDataMut::F32(data),
+        }
+    }
+}
+
+/// Read-only strided view into an image buffer.
+#[derive(Clone, Copy)]
+pub struct SVecView<'a> {
+    shape: Shape,
+    strides: Strides,
+    data: ImgDataRef<'a>,
+}
+
+/// Mutable strided view into an image buffer.
+///
+/// Sub-views (`crop`, `channel`, `rows`) consume the view; use [`SVecViewMut::reborrow`] to keep
+/// the parent around.
+pub struct SVecViewMut<'a> {
+    shape: Shape,
+    strides: Strides,
+    data: ImgDataMut<'a>,
+}
+
+fn crop_region(shape: &Shape, strides: &Strides, x: usize, y: usize, w: usize, h: usize) -> Result<(Shape, Strides), Error> {
+    let (img_h, img_w, c) = shape.get_shape();
+    let x_end = x.checked_add(w).ok_or(Error::OutOfBounds)?;
+    let y_end = y.checked_add(h).ok_or(Error::OutOfBounds)?;
+    if x_end > img_w || y_end > img_h {
+        return Err(Error::OutOfBounds);
+    }
+    if w == 0 || h == 0 {
+        return Err(Error::ZeroDimension);
+    }
+    let strides = Strides {
+        offset: strides.index(y, x, 0),
+        ..*strides
+    };
+    Ok((Shape::new(h, w, c).with_layout(shape.get_layout()), strides))
+}
+
+fn channel_region(shape: &Shape, strides: &Strides, channel: usize) -> Result<(Shape, Strides), Error> {
+    let (h, w, c) = shape.get_shape();
+    if channel >= c.unwrap_or(1) {
+        return Err(Error::OutOfBounds);
+    }
+    let strides = Strides {
+        offset: strides.index(0, 0, channel),
+        ..*strides
+    };
+    Ok((Shape::new(h, w, None), strides))
+}
+
+fn is_contiguous(shape: &Shape, strides: &Strides) -> bool {
+    let (h, w, c) = shape.get_shape();
+    let c = c.unwrap_or(1);
+    let interleaved = (c == 1 || strides.channel == 1) && (w == 1 || strides.col == c) && (h == 1 || strides.row == w * c);
+    let planar = (w == 1 || strides.col == 1) && (h == 1 || strides.row == w) && (c == 1 || strides.channel == h * w);
+    interleaved || planar
+}
+
+fn gather<T: Copy>(data: &[T], shape: &Shape, strides: &Strides) -> Vec<T> {
+    let (h, w, c) = shape.get_shape();
+    let c = c.unwrap_or(1);
+    let mut out = Vec::with_capacity(h * w * c);
+    if shape.get_layout() == Layout::CHW && c > 1 {
+        for ch in 0..c {
+            for y in 0..h {
+                if w == 1 || strides.col == 1 {
+                    let start = strides.index(y, 0, ch);
+                    out.extend_from_slice(&data[start..start + w]);
+                } else {
+                    out.extend((0..w).map(|x| data[strides.index(y, x, ch)]));
+                }
+            }
+        }
+    } else if (c == 1 || strides.channel == 1) && (w == 1 || strides.col == c) {
+        for y in 0..h {
+            let start = strides.index(y, 0, 0);
+            out.extend_from_slice(&data[start..start + w * c]);
+        }
+    } else {
+        for y in 0..h {
+            for x in 0..w {
+                for ch in 0..c {
+                    out.push(data[strides.index(y, x, ch)]);
+                }
+            }
+        }
+    }
+    out
+}
+
+fn scatter<T: Copy>(dst: &mut [T], dst_strides: &Strides, src: &[T], src_strides: &Strides, shape: &Shape) {
+    let (h, w, c) = shape.get_shape();
+    let c = c.unwrap_or(1);
+    for y in 0..h {
+        for x in 0..w {
+            for ch in 0..c {
+                dst[dst_strides.index(y, x, ch)] = src[src_strides.index(y, x, ch)];
+            }
+        }
+    }
+}
+
+impl<'a> SVecView<'a> {
+    pub fn shape(&self) -> (usize, usize, Option<usize>) {
+        self.shape.get_shape()
+    }
+    pub fn get_shape(&self) -> &Shape {
+        &self.shape
+    }
+    pub fn strides(&self) -> Strides {
+        self.strides
+    }
+    pub fn pixel_type(&self) -> PixelType {
+        self.data.pixel_type()
+    }
+    pub fn get_len(&self) -> usize {
+        let (h, w, c) = self.shape();
+        h * w * c.unwrap_or(1)
+    }
+    /// `true` if the view covers one dense interleaved block of its buffer.
+    pub fn is_contiguous(&self) -> bool {
+        is_contiguous(&self.shape, &self.strides)
+    }
+    /// Backing buffer of the view; index it with [`Strides::index`].
+    pub fn get_data<T: 'static>(&self) -> Result<&'a [T], Error> {
+        match self.data {
+            ImgDataRef::U8(data) => cast_slice(data),
+            ImgDataRef::U16(data) => cast_slice(data),
+            ImgDataRef::F32(data) => cast_slice(data),
+            ImgDataRef::I16(data) => cast_slice(data),
+            ImgDataRef::U32(data) => cast_slice(data),
+            ImgDataRef::F16(data) => cast_slice(data),
+            ImgDataRef::F64(data) => cast_slice(data),
+        }
+    }
+    /// Rectangular region `w`x`h` with top-left corner at (`x`, `y`).
+    pub fn crop(&self, x: usize, y: usize, w: usize, h: usize) -> Result<SVecView<'a>, Error> {
+        let (shape, strides) = crop_region(&self.shape, &self.strides, x, y, w, h)?;
+        Ok(SVecView {
+            shape,
+            strides,
+            data: self.data,
+        })
+    }
+    /// Full-width band of `h` rows starting at row `y`.
+    pub fn rows(&self, y: usize, h: usize) -> Result<SVecView<'a>, Error> {
+        self.crop(0, y, self.shape.get_width(), h)
+    }
+    /// Single-channel view of channel `channel`.
+    pub fn channel(&self, channel: usize) -> Result<SVecView<'a>, Error> {
+        let (shape, strides) = channel_region(&self.shape, &self.strides, channel)?;
+        Ok(SVecView {
+            shape,
+            strides,
+            data: self.data,
+        })
+    }
+    /// Copy the viewed pixels into a new, densely packed `SVec` with the layout of the view's shape.
+    pub fn to_svec(&self) -> SVec {
+        self.to_svec_with_layout(self.shape.get_layout())
+    }
+    /// Copy the viewed pixels into a new, densely packed `SVec` with the given layout.
+    pub fn to_svec_with_layout(&self, layout: Layout) -> SVec {
+        let shape = self.shape.with_layout(layout);
+        let data = match self.data {
+            ImgDataRef::U8(data) => ImgData::U8(gather(data, &shape, &self.strides)),
+            ImgDataRef::U16(data) => ImgData::U16(gather(data, &shape, &self.strides)),
+            ImgDataRef::I16(data) => ImgData::I16(gather(data, &shape, &self.strides)),
+            ImgDataRef::U32(data) => ImgData::U32(gather(data, &shape, &self.strides)),
+            ImgDataRef::F16(data) => ImgData::F16(gather(data, &shape, &self.strides)),
+            ImgDataRef::F64(data) => ImgData::F64(gather(data, &shape, &self.strides)),
+            ImgDataRef::F32(data) => ImgData::F32(gather(data, &shape, &self.strides)),
+        };
+        SVec::new(shape, data)
+    }
+}
+
+impl<'a> SVecViewMut<'a> {
+    pub fn shape(&self) -> (usize, usize, Option<usize>) {
+        self.shape.get_shape()
+    }
+    pub fn get_shape(&self) -> &Shape {
+        &self.shape
+    }
+    pub fn strides(&self) -> Strides {
+        self.strides
+    }
+    pub fn pixel_type(&self) -> PixelType {
+        self.data.pixel_type()
+    }
+    pub fn get_len(&self) -> usize {
+        let (h, w, c) = self.shape();
+        h * w * c.unwrap_or(1)
+    }
+    /// `true` if the view covers one dense interleaved block of its buffer.
+    pub fn is_contiguous(&self) -> bool {
+        is_contiguous(&self.shape, &self.strides)
+    }
+    /// Read-only view of the same region.
+    pub fn as_view(&self) -> SVecView<'_> {
+        SVecView {
+            shape: self.shape,
+            strides: self.strides,
+            data: self.data.as_ref(),
+        }
+    }
+    /// Shorter-lived mutable view of the same region, leaving `self` usable afterwards.
+    pub fn reborrow(&mut self) -> SVecViewMut<'_> {
+        SVecViewMut {
+            shape: self.shape,
+            strides: self.strides,
+            data: self.data.reborrow(),
+        }
+    }
+    /// Backing buffer of the view; index it with [`Strides::index`].
+    pub fn get_data<T: 'static>(&self) -> Result<&[T], Error> {
+        self.as_view().get_data::<T>()
+    }
+    /// Mutable backing buffer of the view; index it with [`Strides::index`].
+    pub fn get_data_mut<T: 'static>(&mut self) -> Result<&mut [T], Error> {
+        match &mut self.data {
+            ImgDataMut::U8(data) => cast_slice_mut(data),
+            ImgDataMut::U16(data) => cast_slice_mut(data),
+            ImgDataMut::F32(data) => cast_slice_mut(data),
+            ImgDataMut::I16(data) => cast_slice_mut(data),
+            ImgDataMut::U32(data) => cast_slice_mut(data),
+            ImgDataMut::F16(data) => cast_slice_mut(data),
+            ImgDataMut::F64(data) => cast_slice_mut(data),
+        }
+    }
+    /// Rectangular region `w`x`h` with top-left corner at (`x`, `y`).
+    pub fn crop(self, x: usize, y: usize, w: usize, h: usize) -> Result<SVecViewMut<'a>, Error> {
+        let (shape, strides) = crop_region(&self.shape, &self.strides, x, y, w, h)?;
+        Ok(SVecViewMut {
+            shape,
+            strides,
+            data: self.data,
+        })
+    }
+    /// Full-width band of `h` rows starting at row `y`.
+    pub fn rows(self, y: usize, h: usize) -> Result<SVecViewMut<'a>, Error> {
+        let w = self.shape.get_width();
+        self.crop(0, y, w, h)
+    }
+    /// Single-channel view of channel `channel`.
+    pub fn channel(self, channel: usize) -> Result<SVecViewMut<'a>, Error> {
+        let (shape, strides) = channel_region(&self.shape, &self.strides, channel)?;
+        Ok(SVecViewMut {
+            shape,
+            strides,
+            data: self.data,
+        })
+    }
+    /// Overwrite the viewed pixels with `src`, which must have the same shape and pixel type.
+    pub fn copy_from(&mut self, src: &SVecView) -> Result<(), Error> {
+        if self.shape() != src.shape() {
+            return Err(Error::ShapeMismatch {
+                expected: self.shape(),
+                actual: src.shape(),
+            });
+        }
+        let (dst_strides, src_strides, shape) = (self.strides, src.strides, self.shape);
+        match (&mut self.data, src.data) {
+            (ImgDataMut::U8(dst), ImgDataRef::U8(data)) => scatter(dst, &dst_strides, data, &src_strides, &shape),
+            (ImgDataMut::U16(dst), ImgDataRef::U16(data)) => scatter(dst, &dst_strides, data, &src_strides, &shape),
+            (ImgDataMut::I16(dst), ImgDataRef::I16(data)) => scatter(dst, &dst_strides, data, &src_strides, &shape),
+            (ImgDataMut::U32(dst), ImgDataRef::U32(data)) => scatter(dst, &dst_strides, data, &src_strides, &shape),
+            (ImgDataMut::F16(dst), ImgDataRef::F16(data)) => scatter(dst, &dst_strides, data, &src_strides, &shape),
+            (ImgDataMut::F64(dst), ImgDataRef::F64(data)) => scatter(dst, &dst_strides, data, &src_strides, &shape),
+            (ImgDataMut::F32(dst), ImgDataRef::F32(data)) => scatter(dst, &dst_strides, data, &src_strides, &shape),
+            (dst, src) => {
+                return Err(Error::TypeMismatch {
+                    expected: dst.pixel_type().type_name(),
+                    actual: src.pixel_type().type_name(),
+                });
+            }
+        }
+        Ok(())
+    }
+    /// Copy the viewed pixels into a new, densely packed `SVec` with the layout of the view's shape.
+    pub fn to_svec(&self) -> SVec {
+        self.as_view().to_svec()
+    }
+    /// Copy the viewed pixels into a new, densely packed `SVec` with the given layout.
+    pub fn to_svec_with_layout(&self, layout: Layout) -> SVec {
+        self.as_view().to_svec_with_layout(layout)
+    }
+}
+
+impl SVec {
+    /// Read-only view over the whole image.
+    pub fn view(&self) -> SVecView<'_> {
+        let data = match &self.data {
+            ImgData::U8(data) => ImgDataRef::U8(data),
+            ImgData::U16(data) => ImgDataRef::U16(data),
+            ImgData::I16(data) => ImgDataRef::I16(data),
+            ImgData::U32(data) => ImgDataRef::U32(data),
+            ImgData::F16(data) => ImgDataRef::F16(data),
+            ImgData::F64(data) => ImgDataRef::F64(data),
+            ImgData::F32(data) => ImgDataRef::F32(data),
+        };
+        SVecView {
+            shape: self.shape,
+            strides: Strides::for_shape(&self.shape),
+            data,
+        }
+    }
+    /// Reorder the data into planar (CHW) layout. Does nothing if it already is planar.
+    pub fn to_planar(&mut self) {
+        self.reorder(Layout::CHW)
+    }
+    /// Reorder the data into interleaved (HWC) layout. Does nothing if it already is interleaved.
+    pub fn to_interleaved(&mut self) {
+        self.reorder(Layout::HWC)
+    }
+    fn reorder(&mut self, layout: Layout) {
+        if self.shape.get_layout() == layout {
+            return;
+        }
+        if self.shape.get_channels().unwrap_or(1) == 1 {
+            // A single plane is stored the same way in both layouts
+            self.shape = self.shape.with_layout(layout);
+        } else {
+            *self = self.view().to_svec_with_layout(layout);
+        }
+    }
+    /// Run `f` on the image in interleaved layout and restore the planar layout afterwards.
+    ///
+    /// Ops that index the buffer as HWC go through this so they accept CHW images transparently.
+    pub fn with_interleaved<R>(&mut self, f: impl FnOnce(&mut SVec) -> R) -> R {
+        let planar = self.shape.get_layout() == Layout::CHW;
+        self.to_interleaved();
+        let result = f(self);
+        if planar {
+            self.to_planar();
+        }
+        result
+    }
+    /// Mutable view over the whole image.
+    pub fn view_mut(&mut self) -> SVecViewMut<'_> {
+        let data = match &mut self.data {
+            ImgData::U8(data) => ImgDataMut::U8(data),
+            ImgData::U16(data) => ImgDataMut::U16(data),
+            ImgData::I16(data) => ImgDataMut::I16(data),
+            ImgData::U32(data) => ImgDataMut::U32(data),
+            ImgData::F16(data) => ImgDataMut::F16(data),
+            ImgData::F64(data) => ImgDataMut::F64(data),
+            ImgData::F32(data) => ImgDataMut::F32(data),
+        };
+        SVecViewMut {
+            shape: self.shape,
+            strides: Strides::for_shape(&self.shape),
+            data,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ramp(h: usize, w: usize, c: usize) -> SVec {
+        SVec::new(Shape::new(h, w, Some(c)), ImgData::U8((0..(h * w * c) as u8).collect()))
+    }
+
+    #[test]
+    fn crop_view_matches_copy() {
+        let img = ramp(4, 5, 3);
+        let view = img.view().crop(1, 2, 3, 2).unwrap();
+        assert_eq!(view.shape(), (2, 3, Some(3)));
+        assert!(!view.is_contiguous());
+        let tile = view.to_svec();
+        assert_eq!(
+            tile.get_data::<u8>().unwrap(),
+            &[33, 34, 35, 36, 37, 38, 39, 40, 41, 48, 49, 50, 51, 52, 53, 54, 55, 56]
+        );
+    }
+
+    #[test]
+    fn channel_view_mut_writes_in_place() {
+        let mut img = ramp(2, 2, 3);
+        {
+            let mut blue = img.view_mut().channel(2).unwrap();
+            let strides = blue.strides();
+            let data = blue.get_data_mut::<u8>().unwrap();
+            for y in 0..2 {
+                for x in 0..2 {
+                    data[strides.index(y, x, 0)] = 0;
+                }
+            }
+        }
+        assert_eq!(img.get_data::<u8>().unwrap(), &[0, 1, 0, 3, 4, 0, 6, 7, 0, 9, 10, 0]);
+    }
+
+    #[test]
+    fn nested_views_and_bounds() {
+        let img = ramp(6, 6, 1);
+        let rows = img.view().rows(2, 3).unwrap();
+        let inner = rows.crop(1, 1, 2, 2).unwrap();
+        assert_eq!(inner.to_svec().get_data::<u8>().unwrap(), &[19, 20, 25, 26]);
+        assert!(matches!(rows.crop(5, 0, 2, 1), Err(Error::OutOfBounds)));
+        assert!(matches!(rows.channel(1), Err(Error::OutOfBounds)));
+    }
+
+    #[test]
+    fn copy_from_view() {
+        let src = ramp(2, 2, 1);
+        let mut dst = SVec::new(Shape::new(3, 3, Some(1)), ImgData::U8(vec![0; 9]));
+        dst.view_mut().crop(1, 1, 2, 2).unwrap().copy_from(&src.view()).unwrap();
+        assert_eq!(dst.get_data::<u8>().unwrap(), &[0, 0, 0, 0, 0, 1, 0, 2, 3]);
+        assert!(matches!(
+            dst.view_mut().copy_from(&src.view()),
+            Err(Error::ShapeMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn planar_roundtrip_and_views() {
+        let mut img = ramp(2, 2, 3);
+        img.to_planar();
+        assert_eq!(img.shape.get_layout(), Layout::CHW);
+        assert_eq!(img.get_data::<u8>().unwrap(), &[0, 3, 6, 9, 1, 4, 7, 10, 2, 5, 8, 11]);
+
+        let view = img.view();
+        assert!(view.is_contiguous());
+        assert_eq!(view.get_data::<u8>().unwrap()[view.strides().index(1, 0, 2)], 8);
+        let green = view.channel(1).unwrap().to_svec();
+        assert_eq!(green.get_data::<u8>().unwrap(), &[1, 4, 7, 10]);
+        let column = view.crop(1, 0, 1, 2).unwrap().to_svec_with_layout(Layout::HWC);
+        assert_eq!(column.get_data::<u8>().unwrap(), &[3, 4, 5, 9, 10, 11]);
+
+        img.to_interleaved();
+        assert_eq!(img.get_data::<u8>().unwrap(), ramp(2, 2, 3).get_data::<u8>().unwrap());
+    }
+
+    #[test]
+    fn with_interleaved_restores_layout() {
+        let mut img = ramp(1, 2, 2);
+        img.to_planar();
+        let first = img.with_interleaved(|img| img.get_data::<u8>().unwrap()[..2].to_vec());
+        assert_eq!(first, vec![0, 1]);
+        assert_eq!(img.shape.get_layout(), Layout::CHW);
+        assert_eq!(img.get_data::<u8>().unwrap(), &[0, 2, 1, 3]);
+    }
+}
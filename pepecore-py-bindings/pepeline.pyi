@@ -21,6 +21,17 @@ class ImgFormat(IntEnum):
 
     def __reduce__(self): ...
 
+class ImageFormat(IntEnum):
+    PNG = 0
+    JPEG = 1
+    WEBP = 2
+    TIFF = 3
+    JXL = 4
+    EXR = 5
+    GIF = 6
+
+    def __reduce__(self): ...
+
 class DotType(IntEnum):
     CIRCLE = 0
     CROSS = 1
@@ -30,6 +41,46 @@ class DotType(IntEnum):
 
     def __reduce__(self): ...
 
+class DescreenMode(IntEnum):
+    SMOOTH = 0
+    PRESERVE_LINES = 1
+
+    def __reduce__(self): ...
+
+class DitherKernel(IntEnum):
+    FLOYD_STEINBERG = 0
+    ATKINSON = 1
+    JARVIS_JUDICE_NINKE = 2
+    STUCKI = 3
+    SIERRA = 4
+    TWO_ROW_SIERRA = 5
+    SIERRA_LITE = 6
+
+    def __reduce__(self): ...
+
+class Interpolation(IntEnum):
+    NEAREST = 0
+    BILINEAR = 1
+    BICUBIC = 2
+    LANCZOS3 = 3
+
+    def __reduce__(self): ...
+
+class Border(IntEnum):
+    CONSTANT = 0
+    REPLICATE = 1
+    REFLECT = 2
+    REFLECT_101 = 3
+    WRAP = 4
+
+    def __reduce__(self): ...
+
+class Feather(IntEnum):
+    LINEAR = 0
+    COSINE = 1
+
+    def __reduce__(self): ...
+
 class CVTColor(IntEnum):
     RGB2Gray_2020 = 0
     RGB2Gray_601 = 1
@@ -87,6 +138,19 @@ class ResizesFilter(Enum):
 
     def __reduce__(self): ...
 
+class Anchor(IntEnum):
+    TOP_LEFT = 0
+    TOP = 1
+    TOP_RIGHT = 2
+    LEFT = 3
+    CENTER = 4
+    RIGHT = 5
+    BOTTOM_LEFT = 6
+    BOTTOM = 7
+    BOTTOM_RIGHT = 8
+
+    def __reduce__(self): ...
+
 class ResizesAlg:
     @staticmethod
     def Nearest() -> ResizesAlg: ...
@@ -97,17 +161,65 @@ class ResizesAlg:
     @staticmethod
     def SuperSampling(filter: ResizesFilter, passes: int) -> ResizesAlg: ...
 
-def read(path: str | Path, color_mode: ImgColor = ..., img_format: ImgFormat = ...) -> np.ndarray: ...
-def buff_read(buffer: Union[bytes, bytearray, memoryview], color_mode: ImgColor = ..., img_format: ImgFormat = ...) -> np.ndarray: ...
+def read(path: str | Path, color_mode: ImgColor = ..., img_format: ImgFormat = ..., planar: bool = False) -> np.ndarray: ...
+def buff_read(
+    buffer: Union[bytes, bytearray, memoryview], color_mode: ImgColor = ..., img_format: ImgFormat = ..., planar: bool = False
+) -> np.ndarray: ...
+def buff_save(img: np.ndarray, img_format: ImageFormat = ..., quality: int | None = None) -> bytes: ...
 def save(img: np.ndarray, path: str | Path): ...
+def probe(path: str | Path) -> dict[str, str | int | bool | None]: ...
+def buff_probe(buffer: Union[bytes, bytearray, memoryview]) -> dict[str, str | int | bool | None]: ...
 def cvt_color(img: np.ndarray, cvt_mode: CVTColor): ...
 def crop(img: np.ndarray, x: int, y: int, w: int, h: int) -> np.ndarray: ...
+def flip(img: np.ndarray, horizontal: bool = True) -> np.ndarray: ...
+def transpose(img: np.ndarray) -> np.ndarray: ...
+def rot90(img: np.ndarray, k: int = 1) -> np.ndarray: ...
+def rotate(
+    img: np.ndarray, angle: float, interpolation: Interpolation = ..., expand: bool = False, fill: Sequence[float] | None = None
+) -> np.ndarray: ...
+def pad(
+    img: np.ndarray, top: int, bottom: int, left: int, right: int, border: Border = ..., fill: Sequence[float] | None = None
+) -> np.ndarray: ...
+def pad_to_multiple(
+    img: np.ndarray, n: int, border: Border = ..., fill: Sequence[float] | None = None
+) -> tuple[np.ndarray, tuple[int, int, int, int]]: ...
+def warp_affine(
+    img: np.ndarray,
+    matrix: Sequence[float],
+    out_shape: tuple[int, int],
+    interpolation: Interpolation = ...,
+    border: Border = ...,
+    fill: Sequence[float] | None = None,
+) -> np.ndarray: ...
+def warp_perspective(
+    img: np.ndarray,
+    homography: Sequence[float],
+    out_shape: tuple[int, int],
+    interpolation: Interpolation = ...,
+    border: Border = ...,
+    fill: Sequence[float] | None = None,
+) -> np.ndarray: ...
+def split_tiles(
+    img: np.ndarray, tile_size: int = 512, overlap: int = 32
+) -> list[tuple[tuple[int, int, int, int], np.ndarray]]: ...
+def merge_tiles(
+    tiles: Sequence[tuple[tuple[int, int, int, int], np.ndarray]],
+    shape: tuple[int, int],
+    tile_size: int = 512,
+    overlap: int = 32,
+    scale: int = 1,
+    feather: Feather = ...,
+) -> np.ndarray: ...
 def color_levels(
     img: np.ndarray, in_low: int | None = 0, in_high: int | None = 255, out_low: int | None = 0, out_high: int | None = 255, gamma: float | None = 1.0
 ) -> np.ndarray: ...
 def screentone(
     img: np.ndarray, dot_size: int, angle: int | None = 0, dot_type: DotType | None = ..., scale: float | None = None, resize_alg: ResizesAlg = ..., disable_auto_dot: bool | None = False,
 ) -> np.ndarray: ...
+def descreen(img: np.ndarray, mode: DescreenMode = ...) -> np.ndarray: ...
+def dither(
+    img: np.ndarray, levels: int = 2, kernel: DitherKernel = ..., serpentine: bool = True, palette: Sequence[float] | None = None
+) -> np.ndarray: ...
 def halftone(
     img: np.ndarray,
     dot_sizes: Sequence[int],
@@ -153,6 +265,14 @@ class PaletteAlg(IntEnum):
     MinMaxUniform=3
 def jpeg_encode(img: np.ndarray, quality: int = 100, qt: QuantizeTable=..., sampling_factor: JpegSamplingFactor = ...) -> np.ndarray: ...
 def resize(img: np.ndarray, h: int, w: int, resize_alg: ResizesAlg = ..., alpha: bool = True) -> np.ndarray: ...
+def resize_to_fit(img: np.ndarray, max_h: int, max_w: int, resize_alg: ResizesAlg = ..., alpha: bool = True) -> np.ndarray: ...
+def resize_to_fill(
+    img: np.ndarray, h: int, w: int, anchor: Anchor = ..., resize_alg: ResizesAlg = ..., alpha: bool = True
+) -> np.ndarray: ...
+def resize_scale(img: np.ndarray, factor: float, resize_alg: ResizesAlg = ..., alpha: bool = True) -> np.ndarray: ...
+def resize_side(
+    img: np.ndarray, size: int, long: bool = False, resize_alg: ResizesAlg = ..., alpha: bool = True
+) -> np.ndarray: ...
 def rayon_mode(on:bool = True) -> None:...
 def normalize(img: np.ndarray, scale: float) -> np.ndarray: ...
 def real_hw(img:np.ndarray)->tuple[int, int] :...
@@ -170,25 +290,51 @@ class Bezier:
 def line(lines:Sequence[Bresenham|Bezier],h:int,w:int) -> np.ndarray:...
 def read_tiler(path: str | Path, color_mode: ImgColor = ..., img_format: ImgFormat = ..., tile_size: int = 512) -> np.ndarray:...
 __all__ = [
+    'Anchor',
+    'Border',
     'CVTColor',
+    'DescreenMode',
+    'DitherKernel',
     'DotType',
+    'Feather',
+    'ImageFormat',
     'ImgColor',
     'ImgFormat',
+    'Interpolation',
     'ResizesAlg',
     'ResizesFilter',
     'TypeNoise',
     'best_tile',
+    'buff_probe',
     'buff_read',
+    'buff_save',
     'color_levels',
     'crop',
     'cvt_color',
+    'descreen',
+    'dither',
+    'flip',
     'halftone',
     'jpeg_encode',
+    'merge_tiles',
     'noise',
+    'pad',
+    'pad_to_multiple',
+    'probe',
     'read',
     'resize',
+    'resize_scale',
+    'resize_side',
+    'resize_to_fill',
+    'resize_to_fit',
+    'rot90',
+    'rotate',
     'save',
     'screentone',
+    'split_tiles',
+    'transpose',
+    'warp_affine',
+    'warp_perspective',
     'rayon_mode',
     'normalize',
     'real_hw',
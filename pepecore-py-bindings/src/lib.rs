@@ -2,7 +2,10 @@ mod ops;
 mod structure;
 
 use crate::ops::encode::QuantizeTablePy;
-use crate::structure::enums::{ColorCVT, ColorMode, DotTypePy, ImgFormat, ResizesAlg, ResizesFilter, TypeNoise};
+use crate::structure::enums::{
+    AnchorPy, BorderPy, ColorCVT, ColorMode, DescreenModePy, DitherKernelPy, DotTypePy, FeatherPy, ImageFormatPy, ImgFormat,
+    InterpolationPy, ResizesAlg, ResizesFilter, TypeNoise,
+};
 
 use crate::ops::encode::JpegSamplingFactorPy;
 use crate::ops::get_palette::PyPaletteAlg;
@@ -19,16 +22,35 @@ pub fn rm(on: bool) {
 fn pepeline(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_function(wrap_pyfunction!(ops::read_write::read, m)?)?;
     m.add_function(wrap_pyfunction!(ops::read_write::buff_read, m)?)?;
+    m.add_function(wrap_pyfunction!(ops::read_write::buff_save, m)?)?;
     m.add_function(wrap_pyfunction!(ops::read_write::save, m)?)?;
+    m.add_function(wrap_pyfunction!(ops::read_write::probe, m)?)?;
+    m.add_function(wrap_pyfunction!(ops::read_write::buff_probe, m)?)?;
     m.add_function(wrap_pyfunction!(ops::colors::py_cvt_color, m)?)?;
     m.add_function(wrap_pyfunction!(ops::crop::py_crop, m)?)?;
     m.add_function(wrap_pyfunction!(ops::colors::py_color_levels, m)?)?;
     m.add_function(wrap_pyfunction!(ops::colors::py_screentone, m)?)?;
     m.add_function(wrap_pyfunction!(ops::colors::py_halftone, m)?)?;
+    m.add_function(wrap_pyfunction!(ops::colors::py_descreen, m)?)?;
+    m.add_function(wrap_pyfunction!(ops::colors::py_dither, m)?)?;
+    m.add_function(wrap_pyfunction!(ops::geometry::py_flip, m)?)?;
+    m.add_function(wrap_pyfunction!(ops::geometry::py_transpose, m)?)?;
+    m.add_function(wrap_pyfunction!(ops::geometry::py_rot90, m)?)?;
+    m.add_function(wrap_pyfunction!(ops::geometry::py_rotate, m)?)?;
+    m.add_function(wrap_pyfunction!(ops::geometry::py_warp_affine, m)?)?;
+    m.add_function(wrap_pyfunction!(ops::geometry::py_warp_perspective, m)?)?;
+    m.add_function(wrap_pyfunction!(ops::geometry::py_pad, m)?)?;
+    m.add_function(wrap_pyfunction!(ops::geometry::py_pad_to_multiple, m)?)?;
+    m.add_function(wrap_pyfunction!(ops::tile::py_split_tiles, m)?)?;
+    m.add_function(wrap_pyfunction!(ops::tile::py_merge_tiles, m)?)?;
     m.add_function(wrap_pyfunction!(ops::old_rebind::best_tile, m)?)?;
     m.add_function(wrap_pyfunction!(ops::noise::py_noise, m)?)?;
     m.add_function(wrap_pyfunction!(ops::encode::py_jpeg_encode, m)?)?;
     m.add_function(wrap_pyfunction!(ops::resize::py_resize, m)?)?;
+    m.add_function(wrap_pyfunction!(ops::resize::py_resize_to_fit, m)?)?;
+    m.add_function(wrap_pyfunction!(ops::resize::py_resize_to_fill, m)?)?;
+    m.add_function(wrap_pyfunction!(ops::resize::py_resize_scale, m)?)?;
+    m.add_function(wrap_pyfunction!(ops::resize::py_resize_side, m)?)?;
     m.add_function(wrap_pyfunction!(rm, m)?)?;
     m.add_function(wrap_pyfunction!(ops::normalize::normalize, m)?)?;
     m.add_function(wrap_pyfunction!(ops::original_size::real_hw, m)?)?;
@@ -43,12 +65,19 @@ fn pepeline(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_class::<PyBezier>()?;
     m.add_class::<ColorMode>()?;
     m.add_class::<ImgFormat>()?;
+    m.add_class::<ImageFormatPy>()?;
     m.add_class::<ColorCVT>()?;
     m.add_class::<DotTypePy>()?;
+    m.add_class::<DescreenModePy>()?;
+    m.add_class::<DitherKernelPy>()?;
+    m.add_class::<InterpolationPy>()?;
+    m.add_class::<BorderPy>()?;
+    m.add_class::<FeatherPy>()?;
     m.add_class::<TypeNoise>()?;
     m.add_class::<JpegSamplingFactorPy>()?;
     m.add_class::<QuantizeTablePy>()?;
     m.add_class::<ResizesFilter>()?;
     m.add_class::<ResizesAlg>()?;
+    m.add_class::<AnchorPy>()?;
     Ok(())
 }
@@ -1,12 +1,14 @@
-use crate::structure::enums::{ColorCVT, DotTypePy, ResizesAlg, ResizesFilter};
+use crate::structure::enums::{ColorCVT, DescreenModePy, DitherKernelPy, DotTypePy, ResizesAlg, ResizesFilter};
 use crate::structure::svec_traits::{PySvec, SvecPyArray};
 use pepecore::cvt_color::cvt_color;
 use pepecore::enums::CVTColor;
+use pepecore::ops::svec_ops::halftone::descreen::descreen;
+use pepecore::{dither, dither_palette};
 use pepecore::{
     color_levels, halftone, rotate_halftone, rotate_screentone, screentone, ssaa_halftone, ssaa_rotate_halftone,
     ssaa_rotate_screentone, ssaa_screentone,
 };
-use pepecore_array::PixelType;
+use pepecore_array::{PixelType, f16};
 use pyo3::exceptions::PyValueError;
 use pyo3::{Bound, PyAny, PyResult, Python, pyfunction};
 
@@ -18,6 +20,10 @@ pub fn py_cvt_color<'py>(py: Python<'py>, img: Bound<'py, PyAny>, cvt_mode: Colo
         PixelType::U8 => img.to_pyany::<u8>(py)?,
         PixelType::F32 => img.to_pyany::<f32>(py)?,
         PixelType::U16 => img.to_pyany::<u16>(py)?,
+        PixelType::I16 => img.to_pyany::<i16>(py)?,
+        PixelType::U32 => img.to_pyany::<u32>(py)?,
+        PixelType::F16 => img.to_pyany::<f16>(py)?,
+        PixelType::F64 => img.to_pyany::<f64>(py)?,
     })
 }
 
@@ -46,7 +52,6 @@ pub fn py_color_levels<'py>(
                 gamma,
             );
             img.as_u8();
-            img.to_pyany::<u8>(py)
         }
         PixelType::U16 => {
             img.as_f32();
@@ -60,7 +65,6 @@ pub fn py_color_levels<'py>(
                 gamma,
             );
             img.as_u16();
-            img.to_pyany::<u16>(py)
         }
         PixelType::F32 => {
             let vec = img.get_mut_vec::<f32>().unwrap();
@@ -72,9 +76,25 @@ pub fn py_color_levels<'py>(
                 out_high as f32 / 255.0,
                 gamma,
             );
-            img.to_pyany::<f32>(py)
         }
+        _ => color_levels::color_levels_view(
+            &mut img.view_mut(),
+            in_low as f32 / 255.0,
+            in_high as f32 / 255.0,
+            out_low as f32 / 255.0,
+            out_high as f32 / 255.0,
+            gamma,
+        ),
     }
+    Ok(match img.pixel_type() {
+        PixelType::U8 => img.to_pyany::<u8>(py)?,
+        PixelType::F32 => img.to_pyany::<f32>(py)?,
+        PixelType::U16 => img.to_pyany::<u16>(py)?,
+        PixelType::I16 => img.to_pyany::<i16>(py)?,
+        PixelType::U32 => img.to_pyany::<u32>(py)?,
+        PixelType::F16 => img.to_pyany::<f16>(py)?,
+        PixelType::F64 => img.to_pyany::<f64>(py)?,
+    })
 }
 
 #[pyfunction(name = "screentone")]
@@ -97,22 +117,29 @@ pub fn py_screentone<'py>(
         ));
     }
 
-    if let Some(angle) = angle {
-        if let Some(scale) = scale {
-            py.detach(|| ssaa_rotate_screentone(&mut img, dot_size, angle, &dot_type.into(), scale, resize_alg.into(), disable_auto_dot));
+    py.detach(|| {
+        if let Some(angle) = angle {
+            if let Some(scale) = scale {
+                ssaa_rotate_screentone(&mut img, dot_size, angle, &dot_type.into(), scale, resize_alg.into(), disable_auto_dot)
+            } else {
+                rotate_screentone(&mut img, dot_size, angle, &dot_type.into())
+            }
+        } else if let Some(scale) = scale {
+            ssaa_screentone(&mut img, dot_size, &dot_type.into(), scale, resize_alg.into(), disable_auto_dot)
         } else {
-            py.detach(|| rotate_screentone(&mut img, dot_size, angle, &dot_type.into()));
+            screentone(&mut img, dot_size, &dot_type.into())
         }
-    } else if let Some(scale) = scale {
-        py.detach(|| ssaa_screentone(&mut img, dot_size, &dot_type.into(), scale, resize_alg.into(), disable_auto_dot));
-    } else {
-        py.detach(|| screentone(&mut img, dot_size, &dot_type.into()));
-    }
+    })
+    .map_err(|e| PyValueError::new_err(e.to_string()))?;
 
     Ok(match img.pixel_type() {
         PixelType::U8 => img.to_pyany::<u8>(py)?,
         PixelType::F32 => img.to_pyany::<f32>(py)?,
         PixelType::U16 => img.to_pyany::<u16>(py)?,
+        PixelType::I16 => img.to_pyany::<i16>(py)?,
+        PixelType::U32 => img.to_pyany::<u32>(py)?,
+        PixelType::F16 => img.to_pyany::<f16>(py)?,
+        PixelType::F64 => img.to_pyany::<f64>(py)?,
     })
 }
 
@@ -148,5 +175,52 @@ pub fn py_halftone<'py>(
         PixelType::U8 => img.to_pyany::<u8>(py)?,
         PixelType::F32 => img.to_pyany::<f32>(py)?,
         PixelType::U16 => img.to_pyany::<u16>(py)?,
+        PixelType::I16 => img.to_pyany::<i16>(py)?,
+        PixelType::U32 => img.to_pyany::<u32>(py)?,
+        PixelType::F16 => img.to_pyany::<f16>(py)?,
+        PixelType::F64 => img.to_pyany::<f64>(py)?,
+    })
+}
+
+#[pyfunction(name = "descreen")]
+#[pyo3(signature = (img, mode = DescreenModePy::PRESERVE_LINES))]
+pub fn py_descreen<'py>(py: Python<'py>, img: Bound<'py, PyAny>, mode: DescreenModePy) -> PyResult<Bound<'py, PyAny>> {
+    let mut img = img.to_svec(py)?;
+    py.detach(|| descreen(&mut img, mode.into())).map_err(|e| PyValueError::new_err(e.to_string()))?;
+    Ok(match img.pixel_type() {
+        PixelType::U8 => img.to_pyany::<u8>(py)?,
+        PixelType::F32 => img.to_pyany::<f32>(py)?,
+        PixelType::U16 => img.to_pyany::<u16>(py)?,
+        PixelType::I16 => img.to_pyany::<i16>(py)?,
+        PixelType::U32 => img.to_pyany::<u32>(py)?,
+        PixelType::F16 => img.to_pyany::<f16>(py)?,
+        PixelType::F64 => img.to_pyany::<f64>(py)?,
+    })
+}
+
+#[pyfunction(name = "dither")]
+#[pyo3(signature = (img, levels = 2, kernel = DitherKernelPy::FLOYD_STEINBERG, serpentine = true, palette = None))]
+pub fn py_dither<'py>(
+    py: Python<'py>,
+    img: Bound<'py, PyAny>,
+    levels: usize,
+    kernel: DitherKernelPy,
+    serpentine: bool,
+    palette: Option<Vec<f32>>,
+) -> PyResult<Bound<'py, PyAny>> {
+    let mut img = img.to_svec(py)?;
+    py.detach(|| match &palette {
+        Some(palette) => dither_palette(&mut img, palette, kernel.into(), serpentine),
+        None => dither(&mut img, levels, kernel.into(), serpentine),
+    })
+    .map_err(|e| PyValueError::new_err(e.to_string()))?;
+    Ok(match img.pixel_type() {
+        PixelType::U8 => img.to_pyany::<u8>(py)?,
+        PixelType::F32 => img.to_pyany::<f32>(py)?,
+        PixelType::U16 => img.to_pyany::<u16>(py)?,
+        PixelType::I16 => img.to_pyany::<i16>(py)?,
+        PixelType::U32 => img.to_pyany::<u32>(py)?,
+        PixelType::F16 => img.to_pyany::<f16>(py)?,
+        PixelType::F64 => img.to_pyany::<f64>(py)?,
     })
 }
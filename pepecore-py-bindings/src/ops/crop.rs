@@ -1,6 +1,6 @@
 use crate::structure::svec_traits::{PySvec, SvecPyArray};
 use pepecore::crop;
-use pepecore_array::PixelType;
+use pepecore_array::{PixelType, f16};
 use pyo3::{Bound, PyAny, PyResult, Python, pyfunction};
 
 #[pyfunction(name = "crop")]
@@ -18,5 +18,9 @@ pub fn py_crop<'py>(
         PixelType::U8 => img.to_pyany::<u8>(py)?,
         PixelType::F32 => img.to_pyany::<f32>(py)?,
         PixelType::U16 => img.to_pyany::<u16>(py)?,
+        PixelType::I16 => img.to_pyany::<i16>(py)?,
+        PixelType::U32 => img.to_pyany::<u32>(py)?,
+        PixelType::F16 => img.to_pyany::<f16>(py)?,
+        PixelType::F64 => img.to_pyany::<f64>(py)?,
     })
 }
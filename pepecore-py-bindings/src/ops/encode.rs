@@ -2,7 +2,7 @@ use crate::structure::svec_traits::{PySvec, SvecPyArray};
 use pepecore::enums::YCbCrRatio;
 use pepecore::jpeg_compress;
 use pepecore::ops::svec_ops::jpeg::quantize::QuantizationTableType;
-use pepecore_array::PixelType;
+use pepecore_array::{PixelType, f16};
 use pyo3::prelude::*;
 
 #[pyclass(name = "JpegSamplingFactor")]
@@ -84,5 +84,21 @@ pub fn py_jpeg_encode<'py>(
             img.as_u16();
             img.to_pyany::<u16>(py)?
         }
+        PixelType::I16 => {
+            img.as_i16();
+            img.to_pyany::<i16>(py)?
+        }
+        PixelType::U32 => {
+            img.as_u32();
+            img.to_pyany::<u32>(py)?
+        }
+        PixelType::F16 => {
+            img.as_f16();
+            img.to_pyany::<f16>(py)?
+        }
+        PixelType::F64 => {
+            img.as_f64();
+            img.to_pyany::<f64>(py)?
+        }
     })
 }
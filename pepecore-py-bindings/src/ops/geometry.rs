@@ -0,0 +1,149 @@
+use crate::structure::enums::{BorderPy, InterpolationPy};
+use crate::structure::svec_traits::{PySvec, SvecPyArray};
+use pepecore::{flip_h, flip_v, pad, pad_to_multiple, rot90, rot180, rot270, rotate, transpose, warp_affine, warp_perspective};
+use pepecore_array::{PixelType, SVec, f16};
+use pyo3::exceptions::PyValueError;
+use pyo3::{Bound, PyAny, PyResult, Python, pyfunction};
+
+pub(crate) fn to_py(py: Python<'_>, img: SVec) -> PyResult<Bound<'_, PyAny>> {
+    Ok(match img.pixel_type() {
+        PixelType::U8 => img.to_pyany::<u8>(py)?,
+        PixelType::F32 => img.to_pyany::<f32>(py)?,
+        PixelType::U16 => img.to_pyany::<u16>(py)?,
+        PixelType::I16 => img.to_pyany::<i16>(py)?,
+        PixelType::U32 => img.to_pyany::<u32>(py)?,
+        PixelType::F16 => img.to_pyany::<f16>(py)?,
+        PixelType::F64 => img.to_pyany::<f64>(py)?,
+    })
+}
+
+#[pyfunction(name = "flip")]
+#[pyo3(signature = (img, horizontal = true))]
+pub fn py_flip<'py>(py: Python<'py>, img: Bound<'py, PyAny>, horizontal: bool) -> PyResult<Bound<'py, PyAny>> {
+    let mut img = img.to_svec(py)?;
+    py.detach(|| if horizontal { flip_h(&mut img) } else { flip_v(&mut img) })
+        .map_err(|e| PyValueError::new_err(e.to_string()))?;
+    to_py(py, img)
+}
+
+#[pyfunction(name = "transpose")]
+pub fn py_transpose<'py>(py: Python<'py>, img: Bound<'py, PyAny>) -> PyResult<Bound<'py, PyAny>> {
+    let mut img = img.to_svec(py)?;
+    py.detach(|| transpose(&mut img))
+        .map_err(|e| PyValueError::new_err(e.to_string()))?;
+    to_py(py, img)
+}
+
+/// `k` quarter turns counter-clockwise, negative `k` turns clockwise.
+#[pyfunction(name = "rot90")]
+#[pyo3(signature = (img, k = 1))]
+pub fn py_rot90<'py>(py: Python<'py>, img: Bound<'py, PyAny>, k: i64) -> PyResult<Bound<'py, PyAny>> {
+    let mut img = img.to_svec(py)?;
+    py.detach(|| match k.rem_euclid(4) {
+        1 => rot90(&mut img),
+        2 => rot180(&mut img),
+        3 => rot270(&mut img),
+        _ => Ok(()),
+    })
+    .map_err(|e| PyValueError::new_err(e.to_string()))?;
+    to_py(py, img)
+}
+
+#[pyfunction(name = "rotate")]
+#[pyo3(signature = (img, angle, interpolation = InterpolationPy::BILINEAR, expand = false, fill = None))]
+pub fn py_rotate<'py>(
+    py: Python<'py>,
+    img: Bound<'py, PyAny>,
+    angle: f32,
+    interpolation: InterpolationPy,
+    expand: bool,
+    fill: Option<Vec<f32>>,
+) -> PyResult<Bound<'py, PyAny>> {
+    let mut img = img.to_svec(py)?;
+    py.detach(|| {
+        rotate(
+            &mut img,
+            angle,
+            interpolation.into(),
+            expand,
+            fill.as_deref().unwrap_or_default(),
+        )
+    })
+    .map_err(|e| PyValueError::new_err(e.to_string()))?;
+    to_py(py, img)
+}
+
+#[pyfunction(name = "warp_affine")]
+#[pyo3(signature = (img, matrix, out_shape, interpolation = InterpolationPy::BILINEAR, border = BorderPy::CONSTANT, fill = None))]
+pub fn py_warp_affine<'py>(
+    py: Python<'py>,
+    img: Bound<'py, PyAny>,
+    matrix: [f32; 6],
+    out_shape: (usize, usize),
+    interpolation: InterpolationPy,
+    border: BorderPy,
+    fill: Option<Vec<f32>>,
+) -> PyResult<Bound<'py, PyAny>> {
+    let img = img.to_svec(py)?;
+    let out = py
+        .detach(|| warp_affine(&img, matrix, out_shape, interpolation.into(), border.with_fill(fill)))
+        .map_err(|e| PyValueError::new_err(e.to_string()))?;
+    to_py(py, out)
+}
+
+#[pyfunction(name = "warp_perspective")]
+#[pyo3(signature = (img, homography, out_shape, interpolation = InterpolationPy::BILINEAR, border = BorderPy::CONSTANT, fill = None))]
+pub fn py_warp_perspective<'py>(
+    py: Python<'py>,
+    img: Bound<'py, PyAny>,
+    homography: [f32; 9],
+    out_shape: (usize, usize),
+    interpolation: InterpolationPy,
+    border: BorderPy,
+    fill: Option<Vec<f32>>,
+) -> PyResult<Bound<'py, PyAny>> {
+    let img = img.to_svec(py)?;
+    let out = py
+        .detach(|| warp_perspective(&img, homography, out_shape, interpolation.into(), border.with_fill(fill)))
+        .map_err(|e| PyValueError::new_err(e.to_string()))?;
+    to_py(py, out)
+}
+
+#[pyfunction(name = "pad")]
+#[pyo3(signature = (img, top, bottom, left, right, border = BorderPy::CONSTANT, fill = None))]
+#[allow(clippy::too_many_arguments)]
+pub fn py_pad<'py>(
+    py: Python<'py>,
+    img: Bound<'py, PyAny>,
+    top: usize,
+    bottom: usize,
+    left: usize,
+    right: usize,
+    border: BorderPy,
+    fill: Option<Vec<f32>>,
+) -> PyResult<Bound<'py, PyAny>> {
+    let mut img = img.to_svec(py)?;
+    py.detach(|| pad(&mut img, top, bottom, left, right, border.with_fill(fill)))
+        .map_err(|e| PyValueError::new_err(e.to_string()))?;
+    to_py(py, img)
+}
+
+/// `(top, bottom, left, right)`
+type PaddingPy = (usize, usize, usize, usize);
+
+/// Returns the padded image and its padding.
+#[pyfunction(name = "pad_to_multiple")]
+#[pyo3(signature = (img, n, border = BorderPy::REFLECT_101, fill = None))]
+pub fn py_pad_to_multiple<'py>(
+    py: Python<'py>,
+    img: Bound<'py, PyAny>,
+    n: usize,
+    border: BorderPy,
+    fill: Option<Vec<f32>>,
+) -> PyResult<(Bound<'py, PyAny>, PaddingPy)> {
+    let mut img = img.to_svec(py)?;
+    let padding = py
+        .detach(|| pad_to_multiple(&mut img, n, border.with_fill(fill)))
+        .map_err(|e| PyValueError::new_err(e.to_string()))?;
+    Ok((to_py(py, img)?, (padding.top, padding.bottom, padding.left, padding.right)))
+}
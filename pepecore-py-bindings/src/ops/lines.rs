@@ -2,7 +2,9 @@ use crate::structure::svec_traits::SvecPyArray;
 use pepecore::{Line, Point, draw_lines};
 use pepecore_array::{ImgData, SVec, Shape};
 use pyo3::types::{PyAnyMethods, PySequence, PyTypeMethods};
-use pyo3::{Bound, PyAny, PyRef, PyResult, Python, exceptions::PyTypeError, pyclass, pyfunction, pymethods};
+use pyo3::{
+    Bound, PyAny, PyRef, PyResult, Python, exceptions::PyTypeError, exceptions::PyValueError, pyclass, pyfunction, pymethods,
+};
 use std::cmp::min;
 use std::collections::HashSet;
 #[pyclass(name = "Point")]
@@ -101,7 +103,8 @@ fn draw(lines: &[Line], img: &mut SVec) {
 #[pyfunction(name = "line")]
 pub fn py_line<'py>(py: Python<'py>, lines: Bound<'py, PyAny>, h: usize, w: usize) -> PyResult<Bound<'py, PyAny>> {
     let lines = gather_line_cmds(&lines)?;
-    let mut img = SVec::new(Shape::new(h, w, None), ImgData::U8(vec![0; h * w]));
+    let mut img =
+        SVec::try_new(Shape::new(h, w, None), ImgData::U8(vec![0; h * w])).map_err(|e| PyValueError::new_err(e.to_string()))?;
     py.detach(|| draw(&lines, &mut img));
     Ok(img.to_pyany::<u8>(py)?)
 }
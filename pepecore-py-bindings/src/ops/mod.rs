@@ -1,6 +1,7 @@
 pub mod colors;
 pub mod crop;
 pub mod encode;
+pub mod geometry;
 pub mod get_palette;
 pub mod lines;
 pub mod noise;
@@ -9,3 +10,4 @@ pub mod old_rebind;
 pub mod original_size;
 pub mod read_write;
 pub mod resize;
+pub mod tile;
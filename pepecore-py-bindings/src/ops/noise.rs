@@ -26,7 +26,8 @@ pub fn py_noise<'py>(
                 &frequency,
                 &noise_type,
             )
-        });
+        })
+        .map_err(|e| PyValueError::new_err(e.to_string()))?;
         Ok(vec.to_pyany::<f32>(py)?)
     } else if len_shape == 3 {
         let vec = py.detach(|| {
@@ -37,7 +38,8 @@ pub fn py_noise<'py>(
                 &frequency,
                 &noise_type,
             )
-        });
+        })
+        .map_err(|e| PyValueError::new_err(e.to_string()))?;
         Ok(vec.to_pyany::<f32>(py)?)
     } else {
         return Err(PyValueError::new_err("Unsuported Shape"));
@@ -1,6 +1,6 @@
 use crate::structure::svec_traits::{PySvec, SvecPyArray};
 use pepecore::NormalizeSVec;
-use pepecore_array::PixelType;
+use pepecore_array::{PixelType, f16};
 use pyo3::{Bound, PyAny, PyResult, Python, pyfunction};
 
 #[pyfunction(name = "normalise")]
@@ -12,5 +12,9 @@ pub fn normalize<'py>(py: Python<'py>, img: Bound<'py, PyAny>, scale: f32) -> Py
         PixelType::U8 => img.to_pyany::<u8>(py)?,
         PixelType::F32 => img.to_pyany::<f32>(py)?,
         PixelType::U16 => img.to_pyany::<u16>(py)?,
+        PixelType::I16 => img.to_pyany::<i16>(py)?,
+        PixelType::U32 => img.to_pyany::<u32>(py)?,
+        PixelType::F16 => img.to_pyany::<f16>(py)?,
+        PixelType::F64 => img.to_pyany::<f64>(py)?,
     })
 }
@@ -1,18 +1,28 @@
-use crate::structure::enums::{ColorMode, ImgFormat};
+use crate::structure::enums::{ColorMode, ImageFormatPy, ImgFormat};
 use crate::structure::svec_traits::{PySvec, SvecPyArray};
 use pepecore::enums::ImgColor;
+use pepecore::probe::{ImageInfo, probe_buffer, probe_path};
 use pepecore::read::{read_in_buffer, read_in_path};
-use pepecore::save::svec_save;
-use pepecore_array::{ImgData, PixelType, SVec, Shape};
+use pepecore::region::{Tiles, read_tiles_path};
+use pepecore::save::{SaveOptions, svec_encode, svec_save};
+use pepecore_array::{PixelType, SVec, f16};
 use pyo3::exceptions::PyRuntimeError;
-use pyo3::{Bound, PyAny, PyRef, PyResult, Python, pyclass, pyfunction, pymethods};
+use pyo3::types::{PyBytes, PyDict, PyDictMethods};
+use pyo3::{Bound, PyAny, PyRefMut, PyResult, Python, pyclass, pyfunction, pymethods};
 use std::panic::{AssertUnwindSafe, catch_unwind};
+use std::sync::{Mutex, PoisonError};
 
 // для ловли паник
 
 #[pyfunction]
-#[pyo3(signature = (path, color_mode = ColorMode::DYNAMIC, img_format =  ImgFormat::DYNAMIC))]
-pub fn read(py: Python<'_>, path: String, color_mode: ColorMode, img_format: ImgFormat) -> PyResult<Bound<'_, PyAny>> {
+#[pyo3(signature = (path, color_mode = ColorMode::DYNAMIC, img_format =  ImgFormat::DYNAMIC, planar = false))]
+pub fn read(
+    py: Python<'_>,
+    path: String,
+    color_mode: ColorMode,
+    img_format: ImgFormat,
+    planar: bool,
+) -> PyResult<Bound<'_, PyAny>> {
     // ловим панику на верхнем уровне
     let result = catch_unwind(AssertUnwindSafe(|| {
         py.detach(|| match img_format {
@@ -36,50 +46,59 @@ pub fn read(py: Python<'_>, path: String, color_mode: ColorMode, img_format: Img
     }));
 
     match result {
-        Ok(img) => Ok(match img.pixel_type() {
-            PixelType::U8 => img.to_pyany::<u8>(py)?,
-            PixelType::F32 => img.to_pyany::<f32>(py)?,
-            PixelType::U16 => img.to_pyany::<u16>(py)?,
+        Ok(mut img) => Ok({
+            if planar {
+                img.to_planar();
+            }
+            match img.pixel_type() {
+                PixelType::U8 => img.to_pyany::<u8>(py)?,
+                PixelType::F32 => img.to_pyany::<f32>(py)?,
+                PixelType::U16 => img.to_pyany::<u16>(py)?,
+                PixelType::I16 => img.to_pyany::<i16>(py)?,
+                PixelType::U32 => img.to_pyany::<u32>(py)?,
+                PixelType::F16 => img.to_pyany::<f16>(py)?,
+                PixelType::F64 => img.to_pyany::<f64>(py)?,
+            }
         }),
         Err(_) => Err(PyRuntimeError::new_err("Rust panic caught in read()")),
     }
 }
 
+/// Lazy tile iterator over an image file. Tiles are decoded one band of rows at a time and the
+/// file is re-opened at the start of every pass, so the same object can be iterated per epoch.
 #[pyclass]
 pub struct Image {
-    inner: SVec,
+    path: String,
+    color: ImgColor,
+    tiles: Mutex<Option<Tiles<'static>>>,
+    shape: (usize, usize, Option<usize>),
     format: ImgFormat,
     tile_size: usize,
-    x_y: Vec<[usize; 2]>,
-    color: bool,
     pos: usize,
 }
 
-fn get_tile<T>(data: &[T], tile_size: usize, c: usize, w: usize, y_x: [usize; 2]) -> Vec<T>
-where
-    T: Clone,
-{
-    let mut result_vec: Vec<T> = Vec::with_capacity(tile_size * tile_size * c);
-    let x = y_x[1];
-    let row_stride = w * c;
-    for y in y_x[0]..y_x[0] + tile_size {
-        let start = y * row_stride + x * c;
-        let end = start + tile_size * c;
-        result_vec.extend_from_slice(&data[start..end])
+impl Image {
+    fn open(path: &str, color: ImgColor, tile_size: usize) -> PyResult<Tiles<'static>> {
+        read_tiles_path(path, tile_size, tile_size, color).map_err(|e| PyRuntimeError::new_err(format!("read_tiler: {:?}", e)))
     }
-    result_vec
 }
 
 #[pymethods]
 impl Image {
-    fn __iter__(slf: PyRef<Self>) -> PyRef<Self> {
+    fn __iter__(mut slf: PyRefMut<Self>) -> PyRefMut<Self> {
+        // a new pass started mid-way drops the old iterator and re-opens the file on the next tile
+        if slf.pos > 0 {
+            slf.pos = 0;
+            *slf.tiles.get_mut().unwrap_or_else(PoisonError::into_inner) = None;
+        }
         slf
     }
     fn __len__(&self) -> usize {
-        self.x_y.len()
+        let (h, w, _) = self.shape;
+        h.div_ceil(self.tile_size) * w.div_ceil(self.tile_size)
     }
     fn __str__(&self) -> String {
-        let (h, w, c) = self.inner.shape();
+        let (h, w, c) = self.shape;
 
         let channels = match c {
             Some(v) => v.to_string(),
@@ -87,13 +106,12 @@ impl Image {
         };
 
         format!(
-            "Image(shape=({}, {}, {}), tile_size={}, tiles={}, color={}, pos={})",
+            "Image(shape=({}, {}, {}), tile_size={}, tiles={}, pos={})",
             h,
             w,
             channels,
             self.tile_size,
-            self.x_y.len(),
-            self.color,
+            self.__len__(),
             self.pos,
         )
     }
@@ -103,65 +121,51 @@ impl Image {
     }
     #[getter]
     fn shape(&self) -> (usize, usize, Option<usize>) {
-        self.inner.shape()
+        self.shape
     }
     fn __next__<'py>(&mut self, py: Python<'py>) -> PyResult<Option<Bound<'py, PyAny>>> {
-        if self.pos >= self.x_y.len() {
-            self.pos = 0;
-            return Ok(None);
-        }
-
-        let (_h, w, c) = self.inner.shape();
-        let svec = py.detach(|| {
-            let mut svec = match self.inner.pixel_type() {
-                PixelType::U8 => {
-                    let tile = get_tile::<u8>(
-                        self.inner.get_data::<u8>().unwrap(),
-                        self.tile_size,
-                        c.unwrap_or(1),
-                        w,
-                        self.x_y[self.pos],
-                    );
-                    SVec::new(Shape::new(self.tile_size, self.tile_size, c), ImgData::U8(tile))
-                }
-                PixelType::F32 => {
-                    let tile = get_tile::<f32>(
-                        self.inner.get_data::<f32>().unwrap(),
-                        self.tile_size,
-                        c.unwrap_or(1),
-                        w,
-                        self.x_y[self.pos],
-                    );
-                    SVec::new(Shape::new(self.tile_size, self.tile_size, c), ImgData::F32(tile))
-                }
-                PixelType::U16 => {
-                    let tile = get_tile::<u16>(
-                        self.inner.get_data::<u16>().unwrap(),
-                        self.tile_size,
-                        c.unwrap_or(1),
-                        w,
-                        self.x_y[self.pos],
-                    );
-                    SVec::new(Shape::new(self.tile_size, self.tile_size, c), ImgData::U16(tile))
-                }
+        let (path, color, tile_size, format) = (&self.path, self.color, self.tile_size, self.format);
+        let tiles = self.tiles.get_mut().unwrap_or_else(PoisonError::into_inner);
+        let tile = py.detach(|| -> PyResult<Option<SVec>> {
+            let tile = match tiles {
+                Some(tiles) => tiles.next(),
+                None => tiles.insert(Image::open(path, color, tile_size)?).next(),
             };
-            match self.format {
+            let Some(tile) = tile else {
+                // the pass is over, the next one re-opens the file
+                *tiles = None;
+                return Ok(None);
+            };
+            let mut svec = tile
+                .map_err(|e| PyRuntimeError::new_err(format!("read_tiler: {:?}", e)))?
+                .image;
+            match format {
                 ImgFormat::F32 => svec.as_f32(),
                 ImgFormat::U8 => svec.as_u8(),
                 ImgFormat::U16 => svec.as_u16(),
                 ImgFormat::DYNAMIC => {}
             }
-            svec
-        });
+            Ok(Some(svec))
+        })?;
 
+        let Some(svec) = tile else {
+            self.pos = 0;
+            return Ok(None);
+        };
         self.pos += 1;
         Ok(Some(match svec.pixel_type() {
             PixelType::U8 => svec.to_pyany::<u8>(py)?,
             PixelType::F32 => svec.to_pyany::<f32>(py)?,
             PixelType::U16 => svec.to_pyany::<u16>(py)?,
+            PixelType::I16 => svec.to_pyany::<i16>(py)?,
+            PixelType::U32 => svec.to_pyany::<u32>(py)?,
+            PixelType::F16 => svec.to_pyany::<f16>(py)?,
+            PixelType::F64 => svec.to_pyany::<f64>(py)?,
         }))
     }
 }
+/// Tiles on the bottom and right edges are cut to the image, so they can be smaller than
+/// `tile_size`. Returns `None` for an empty image.
 #[pyfunction]
 #[pyo3(signature = (path, color_mode = ColorMode::DYNAMIC, img_format =  ImgFormat::DYNAMIC,tile_size=512))]
 pub fn read_tiler(
@@ -171,45 +175,33 @@ pub fn read_tiler(
     img_format: ImgFormat,
     tile_size: usize,
 ) -> PyResult<Option<Image>> {
-    let result = catch_unwind(AssertUnwindSafe(|| {
-        py.detach(|| read_in_path(&*path, ImgColor::from(color_mode)).unwrap())
-    }));
-
-    match result {
-        Ok(img) => {
-            let (h, w, _c) = img.shape();
-            let n_h = h / tile_size;
-            let n_w = w / tile_size;
-            if n_h == 0 || n_w == 0 {
-                return Ok(None);
-            }
-            let mut tiles: Vec<[usize; 2]> = Vec::new();
-            for y in 0..n_h {
-                for x in 0..n_w {
-                    tiles.push([y * tile_size, x * tile_size])
-                }
-            }
-            Ok(Some(Image {
-                inner: img,
-                format: img_format,
-                tile_size,
-                x_y: tiles,
-                color: true,
-                pos: 0,
-            }))
-        }
-        Err(e) => Err(PyRuntimeError::new_err(format!("Rust panic caught in read(){:?}", e))),
+    let color = ImgColor::from(color_mode);
+    // the first pass reuses the iterator that told the shape
+    let tiles = py.detach(|| Image::open(&path, color, tile_size))?;
+    let shape = tiles.shape();
+    if shape.0 == 0 || shape.1 == 0 {
+        return Ok(None);
     }
+    Ok(Some(Image {
+        path,
+        color,
+        tiles: Mutex::new(Some(tiles)),
+        shape,
+        format: img_format,
+        tile_size,
+        pos: 0,
+    }))
 }
 #[pyfunction]
-#[pyo3(signature = (buffer, color_mode = ColorMode::DYNAMIC, img_format =  ImgFormat::DYNAMIC))]
+#[pyo3(signature = (buffer, color_mode = ColorMode::DYNAMIC, img_format =  ImgFormat::DYNAMIC, planar = false))]
 pub fn buff_read<'py>(
     py: Python<'py>,
     buffer: &[u8],
     color_mode: ColorMode,
     img_format: ImgFormat,
+    planar: bool,
 ) -> PyResult<Bound<'py, PyAny>> {
-    let img = py.detach(|| match img_format {
+    let mut img = py.detach(|| match img_format {
         ImgFormat::F32 => {
             let mut buff = read_in_buffer(buffer, ImgColor::from(color_mode)).unwrap();
             buff.as_f32();
@@ -227,17 +219,75 @@ pub fn buff_read<'py>(
         }
         ImgFormat::DYNAMIC => read_in_buffer(buffer, ImgColor::from(color_mode)).unwrap(),
     });
+    if planar {
+        img.to_planar();
+    }
 
     Ok(match img.pixel_type() {
         PixelType::U8 => img.to_pyany::<u8>(py)?,
         PixelType::F32 => img.to_pyany::<f32>(py)?,
         PixelType::U16 => img.to_pyany::<u16>(py)?,
+        PixelType::I16 => img.to_pyany::<i16>(py)?,
+        PixelType::U32 => img.to_pyany::<u32>(py)?,
+        PixelType::F16 => img.to_pyany::<f16>(py)?,
+        PixelType::F64 => img.to_pyany::<f64>(py)?,
     })
 }
 
+#[pyfunction]
+#[pyo3(signature = (img, img_format = ImageFormatPy::PNG, quality = None))]
+pub fn buff_save<'py>(
+    py: Python<'py>,
+    img: Bound<'py, PyAny>,
+    img_format: ImageFormatPy,
+    quality: Option<u8>,
+) -> PyResult<Bound<'py, PyBytes>> {
+    let img = img.to_svec(py)?;
+    let mut options = SaveOptions::default();
+    // quality drives JPEG and switches WebP to lossy
+    if let Some(quality) = quality {
+        options.jpeg.quality = quality;
+        options.webp.lossless = false;
+        options.webp.quality = quality as f32;
+    }
+    let bytes = py
+        .detach(|| svec_encode(&img, img_format.into(), options))
+        .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+    Ok(PyBytes::new(py, &bytes))
+}
+
 #[pyfunction]
 pub fn save<'py>(py: Python<'py>, img: Bound<'py, PyAny>, path: String) -> PyResult<()> {
     let img = img.to_svec(py)?;
-    py.detach(|| svec_save(img, &*path).expect("Failed to save image"));
-    Ok(())
+    py.detach(|| svec_save(img, &*path))
+        .map_err(|e| PyRuntimeError::new_err(e.to_string()))
+}
+
+fn info_dict(py: Python<'_>, info: ImageInfo) -> PyResult<Bound<'_, PyDict>> {
+    let dict = PyDict::new(py);
+    dict.set_item("format", format!("{:?}", info.format).to_lowercase())?;
+    dict.set_item("width", info.width)?;
+    dict.set_item("height", info.height)?;
+    dict.set_item("channels", info.channels)?;
+    dict.set_item("bit_depth", info.bit_depth)?;
+    dict.set_item("float", info.float)?;
+    dict.set_item("color_type", format!("{:?}", info.color_type).to_lowercase())?;
+    dict.set_item("frames", info.frames)?;
+    Ok(dict)
+}
+
+#[pyfunction]
+pub fn probe(py: Python<'_>, path: String) -> PyResult<Bound<'_, PyDict>> {
+    let info = py
+        .detach(|| probe_path(&*path))
+        .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+    info_dict(py, info)
+}
+
+#[pyfunction]
+pub fn buff_probe<'py>(py: Python<'py>, buffer: &[u8]) -> PyResult<Bound<'py, PyDict>> {
+    let info = py
+        .detach(|| probe_buffer(buffer))
+        .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+    info_dict(py, info)
 }
@@ -1,9 +1,32 @@
-use crate::structure::enums::{ResizesAlg, ResizesFilter};
-use crate::structure::svec_traits::{PySvec, SvecPyArray};
+use crate::ops::geometry::to_py;
+use crate::structure::enums::{AnchorPy, ResizesAlg, ResizesFilter};
+use crate::structure::svec_traits::PySvec;
 use pepecore::ops::svec_ops::resize::fir::ResizeSVec;
-use pepecore_array::PixelType;
+use pepecore_array::SVec;
+use pepecore_array::error::Error;
+use pyo3::exceptions::PyValueError;
 use pyo3::{Bound, PyAny, PyResult, Python, pyfunction};
 
+/// `alpha` only applies to gray + alpha and RGBA images, so the default leaves feature maps
+/// with other channel counts unpremultiplied.
+fn alpha_for(img: &SVec, alpha: bool) -> bool {
+    alpha && matches!(img.shape().2, Some(2 | 4))
+}
+
+/// Run one of the aspect-aware resizes on `img` without the GIL, with `alpha` as per [`alpha_for`].
+fn resize_with<'py>(
+    py: Python<'py>,
+    img: Bound<'py, PyAny>,
+    alpha: bool,
+    op: impl FnOnce(&mut SVec, bool) -> Result<(), Error> + Send,
+) -> PyResult<Bound<'py, PyAny>> {
+    let mut img = img.to_svec(py)?;
+    let alpha = alpha_for(&img, alpha);
+    py.detach(|| op(&mut img, alpha))
+        .map_err(|e| PyValueError::new_err(e.to_string()))?;
+    to_py(py, img)
+}
+
 #[pyfunction(name = "resize")]
 #[pyo3(signature = (img, h,w, resize_alg=ResizesAlg::Conv(ResizesFilter::CatmullRom),alpha=true))]
 pub fn py_resize<'py>(
@@ -15,11 +38,92 @@ pub fn py_resize<'py>(
     alpha: bool,
 ) -> PyResult<Bound<'py, PyAny>> {
     let mut img = img.to_svec(py)?;
-
+    let alpha = alpha_for(&img, alpha);
     py.detach(|| img.resize(h, w, resize_alg.into(), alpha));
-    Ok(match img.pixel_type() {
-        PixelType::U8 => img.to_pyany::<u8>(py)?,
-        PixelType::F32 => img.to_pyany::<f32>(py)?,
-        PixelType::U16 => img.to_pyany::<u16>(py)?,
+    to_py(py, img)
+}
+
+#[pyfunction(name = "resize_to_fit")]
+#[pyo3(signature = (img, max_h, max_w, resize_alg=ResizesAlg::Conv(ResizesFilter::CatmullRom), alpha=true))]
+pub fn py_resize_to_fit<'py>(
+    py: Python<'py>,
+    img: Bound<'py, PyAny>,
+    max_h: usize,
+    max_w: usize,
+    resize_alg: ResizesAlg,
+    alpha: bool,
+) -> PyResult<Bound<'py, PyAny>> {
+    resize_with(py, img, alpha, |img, alpha| {
+        img.resize_to_fit(max_h, max_w, resize_alg.into(), alpha)
     })
 }
+
+#[pyfunction(name = "resize_to_fill")]
+#[pyo3(signature = (img, h, w, anchor=AnchorPy::CENTER, resize_alg=ResizesAlg::Conv(ResizesFilter::CatmullRom), alpha=true))]
+pub fn py_resize_to_fill<'py>(
+    py: Python<'py>,
+    img: Bound<'py, PyAny>,
+    h: usize,
+    w: usize,
+    anchor: AnchorPy,
+    resize_alg: ResizesAlg,
+    alpha: bool,
+) -> PyResult<Bound<'py, PyAny>> {
+    resize_with(py, img, alpha, |img, alpha| {
+        img.resize_to_fill(h, w, anchor.into(), resize_alg.into(), alpha)
+    })
+}
+
+#[pyfunction(name = "resize_scale")]
+#[pyo3(signature = (img, factor, resize_alg=ResizesAlg::Conv(ResizesFilter::CatmullRom), alpha=true))]
+pub fn py_resize_scale<'py>(
+    py: Python<'py>,
+    img: Bound<'py, PyAny>,
+    factor: f64,
+    resize_alg: ResizesAlg,
+    alpha: bool,
+) -> PyResult<Bound<'py, PyAny>> {
+    resize_with(py, img, alpha, |img, alpha| {
+        img.resize_scale(factor, resize_alg.into(), alpha)
+    })
+}
+
+/// Scale so the shorter side, or with `long` the longer side, is `size` pixels.
+#[pyfunction(name = "resize_side")]
+#[pyo3(signature = (img, size, long=false, resize_alg=ResizesAlg::Conv(ResizesFilter::CatmullRom), alpha=true))]
+pub fn py_resize_side<'py>(
+    py: Python<'py>,
+    img: Bound<'py, PyAny>,
+    size: usize,
+    long: bool,
+    resize_alg: ResizesAlg,
+    alpha: bool,
+) -> PyResult<Bound<'py, PyAny>> {
+    resize_with(py, img, alpha, |img, alpha| match long {
+        true => img.resize_long_side(size, resize_alg.into(), alpha),
+        false => img.resize_short_side(size, resize_alg.into(), alpha),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pepecore_array::{ImgData, Shape};
+
+    #[test]
+    fn default_alpha_leaves_feature_maps() {
+        // a zero last channel would wipe the others if it were taken for alpha
+        let px = [0.5f32, 0.25, 1.0, 0.75, 0.1, 0.0];
+        let mut img = SVec::new(Shape::new(4, 4, Some(6)), ImgData::from(px.repeat(16)));
+        let alpha = alpha_for(&img, true);
+        assert!(!alpha);
+        img.resize(2, 2, ResizesAlg::Conv(ResizesFilter::CatmullRom).into(), alpha);
+        assert_eq!(img.shape(), (2, 2, Some(6)));
+        for out in img.get_data::<f32>().unwrap().chunks_exact(6) {
+            assert!(out.iter().zip(px).all(|(a, b)| (a - b).abs() < 1e-5), "{:?}", out);
+        }
+
+        let rgba = SVec::new(Shape::new(1, 1, Some(4)), ImgData::from(vec![0u8; 4]));
+        assert!(alpha_for(&rgba, true) && !alpha_for(&rgba, false));
+    }
+}
@@ -0,0 +1,61 @@
+use crate::ops::geometry::to_py;
+use crate::structure::enums::FeatherPy;
+use crate::structure::svec_traits::PySvec;
+use pepecore::region::{Region, Tile};
+use pepecore::tile::{Merger, Tiler};
+use pyo3::exceptions::PyValueError;
+use pyo3::{Bound, PyAny, PyResult, Python, pyfunction};
+
+/// `(top, left, height, width)` of a tile.
+type RegionPy = (usize, usize, usize, usize);
+
+/// Overlapping tiles covering the whole image, the last row and column shifted inward.
+#[pyfunction(name = "split_tiles")]
+#[pyo3(signature = (img, tile_size = 512, overlap = 32))]
+pub fn py_split_tiles<'py>(
+    py: Python<'py>,
+    img: Bound<'py, PyAny>,
+    tile_size: usize,
+    overlap: usize,
+) -> PyResult<Vec<(RegionPy, Bound<'py, PyAny>)>> {
+    let img = img.to_svec(py)?;
+    let (h, w, _) = img.shape();
+    let tiles = py
+        .detach(|| {
+            let tiler = Tiler::new(h, w, tile_size, overlap)?;
+            tiler.split(&img).collect::<Result<Vec<Tile>, _>>()
+        })
+        .map_err(|e| PyValueError::new_err(e.to_string()))?;
+    tiles
+        .into_iter()
+        .map(|Tile { region, image }| Ok(((region.top, region.left, region.height, region.width), to_py(py, image)?)))
+        .collect()
+}
+
+/// Blend tiles from `split_tiles`, processed and possibly upscaled by `scale`, back into one image.
+#[pyfunction(name = "merge_tiles")]
+#[pyo3(signature = (tiles, shape, tile_size = 512, overlap = 32, scale = 1, feather = FeatherPy::COSINE))]
+pub fn py_merge_tiles<'py>(
+    py: Python<'py>,
+    tiles: Vec<(RegionPy, Bound<'py, PyAny>)>,
+    shape: (usize, usize),
+    tile_size: usize,
+    overlap: usize,
+    scale: usize,
+    feather: FeatherPy,
+) -> PyResult<Bound<'py, PyAny>> {
+    let tiler = Tiler::new(shape.0, shape.1, tile_size, overlap).map_err(|e| PyValueError::new_err(e.to_string()))?;
+    let mut merger = Merger::new(&tiler, scale, feather.into()).map_err(|e| PyValueError::new_err(e.to_string()))?;
+    for ((top, left, height, width), image) in tiles {
+        let tile = Tile {
+            region: Region::new(top, left, height, width),
+            image: image.to_svec(py)?,
+        };
+        py.detach(|| merger.add(&tile))
+            .map_err(|e| PyValueError::new_err(e.to_string()))?;
+    }
+    let img = py
+        .detach(|| merger.finish())
+        .map_err(|e| PyValueError::new_err(e.to_string()))?;
+    to_py(py, img)
+}
@@ -1,7 +1,7 @@
 use fast_image_resize::{FilterType, ResizeAlg};
 use fastnoise_lite::NoiseType;
 use pepecore::enums::ImgColor;
-use pepecore::enums::{CVTColor, DotType};
+use pepecore::enums::{Anchor, Border, CVTColor, DescreenMode, DitherKernel, DotType, Feather, ImageFormat, Interpolation};
 use pyo3::pyclass;
 #[pyclass(name = "ImgColor")]
 #[derive(Clone, Copy)]
@@ -34,6 +34,33 @@ pub enum ImgFormat {
     DYNAMIC,
 }
 
+#[allow(clippy::upper_case_acronyms)]
+#[pyclass(name = "ImageFormat")]
+#[derive(Clone, Copy)]
+pub enum ImageFormatPy {
+    PNG,
+    JPEG,
+    WEBP,
+    TIFF,
+    JXL,
+    EXR,
+    GIF,
+}
+
+impl From<ImageFormatPy> for ImageFormat {
+    fn from(value: ImageFormatPy) -> Self {
+        match value {
+            ImageFormatPy::PNG => ImageFormat::Png,
+            ImageFormatPy::JPEG => ImageFormat::Jpeg,
+            ImageFormatPy::WEBP => ImageFormat::WebP,
+            ImageFormatPy::TIFF => ImageFormat::Tiff,
+            ImageFormatPy::JXL => ImageFormat::Jxl,
+            ImageFormatPy::EXR => ImageFormat::Exr,
+            ImageFormatPy::GIF => ImageFormat::Gif,
+        }
+    }
+}
+
 #[pyclass(name = "CVTColor")]
 #[derive(Clone, Copy)]
 #[allow(non_camel_case_types)]
@@ -113,6 +140,143 @@ impl From<DotTypePy> for DotType {
     }
 }
 
+#[allow(non_camel_case_types, clippy::upper_case_acronyms)]
+#[pyclass(name = "DescreenMode")]
+#[derive(Clone, Copy)]
+pub enum DescreenModePy {
+    SMOOTH,
+    PRESERVE_LINES,
+}
+
+impl From<DescreenModePy> for DescreenMode {
+    fn from(value: DescreenModePy) -> Self {
+        match value {
+            DescreenModePy::SMOOTH => DescreenMode::Smooth,
+            DescreenModePy::PRESERVE_LINES => DescreenMode::PreserveLines,
+        }
+    }
+}
+
+#[allow(non_camel_case_types, clippy::upper_case_acronyms)]
+#[pyclass(name = "DitherKernel")]
+#[derive(Clone, Copy)]
+pub enum DitherKernelPy {
+    FLOYD_STEINBERG,
+    ATKINSON,
+    JARVIS_JUDICE_NINKE,
+    STUCKI,
+    SIERRA,
+    TWO_ROW_SIERRA,
+    SIERRA_LITE,
+}
+
+impl From<DitherKernelPy> for DitherKernel {
+    fn from(value: DitherKernelPy) -> Self {
+        match value {
+            DitherKernelPy::FLOYD_STEINBERG => DitherKernel::FloydSteinberg,
+            DitherKernelPy::ATKINSON => DitherKernel::Atkinson,
+            DitherKernelPy::JARVIS_JUDICE_NINKE => DitherKernel::JarvisJudiceNinke,
+            DitherKernelPy::STUCKI => DitherKernel::Stucki,
+            DitherKernelPy::SIERRA => DitherKernel::Sierra,
+            DitherKernelPy::TWO_ROW_SIERRA => DitherKernel::TwoRowSierra,
+            DitherKernelPy::SIERRA_LITE => DitherKernel::SierraLite,
+        }
+    }
+}
+
+#[allow(non_camel_case_types, clippy::upper_case_acronyms)]
+#[pyclass(name = "Interpolation")]
+#[derive(Clone, Copy)]
+pub enum InterpolationPy {
+    NEAREST,
+    BILINEAR,
+    BICUBIC,
+    LANCZOS3,
+}
+
+impl From<InterpolationPy> for Interpolation {
+    fn from(value: InterpolationPy) -> Self {
+        match value {
+            InterpolationPy::NEAREST => Interpolation::Nearest,
+            InterpolationPy::BILINEAR => Interpolation::Bilinear,
+            InterpolationPy::BICUBIC => Interpolation::Bicubic,
+            InterpolationPy::LANCZOS3 => Interpolation::Lanczos3,
+        }
+    }
+}
+
+#[allow(non_camel_case_types, clippy::upper_case_acronyms)]
+#[pyclass(name = "Border")]
+#[derive(Clone, Copy)]
+pub enum BorderPy {
+    CONSTANT,
+    REPLICATE,
+    REFLECT,
+    REFLECT_101,
+    WRAP,
+}
+
+impl BorderPy {
+    /// `fill` is only used by `CONSTANT`.
+    pub fn with_fill(self, fill: Option<Vec<f32>>) -> Border {
+        match self {
+            BorderPy::CONSTANT => Border::Constant(fill.unwrap_or_default()),
+            BorderPy::REPLICATE => Border::Replicate,
+            BorderPy::REFLECT => Border::Reflect,
+            BorderPy::REFLECT_101 => Border::Reflect101,
+            BorderPy::WRAP => Border::Wrap,
+        }
+    }
+}
+
+#[allow(non_camel_case_types, clippy::upper_case_acronyms)]
+#[pyclass(name = "Feather")]
+#[derive(Clone, Copy)]
+pub enum FeatherPy {
+    LINEAR,
+    COSINE,
+}
+
+impl From<FeatherPy> for Feather {
+    fn from(value: FeatherPy) -> Self {
+        match value {
+            FeatherPy::LINEAR => Feather::Linear,
+            FeatherPy::COSINE => Feather::Cosine,
+        }
+    }
+}
+
+#[allow(non_camel_case_types, clippy::upper_case_acronyms)]
+#[pyclass(name = "Anchor")]
+#[derive(Clone, Copy)]
+pub enum AnchorPy {
+    TOP_LEFT,
+    TOP,
+    TOP_RIGHT,
+    LEFT,
+    CENTER,
+    RIGHT,
+    BOTTOM_LEFT,
+    BOTTOM,
+    BOTTOM_RIGHT,
+}
+
+impl From<AnchorPy> for Anchor {
+    fn from(value: AnchorPy) -> Self {
+        match value {
+            AnchorPy::TOP_LEFT => Anchor::TopLeft,
+            AnchorPy::TOP => Anchor::Top,
+            AnchorPy::TOP_RIGHT => Anchor::TopRight,
+            AnchorPy::LEFT => Anchor::Left,
+            AnchorPy::CENTER => Anchor::Center,
+            AnchorPy::RIGHT => Anchor::Right,
+            AnchorPy::BOTTOM_LEFT => Anchor::BottomLeft,
+            AnchorPy::BOTTOM => Anchor::Bottom,
+            AnchorPy::BOTTOM_RIGHT => Anchor::BottomRight,
+        }
+    }
+}
+
 #[derive(Clone)]
 #[pyclass]
 pub enum TypeNoise {
@@ -1,6 +1,7 @@
 use numpy::{Element, PyArrayDyn, PyArrayMethods, PyUntypedArrayMethods};
-use pepecore_array::{ImgData, SVec, Shape};
-use pyo3::{Bound, IntoPyObjectExt, PyAny, PyResult, Python, exceptions::PyRuntimeError};
+use pepecore_array::{ImgData, Layout, SVec, Shape, f16};
+use pyo3::exceptions::{PyRuntimeError, PyValueError};
+use pyo3::{Bound, IntoPyObjectExt, PyAny, PyResult, Python};
 use rayon::prelude::*;
 use std::mem::size_of;
 
@@ -53,7 +54,7 @@ where
 {
     // Получаем readonly‑view
     let readonly = np.try_readonly()?;
-    let shape = readonly.shape();
+    let shape = Shape::try_from(readonly.shape()).map_err(|e| PyValueError::new_err(e.to_string()))?;
     let total = readonly.len();
     let mut buffer: Vec<T> = Vec::with_capacity(total);
 
@@ -68,7 +69,7 @@ where
         buffer = readonly.to_owned_array().into_raw_vec_and_offset().0;
     }
 
-    Ok(SVec::new(Shape::from(shape), ImgData::from(buffer)))
+    SVec::try_new(shape, ImgData::from(buffer)).map_err(|e| PyValueError::new_err(e.to_string()))
 }
 
 impl PySvec for Bound<'_, PyAny> {
@@ -79,6 +80,14 @@ impl PySvec for Bound<'_, PyAny> {
             alloc_from_np(py, np_array)
         } else if let Ok(np_array) = self.cast::<PyArrayDyn<u16>>() {
             alloc_from_np(py, np_array)
+        } else if let Ok(np_array) = self.cast::<PyArrayDyn<i16>>() {
+            alloc_from_np(py, np_array)
+        } else if let Ok(np_array) = self.cast::<PyArrayDyn<u32>>() {
+            alloc_from_np(py, np_array)
+        } else if let Ok(np_array) = self.cast::<PyArrayDyn<f16>>() {
+            alloc_from_np(py, np_array)
+        } else if let Ok(np_array) = self.cast::<PyArrayDyn<f64>>() {
+            alloc_from_np(py, np_array)
         } else {
             Err(PyRuntimeError::new_err("Unsupported type: Expected NumPy ndarray or list"))
         }
@@ -92,6 +101,7 @@ impl SvecPyArray for SVec {
     {
         let (height, width, channels_opt) = self.shape();
         let dims: &[usize] = match channels_opt {
+            Some(c) if self.shape.get_layout() == Layout::CHW => &[c, height, width],
             Some(c) => &[height, width, c],
             None => &[height, width],
         };
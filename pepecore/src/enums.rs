@@ -60,3 +60,229 @@ pub enum PaletteAlg {
     Wu,
     MinMaxUniform,
 }
+
+/// Element-wise operation applied by [`arithmetic`](crate::ops::svec_ops::arithmetic::element::arithmetic).
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum ArithOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+}
+
+/// Gaussian frequency-domain filter used by
+/// [`frequency_filter`](crate::ops::svec_ops::frequency::frequency_filter).
+///
+/// Frequencies are in cycles per pixel (`0.0..=0.5` along each axis); cutoffs are the standard
+/// deviation of the Gaussian.
+#[derive(Debug, Clone, PartialEq)]
+pub enum FreqFilter {
+    LowPass(f32),
+    HighPass(f32),
+    /// Keeps frequencies between `low` and `high`.
+    BandPass {
+        low: f32,
+        high: f32,
+    },
+    /// Rejects the listed `(fy, fx)` frequencies and their mirrored counterparts.
+    Notch {
+        points: Vec<(f32, f32)>,
+        radius: f32,
+    },
+}
+
+/// How [`descreen`](crate::ops::svec_ops::halftone::descreen::descreen) suppresses the screen.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum DescreenMode {
+    /// Gaussian low-pass below the screen frequency; smooth tones, softer edges.
+    Smooth,
+    /// Notch out only the screen lattice and its harmonics, keeping line art sharp.
+    PreserveLines,
+}
+
+/// Error-diffusion kernel used by [`dither`](crate::ops::svec_ops::halftone::dither::dither).
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum DitherKernel {
+    FloydSteinberg,
+    Atkinson,
+    JarvisJudiceNinke,
+    Stucki,
+    Sierra,
+    TwoRowSierra,
+    SierraLite,
+}
+
+/// Sampling filter for [`rotate`](crate::ops::svec_ops::geometry::rotate::rotate) and the
+/// [`warp`](crate::ops::svec_ops::geometry::warp) functions.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Default)]
+pub enum Interpolation {
+    Nearest,
+    #[default]
+    Bilinear,
+    /// Catmull-Rom cubic, integer results are clamped to the type range.
+    Bicubic,
+    /// Windowed sinc over 6x6 pixels, integer results are clamped to the type range.
+    Lanczos3,
+}
+
+/// How pixels outside the image are made up, shown for a row `abcd`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Border {
+    /// `xx|abcd|xx`, normalized values per channel, one value for all channels or empty for zero.
+    Constant(Vec<f32>),
+    /// `aa|abcd|dd`
+    Replicate,
+    /// `ba|abcd|dc`
+    Reflect,
+    /// `cb|abcd|cb`, reflection without repeating the edge pixel.
+    Reflect101,
+    /// `cd|abcd|ab`
+    Wrap,
+}
+
+/// Weight ramp across tile overlaps when a [`Merger`](crate::ops::svec_ops::tile::Merger) blends tiles.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Default)]
+pub enum Feather {
+    /// Straight ramp from the tile edge to the end of the overlap.
+    Linear,
+    /// Raised-cosine ramp, flat at both ends of the overlap.
+    #[default]
+    Cosine,
+}
+
+/// Which part of the image [`resize_to_fill`](crate::ops::svec_ops::resize::fir::ResizeSVec::resize_to_fill)
+/// keeps when cropping the overflow.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Default)]
+pub enum Anchor {
+    TopLeft,
+    Top,
+    TopRight,
+    Left,
+    #[default]
+    Center,
+    Right,
+    BottomLeft,
+    Bottom,
+    BottomRight,
+}
+
+/// Output format for [`svec_encode`](crate::ops::save::save::svec_encode).
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum ImageFormat {
+    Png,
+    Jpeg,
+    WebP,
+    Tiff,
+    Jxl,
+    Exr,
+    Gif,
+}
+
+/// What a viewer does with a frame's area before drawing the next animation frame.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Default)]
+pub enum Disposal {
+    /// Leave the frame in place.
+    #[default]
+    Keep,
+    /// Clear the area to the background (transparent).
+    Background,
+    /// Restore what was there before the frame was drawn.
+    Previous,
+}
+
+/// Deflate effort used by the PNG encoder.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Default)]
+pub enum PngCompression {
+    #[default]
+    Fast,
+    Default,
+    Best,
+}
+
+/// Scanline filter applied before PNG compression; `Adaptive` picks one per row.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Default)]
+pub enum PngFilter {
+    NoFilter,
+    Sub,
+    Up,
+    Avg,
+    Paeth,
+    #[default]
+    Adaptive,
+}
+
+/// Compression scheme written into TIFF strips.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Default)]
+pub enum TiffCompression {
+    #[default]
+    None,
+    Lzw,
+    Deflate,
+    PackBits,
+}
+
+/// Layer blend mode stored in a PSD layer record, see [`read_psd_layers`](crate::ops::read::psd::read_psd_layers).
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum PsdBlendMode {
+    /// Group mode: children blend straight into the backdrop.
+    PassThrough,
+    Normal,
+    Dissolve,
+    Darken,
+    Multiply,
+    ColorBurn,
+    LinearBurn,
+    DarkerColor,
+    Lighten,
+    Screen,
+    ColorDodge,
+    LinearDodge,
+    LighterColor,
+    Overlay,
+    SoftLight,
+    HardLight,
+    VividLight,
+    LinearLight,
+    PinLight,
+    HardMix,
+    Difference,
+    Exclusion,
+    Subtract,
+    Divide,
+    Hue,
+    Saturation,
+    Color,
+    Luminosity,
+    /// Unknown four-byte key, composited as `Normal`.
+    Other([u8; 4]),
+}
+
+/// Container format reported by [`probe_buffer`](crate::ops::read::probe::probe_buffer).
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum FileFormat {
+    Png,
+    Jpeg,
+    Gif,
+    WebP,
+    Tiff,
+    Bmp,
+    Psd,
+    Exr,
+    Jxl,
+    /// Recognised by the `image` crate fallback (TGA, ICO, QOI, PNM, HDR, ...).
+    Other,
+}
+
+/// Color model stored in a file, as reported by [`probe_buffer`](crate::ops::read::probe::probe_buffer).
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum ColorType {
+    Gray,
+    GrayAlpha,
+    Rgb,
+    Rgba,
+    Cmyk,
+    /// Palette indices, a single stored channel.
+    Indexed,
+    Lab,
+    Other,
+}
@@ -1,3 +1,5 @@
+use crate::enums::CVTColor;
+use pepecore_array::PixelType;
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -6,8 +8,18 @@ pub enum DecodeError {
     PsdDecodingError(String),
     #[error("ImgDecode Error: {0}")]
     ImgDecodingError(String),
+    #[error("ExrDecode Error: {0}")]
+    ExrDecodingError(String),
+    #[error("JxlDecode Error: {0}")]
+    JxlDecodingError(String),
+    #[error("Unsupported format: {0}")]
+    UnsupportedFormat(String),
+    #[error("Icc Error: {0}")]
+    IccError(String),
     #[error("File open Error: {0}")]
     FileOpenError(String),
+    #[error(transparent)]
+    SVecError(#[from] pepecore_array::error::Error),
 }
 #[derive(Error, Debug)]
 pub enum SaveError {
@@ -17,6 +29,24 @@ pub enum SaveError {
     GraySaveError(String),
     #[error("Jxl save Error: {0}")]
     JxlSaveError(String),
+    #[error("Jpeg save Error: {0}")]
+    JpegSaveError(String),
+    #[error("Png save Error: {0}")]
+    PngSaveError(String),
+    #[error("WebP save Error: {0}")]
+    WebpSaveError(String),
+    #[error("Tiff save Error: {0}")]
+    TiffSaveError(String),
+    #[error("Exr save Error: {0}")]
+    ExrSaveError(String),
+    #[error("Gif save Error: {0}")]
+    GifSaveError(String),
+    #[error("Animation save Error: {0}")]
+    AnimationSaveError(String),
+    #[error("{format} can't store {pixel_type:?} samples without quantising, convert the image first")]
+    UnsupportedPixelTypeSaveError { format: String, pixel_type: PixelType },
+    #[error(transparent)]
+    IoError(#[from] std::io::Error),
     #[error("Unsupported Channel Save Error: channel - {0}")]
     UnsupportedChannelSaveError(String),
 }
@@ -29,4 +59,49 @@ pub enum HalftoneError {
     DotSizeMismatch(usize, usize),
     #[error("dot_circle returned invalid data: {0}")]
     DotCircleError(String),
+    #[error("Dithering needs at least 2 levels, got {0}")]
+    InvalidLevels(usize),
+    #[error("Palette dithering expects an RGB image and RGB palette triplets, got {channels} channels and {len} palette values")]
+    PaletteMismatch { channels: usize, len: usize },
+}
+#[derive(Debug, Error)]
+pub enum CvtColorError {
+    #[error(transparent)]
+    SVecError(#[from] pepecore_array::error::Error),
+    #[error("Conversion {0:?} changes the channel count and cannot be applied in-place to a view")]
+    UnsupportedOnView(CVTColor),
+    #[error("Conversion expects {expected} channels, found {actual}")]
+    ChannelMismatch { expected: usize, actual: usize },
+}
+#[derive(Debug, Error)]
+pub enum ArithmeticError {
+    #[error(transparent)]
+    SVecError(#[from] pepecore_array::error::Error),
+    #[error("Shape mismatch: {0:?} and {1:?} cannot be combined")]
+    ShapeMismatch((usize, usize, Option<usize>), (usize, usize, Option<usize>)),
+    #[error("Compositing expects GRAYA or RGBA source over matching destination, found {src} and {dst} channels")]
+    UnsupportedChannels { src: usize, dst: usize },
+}
+#[derive(Debug, Error)]
+pub enum GeometryError {
+    #[error(transparent)]
+    SVecError(#[from] pepecore_array::error::Error),
+    #[error("Transform matrix is singular and cannot be inverted")]
+    SingularMatrix,
+}
+#[derive(Debug, Error)]
+pub enum TileError {
+    #[error(transparent)]
+    SVecError(#[from] pepecore_array::error::Error),
+    #[error("Overlap {overlap} must be smaller than the tile size {tile}")]
+    InvalidOverlap { overlap: usize, tile: usize },
+    #[error("Shape mismatch: expected {expected:?}, found {actual:?}")]
+    ShapeMismatch {
+        expected: (usize, usize, Option<usize>),
+        actual: (usize, usize, Option<usize>),
+    },
+    #[error("Tile pixel type {actual:?} differs from {expected:?}")]
+    PixelTypeMismatch { expected: PixelType, actual: PixelType },
+    #[error("No tile was added for part of the output")]
+    Incomplete,
 }
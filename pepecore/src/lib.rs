@@ -2,11 +2,16 @@
 //!
 //! This crate provides:
 //! - **Array and SVec** structures for image data representation (`array::svec`).
+//! - **Strided views** (`array::SVecView`, `array::SVecViewMut`) to process crops, channels or tiles in place.
 //! - **Image decoding** from file paths or byte buffers (`read::read_in_path`).
 //! - **Image saving** to various formats (`save::save`).
+//! - **Arithmetic and blending** (`arithmetic`, `arithmetic_scalar`, `over`).
+//! - **Frequency-domain filtering** (`frequency_filter`) and spectrum export (`array::fft`).
 //! - **Color conversions** (grayscale, YCbCr, CMYK, channel swaps) via `cvt_color`.
 //! - **Halftone effects** (`halftone`, `rotate_halftone`).
 //! - **Screentone effects** (`screentone`, `rotate_screentone`).
+//! - **Error-diffusion dithering** to levels or palettes (`dither`, `dither_palette`).
+//! - **Descreening** of scanned halftones (`descreen`, `estimate_screen`).
 //!
 //! # Usage Example
 //!
@@ -32,7 +37,7 @@
 //! cvt_color(&mut img, CVTColor::RGB2Gray_709);
 //!
 //! // Apply a halftone effect:
-//! screentone(&mut img, 5, &DotType::CIRCLE);
+//! screentone(&mut img, 5, &DotType::CIRCLE).unwrap();
 //!
 //!
 //! // Save result as PNG:
@@ -49,14 +54,30 @@ pub use ops::svec_ops::line::line_draw::draw_lines;
 pub use ops::svec_ops::line::objects::{Line, Point};
 // Re-export common types and functions
 pub use global_params::rayon_mode;
+pub use ops::read::frames;
+pub use ops::read::probe;
+pub use ops::read::psd;
 pub use ops::read::read;
+pub use ops::read::region;
 pub use ops::save::save;
 pub use ops::svec_ops::real_size;
+pub use ops::svec_ops::tile;
 
+pub use ops::svec_ops::arithmetic::blend::over;
+pub use ops::svec_ops::arithmetic::element::{arithmetic, arithmetic_scalar};
 pub use ops::svec_ops::color_levels;
 pub use ops::svec_ops::crop::crop;
 pub use ops::svec_ops::cvtcolor::cvt_color;
+pub use ops::svec_ops::frequency::frequency_filter;
+pub use ops::svec_ops::geometry::flip::{flip_h, flip_v, rot90, rot180, rot270, transpose};
+pub use ops::svec_ops::geometry::pad::{Padding, pad, pad_to_multiple};
+pub use ops::svec_ops::geometry::rotate::rotate;
+pub use ops::svec_ops::geometry::warp::{perspective_transform, warp_affine, warp_perspective};
+pub use ops::svec_ops::halftone::descreen::{descreen, estimate_screen};
+pub use ops::svec_ops::halftone::dither::{dither, dither_palette};
 pub use ops::svec_ops::halftone::halftone::{halftone, rotate_halftone, ssaa_halftone, ssaa_rotate_halftone};
-pub use ops::svec_ops::halftone::screentone::{rotate_screentone, screentone, ssaa_rotate_screentone, ssaa_screentone};
+pub use ops::svec_ops::halftone::screentone::{
+    rotate_screentone, rotate_screentone_view, screentone, screentone_view, ssaa_rotate_screentone, ssaa_screentone,
+};
 pub use ops::svec_ops::jpeg::encode::jpeg_compress;
 pub use ops::svec_ops::normalize::NormalizeSVec;
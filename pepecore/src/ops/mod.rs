@@ -1,3 +1,4 @@
 pub mod read;
+pub mod region;
 pub mod save;
 pub mod svec_ops;
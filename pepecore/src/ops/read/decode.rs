@@ -2,12 +2,19 @@
 //!
 //! This module provides functions to decode raw bytes of PSD files as well as other image formats
 //! into `SVec`, using different channel configurations (gray, rgb, rgba, gray+a) and dynamic
-//! data types (U8, U16, F32).
+//! data types (U8, U16, F32). OpenEXR is decoded separately through the `exr` crate.
 use std::io::Cursor;
 
+use crate::enums::ImgColor;
 use crate::errors::DecodeError;
-use crate::errors::DecodeError::{ImgDecodingError, PsdDecodingError};
-use image::DynamicImage;
+use crate::errors::DecodeError::{ExrDecodingError, ImgDecodingError, JxlDecodingError, PsdDecodingError};
+use crate::ops::read::metadata::{dynamic_icc_to_srgb, icc_to_srgb, reset_exif_orientation};
+use crate::ops::read::options::{ImageMetadata, ReadOptions};
+use exr::prelude::traits::{ReadChannels, ReadLayers};
+use image::metadata::Orientation;
+use image::{DynamicImage, ImageBuffer, ImageDecoder, Luma, LumaA, Pixel, Rgb, Rgba};
+use jxl_oxide::image::BitDepth;
+use jxl_oxide::{AuxBoxData, JxlImage};
 use pepecore_array::{ImgData, SVec, Shape};
 use zune_core::bytestream::ZCursor;
 use zune_psd::PSDDecoder;
@@ -23,7 +30,7 @@ use zune_psd::PSDDecoder;
 /// # Returns
 ///
 /// A tuple `(height, width)` of the PSD canvas.
-fn decode_size_psd(bytes: &[u8]) -> (u32, u32) {
+pub(crate) fn decode_size_psd(bytes: &[u8]) -> (u32, u32) {
     let mut height: u32 = 0;
     let mut width: u32 = 0;
     height += bytes[3] as u32;
@@ -55,7 +62,7 @@ pub fn psd_din_decode(buffer: &[u8]) -> Result<SVec, DecodeError> {
 
     let (height, width) = decode_size_psd(size_bites);
     Ok(if buffer[23] == 16 {
-        SVec::new(
+        SVec::try_new(
             Shape::new(height as usize, width as usize, channels),
             ImgData::U16(unsafe {
                 let len = px.len() / 2;
@@ -69,9 +76,9 @@ pub fn psd_din_decode(buffer: &[u8]) -> Result<SVec, DecodeError> {
                 }
                 vec
             }),
-        )
+        )?
     } else {
-        SVec::new(Shape::new(height as usize, width as usize, channels), ImgData::U8(px))
+        SVec::try_new(Shape::new(height as usize, width as usize, channels), ImgData::U8(px))?
     })
 }
 /// Decode PSD buffer into RGB SVec.
@@ -85,7 +92,7 @@ pub fn psd_rgb_decode(buffer: &[u8]) -> Result<SVec, DecodeError> {
     let px = decoder.decode_raw().map_err(|e| PsdDecodingError(format!("{:?}", e)))?;
 
     let (height, width) = decode_size_psd(size_bites);
-    Ok(SVec::new(
+    Ok(SVec::try_new(
         Shape::new(height as usize, width as usize, Some(3)),
         if buffer[23] == 16 {
             ImgData::U16(if channels == 3 {
@@ -131,7 +138,7 @@ pub fn psd_rgb_decode(buffer: &[u8]) -> Result<SVec, DecodeError> {
         } else {
             return Err(PsdDecodingError(format!("Unexpected channel count = {}", channels)));
         },
-    ))
+    )?)
 }
 /// Decode PSD buffer into RGBA SVec, adding full alpha channel.
 ///
@@ -144,7 +151,7 @@ pub fn psd_rgba_decode(buffer: &[u8]) -> Result<SVec, DecodeError> {
     let px = decoder.decode_raw().map_err(|e| PsdDecodingError(format!("{:?}", e)))?;
 
     let (height, width) = decode_size_psd(size_bites);
-    Ok(SVec::new(
+    Ok(SVec::try_new(
         Shape::new(height as usize, width as usize, Some(4)),
         if buffer[23] == 16 {
             ImgData::U16(if channels == 3 {
@@ -186,9 +193,9 @@ pub fn psd_rgba_decode(buffer: &[u8]) -> Result<SVec, DecodeError> {
             let mut vec = Vec::with_capacity((height * width * 4) as usize);
             for i in 0..px.len() / 3 {
                 // Добавляем три элемента из старого вектора
-                vec.push(vec[i * 3]);
-                vec.push(vec[i * 3 + 1]);
-                vec.push(vec[i * 3 + 2]);
+                vec.push(px[i * 3]);
+                vec.push(px[i * 3 + 1]);
+                vec.push(px[i * 3 + 2]);
                 vec.push(u8::MAX);
             }
             ImgData::U8(vec)
@@ -202,7 +209,7 @@ pub fn psd_rgba_decode(buffer: &[u8]) -> Result<SVec, DecodeError> {
         } else {
             return Err(PsdDecodingError(format!("Unexpected channel count = {}", channels)));
         },
-    ))
+    )?)
 }
 /// Decode PSD buffer to grayscale SVec, converting RGB using BT.709.
 ///
@@ -215,7 +222,7 @@ pub fn psd_gray_decode(buffer: &[u8]) -> Result<SVec, DecodeError> {
     let px = decoder.decode_raw().map_err(|e| PsdDecodingError(format!("{:?}", e)))?;
 
     let (height, width) = decode_size_psd(size_bites);
-    Ok(SVec::new(
+    Ok(SVec::try_new(
         Shape::new(height as usize, width as usize, None),
         if buffer[23] == 16 {
             ImgData::U16(if channels == 3 {
@@ -275,7 +282,7 @@ pub fn psd_gray_decode(buffer: &[u8]) -> Result<SVec, DecodeError> {
         } else {
             return Err(PsdDecodingError(format!("Unexpected channel count = {}", channels)));
         },
-    ))
+    )?)
 }
 /// Decode PSD buffer to grayscale with alpha SVec.
 ///
@@ -288,12 +295,12 @@ pub fn psd_graya_decode(buffer: &[u8]) -> Result<SVec, DecodeError> {
     let px = decoder.decode_raw().map_err(|e| PsdDecodingError(format!("{:?}", e)))?;
 
     let (height, width) = decode_size_psd(size_bites);
-    Ok(SVec::new(
+    Ok(SVec::try_new(
         Shape::new(height as usize, width as usize, Some(2)),
         if buffer[23] == 16 {
             ImgData::U16(if channels == 3 {
                 unsafe {
-                    let len = px.len() / 3; // Так как каждый пиксель состоит из 3 компонентов, каждый по 2 байта
+                    let len = px.len() / 6; // Так как каждый пиксель состоит из 3 компонентов, каждый по 2 байта
                     let ptr = px.as_ptr();
                     let mut vec = Vec::with_capacity(len);
 
@@ -351,43 +358,162 @@ pub fn psd_graya_decode(buffer: &[u8]) -> Result<SVec, DecodeError> {
         } else {
             return Err(PsdDecodingError(format!("Unexpected channel count = {}", channels)));
         },
-    ))
+    )?)
+}
+
+fn load_image(buffer: &[u8]) -> Result<DynamicImage, DecodeError> {
+    image::ImageReader::new(Cursor::new(buffer))
+        .with_guessed_format()
+        .map_err(|e| ImgDecodingError(format!("{:?}", e)))?
+        .decode()
+        .map_err(|e| ImgDecodingError(format!("{:?}", e)))
+}
+
+/// Decode a common image buffer with its ICC profile and EXIF, see
+/// [`read_in_buffer_with`](crate::ops::read::read::read_in_buffer_with).
+///
+/// Orientation is applied and the ICC conversion done on the decoded image, before it is
+/// converted to `img_color`.
+pub fn img_decode_with(buffer: &[u8], img_color: ImgColor, options: ReadOptions) -> Result<(SVec, ImageMetadata), DecodeError> {
+    let err = |e| ImgDecodingError(format!("{:?}", e));
+    let mut decoder = image::ImageReader::new(Cursor::new(buffer))
+        .with_guessed_format()
+        .map_err(|e| ImgDecodingError(format!("{:?}", e)))?
+        .into_decoder()
+        .map_err(err)?;
+    let mut metadata = ImageMetadata {
+        icc_profile: decoder.icc_profile().map_err(err)?,
+        exif: decoder.exif_metadata().map_err(err)?,
+    };
+    let orientation = decoder.orientation().map_err(err)?;
+    let mut img = DynamicImage::from_decoder(decoder).map_err(err)?;
+    if options.apply_orientation && orientation != Orientation::NoTransforms {
+        img.apply_orientation(orientation);
+        if let Some(exif) = &mut metadata.exif {
+            reset_exif_orientation(exif);
+        }
+    }
+    if options.icc_to_srgb {
+        if let Some(icc) = &metadata.icc_profile {
+            if dynamic_icc_to_srgb(&mut img, icc)? {
+                metadata.icc_profile = None;
+            }
+        }
+    }
+    Ok((img_color_from(&img, img_color)?, metadata))
+}
+
+/// Convert an already decoded `image` buffer to `img_color`.
+pub(crate) fn img_color_from(img: &DynamicImage, img_color: ImgColor) -> Result<SVec, DecodeError> {
+    match img_color {
+        ImgColor::DYNAMIC => img_din_from(img),
+        ImgColor::GRAY => img_gray_from(img),
+        ImgColor::RGB => img_rgb_from(img),
+        ImgColor::RGBA => img_rgba_from(img),
+        ImgColor::GRAYA => img_graya_from(img),
+    }
+}
+
+/// Interleaved samples decoded outside the `image` crate (TIFF chunks, PNG rows).
+pub(crate) enum RawSamples {
+    U8(Vec<u8>),
+    U16(Vec<u16>),
+    F32(Vec<f32>),
+}
+
+/// Wrap raw samples in an `image` buffer. `image` has no float gray, so float gray and
+/// gray+alpha are replicated to RGB and RGBA.
+pub(crate) fn raw_to_dynamic(
+    width: usize,
+    height: usize,
+    channels: usize,
+    samples: RawSamples,
+) -> Result<DynamicImage, DecodeError> {
+    fn buffer<P: Pixel>(
+        width: usize,
+        height: usize,
+        data: Vec<P::Subpixel>,
+    ) -> Result<ImageBuffer<P, Vec<P::Subpixel>>, DecodeError> {
+        ImageBuffer::from_raw(width as u32, height as u32, data)
+            .ok_or_else(|| ImgDecodingError("Image data is truncated".to_string()))
+    }
+    Ok(match (samples, channels) {
+        (RawSamples::U8(data), 1) => buffer::<Luma<u8>>(width, height, data)?.into(),
+        (RawSamples::U8(data), 2) => buffer::<LumaA<u8>>(width, height, data)?.into(),
+        (RawSamples::U8(data), 3) => buffer::<Rgb<u8>>(width, height, data)?.into(),
+        (RawSamples::U8(data), 4) => buffer::<Rgba<u8>>(width, height, data)?.into(),
+        (RawSamples::U16(data), 1) => buffer::<Luma<u16>>(width, height, data)?.into(),
+        (RawSamples::U16(data), 2) => buffer::<LumaA<u16>>(width, height, data)?.into(),
+        (RawSamples::U16(data), 3) => buffer::<Rgb<u16>>(width, height, data)?.into(),
+        (RawSamples::U16(data), 4) => buffer::<Rgba<u16>>(width, height, data)?.into(),
+        (RawSamples::F32(data), 1) => {
+            let rgb = data.iter().flat_map(|&v| [v, v, v]).collect();
+            buffer::<Rgb<f32>>(width, height, rgb)?.into()
+        }
+        (RawSamples::F32(data), 2) => {
+            let rgba = data.chunks_exact(2).flat_map(|p| [p[0], p[0], p[0], p[1]]).collect();
+            buffer::<Rgba<f32>>(width, height, rgba)?.into()
+        }
+        (RawSamples::F32(data), 3) => buffer::<Rgb<f32>>(width, height, data)?.into(),
+        (RawSamples::F32(data), 4) => buffer::<Rgba<f32>>(width, height, data)?.into(),
+        (_, channels) => return Err(ImgDecodingError(format!("Unsupported channel count {}", channels))),
+    })
+}
+
+/// Channel count of a TIFF color type, 8/16-bit integer or 32-bit float samples only.
+pub(crate) fn tiff_channels(color: tiff::ColorType) -> Result<usize, DecodeError> {
+    match color {
+        tiff::ColorType::Gray(8 | 16 | 32) => Ok(1),
+        tiff::ColorType::GrayA(8 | 16 | 32) => Ok(2),
+        tiff::ColorType::RGB(8 | 16 | 32) => Ok(3),
+        tiff::ColorType::RGBA(8 | 16 | 32) => Ok(4),
+        color => Err(ImgDecodingError(format!("Unsupported TIFF color type {:?}", color))),
+    }
+}
+
+pub(crate) fn tiff_samples(result: tiff::decoder::DecodingResult) -> Result<RawSamples, DecodeError> {
+    match result {
+        tiff::decoder::DecodingResult::U8(data) => Ok(RawSamples::U8(data)),
+        tiff::decoder::DecodingResult::U16(data) => Ok(RawSamples::U16(data)),
+        tiff::decoder::DecodingResult::F32(data) => Ok(RawSamples::F32(data)),
+        _ => Err(ImgDecodingError("Unsupported TIFF sample format".to_string())),
+    }
+}
+
+pub(crate) fn tiff_err(e: tiff::TiffError) -> DecodeError {
+    ImgDecodingError(format!("{:?}", e))
 }
 
 /// Decode common image buffer into dynamic SVec (all color modes).
 ///
 /// Uses `image` crate to detect format and return proper channel count.
 pub fn img_din_decode(buffer: &[u8]) -> Result<SVec, DecodeError> {
-    let img = image::ImageReader::new(Cursor::new(buffer))
-        .with_guessed_format()
-        .map_err(|e| ImgDecodingError(format!("{:?}", e)))?
-        .decode()
-        .map_err(|e| ImgDecodingError(format!("{:?}", e)))?;
+    img_din_from(&load_image(buffer)?)
+}
+fn img_din_from(img: &DynamicImage) -> Result<SVec, DecodeError> {
     let width = img.width() as usize;
     let height = img.height() as usize;
     Ok(match &img {
-        DynamicImage::ImageLuma8(img) => SVec::new(Shape::new(height, width, None), ImgData::U8(img.as_raw().clone())),
-        DynamicImage::ImageLumaA8(img) => SVec::new(Shape::new(height, width, Some(2)), ImgData::U8(img.as_raw().clone())),
-        DynamicImage::ImageRgb8(img) => SVec::new(Shape::new(height, width, Some(3)), ImgData::U8(img.as_raw().clone())),
-        DynamicImage::ImageRgba8(img) => SVec::new(Shape::new(height, width, Some(4)), ImgData::U8(img.as_raw().clone())),
-        DynamicImage::ImageLuma16(img) => SVec::new(Shape::new(height, width, None), ImgData::U16(img.as_raw().clone())),
-        DynamicImage::ImageLumaA16(img) => SVec::new(Shape::new(height, width, Some(2)), ImgData::U16(img.as_raw().clone())),
-        DynamicImage::ImageRgb16(img) => SVec::new(Shape::new(height, width, Some(3)), ImgData::U16(img.as_raw().clone())),
-        DynamicImage::ImageRgba16(img) => SVec::new(Shape::new(height, width, Some(4)), ImgData::U16(img.as_raw().clone())),
-        DynamicImage::ImageRgb32F(img) => SVec::new(Shape::new(height, width, Some(3)), ImgData::F32(img.as_raw().clone())),
-        DynamicImage::ImageRgba32F(img) => SVec::new(Shape::new(height, width, Some(4)), ImgData::F32(img.as_raw().clone())),
+        DynamicImage::ImageLuma8(img) => SVec::try_new(Shape::new(height, width, None), ImgData::U8(img.as_raw().clone()))?,
+        DynamicImage::ImageLumaA8(img) => SVec::try_new(Shape::new(height, width, Some(2)), ImgData::U8(img.as_raw().clone()))?,
+        DynamicImage::ImageRgb8(img) => SVec::try_new(Shape::new(height, width, Some(3)), ImgData::U8(img.as_raw().clone()))?,
+        DynamicImage::ImageRgba8(img) => SVec::try_new(Shape::new(height, width, Some(4)), ImgData::U8(img.as_raw().clone()))?,
+        DynamicImage::ImageLuma16(img) => SVec::try_new(Shape::new(height, width, None), ImgData::U16(img.as_raw().clone()))?,
+        DynamicImage::ImageLumaA16(img) => SVec::try_new(Shape::new(height, width, Some(2)), ImgData::U16(img.as_raw().clone()))?,
+        DynamicImage::ImageRgb16(img) => SVec::try_new(Shape::new(height, width, Some(3)), ImgData::U16(img.as_raw().clone()))?,
+        DynamicImage::ImageRgba16(img) => SVec::try_new(Shape::new(height, width, Some(4)), ImgData::U16(img.as_raw().clone()))?,
+        DynamicImage::ImageRgb32F(img) => SVec::try_new(Shape::new(height, width, Some(3)), ImgData::F32(img.as_raw().clone()))?,
+        DynamicImage::ImageRgba32F(img) => SVec::try_new(Shape::new(height, width, Some(4)), ImgData::F32(img.as_raw().clone()))?,
         _ => return Err(ImgDecodingError("Unsupported image color mod".to_string())),
     })
 }
 pub fn img_rgb_decode(buffer: &[u8]) -> Result<SVec, DecodeError> {
-    let img = image::ImageReader::new(Cursor::new(buffer))
-        .with_guessed_format()
-        .map_err(|e| ImgDecodingError(format!("{:?}", e)))?
-        .decode()
-        .map_err(|e| ImgDecodingError(format!("{:?}", e)))?;
+    img_rgb_from(&load_image(buffer)?)
+}
+fn img_rgb_from(img: &DynamicImage) -> Result<SVec, DecodeError> {
     let width = img.width() as usize;
     let height = img.height() as usize;
-    Ok(SVec::new(
+    Ok(SVec::try_new(
         Shape::new(height, width, Some(3)),
         match &img {
             DynamicImage::ImageLuma8(_) | DynamicImage::ImageLumaA8(_) | DynamicImage::ImageRgba8(_) => {
@@ -408,17 +534,15 @@ pub fn img_rgb_decode(buffer: &[u8]) -> Result<SVec, DecodeError> {
 
             _ => return Err(ImgDecodingError("Unsupported image color mod".to_string())),
         },
-    ))
+    )?)
 }
 pub fn img_rgba_decode(buffer: &[u8]) -> Result<SVec, DecodeError> {
-    let img = image::ImageReader::new(Cursor::new(buffer))
-        .with_guessed_format()
-        .map_err(|e| ImgDecodingError(format!("{:?}", e)))?
-        .decode()
-        .map_err(|e| ImgDecodingError(format!("{:?}", e)))?;
+    img_rgba_from(&load_image(buffer)?)
+}
+fn img_rgba_from(img: &DynamicImage) -> Result<SVec, DecodeError> {
     let width = img.width() as usize;
     let height = img.height() as usize;
-    Ok(SVec::new(
+    Ok(SVec::try_new(
         Shape::new(height, width, Some(4)),
         match &img {
             DynamicImage::ImageLuma8(_) | DynamicImage::ImageLumaA8(_) | DynamicImage::ImageRgb8(_) => {
@@ -438,17 +562,15 @@ pub fn img_rgba_decode(buffer: &[u8]) -> Result<SVec, DecodeError> {
 
             _ => return Err(ImgDecodingError("Unsupported image color mod".to_string())),
         },
-    ))
+    )?)
 }
 pub fn img_gray_decode(buffer: &[u8]) -> Result<SVec, DecodeError> {
-    let img = image::ImageReader::new(Cursor::new(buffer))
-        .with_guessed_format()
-        .map_err(|e| ImgDecodingError(format!("{:?}", e)))?
-        .decode()
-        .map_err(|e| ImgDecodingError(format!("{:?}", e)))?;
+    img_gray_from(&load_image(buffer)?)
+}
+fn img_gray_from(img: &DynamicImage) -> Result<SVec, DecodeError> {
     let width = img.width() as usize;
     let height = img.height() as usize;
-    Ok(SVec::new(
+    Ok(SVec::try_new(
         Shape::new(height, width, None),
         match &img {
             DynamicImage::ImageRgba8(_) | DynamicImage::ImageLumaA8(_) | DynamicImage::ImageRgb8(_) => {
@@ -462,18 +584,16 @@ pub fn img_gray_decode(buffer: &[u8]) -> Result<SVec, DecodeError> {
             DynamicImage::ImageRgb32F(_) | DynamicImage::ImageRgba32F(_) => ImgData::F32(img.to_luma32f().as_raw().clone()),
             _ => return Err(ImgDecodingError("Unsupported image color mod".to_string())),
         },
-    ))
+    )?)
 }
 pub fn img_graya_decode(buffer: &[u8]) -> Result<SVec, DecodeError> {
-    let img = image::ImageReader::new(Cursor::new(buffer))
-        .with_guessed_format()
-        .map_err(|e| ImgDecodingError(format!("{:?}", e)))?
-        .decode()
-        .map_err(|e| ImgDecodingError(format!("{:?}", e)))?;
+    img_graya_from(&load_image(buffer)?)
+}
+fn img_graya_from(img: &DynamicImage) -> Result<SVec, DecodeError> {
     let width = img.width() as usize;
     let height = img.height() as usize;
-    Ok(SVec::new(
-        Shape::new(height, width, None),
+    Ok(SVec::try_new(
+        Shape::new(height, width, Some(2)),
         match &img {
             DynamicImage::ImageRgba8(_) | DynamicImage::ImageLuma8(_) | DynamicImage::ImageRgb8(_) => {
                 ImgData::U8(img.to_luma_alpha8().as_raw().clone())
@@ -486,5 +606,285 @@ pub fn img_graya_decode(buffer: &[u8]) -> Result<SVec, DecodeError> {
             DynamicImage::ImageRgb32F(_) | DynamicImage::ImageRgba32F(_) => ImgData::F32(img.to_luma_alpha32f().as_raw().clone()),
             _ => return Err(ImgDecodingError("Unsupported image color mod".to_string())),
         },
-    ))
+    )?)
+}
+
+/// Decode the first layer of an OpenEXR buffer into an `ImgData::F32` SVec.
+///
+/// Channels are matched by name (`R`, `G`, `B`, `A`, `Y`, case-insensitive, ignoring any
+/// `layer.` prefix). Missing alpha is filled with `1.0`, RGB is reduced to gray with Rec.709
+/// luminance weights and gray is replicated to RGB. With `ImgColor::DYNAMIC` files without
+/// recognised names keep all their channels, in the order EXR stores them (alphabetical).
+/// A layer whose only channel is alpha returns `ExrDecodingError`.
+pub fn exr_decode(buffer: &[u8], img_color: ImgColor) -> Result<SVec, DecodeError> {
+    let image = exr::prelude::read()
+        .no_deep_data()
+        .largest_resolution_level()
+        .all_channels()
+        .first_valid_layer()
+        .all_attributes()
+        .from_buffered(Cursor::new(buffer))
+        .map_err(|e| ExrDecodingError(format!("{:?}", e)))?;
+    let layer = image.layer_data;
+    let (width, height) = (layer.size.width(), layer.size.height());
+    let planes: Vec<(String, Vec<f32>)> = layer
+        .channel_data
+        .list
+        .iter()
+        .map(|channel| {
+            let name = channel.name.to_string();
+            let short = name.rsplit('.').next().unwrap_or("").to_ascii_uppercase();
+            (short, channel.sample_data.values_as_f32().collect())
+        })
+        .collect();
+    let find = |name: &str| planes.iter().find(|(n, _)| n == name).map(|(_, p)| p.as_slice());
+
+    let alpha = find("A");
+    let rgb = match (find("R"), find("G"), find("B")) {
+        (Some(r), Some(g), Some(b)) => Some([r, g, b]),
+        _ => None,
+    };
+    let gray = find("Y");
+    let color: Vec<&[f32]> = match (rgb, gray) {
+        (Some(rgb), _) => rgb.to_vec(),
+        (None, Some(y)) => vec![y],
+        (None, None) => {
+            if alpha.is_some() && planes.len() == 1 {
+                return Err(ExrDecodingError(
+                    "Layer has an alpha channel but no colour channels".to_string(),
+                ));
+            }
+            // no usable names, fall back to position
+            let unnamed: Vec<&[f32]> = planes.iter().map(|(_, p)| p.as_slice()).collect();
+            if img_color == ImgColor::DYNAMIC {
+                let channels = if unnamed.len() > 1 { Some(unnamed.len()) } else { None };
+                return Ok(SVec::try_new(
+                    Shape::new(height, width, channels),
+                    ImgData::F32(interleave(&unnamed)),
+                )?);
+            }
+            // alpha stays alpha, only the unrecognised channels can stand in for colour
+            let unnamed: Vec<&[f32]> = planes.iter().filter(|(n, _)| n != "A").map(|(_, p)| p.as_slice()).collect();
+            match unnamed.len() {
+                0 => return Err(ExrDecodingError("Layer has no channels".to_string())),
+                1 | 2 => unnamed[..1].to_vec(),
+                _ => unnamed[..3].to_vec(),
+            }
+        }
+    };
+    let ones;
+    let alpha = match alpha {
+        Some(a) => a,
+        None => {
+            ones = vec![1.0f32; width * height];
+            &ones
+        }
+    };
+    let luma;
+    let gray = |color: &[&[f32]]| -> Vec<f32> {
+        if color.len() == 1 {
+            return color[0].to_vec();
+        }
+        (0..width * height)
+            .map(|i| 0.2126 * color[0][i] + 0.7152 * color[1][i] + 0.0722 * color[2][i])
+            .collect()
+    };
+    let (channels, planes): (Option<usize>, Vec<&[f32]>) = match img_color {
+        ImgColor::DYNAMIC => {
+            let mut planes = color.clone();
+            if find("A").is_some() {
+                planes.push(alpha);
+            }
+            ((planes.len() > 1).then_some(planes.len()), planes)
+        }
+        ImgColor::GRAY => {
+            luma = gray(&color);
+            (None, vec![&luma])
+        }
+        ImgColor::GRAYA => {
+            luma = gray(&color);
+            (Some(2), vec![&luma, alpha])
+        }
+        ImgColor::RGB | ImgColor::RGBA => {
+            let mut planes = if color.len() == 1 { vec![color[0]; 3] } else { color.clone() };
+            if img_color == ImgColor::RGBA {
+                planes.push(alpha);
+            }
+            (Some(planes.len()), planes)
+        }
+    };
+    Ok(SVec::try_new(
+        Shape::new(height, width, channels),
+        ImgData::F32(interleave(&planes)),
+    )?)
+}
+
+fn interleave(planes: &[&[f32]]) -> Vec<f32> {
+    let len = planes.first().map_or(0, |p| p.len());
+    let mut out = Vec::with_capacity(len * planes.len());
+    for i in 0..len {
+        out.extend(planes.iter().map(|p| p[i]));
+    }
+    out
+}
+
+/// Decode the first frame of a JPEG XL buffer, see [`jxl_decode_with`].
+pub fn jxl_decode(buffer: &[u8], img_color: ImgColor) -> Result<SVec, DecodeError> {
+    let image = jxl_open(buffer)?;
+    let (width, height, channels, samples) = jxl_samples(&image)?;
+    jxl_color(width, height, channels, samples, img_color)
+}
+
+/// Decode the first frame of a JPEG XL buffer with its ICC profile and EXIF.
+///
+/// Integer samples of up to 8 bits come out as `u8`, up to 16 bits as `u16`; deeper integer and
+/// float samples come out as `f32`. The decoder always applies the orientation from the
+/// codestream, so the returned EXIF orientation is reset to 1 regardless of
+/// `options.apply_orientation`. CMYK images return `JxlDecodingError`.
+pub fn jxl_decode_with(buffer: &[u8], img_color: ImgColor, options: ReadOptions) -> Result<(SVec, ImageMetadata), DecodeError> {
+    let image = jxl_open(buffer)?;
+    let (width, height, channels, samples) = jxl_samples(&image)?;
+    let mut img = jxl_color(width, height, channels, samples, img_color)?;
+    let exif = match image.aux_boxes().first_exif() {
+        Ok(AuxBoxData::Data(exif)) => exif.payload().get(exif.tiff_header_offset() as usize..).map(|e| e.to_vec()),
+        _ => None,
+    };
+    let mut metadata = ImageMetadata {
+        // the rendered profile describes the pixels we return, it matches the embedded one
+        // unless the image was stored in XYB
+        icc_profile: image.original_icc().map(|_| image.rendered_icc()),
+        exif,
+    };
+    if let Some(exif) = &mut metadata.exif {
+        reset_exif_orientation(exif);
+    }
+    if options.icc_to_srgb {
+        if let Some(icc) = &metadata.icc_profile {
+            if icc_to_srgb(&mut img, icc)? {
+                metadata.icc_profile = None;
+            }
+        }
+    }
+    Ok((img, metadata))
+}
+
+fn jxl_open(buffer: &[u8]) -> Result<JxlImage, DecodeError> {
+    JxlImage::builder()
+        .read(Cursor::new(buffer))
+        .map_err(|e| JxlDecodingError(format!("{:?}", e)))
+}
+
+/// Interleaved samples of the first frame at the depth the file was stored with.
+fn jxl_samples(image: &JxlImage) -> Result<(usize, usize, usize, RawSamples), DecodeError> {
+    if image.pixel_format().has_black() {
+        return Err(JxlDecodingError("CMYK images are not supported".to_string()));
+    }
+    let render = image.render_frame(0).map_err(|e| JxlDecodingError(format!("{:?}", e)))?;
+    // colour and alpha only, other extra channels (depth, spot colours) are dropped
+    let mut stream = render.stream();
+    let (width, height, channels) = (stream.width() as usize, stream.height() as usize, stream.channels() as usize);
+    let len = width * height * channels;
+    let samples = match image.image_header().metadata.bit_depth {
+        BitDepth::IntegerSample { bits_per_sample: 1..=8 } => {
+            let mut data = vec![0u8; len];
+            stream.write_to_buffer(&mut data);
+            RawSamples::U8(data)
+        }
+        BitDepth::IntegerSample { bits_per_sample: 9..=16 } => {
+            let mut data = vec![0u16; len];
+            stream.write_to_buffer(&mut data);
+            RawSamples::U16(data)
+        }
+        _ => {
+            let mut data = vec![0f32; len];
+            stream.write_to_buffer(&mut data);
+            RawSamples::F32(data)
+        }
+    };
+    Ok((width, height, channels, samples))
+}
+
+fn jxl_color(
+    width: usize,
+    height: usize,
+    channels: usize,
+    samples: RawSamples,
+    img_color: ImgColor,
+) -> Result<SVec, DecodeError> {
+    if img_color != ImgColor::DYNAMIC {
+        return img_color_from(&raw_to_dynamic(width, height, channels, samples)?, img_color);
+    }
+    // kept as is, `raw_to_dynamic` would widen float gray to RGB
+    let data = match samples {
+        RawSamples::U8(data) => ImgData::U8(data),
+        RawSamples::U16(data) => ImgData::U16(data),
+        RawSamples::F32(data) => ImgData::F32(data),
+    };
+    Ok(SVec::try_new(
+        Shape::new(height, width, (channels > 1).then_some(channels)),
+        data,
+    )?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::enums::ImageFormat;
+    use crate::read::read_in_buffer;
+    use crate::save::{SaveOptions, svec_encode};
+    use exr::prelude::{AnyChannel, AnyChannels, Encoding, FlatSamples, Image, Layer, LayerAttributes, WritableImage};
+
+    #[test]
+    fn test_exr_roundtrip() {
+        let rgba: Vec<f32> = (0..8 * 8 * 4).map(|i| i as f32 * 0.25 - 10.0).collect();
+        let img = SVec::new(Shape::new(8, 8, Some(4)), ImgData::F32(rgba.clone()));
+        let exr = svec_encode(&img, ImageFormat::Exr, SaveOptions::default()).unwrap();
+        let back = read_in_buffer(&exr, ImgColor::DYNAMIC).unwrap();
+        assert_eq!(back.shape(), (8, 8, Some(4)));
+        assert_eq!(back.get_data::<f32>().unwrap(), rgba);
+
+        let rgb = read_in_buffer(&exr, ImgColor::RGB).unwrap();
+        assert_eq!(rgb.get_data::<f32>().unwrap()[..3], rgba[..3]);
+        let gray = read_in_buffer(&exr, ImgColor::GRAYA).unwrap();
+        assert_eq!(gray.shape(), (8, 8, Some(2)));
+        let luma = 0.2126 * rgba[0] + 0.7152 * rgba[1] + 0.0722 * rgba[2];
+        assert!((gray.get_data::<f32>().unwrap()[0] - luma).abs() < 1e-5);
+        assert_eq!(gray.get_data::<f32>().unwrap()[1], rgba[3]);
+
+        let img = SVec::new(Shape::new(2, 2, None), ImgData::U16(vec![0, 1, 32768, 65535]));
+        let exr = svec_encode(&img, ImageFormat::Exr, SaveOptions::default()).unwrap();
+        let back = read_in_buffer(&exr, ImgColor::RGBA).unwrap();
+        let back = back.get_data::<f32>().unwrap();
+        assert_eq!(back[12..], [1.0, 1.0, 1.0, 1.0]);
+        assert_eq!((back[4] * 65535.0).round(), 1.0);
+    }
+
+    fn exr_with(names: &[&str]) -> Vec<u8> {
+        let channels = names
+            .iter()
+            .enumerate()
+            .map(|(c, name)| AnyChannel::new(*name, FlatSamples::F32(vec![c as f32 * 0.25; 4])))
+            .collect();
+        let layer = Layer::new(
+            (2, 2),
+            LayerAttributes::default(),
+            Encoding::FAST_LOSSLESS,
+            AnyChannels::sort(channels),
+        );
+        let mut out = Cursor::new(Vec::new());
+        Image::from_layer(layer).write().to_buffered(&mut out).unwrap();
+        out.into_inner()
+    }
+
+    #[test]
+    fn test_exr_alpha_is_not_colour() {
+        let alpha_only = exr_with(&["A"]);
+        for img_color in [ImgColor::DYNAMIC, ImgColor::GRAY, ImgColor::RGBA] {
+            assert!(matches!(exr_decode(&alpha_only, img_color), Err(ExrDecodingError(_))));
+        }
+
+        // "A" sorts before "depth", the unnamed channel is still the one used as gray
+        let img = exr_decode(&exr_with(&["depth", "A"]), ImgColor::GRAYA).unwrap();
+        assert_eq!(img.get_data::<f32>().unwrap()[..2], [0.0, 0.25]);
+    }
 }
@@ -0,0 +1,348 @@
+//! Frame-by-frame reading of animated GIF, WebP and APNG files and multi-page TIFFs.
+//!
+//! [`read_frames`] decodes lazily, one [`Frame`] per `next()`. Animation frames come out already
+//! composited onto the full canvas, so every frame is a complete picture; `disposal` is what the
+//! file asks for after showing the frame, kept so the sequence can be written back with
+//! [`svec_save_frames`](crate::ops::save::save::svec_save_frames). TIFF pages are independent
+//! images and may differ in size. Any other format yields its single image.
+//!
+//! Animation decoders return 8-bit RGBA, so `ImgColor::DYNAMIC` gives RGBA `u8` frames for GIF,
+//! WebP and APNG.
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//! use pepecore::enums::ImgColor;
+//! use pepecore::frames::read_frames_path;
+//!
+//! for frame in read_frames_path("anim.gif", ImgColor::RGBA).unwrap() {
+//!     let frame = frame.unwrap();
+//!     println!("{:?} shown for {:?}", frame.image.shape(), frame.delay);
+//! }
+//! ```
+
+use crate::enums::{Disposal, ImgColor};
+use crate::errors::DecodeError;
+use crate::errors::DecodeError::{FileOpenError, ImgDecodingError};
+use crate::ops::read::decode::{img_color_from, raw_to_dynamic, tiff_channels, tiff_err, tiff_samples};
+use crate::ops::read::probe::{be32, bytes, color_table, le32, skip_sub_blocks};
+use crate::ops::read::read::read_in_buffer;
+use filebuffer::FileBuffer;
+use image::codecs::gif::GifDecoder;
+use image::codecs::png::PngDecoder;
+use image::codecs::webp::WebPDecoder;
+use image::{AnimationDecoder, DynamicImage};
+use pepecore_array::SVec;
+use std::fmt::Debug;
+use std::io::{Cursor, Read, Seek};
+use std::path::Path;
+use std::time::Duration;
+use tiff::decoder::Decoder as TiffDecoder;
+
+/// One animation frame or document page.
+#[derive(Debug, Clone)]
+pub struct Frame {
+    pub image: SVec,
+    /// How long the frame is shown; zero for TIFF pages and still images.
+    pub delay: Duration,
+    pub disposal: Disposal,
+}
+
+impl Frame {
+    /// A frame with no delay and `Disposal::Keep`.
+    pub fn new(image: SVec) -> Self {
+        Self {
+            image,
+            delay: Duration::ZERO,
+            disposal: Disposal::Keep,
+        }
+    }
+}
+
+/// Lazy iterator over the frames of a file, see [`read_frames`].
+///
+/// Decoding stops after the first error.
+pub struct Frames<'a> {
+    inner: Box<dyn Iterator<Item = Result<Frame, DecodeError>> + 'a>,
+}
+
+impl Iterator for Frames<'_> {
+    type Item = Result<Frame, DecodeError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next()
+    }
+}
+
+/// Read the frames of an image file, see [`read_frames`].
+///
+/// The file stays memory-mapped while the iterator is alive.
+pub fn read_frames_path<P: Debug + AsRef<Path> + ?Sized>(path: &P, img_color: ImgColor) -> Result<Frames<'static>, DecodeError> {
+    let buffer = FileBuffer::open(path).map_err(|e| FileOpenError(format!("Path: {:?} FileBuffer error: {:?}", path, e)))?;
+    frames(buffer, img_color)
+}
+
+/// Iterate over the frames of an animated GIF, WebP or APNG, or the pages of a TIFF.
+///
+/// Still images yield a single frame decoded with [`read_in_buffer`].
+///
+/// # Errors
+///
+/// Fails up front on unreadable headers (and, for still images, with the errors of
+/// [`read_in_buffer`]); frames that fail to decode are returned as `ImgDecodingError` items.
+pub fn read_frames(buffer: &[u8], img_color: ImgColor) -> Result<Frames<'_>, DecodeError> {
+    frames(buffer, img_color)
+}
+
+enum Container {
+    Gif(Vec<Disposal>),
+    Apng(Vec<Disposal>),
+    WebP(Vec<Disposal>),
+    Tiff,
+    Still,
+}
+
+fn frames<'a, B: AsRef<[u8]> + 'a>(buffer: B, img_color: ImgColor) -> Result<Frames<'a>, DecodeError> {
+    let data = buffer.as_ref();
+    let container = match data {
+        [b'G', b'I', b'F', b'8', ..] => Container::Gif(gif_disposals(data)),
+        [0x89, b'P', b'N', b'G', ..] => apng_disposals(data).map_or(Container::Still, Container::Apng),
+        [b'R', b'I', b'F', b'F', _, _, _, _, b'W', b'E', b'B', b'P', ..] => {
+            webp_disposals(data).map_or(Container::Still, Container::WebP)
+        }
+        [b'I', b'I', 42 | 43, 0, ..] | [b'M', b'M', 0, 42 | 43, ..] => Container::Tiff,
+        _ => Container::Still,
+    };
+    let err = |e| ImgDecodingError(format!("{:?}", e));
+    match container {
+        Container::Gif(disposals) => {
+            let decoder = GifDecoder::new(Cursor::new(buffer)).map_err(err)?;
+            Ok(animation(decoder.into_frames(), disposals, img_color))
+        }
+        Container::Apng(disposals) => {
+            let decoder = PngDecoder::new(Cursor::new(buffer)).map_err(err)?.apng().map_err(err)?;
+            Ok(animation(decoder.into_frames(), disposals, img_color))
+        }
+        Container::WebP(disposals) => {
+            let decoder = WebPDecoder::new(Cursor::new(buffer)).map_err(err)?;
+            Ok(animation(decoder.into_frames(), disposals, img_color))
+        }
+        Container::Tiff => tiff_pages(buffer, img_color),
+        Container::Still => {
+            let frame = Frame::new(read_in_buffer(buffer.as_ref(), img_color)?);
+            Ok(Frames {
+                inner: Box::new(std::iter::once(Ok(frame))),
+            })
+        }
+    }
+}
+
+fn animation(frames: image::Frames<'_>, disposals: Vec<Disposal>, img_color: ImgColor) -> Frames<'_> {
+    let mut disposals = disposals.into_iter();
+    let mut failed = false;
+    let inner = frames.map_while(move |frame| {
+        if failed {
+            return None;
+        }
+        let frame = frame.map_err(|e| ImgDecodingError(format!("{:?}", e))).and_then(|frame| {
+            let delay = Duration::from(frame.delay());
+            let image = img_color_from(&DynamicImage::ImageRgba8(frame.into_buffer()), img_color)?;
+            Ok(Frame {
+                image,
+                delay,
+                disposal: disposals.next().unwrap_or_default(),
+            })
+        });
+        failed = frame.is_err();
+        Some(frame)
+    });
+    Frames { inner: Box::new(inner) }
+}
+
+fn tiff_pages<'a, B: AsRef<[u8]> + 'a>(buffer: B, img_color: ImgColor) -> Result<Frames<'a>, DecodeError> {
+    let mut decoder = Some(TiffDecoder::new(Cursor::new(buffer)).map_err(tiff_err)?);
+    let mut first = true;
+    let inner = std::iter::from_fn(move || {
+        let d = decoder.as_mut()?;
+        if !first {
+            if !d.more_images() {
+                decoder = None;
+                return None;
+            }
+            if let Err(e) = d.next_image() {
+                decoder = None;
+                return Some(Err(tiff_err(e)));
+            }
+        }
+        first = false;
+        let page = tiff_page(d, img_color);
+        if page.is_err() {
+            decoder = None;
+        }
+        Some(page)
+    });
+    Ok(Frames { inner: Box::new(inner) })
+}
+
+fn tiff_page<R: Read + Seek>(decoder: &mut TiffDecoder<R>, img_color: ImgColor) -> Result<Frame, DecodeError> {
+    let (width, height) = decoder.dimensions().map_err(tiff_err)?;
+    let channels = tiff_channels(decoder.colortype().map_err(tiff_err)?)?;
+    let samples = tiff_samples(decoder.read_image().map_err(tiff_err)?)?;
+    let img = raw_to_dynamic(width as usize, height as usize, channels, samples)?;
+    Ok(Frame::new(img_color_from(&img, img_color)?))
+}
+
+// The disposal scans are best effort: they stop at malformed data and leave the real error to
+// the decoder, frames past the end of the list get `Disposal::Keep`.
+
+/// Disposal method from each Graphic Control Extension, in image descriptor order.
+fn gif_disposals(buf: &[u8]) -> Vec<Disposal> {
+    let mut disposals = Vec::new();
+    let Ok([flags]) = bytes(buf, 10) else {
+        return disposals;
+    };
+    let mut at = 13 + color_table(flags);
+    let mut pending = Disposal::Keep;
+    while let Ok([block]) = bytes(buf, at) {
+        let next = match block {
+            0x2c => {
+                disposals.push(std::mem::take(&mut pending));
+                bytes(buf, at + 9).and_then(|[flags]| skip_sub_blocks(buf, at + 11 + color_table(flags)))
+            }
+            0x21 => {
+                if let Ok([0xf9, _, packed]) = bytes(buf, at + 1) {
+                    pending = match (packed >> 2) & 7 {
+                        2 => Disposal::Background,
+                        3 => Disposal::Previous,
+                        _ => Disposal::Keep,
+                    };
+                }
+                skip_sub_blocks(buf, at + 2)
+            }
+            _ => break,
+        };
+        match next {
+            Ok(next) => at = next,
+            Err(_) => break,
+        }
+    }
+    disposals
+}
+
+/// `dispose_op` of each `fcTL` chunk, `None` for a PNG without `acTL`.
+fn apng_disposals(buf: &[u8]) -> Option<Vec<Disposal>> {
+    let mut animated = false;
+    let mut disposals = Vec::new();
+    let mut at = 8;
+    while let (Ok(len), Some(kind)) = (be32(buf, at), buf.get(at + 4..at + 8)) {
+        match kind {
+            b"acTL" => animated = true,
+            b"fcTL" => disposals.push(match buf.get(at + 32) {
+                Some(1) => Disposal::Background,
+                Some(2) => Disposal::Previous,
+                _ => Disposal::Keep,
+            }),
+            b"IEND" => break,
+            _ => {}
+        }
+        at += 12 + len;
+    }
+    animated.then_some(disposals)
+}
+
+/// Dispose bit of each `ANMF` chunk, `None` for a still WebP.
+fn webp_disposals(buf: &[u8]) -> Option<Vec<Disposal>> {
+    match (buf.get(12..16), buf.get(20)) {
+        (Some(b"VP8X"), Some(flags)) if flags & 0x02 != 0 => {}
+        _ => return None,
+    }
+    let mut disposals = Vec::new();
+    let mut at = 12;
+    while let (Ok(len), Some(kind)) = (le32(buf, at + 4), buf.get(at..at + 4)) {
+        if kind == b"ANMF" {
+            disposals.push(match buf.get(at + 23) {
+                Some(flags) if flags & 1 != 0 => Disposal::Background,
+                _ => Disposal::Keep,
+            });
+        }
+        at += 8 + len + (len & 1);
+    }
+    Some(disposals)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::enums::ImageFormat;
+    use crate::save::{AnimationOptions, SaveOptions, svec_encode_frames};
+    use pepecore_array::{ImgData, Shape};
+    use tiff::encoder::TiffEncoder;
+    use tiff::encoder::colortype::{Gray8, RGB8};
+
+    fn solid(rgb: [u8; 3]) -> SVec {
+        SVec::new(Shape::new(4, 6, Some(3)), ImgData::U8(rgb.repeat(24)))
+    }
+
+    #[test]
+    fn test_animation_roundtrip() {
+        let colors = [[255, 0, 0], [0, 255, 0], [0, 0, 255]];
+        let disposals = [Disposal::Keep, Disposal::Background, Disposal::Keep];
+        let frames: Vec<Frame> = colors
+            .iter()
+            .zip(disposals)
+            .enumerate()
+            .map(|(i, (&rgb, disposal))| Frame {
+                image: solid(rgb),
+                delay: Duration::from_millis(100 * (i as u64 + 1)),
+                disposal,
+            })
+            .collect();
+        let options = SaveOptions {
+            animation: AnimationOptions { loop_count: 2 },
+            ..Default::default()
+        };
+        for format in [ImageFormat::Gif, ImageFormat::Png, ImageFormat::WebP] {
+            let encoded = svec_encode_frames(&frames, format, options).unwrap();
+            let read: Vec<Frame> = read_frames(&encoded, ImgColor::RGB)
+                .unwrap()
+                .collect::<Result<_, _>>()
+                .unwrap();
+            assert_eq!(read.len(), 3, "{:?}", format);
+            for (read, frame) in read.iter().zip(&frames) {
+                assert_eq!(read.image.shape(), (4, 6, Some(3)), "{:?}", format);
+                assert_eq!(read.image.get_data::<u8>().unwrap(), frame.image.get_data::<u8>().unwrap());
+                assert_eq!((read.delay, read.disposal), (frame.delay, frame.disposal), "{:?}", format);
+            }
+        }
+
+        let mismatched = [
+            Frame::new(solid([0, 0, 0])),
+            Frame::new(SVec::new(Shape::new(1, 1, None), ImgData::U8(vec![0]))),
+        ];
+        assert!(svec_encode_frames(&mismatched, ImageFormat::Gif, options).is_err());
+        assert!(svec_encode_frames(&frames, ImageFormat::Jpeg, options).is_err());
+    }
+
+    #[test]
+    fn test_tiff_pages_and_still_images() {
+        let mut tiff = Cursor::new(Vec::new());
+        let mut encoder = TiffEncoder::new(&mut tiff).unwrap();
+        encoder.write_image::<Gray8>(3, 2, &[0, 50, 100, 150, 200, 250]).unwrap();
+        encoder.write_image::<RGB8>(1, 1, &[10, 20, 30]).unwrap();
+        let pages: Vec<Frame> = read_frames(tiff.get_ref(), ImgColor::DYNAMIC)
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(pages.len(), 2);
+        assert_eq!(pages[0].image.shape(), (2, 3, None));
+        assert_eq!(pages[0].image.get_data::<u8>().unwrap(), &[0, 50, 100, 150, 200, 250]);
+        assert_eq!(pages[1].image.shape(), (1, 1, Some(3)));
+        assert_eq!(pages[1].delay, Duration::ZERO);
+
+        // a plain PNG is a single frame, not an APNG
+        let png = crate::save::svec_encode(&solid([1, 2, 3]), ImageFormat::Png, SaveOptions::default()).unwrap();
+        let still: Vec<_> = read_frames(&png, ImgColor::GRAY).unwrap().collect();
+        assert_eq!(still.len(), 1);
+        assert_eq!(still[0].as_ref().unwrap().image.shape(), (4, 6, None));
+    }
+}
@@ -0,0 +1,241 @@
+//! ICC and EXIF handling for [`read_in_path_with`](crate::ops::read::read::read_in_path_with).
+//!
+//! Color management goes through `moxcms`. Only RGB and gray profiles are applied, and only
+//! when the pixel layout matches the profile: a CMYK profile on an image the decoder already
+//! turned into RGB is returned as-is instead of producing wrong colors.
+
+use crate::errors::DecodeError;
+use crate::errors::DecodeError::IccError;
+use image::DynamicImage;
+use moxcms::{ColorProfile, DataColorSpace, Layout, TransformOptions};
+use pepecore_array::{ImgData, SVec};
+
+/// Convert `img` from the `icc` profile to sRGB in place.
+///
+/// Gray and gray+alpha images need a gray profile, RGB and RGBA an RGB one. Returns `false`
+/// (leaving the pixels untouched) when the profile doesn't match the image or the pixel type
+/// isn't `u8`, `u16` or `f32`.
+///
+/// # Errors
+///
+/// Returns `IccError` when the profile can't be parsed or no transform can be built from it.
+pub fn icc_to_srgb(img: &mut SVec, icc: &[u8]) -> Result<bool, DecodeError> {
+    let channels = img.shape.get_channels().unwrap_or(1);
+    img.with_interleaved(|img| match &mut img.data {
+        ImgData::U8(data) => transform(icc, channels, Samples::U8(data)),
+        ImgData::U16(data) => transform(icc, channels, Samples::U16(data)),
+        ImgData::F32(data) => transform(icc, channels, Samples::F32(data)),
+        _ => Ok(false),
+    })
+}
+
+/// [`icc_to_srgb`] on a decoded `image` buffer, before it is turned into an `SVec`.
+pub(crate) fn dynamic_icc_to_srgb(img: &mut DynamicImage, icc: &[u8]) -> Result<bool, DecodeError> {
+    match img {
+        DynamicImage::ImageLuma8(b) => transform(icc, 1, Samples::U8(b)),
+        DynamicImage::ImageLumaA8(b) => transform(icc, 2, Samples::U8(b)),
+        DynamicImage::ImageRgb8(b) => transform(icc, 3, Samples::U8(b)),
+        DynamicImage::ImageRgba8(b) => transform(icc, 4, Samples::U8(b)),
+        DynamicImage::ImageLuma16(b) => transform(icc, 1, Samples::U16(b)),
+        DynamicImage::ImageLumaA16(b) => transform(icc, 2, Samples::U16(b)),
+        DynamicImage::ImageRgb16(b) => transform(icc, 3, Samples::U16(b)),
+        DynamicImage::ImageRgba16(b) => transform(icc, 4, Samples::U16(b)),
+        DynamicImage::ImageRgb32F(b) => transform(icc, 3, Samples::F32(b)),
+        DynamicImage::ImageRgba32F(b) => transform(icc, 4, Samples::F32(b)),
+        _ => Ok(false),
+    }
+}
+
+enum Samples<'a> {
+    U8(&'a mut [u8]),
+    U16(&'a mut [u16]),
+    F32(&'a mut [f32]),
+}
+
+fn transform(icc: &[u8], channels: usize, samples: Samples) -> Result<bool, DecodeError> {
+    let src = ColorProfile::new_from_slice(icc).map_err(|e| IccError(format!("{:?}", e)))?;
+    let (layout, gray) = match channels {
+        1 => (Layout::Gray, true),
+        2 => (Layout::GrayAlpha, true),
+        3 => (Layout::Rgb, false),
+        4 => (Layout::Rgba, false),
+        _ => return Ok(false),
+    };
+    let expected = if gray { DataColorSpace::Gray } else { DataColorSpace::Rgb };
+    if src.color_space != expected {
+        return Ok(false);
+    }
+    let srgb = ColorProfile::new_srgb();
+    let dst = if gray {
+        // sRGB tone curve on a gray profile, so gray stays gray
+        let mut dst = ColorProfile::new_gray_with_gamma(2.2);
+        dst.gray_trc = srgb.red_trc.clone();
+        dst
+    } else {
+        srgb
+    };
+    let options = TransformOptions::default();
+    let err = |e| IccError(format!("{:?}", e));
+    match samples {
+        Samples::U8(data) => {
+            let src_data = data.to_vec();
+            let t = src.create_transform_8bit(layout, &dst, layout, options).map_err(err)?;
+            t.transform(&src_data, data).map_err(err)?;
+        }
+        Samples::U16(data) => {
+            let src_data = data.to_vec();
+            let t = src.create_transform_16bit(layout, &dst, layout, options).map_err(err)?;
+            t.transform(&src_data, data).map_err(err)?;
+        }
+        Samples::F32(data) => {
+            let src_data = data.to_vec();
+            let t = src.create_transform_f32(layout, &dst, layout, options).map_err(err)?;
+            t.transform(&src_data, data).map_err(err)?;
+        }
+    }
+    Ok(true)
+}
+
+/// Set the IFD0 orientation tag of a TIFF-structured EXIF block to 1 (upright).
+pub(crate) fn reset_exif_orientation(exif: &mut [u8]) {
+    let le = match exif.get(..4) {
+        Some([0x49, 0x49, 42, 0]) => true,
+        Some([0x4d, 0x4d, 0, 42]) => false,
+        _ => return,
+    };
+    let u16_at = |b: &[u8], at: usize| {
+        b.get(at..at + 2)
+            .map(|s| if le { u16::from_le_bytes([s[0], s[1]]) } else { u16::from_be_bytes([s[0], s[1]]) } as usize)
+    };
+    let ifd = match exif.get(4..8) {
+        Some(s) if le => u32::from_le_bytes([s[0], s[1], s[2], s[3]]) as usize,
+        Some(s) => u32::from_be_bytes([s[0], s[1], s[2], s[3]]) as usize,
+        None => return,
+    };
+    let Some(entries) = u16_at(exif, ifd) else {
+        return;
+    };
+    for i in 0..entries {
+        let entry = ifd + 2 + i * 12;
+        if u16_at(exif, entry) == Some(0x112) && u16_at(exif, entry + 2) == Some(3) && entry + 10 <= exif.len() {
+            let one = if le { 1u16.to_le_bytes() } else { 1u16.to_be_bytes() };
+            exif[entry + 8..entry + 10].copy_from_slice(&one);
+        }
+    }
+}
+
+/// ICC profile (resource 1039) and EXIF (resource 1058) from the PSD image resources section.
+pub(crate) fn psd_metadata(buffer: &[u8]) -> (Option<Vec<u8>>, Option<Vec<u8>>) {
+    let be32 = |at: usize| {
+        buffer
+            .get(at..at + 4)
+            .map(|s| u32::from_be_bytes([s[0], s[1], s[2], s[3]]) as usize)
+    };
+    let (mut icc, mut exif) = (None, None);
+    let Some(color_data) = be32(26) else {
+        return (icc, exif);
+    };
+    let start = 30 + color_data;
+    let Some(len) = be32(start) else {
+        return (icc, exif);
+    };
+    let end = (start + 4 + len).min(buffer.len());
+    let mut at = start + 4;
+    while at + 12 <= end && &buffer[at..at + 4] == b"8BIM" {
+        let id = u16::from_be_bytes([buffer[at + 4], buffer[at + 5]]);
+        // Pascal name padded to an even length, then the data padded the same way
+        let name_len = buffer[at + 6] as usize;
+        let size_at = at + 6 + (name_len + 2) / 2 * 2;
+        let Some(size) = be32(size_at) else {
+            break;
+        };
+        let data = buffer.get(size_at + 4..size_at + 4 + size);
+        match id {
+            1039 => icc = data.map(<[u8]>::to_vec),
+            1058 => exif = data.map(<[u8]>::to_vec),
+            _ => {}
+        }
+        at = size_at + 4 + size + (size & 1);
+    }
+    (icc, exif)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::enums::{ImageFormat, ImgColor};
+    use crate::ops::read::options::{ImageMetadata, ReadOptions};
+    use crate::read::read_in_buffer_with;
+    use crate::save::{SaveOptions, svec_encode_with_metadata};
+    use pepecore_array::Shape;
+
+    /// Little-endian EXIF with a single orientation entry.
+    fn exif(orientation: u16) -> Vec<u8> {
+        let mut exif = b"II*\0".to_vec();
+        exif.extend_from_slice(&8u32.to_le_bytes());
+        exif.extend_from_slice(&1u16.to_le_bytes());
+        exif.extend_from_slice(&0x112u16.to_le_bytes());
+        exif.extend_from_slice(&3u16.to_le_bytes());
+        exif.extend_from_slice(&1u32.to_le_bytes());
+        exif.extend_from_slice(&orientation.to_le_bytes());
+        exif.extend_from_slice(&[0, 0, 0, 0, 0, 0]);
+        exif
+    }
+
+    #[test]
+    fn test_orientation_applied_and_reset() {
+        // 2 rows x 3 columns, gray values 0..6
+        let img = SVec::new(Shape::new(2, 3, None), ImgData::U8(vec![0, 10, 20, 30, 40, 50]));
+        let metadata = ImageMetadata {
+            icc_profile: None,
+            exif: Some(exif(6)),
+        };
+        let webp = svec_encode_with_metadata(&img, ImageFormat::WebP, SaveOptions::default(), &metadata).unwrap();
+
+        let (upright, metadata) = read_in_buffer_with(&webp, ImgColor::GRAY, ReadOptions::default()).unwrap();
+        // orientation 6 is a clockwise quarter turn
+        assert_eq!(upright.shape(), (3, 2, None));
+        assert_eq!(upright.get_data::<u8>().unwrap(), &[30, 0, 40, 10, 50, 20]);
+        assert_eq!(metadata.exif, Some(exif(1)));
+
+        let options = ReadOptions {
+            apply_orientation: false,
+            ..Default::default()
+        };
+        let (raw, metadata) = read_in_buffer_with(&webp, ImgColor::GRAY, options).unwrap();
+        assert_eq!(raw.shape(), (2, 3, None));
+        assert_eq!(metadata.exif, Some(exif(6)));
+    }
+
+    #[test]
+    fn test_icc_roundtrip_and_srgb_conversion() {
+        let icc = ColorProfile::new_adobe_rgb().encode().unwrap();
+        let img = SVec::new(Shape::new(1, 2, Some(3)), ImgData::U8(vec![100, 150, 200, 20, 220, 40]));
+        let metadata = ImageMetadata {
+            icc_profile: Some(icc.clone()),
+            exif: Some(exif(1)),
+        };
+        for format in [ImageFormat::Png, ImageFormat::Jpeg, ImageFormat::WebP] {
+            let encoded = svec_encode_with_metadata(&img, format, SaveOptions::default(), &metadata).unwrap();
+            let (_, read) = read_in_buffer_with(&encoded, ImgColor::RGB, ReadOptions::default()).unwrap();
+            assert_eq!(read.icc_profile.as_ref(), Some(&icc), "{:?}", format);
+        }
+
+        let png = svec_encode_with_metadata(&img, ImageFormat::Png, SaveOptions::default(), &metadata).unwrap();
+        let options = ReadOptions {
+            icc_to_srgb: true,
+            ..Default::default()
+        };
+        let (srgb, read) = read_in_buffer_with(&png, ImgColor::RGB, options).unwrap();
+        assert!(read.icc_profile.is_none());
+        // Adobe RGB green is outside sRGB, red has to drop to keep the hue
+        let px = srgb.get_data::<u8>().unwrap();
+        assert!(px[4] >= 220 && px[3] < 20, "{:?}", px);
+        assert_ne!(px, img.get_data::<u8>().unwrap());
+
+        // an RGB profile is not applied to gray pixels
+        let mut gray = SVec::new(Shape::new(1, 2, None), ImgData::U8(vec![10, 200]));
+        assert!(!icc_to_srgb(&mut gray, &icc).unwrap());
+        assert_eq!(gray.get_data::<u8>().unwrap(), &[10, 200]);
+    }
+}
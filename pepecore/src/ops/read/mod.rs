@@ -1,2 +1,8 @@
 pub(crate) mod decode;
+pub mod frames;
+pub mod metadata;
+pub mod options;
+pub mod probe;
+pub mod psd;
 pub mod read;
+pub mod region;
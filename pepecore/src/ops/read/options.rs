@@ -0,0 +1,30 @@
+//! Settings and metadata for [`read_in_path_with`](crate::ops::read::read::read_in_path_with).
+//!
+//! `ReadOptions::default()` rotates by EXIF orientation and returns the ICC profile untouched.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReadOptions {
+    /// Rotate/flip by the EXIF orientation tag, which is then reset to 1 in the returned EXIF.
+    pub apply_orientation: bool,
+    /// Convert pixels from the embedded ICC profile to sRGB and drop the profile from the result.
+    pub icc_to_srgb: bool,
+}
+
+impl Default for ReadOptions {
+    fn default() -> Self {
+        Self {
+            apply_orientation: true,
+            icc_to_srgb: false,
+        }
+    }
+}
+
+/// Metadata carried from a decoded file, pass it to
+/// [`svec_save_with_metadata`](crate::ops::save::save::svec_save_with_metadata) to keep it.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct ImageMetadata {
+    /// Raw ICC profile.
+    pub icc_profile: Option<Vec<u8>>,
+    /// EXIF as a TIFF structure (starts with `II`/`MM`, no `Exif\0\0` prefix).
+    pub exif: Option<Vec<u8>>,
+}
@@ -0,0 +1,767 @@
+//! Header-only image probing.
+//!
+//! [`probe_path`] and [`probe_buffer`] report size, channel layout and frame count without
+//! decoding pixels. PNG, JPEG, GIF, WebP, TIFF, BMP, PSD, OpenEXR and JPEG XL headers are parsed
+//! here; anything else the `image` crate recognises is probed through its decoder headers.
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//! use pepecore::probe::probe_path;
+//!
+//! let info = probe_path("page.png").unwrap();
+//! println!("{:?} {}x{} {} ch", info.format, info.width, info.height, info.channels);
+//! ```
+
+use crate::enums::{ColorType, FileFormat};
+use crate::errors::DecodeError;
+use crate::errors::DecodeError::{FileOpenError, ImgDecodingError, UnsupportedFormat};
+use crate::ops::read::decode::decode_size_psd;
+use crate::ops::read::read::is_jxl;
+use filebuffer::FileBuffer;
+use image::{ExtendedColorType, ImageDecoder, ImageReader};
+use std::fmt::Debug;
+use std::io::Cursor;
+use std::path::Path;
+
+/// Image metadata read from headers.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct ImageInfo {
+    pub format: FileFormat,
+    pub width: usize,
+    pub height: usize,
+    /// Channels stored in the file, alpha included; `1` for palette images.
+    pub channels: usize,
+    /// Bits per stored sample.
+    pub bit_depth: u8,
+    /// Samples are IEEE floats (EXR, float TIFF, float JPEG XL).
+    pub float: bool,
+    pub color_type: ColorType,
+    /// Frames or pages; `None` when counting them would need decoding (animated JPEG XL).
+    pub frames: Option<usize>,
+}
+
+impl ImageInfo {
+    fn still(format: FileFormat, width: usize, height: usize, channels: usize, bit_depth: u8, color_type: ColorType) -> Self {
+        Self {
+            format,
+            width,
+            height,
+            channels,
+            bit_depth,
+            float: false,
+            color_type,
+            frames: Some(1),
+        }
+    }
+}
+
+/// Probe an image file, see [`probe_buffer`].
+///
+/// The file is memory-mapped, so only the header pages are actually read from disk.
+pub fn probe_path<P: Debug + AsRef<Path> + ?Sized>(path: &P) -> Result<ImageInfo, DecodeError> {
+    let buffer = FileBuffer::open(path).map_err(|e| FileOpenError(format!("Path: {:?} FileBuffer error: {:?}", path, e)))?;
+    probe_buffer(&buffer)
+}
+
+/// Read format, size, channels, bit depth, color type and frame count from image headers.
+///
+/// # Errors
+///
+/// Returns `UnsupportedFormat` for unrecognised data and `ImgDecodingError` for truncated or
+/// malformed headers.
+pub fn probe_buffer(buffer: &[u8]) -> Result<ImageInfo, DecodeError> {
+    match buffer {
+        [0x89, b'P', b'N', b'G', ..] => probe_png(buffer),
+        [0xff, 0xd8, ..] => probe_jpeg(buffer),
+        [b'G', b'I', b'F', b'8', ..] => probe_gif(buffer),
+        [b'R', b'I', b'F', b'F', _, _, _, _, b'W', b'E', b'B', b'P', ..] => probe_webp(buffer),
+        [b'I', b'I', 42 | 43, 0, ..] | [b'M', b'M', 0, 42 | 43, ..] => probe_tiff(buffer),
+        [b'B', b'M', ..] => probe_bmp(buffer),
+        [56, 66, 80, 83, ..] => probe_psd(buffer),
+        [0x76, 0x2f, 0x31, 0x01, ..] => probe_exr(buffer),
+        _ if is_jxl(buffer) => probe_jxl(buffer),
+        _ => probe_other(buffer),
+    }
+}
+
+fn truncated() -> DecodeError {
+    ImgDecodingError("Image header is truncated".to_string())
+}
+
+fn overflow() -> DecodeError {
+    ImgDecodingError("Image header has an out of range size or offset".to_string())
+}
+
+pub(crate) fn bytes<const N: usize>(buf: &[u8], at: usize) -> Result<[u8; N], DecodeError> {
+    buf.get(at..at + N).and_then(|b| b.try_into().ok()).ok_or_else(truncated)
+}
+
+fn be16(buf: &[u8], at: usize) -> Result<usize, DecodeError> {
+    Ok(u16::from_be_bytes(bytes(buf, at)?) as usize)
+}
+
+pub(crate) fn be32(buf: &[u8], at: usize) -> Result<usize, DecodeError> {
+    Ok(u32::from_be_bytes(bytes(buf, at)?) as usize)
+}
+
+fn le16(buf: &[u8], at: usize) -> Result<usize, DecodeError> {
+    Ok(u16::from_le_bytes(bytes(buf, at)?) as usize)
+}
+
+pub(crate) fn le32(buf: &[u8], at: usize) -> Result<usize, DecodeError> {
+    Ok(u32::from_le_bytes(bytes(buf, at)?) as usize)
+}
+
+fn probe_png(buf: &[u8]) -> Result<ImageInfo, DecodeError> {
+    let width = be32(buf, 16)?;
+    let height = be32(buf, 20)?;
+    let [bit_depth, color] = bytes(buf, 24)?;
+    let (channels, color_type) = match color {
+        0 => (1, ColorType::Gray),
+        2 => (3, ColorType::Rgb),
+        3 => (1, ColorType::Indexed),
+        4 => (2, ColorType::GrayAlpha),
+        6 => (4, ColorType::Rgba),
+        c => return Err(ImgDecodingError(format!("Unknown PNG color type {}", c))),
+    };
+    let mut info = ImageInfo::still(FileFormat::Png, width, height, channels, bit_depth, color_type);
+    // APNG declares its frame count in `acTL`, which must precede the first IDAT
+    let mut at = 8;
+    while at + 8 <= buf.len() {
+        let len = be32(buf, at)?;
+        match &buf[at + 4..at + 8] {
+            b"acTL" => {
+                info.frames = Some(be32(buf, at + 8)?);
+                break;
+            }
+            b"IDAT" | b"IEND" => break,
+            _ => at += 12 + len,
+        }
+    }
+    Ok(info)
+}
+
+fn probe_jpeg(buf: &[u8]) -> Result<ImageInfo, DecodeError> {
+    let mut at = 2;
+    loop {
+        // markers may be preceded by any number of 0xFF fill bytes
+        while buf.get(at) == Some(&0xff) && buf.get(at + 1) == Some(&0xff) {
+            at += 1;
+        }
+        let [ff, marker] = bytes(buf, at)?;
+        if ff != 0xff {
+            return Err(ImgDecodingError("Invalid JPEG marker".to_string()));
+        }
+        match marker {
+            0xc0..=0xcf if !matches!(marker, 0xc4 | 0xc8 | 0xcc) => {
+                let [precision] = bytes(buf, at + 4)?;
+                let height = be16(buf, at + 5)?;
+                let width = be16(buf, at + 7)?;
+                let [components] = bytes(buf, at + 9)?;
+                let color_type = match components {
+                    1 => ColorType::Gray,
+                    3 => ColorType::Rgb,
+                    4 => ColorType::Cmyk,
+                    _ => ColorType::Other,
+                };
+                return Ok(ImageInfo::still(
+                    FileFormat::Jpeg,
+                    width,
+                    height,
+                    components as usize,
+                    precision,
+                    color_type,
+                ));
+            }
+            0xd8 | 0x01 | 0xd0..=0xd7 => at += 2,
+            0xd9 | 0xda => return Err(ImgDecodingError("JPEG has no frame header".to_string())),
+            _ => at += 2 + be16(buf, at + 2)?,
+        }
+    }
+}
+
+fn probe_gif(buf: &[u8]) -> Result<ImageInfo, DecodeError> {
+    let width = le16(buf, 6)?;
+    let height = le16(buf, 8)?;
+    let [flags] = bytes(buf, 10)?;
+    let mut at = 13 + color_table(flags);
+    let mut frames = 0;
+    // walk the block structure, skipping image data sub-blocks without decompressing them
+    loop {
+        let [block] = bytes(buf, at)?;
+        match block {
+            0x2c => {
+                frames += 1;
+                let [flags] = bytes(buf, at + 9)?;
+                at = skip_sub_blocks(buf, at + 11 + color_table(flags))?;
+            }
+            0x21 => at = skip_sub_blocks(buf, at + 2)?,
+            0x3b => break,
+            b => return Err(ImgDecodingError(format!("Unknown GIF block {:#04x}", b))),
+        }
+    }
+    let mut info = ImageInfo::still(FileFormat::Gif, width, height, 1, 8, ColorType::Indexed);
+    info.frames = Some(frames);
+    Ok(info)
+}
+
+pub(crate) fn color_table(flags: u8) -> usize {
+    if flags & 0x80 != 0 { 3 << ((flags & 7) + 1) } else { 0 }
+}
+
+pub(crate) fn skip_sub_blocks(buf: &[u8], mut at: usize) -> Result<usize, DecodeError> {
+    loop {
+        let [len] = bytes(buf, at)?;
+        at += 1 + len as usize;
+        if len == 0 {
+            return Ok(at);
+        }
+    }
+}
+
+fn probe_webp(buf: &[u8]) -> Result<ImageInfo, DecodeError> {
+    let chunk: [u8; 4] = bytes(buf, 12)?;
+    let webp = |width, height, alpha: bool| {
+        let (channels, color_type) = if alpha { (4, ColorType::Rgba) } else { (3, ColorType::Rgb) };
+        ImageInfo::still(FileFormat::WebP, width, height, channels, 8, color_type)
+    };
+    match &chunk {
+        b"VP8 " => {
+            let width = le16(buf, 26)? & 0x3fff;
+            let height = le16(buf, 28)? & 0x3fff;
+            Ok(webp(width, height, false))
+        }
+        b"VP8L" => {
+            let bits = le32(buf, 21)?;
+            Ok(webp((bits & 0x3fff) + 1, ((bits >> 14) & 0x3fff) + 1, bits & (1 << 28) != 0))
+        }
+        b"VP8X" => {
+            let [flags] = bytes(buf, 20)?;
+            let [w0, w1, w2, h0, h1, h2] = bytes(buf, 24)?;
+            let width = u32::from_le_bytes([w0, w1, w2, 0]) as usize + 1;
+            let height = u32::from_le_bytes([h0, h1, h2, 0]) as usize + 1;
+            let mut info = webp(width, height, flags & 0x10 != 0);
+            if flags & 0x02 != 0 {
+                let mut frames = 0;
+                let mut at = 12;
+                while at + 8 <= buf.len() {
+                    if &buf[at..at + 4] == b"ANMF" {
+                        frames += 1;
+                    }
+                    let len = le32(buf, at + 4)?;
+                    at += 8 + len + (len & 1);
+                }
+                info.frames = Some(frames);
+            }
+            Ok(info)
+        }
+        _ => Err(ImgDecodingError("Unknown WebP chunk".to_string())),
+    }
+}
+
+fn probe_tiff(buf: &[u8]) -> Result<ImageInfo, DecodeError> {
+    let le = buf[0] == b'I';
+    let big = buf[2] == 43 || buf[3] == 43;
+    let num = |at: usize, size: usize| -> Result<usize, DecodeError> {
+        let b: [u8; 8] = match at.checked_add(size).and_then(|end| buf.get(at..end)) {
+            Some(s) => {
+                let mut b = [0u8; 8];
+                if le {
+                    b[..size].copy_from_slice(s);
+                } else {
+                    b[8 - size..].copy_from_slice(s);
+                }
+                b
+            }
+            None => return Err(truncated()),
+        };
+        Ok(if le { u64::from_le_bytes(b) } else { u64::from_be_bytes(b) } as usize)
+    };
+    // classic TIFF: 2-byte entry count, 12-byte entries, 4-byte offsets; BigTIFF widens all three
+    let (count_size, entry_size, offset_size) = if big { (8, 20, 8) } else { (2, 12, 4) };
+    let first = num(if big { 8 } else { 4 }, offset_size)?;
+
+    let (mut width, mut height, mut bits, mut samples, mut photometric, mut extra, mut float) = (0, 0, 1, 1, 1, 0, false);
+    let mut ifd = first;
+    let mut frames = 0;
+    while ifd != 0 && frames < 65536 {
+        let entries = num(ifd, count_size)?;
+        if frames == 0 {
+            for i in 0..entries {
+                let entry = ifd + count_size + i * entry_size;
+                let tag = num(entry, 2)?;
+                let kind = num(entry + 2, 2)?;
+                let count = num(entry + 4, offset_size)?;
+                let size: usize = match kind {
+                    1 | 2 | 6 | 7 => 1,
+                    3 | 8 => 2,
+                    16 | 17 => 8,
+                    _ => 4,
+                };
+                // the first value is inline when all of them fit, otherwise behind an offset
+                let value_at = entry + 4 + offset_size;
+                let at = if size.checked_mul(count).ok_or_else(overflow)? <= offset_size {
+                    value_at
+                } else {
+                    num(value_at, offset_size)?
+                };
+                let value = num(at, size)?;
+                match tag {
+                    256 => width = value,
+                    257 => height = value,
+                    258 => bits = value,
+                    262 => photometric = value,
+                    277 => samples = value,
+                    338 => extra = count,
+                    339 => float = value == 3,
+                    _ => {}
+                }
+            }
+        }
+        frames += 1;
+        let next = entries
+            .checked_mul(entry_size)
+            .and_then(|len| len.checked_add(ifd + count_size))
+            .ok_or_else(overflow)?;
+        ifd = num(next, offset_size)?;
+    }
+    let color_type = match (photometric, extra > 0) {
+        (0 | 1, false) => ColorType::Gray,
+        (0 | 1, true) => ColorType::GrayAlpha,
+        (2 | 6, false) => ColorType::Rgb,
+        (2 | 6, true) => ColorType::Rgba,
+        (3, _) => ColorType::Indexed,
+        (5, _) => ColorType::Cmyk,
+        (8..=10, _) => ColorType::Lab,
+        _ => ColorType::Other,
+    };
+    Ok(ImageInfo {
+        format: FileFormat::Tiff,
+        width,
+        height,
+        channels: samples,
+        bit_depth: bits as u8,
+        float,
+        color_type,
+        frames: Some(frames),
+    })
+}
+
+fn probe_bmp(buf: &[u8]) -> Result<ImageInfo, DecodeError> {
+    let (width, height, bpp) = if le32(buf, 14)? == 12 {
+        (le16(buf, 18)?, le16(buf, 20)?, le16(buf, 24)?)
+    } else {
+        let height = i32::from_le_bytes(bytes(buf, 22)?).unsigned_abs() as usize;
+        (le32(buf, 18)?, height, le16(buf, 28)?)
+    };
+    Ok(match bpp {
+        1..=8 => ImageInfo::still(FileFormat::Bmp, width, height, 1, bpp as u8, ColorType::Indexed),
+        32 => ImageInfo::still(FileFormat::Bmp, width, height, 4, 8, ColorType::Rgba),
+        16 => ImageInfo::still(FileFormat::Bmp, width, height, 3, 5, ColorType::Rgb),
+        _ => ImageInfo::still(FileFormat::Bmp, width, height, 3, 8, ColorType::Rgb),
+    })
+}
+
+fn probe_psd(buf: &[u8]) -> Result<ImageInfo, DecodeError> {
+    let header: [u8; 26] = bytes(buf, 0)?;
+    let (height, width) = decode_size_psd(&header[14..22]);
+    let channels = be16(&header, 12)?;
+    let depth = be16(&header, 22)?;
+    let colors = match be16(&header, 24)? {
+        0 | 1 | 8 => (1, ColorType::Gray),
+        2 => (1, ColorType::Indexed),
+        3 => (3, ColorType::Rgb),
+        4 => (4, ColorType::Cmyk),
+        9 => (3, ColorType::Lab),
+        _ => (channels, ColorType::Other),
+    };
+    let color_type = match colors {
+        (1, ColorType::Gray) if channels > 1 => ColorType::GrayAlpha,
+        (3, ColorType::Rgb) if channels > 3 => ColorType::Rgba,
+        (_, color) => color,
+    };
+    let mut info = ImageInfo::still(
+        FileFormat::Psd,
+        width as usize,
+        height as usize,
+        channels,
+        depth as u8,
+        color_type,
+    );
+    info.float = depth == 32;
+    Ok(info)
+}
+
+fn probe_exr(buf: &[u8]) -> Result<ImageInfo, DecodeError> {
+    let cstr = |at: usize| -> Result<(&[u8], usize), DecodeError> {
+        let len = buf
+            .get(at..)
+            .and_then(|s| s.iter().position(|&b| b == 0))
+            .ok_or_else(truncated)?;
+        Ok((&buf[at..at + len], at + len + 1))
+    };
+    let (mut width, mut height) = (0, 0);
+    let mut names: Vec<Vec<u8>> = Vec::new();
+    let mut bit_depth = 16;
+    let mut at = 8;
+    loop {
+        let (name, next) = cstr(at)?;
+        if name.is_empty() {
+            break;
+        }
+        let (_, next) = cstr(next)?;
+        let size = le32(buf, next)?;
+        let value = next + 4;
+        match name {
+            b"channels" => {
+                let mut c = value;
+                loop {
+                    let (channel, next) = cstr(c)?;
+                    if channel.is_empty() {
+                        break;
+                    }
+                    names.push(channel.to_vec());
+                    // 0 uint, 1 half, 2 float
+                    bit_depth = bit_depth.max(if le32(buf, next)? == 1 { 16 } else { 32 });
+                    c = next + 16;
+                }
+            }
+            b"dataWindow" => {
+                let [x0, y0, x1, y1] = [0, 4, 8, 12].map(|o| bytes(buf, value + o).map(i32::from_le_bytes));
+                width = (x1? as i64 - x0? as i64 + 1).max(0) as usize;
+                height = (y1? as i64 - y0? as i64 + 1).max(0) as usize;
+            }
+            _ => {}
+        }
+        at = value + size;
+    }
+    // channels of the first layer, named like decoding maps them
+    let has = |c: &str| {
+        names.iter().any(|n| {
+            let n = String::from_utf8_lossy(n);
+            n.rsplit('.').next().is_some_and(|s| s.eq_ignore_ascii_case(c))
+        })
+    };
+    let color_type = match (has("R") && has("G") && has("B"), has("Y"), has("A")) {
+        (true, _, false) => ColorType::Rgb,
+        (true, _, true) => ColorType::Rgba,
+        (_, true, false) => ColorType::Gray,
+        (_, true, true) => ColorType::GrayAlpha,
+        _ => ColorType::Other,
+    };
+    let mut info = ImageInfo::still(FileFormat::Exr, width, height, names.len(), bit_depth, color_type);
+    info.float = true;
+    Ok(info)
+}
+
+fn probe_jxl(buf: &[u8]) -> Result<ImageInfo, DecodeError> {
+    let codestream = if buf.starts_with(&[0xff, 0x0a]) {
+        buf
+    } else {
+        jxl_codestream(buf)?
+    };
+    let mut bits = BitReader {
+        buf: codestream,
+        pos: 16,
+    };
+    let (width, height) = bits.size_header()?;
+    let mut info = ImageInfo::still(FileFormat::Jxl, width, height, 3, 8, ColorType::Rgb);
+    // ImageMetadata; `all_default` means 8-bit sRGB without extra channels
+    if bits.bool()? {
+        return Ok(info);
+    }
+    if bits.bool()? {
+        bits.bits(3)?; // orientation
+        if bits.bool()? {
+            bits.size_header()?;
+        }
+        if bits.bool()? {
+            bits.preview_header()?;
+        }
+        if bits.bool()? {
+            // animation header: tps numerator/denominator, loops, timecodes
+            bits.u32([(0, 100), (0, 1000), (10, 1), (30, 1)])?;
+            bits.u32([(0, 1), (0, 1001), (8, 1), (10, 1)])?;
+            bits.u32([(0, 0), (3, 0), (16, 0), (32, 0)])?;
+            bits.bool()?;
+            info.frames = None;
+        }
+    }
+    let (depth, float) = bits.bit_depth()?;
+    info.bit_depth = depth;
+    info.float = float;
+    bits.bool()?; // modular_16bit_buffers
+    let extra = bits.u32([(0, 0), (0, 1), (4, 2), (12, 1)])?;
+    let mut alpha = false;
+    for _ in 0..extra {
+        if bits.bool()? {
+            alpha = true;
+            continue;
+        }
+        let kind = bits.enumeration()?;
+        alpha |= kind == 0;
+        bits.bit_depth()?;
+        bits.u32([(0, 0), (0, 3), (0, 4), (3, 1)])?;
+        let name_len = bits.u32([(0, 0), (4, 0), (5, 16), (10, 48)])?;
+        bits.skip(name_len * 8)?;
+        match kind {
+            0 => bits.skip(1)?,
+            2 => bits.skip(64)?,
+            5 => {
+                bits.u32([(0, 1), (2, 0), (4, 3), (8, 19)])?;
+            }
+            _ => {}
+        }
+    }
+    bits.bool()?; // xyb_encoded
+    let gray = if bits.bool()? {
+        false
+    } else {
+        bits.bool()?; // want_icc
+        bits.enumeration()? == 1
+    };
+    let colors = if gray { 1 } else { 3 };
+    info.channels = colors + extra;
+    info.color_type = match (gray, alpha) {
+        (true, false) => ColorType::Gray,
+        (true, true) => ColorType::GrayAlpha,
+        (false, false) => ColorType::Rgb,
+        (false, true) => ColorType::Rgba,
+    };
+    Ok(info)
+}
+
+/// Start of the codestream inside an ISO-BMFF container (`jxlc`, or the first `jxlp` part).
+fn jxl_codestream(buf: &[u8]) -> Result<&[u8], DecodeError> {
+    let mut at = 0;
+    while at + 8 <= buf.len() {
+        let mut len = be32(buf, at)?;
+        let mut header = 8;
+        if len == 1 {
+            len = u64::from_be_bytes(bytes(buf, at + 8)?) as usize;
+            header = 16;
+        }
+        match &buf[at + 4..at + 8] {
+            b"jxlc" => return buf.get(at + header..).ok_or_else(truncated),
+            b"jxlp" => return buf.get(at + header + 4..).ok_or_else(truncated),
+            _ if len == 0 => break,
+            _ => at += len,
+        }
+    }
+    Err(ImgDecodingError("JPEG XL container has no codestream box".to_string()))
+}
+
+/// LSB-first bit reader for JPEG XL headers.
+struct BitReader<'a> {
+    buf: &'a [u8],
+    pos: usize,
+}
+
+impl BitReader<'_> {
+    fn bits(&mut self, n: usize) -> Result<usize, DecodeError> {
+        let mut v = 0;
+        for i in 0..n {
+            let byte = *self.buf.get(self.pos / 8).ok_or_else(truncated)?;
+            v |= (((byte >> (self.pos % 8)) & 1) as usize) << i;
+            self.pos += 1;
+        }
+        Ok(v)
+    }
+
+    fn skip(&mut self, n: usize) -> Result<(), DecodeError> {
+        self.pos += n;
+        if self.pos > self.buf.len() * 8 {
+            Err(truncated())
+        } else {
+            Ok(())
+        }
+    }
+
+    fn bool(&mut self) -> Result<bool, DecodeError> {
+        Ok(self.bits(1)? == 1)
+    }
+
+    /// `U32` field: a 2-bit selector picks one of four `(bits, offset)` distributions.
+    fn u32(&mut self, dist: [(usize, usize); 4]) -> Result<usize, DecodeError> {
+        let (n, offset) = dist[self.bits(2)?];
+        Ok(self.bits(n)? + offset)
+    }
+
+    fn enumeration(&mut self) -> Result<usize, DecodeError> {
+        self.u32([(0, 0), (0, 1), (4, 2), (6, 18)])
+    }
+
+    fn size_header(&mut self) -> Result<(usize, usize), DecodeError> {
+        let dist = [(9, 1), (13, 1), (18, 1), (30, 1)];
+        let small = self.bool()?;
+        let height = if small { (self.bits(5)? + 1) * 8 } else { self.u32(dist)? };
+        let ratio = self.bits(3)?;
+        let width = match ratio {
+            0 if small => (self.bits(5)? + 1) * 8,
+            0 => self.u32(dist)?,
+            r => jxl_ratio(height, r),
+        };
+        Ok((width, height))
+    }
+
+    fn preview_header(&mut self) -> Result<(), DecodeError> {
+        let div8 = self.bool()?;
+        let dist = if div8 {
+            [(0, 16), (0, 32), (5, 1), (9, 33)]
+        } else {
+            [(6, 1), (8, 65), (10, 321), (12, 1345)]
+        };
+        self.u32(dist)?;
+        if self.bits(3)? == 0 {
+            self.u32(dist)?;
+        }
+        Ok(())
+    }
+
+    fn bit_depth(&mut self) -> Result<(u8, bool), DecodeError> {
+        if self.bool()? {
+            let bits = self.u32([(0, 32), (0, 16), (0, 24), (6, 1)])?;
+            self.bits(4)?;
+            Ok((bits as u8, true))
+        } else {
+            Ok((self.u32([(0, 8), (0, 10), (0, 12), (6, 1)])? as u8, false))
+        }
+    }
+}
+
+fn jxl_ratio(height: usize, ratio: usize) -> usize {
+    let (num, den) = [(1, 1), (12, 10), (4, 3), (3, 2), (16, 9), (5, 4), (2, 1)][ratio - 1];
+    height * num / den
+}
+
+fn probe_other(buf: &[u8]) -> Result<ImageInfo, DecodeError> {
+    let reader = ImageReader::new(Cursor::new(buf))
+        .with_guessed_format()
+        .map_err(|e| ImgDecodingError(format!("{:?}", e)))?;
+    if reader.format().is_none() {
+        return Err(UnsupportedFormat("Unrecognised image data".to_string()));
+    }
+    let decoder = reader.into_decoder().map_err(|e| ImgDecodingError(format!("{:?}", e)))?;
+    let (width, height) = decoder.dimensions();
+    let color = decoder.original_color_type();
+    let color_type = match color.channel_count() {
+        1 => ColorType::Gray,
+        2 => ColorType::GrayAlpha,
+        3 => ColorType::Rgb,
+        _ => ColorType::Rgba,
+    };
+    let channels = color.channel_count() as usize;
+    let mut info = ImageInfo::still(
+        FileFormat::Other,
+        width as usize,
+        height as usize,
+        channels,
+        (color.bits_per_pixel() as usize / channels) as u8,
+        color_type,
+    );
+    info.float = matches!(color, ExtendedColorType::Rgb32F | ExtendedColorType::Rgba32F);
+    Ok(info)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::enums::ImageFormat;
+    use crate::save::{SaveOptions, svec_encode};
+    use image::codecs::gif::GifEncoder;
+    use image::{Frame, RgbaImage};
+    use pepecore_array::{ImgData, SVec, Shape};
+
+    fn encoded(format: ImageFormat, channels: usize) -> Vec<u8> {
+        let img = SVec::new(Shape::new(12, 20, Some(channels)), ImgData::U8(vec![90; 12 * 20 * channels]));
+        svec_encode(&img, format, SaveOptions::default()).unwrap()
+    }
+
+    #[test]
+    fn test_probe_encoded_formats() {
+        for (format, file_format, channels, color_type) in [
+            (ImageFormat::Png, FileFormat::Png, 4, ColorType::Rgba),
+            (ImageFormat::Jpeg, FileFormat::Jpeg, 3, ColorType::Rgb),
+            (ImageFormat::WebP, FileFormat::WebP, 3, ColorType::Rgb),
+            (ImageFormat::Tiff, FileFormat::Tiff, 3, ColorType::Rgb),
+            (ImageFormat::Exr, FileFormat::Exr, 4, ColorType::Rgba),
+        ] {
+            let info = probe_buffer(&encoded(format, channels)).unwrap();
+            assert_eq!(info.format, file_format);
+            assert_eq!((info.width, info.height), (20, 12), "{:?}", format);
+            assert_eq!((info.channels, info.color_type), (channels, color_type), "{:?}", format);
+            assert_eq!(info.frames, Some(1));
+        }
+        let exr = probe_buffer(&encoded(ImageFormat::Exr, 1)).unwrap();
+        assert_eq!((exr.color_type, exr.bit_depth, exr.float), (ColorType::Gray, 32, true));
+        let png = probe_buffer(&encoded(ImageFormat::Png, 1)).unwrap();
+        assert_eq!((png.color_type, png.bit_depth), (ColorType::Gray, 8));
+    }
+
+    #[test]
+    fn test_probe_counts_frames() {
+        let mut gif = Vec::new();
+        {
+            let mut encoder = GifEncoder::new(&mut gif);
+            let frames = (0..3).map(|i| Frame::new(RgbaImage::from_pixel(7, 5, image::Rgba([i * 50, 0, 0, 255]))));
+            encoder.encode_frames(frames).unwrap();
+        }
+        let info = probe_buffer(&gif).unwrap();
+        assert_eq!((info.format, info.width, info.height), (FileFormat::Gif, 7, 5));
+        assert_eq!((info.color_type, info.frames), (ColorType::Indexed, Some(3)));
+    }
+
+    #[test]
+    fn test_probe_headers() {
+        let mut bmp = Vec::new();
+        RgbaImage::new(9, 4)
+            .write_to(&mut Cursor::new(&mut bmp), image::ImageFormat::Bmp)
+            .unwrap();
+        let info = probe_buffer(&bmp).unwrap();
+        assert_eq!(
+            (info.format, info.width, info.height, info.channels),
+            (FileFormat::Bmp, 9, 4, 4)
+        );
+
+        let mut psd = b"8BPS".to_vec();
+        psd.extend_from_slice(&[0, 1, 0, 0, 0, 0, 0, 0, 0, 4]);
+        psd.extend_from_slice(&300u32.to_be_bytes());
+        psd.extend_from_slice(&200u32.to_be_bytes());
+        psd.extend_from_slice(&[0, 16, 0, 3]);
+        let info = probe_buffer(&psd).unwrap();
+        assert_eq!((info.format, info.width, info.height), (FileFormat::Psd, 200, 300));
+        assert_eq!((info.channels, info.bit_depth, info.color_type), (4, 16, ColorType::Rgba));
+
+        // small size header: 16 rows, 1:1 ratio, default metadata
+        let info = probe_buffer(&[0xff, 0x0a, 0x43, 0x02]).unwrap();
+        assert_eq!((info.format, info.width, info.height), (FileFormat::Jxl, 16, 16));
+        assert_eq!((info.channels, info.bit_depth, info.color_type), (3, 8, ColorType::Rgb));
+
+        assert!(matches!(probe_buffer(b"not an image at all"), Err(UnsupportedFormat(_))));
+    }
+
+    #[test]
+    fn test_probe_bad_tiff() {
+        // BigTIFF, one IFD at 16 holding one 8-byte tag whose count overflows the value size
+        let mut tiff = vec![b'I', b'I', 43, 0, 8, 0, 0, 0];
+        tiff.extend_from_slice(&16u64.to_le_bytes());
+        tiff.extend_from_slice(&1u64.to_le_bytes());
+        tiff.extend_from_slice(&[0, 1, 16, 0]);
+        tiff.extend_from_slice(&(u64::MAX / 4).to_le_bytes());
+        tiff.extend_from_slice(&0u64.to_le_bytes());
+        tiff.extend_from_slice(&0u64.to_le_bytes());
+        assert!(matches!(probe_buffer(&tiff), Err(ImgDecodingError(_))));
+
+        // first IFD offset at the very end of the address space
+        let mut tiff = vec![b'I', b'I', 43, 0, 8, 0, 0, 0];
+        tiff.extend_from_slice(&u64::MAX.to_le_bytes());
+        assert!(matches!(probe_buffer(&tiff), Err(ImgDecodingError(_))));
+
+        // classic TIFF cut off inside the first IFD
+        let mut tiff = vec![b'M', b'M', 0, 42, 0, 0, 0, 8, 0, 5, 1, 0];
+        assert!(matches!(probe_buffer(&tiff), Err(ImgDecodingError(_))));
+        tiff.truncate(4);
+        assert!(matches!(probe_buffer(&tiff), Err(ImgDecodingError(_))));
+    }
+}
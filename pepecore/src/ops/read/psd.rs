@@ -0,0 +1,1105 @@
+//! Layer-level access to PSD/PSB files.
+//!
+//! `zune_psd` only returns the merged composite, so the layer and mask section is parsed here:
+//! [`read_psd_layers`] returns every layer record with its pixels, and [`PsdLayers::composite`]
+//! flattens any subset of them again.
+//!
+//! Grayscale and RGB documents at 8, 16 and 32 bits are supported. Layer pixels are GRAYA or RGBA
+//! (`u8`, `u16` or `f32` by depth), alpha is opaque when the layer has no transparency channel.
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//! use pepecore::psd::read_psd_layers_path;
+//!
+//! let psd = read_psd_layers_path("page.psd").unwrap();
+//! for layer in &psd.layers {
+//!     println!("{} {:?} visible={}", layer.name, layer.bounds(), layer.visible);
+//! }
+//! // flatten the visible layers except text layers
+//! let clean = psd
+//!     .composite(|_, layer| layer.visible && !layer.name.starts_with("text"))
+//!     .unwrap();
+//! ```
+
+use crate::enums::PsdBlendMode;
+use crate::errors::DecodeError;
+use crate::errors::DecodeError::{FileOpenError, PsdDecodingError};
+use filebuffer::FileBuffer;
+use flate2::read::ZlibDecoder;
+use pepecore_array::{ImgData, PixelType, SVec, Shape};
+use std::fmt::Debug;
+use std::io::Read;
+use std::path::Path;
+
+/// Role of a layer record in the layer tree.
+///
+/// Groups are stored flat, bottom to top: a `GroupEnd` divider, the children, then the
+/// `Group` record carrying the group's name, visibility, opacity and blend mode.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum PsdLayerKind {
+    Pixel,
+    Group { open: bool },
+    GroupEnd,
+}
+
+/// User (raster) layer mask, `0` hides and `255` shows.
+#[derive(Debug, Clone)]
+pub struct PsdMask {
+    pub top: i32,
+    pub left: i32,
+    pub bottom: i32,
+    pub right: i32,
+    /// Mask value outside its bounds.
+    pub default_color: u8,
+    pub disabled: bool,
+    /// Gray `u8` mask, `bottom - top` by `right - left`.
+    pub image: SVec,
+}
+
+#[derive(Debug, Clone)]
+pub struct PsdLayer {
+    pub name: String,
+    /// Canvas position; `bottom`/`right` are exclusive and may lie outside the canvas.
+    pub top: i32,
+    pub left: i32,
+    pub bottom: i32,
+    pub right: i32,
+    pub opacity: u8,
+    pub blend_mode: PsdBlendMode,
+    pub visible: bool,
+    /// Clipped to the first non-clipped layer below.
+    pub clipping: bool,
+    pub kind: PsdLayerKind,
+    pub mask: Option<PsdMask>,
+    /// Layer pixels, `bottom - top` by `right - left`, GRAYA or RGBA; `None` for empty
+    /// layers and group records.
+    pub image: Option<SVec>,
+}
+
+impl PsdLayer {
+    /// `(top, left, bottom, right)`.
+    pub fn bounds(&self) -> (i32, i32, i32, i32) {
+        (self.top, self.left, self.bottom, self.right)
+    }
+}
+
+/// All layer records of a document, bottom to top.
+#[derive(Debug, Clone)]
+pub struct PsdLayers {
+    pub height: usize,
+    pub width: usize,
+    /// 1 for grayscale, 3 for RGB documents.
+    pub color_channels: usize,
+    pub pixel_type: PixelType,
+    pub layers: Vec<PsdLayer>,
+}
+
+/// Read the layers of a PSD/PSB file, see [`read_psd_layers`].
+pub fn read_psd_layers_path<P: Debug + AsRef<Path> + ?Sized>(path: &P) -> Result<PsdLayers, DecodeError> {
+    let buffer = FileBuffer::open(path).map_err(|e| FileOpenError(format!("Path: {:?} FileBuffer error: {:?}", path, e)))?;
+    read_psd_layers(&buffer)
+}
+
+/// Parse every layer record of a PSD/PSB buffer with its pixels and mask.
+///
+/// # Errors
+///
+/// Returns `PsdDecodingError` for truncated or malformed files, unsupported color modes
+/// (only grayscale and RGB) and unsupported bit depths (only 8, 16 and 32).
+pub fn read_psd_layers(buffer: &[u8]) -> Result<PsdLayers, DecodeError> {
+    let mut r = Reader::new(buffer, false);
+    if r.take(4)? != b"8BPS" {
+        return Err(PsdDecodingError("Missing 8BPS signature".to_string()));
+    }
+    r.psb = match r.u16()? {
+        1 => false,
+        2 => true,
+        v => return Err(PsdDecodingError(format!("Unknown version {}", v))),
+    };
+    r.skip(6)?;
+    r.skip(2)?; // merged channel count
+    let height = r.u32()? as usize;
+    let width = r.u32()? as usize;
+    let depth = r.u16()?;
+    let color_channels = match r.u16()? {
+        1 => 1,
+        3 => 3,
+        mode => {
+            return Err(PsdDecodingError(format!(
+                "Layer access supports grayscale and RGB documents, got color mode {}",
+                mode
+            )));
+        }
+    };
+    let pixel_type = match depth {
+        8 => PixelType::U8,
+        16 => PixelType::U16,
+        32 => PixelType::F32,
+        d => return Err(PsdDecodingError(format!("Unsupported layer bit depth {}", d))),
+    };
+    let color_data = r.u32()? as usize;
+    r.skip(color_data)?;
+    let resources = r.u32()? as usize;
+    r.skip(resources)?;
+
+    let section_len = r.len()?;
+    let mut section = r.sub(section_len)?;
+    let mut layers = Vec::new();
+    if section_len > 0 {
+        let info_len = section.len()?;
+        let mut info = section.sub(info_len)?;
+        if info_len > 0 {
+            layers = read_layer_info(&mut info, depth, color_channels)?;
+        }
+        // 16 and 32-bit documents keep their layers in a tagged block after the global mask
+        if section.remaining() >= 4 {
+            let global_mask = section.u32()? as usize;
+            section.skip(global_mask)?;
+        }
+        while layers.is_empty() && section.remaining() >= 12 {
+            let Some((key, mut data)) = section.tagged_block(4)? else {
+                break;
+            };
+            if key == *b"Lr16" || key == *b"Lr32" {
+                layers = read_layer_info(&mut data, depth, color_channels)?;
+            }
+        }
+    }
+    Ok(PsdLayers {
+        height,
+        width,
+        color_channels,
+        pixel_type,
+        layers,
+    })
+}
+
+fn read_layer_info(r: &mut Reader, depth: u16, color_channels: usize) -> Result<Vec<PsdLayer>, DecodeError> {
+    let count = r.i16()?.unsigned_abs() as usize;
+    let mut records = Vec::with_capacity(count);
+    for _ in 0..count {
+        records.push(read_record(r)?);
+    }
+    let mut layers = Vec::with_capacity(count);
+    for record in records {
+        let (h, w) = record.size();
+        let mut color: Vec<Option<Vec<f32>>> = vec![None; color_channels];
+        let mut alpha = None;
+        let mut mask = None;
+        for &(id, len) in &record.channels {
+            let mut data = r.sub(len)?;
+            match id {
+                0..=2 if (id as usize) < color_channels => {
+                    color[id as usize] = Some(read_channel(&mut data, h, w, depth, r.psb)?)
+                }
+                -1 => alpha = Some(read_channel(&mut data, h, w, depth, r.psb)?),
+                -2 => {
+                    if let Some(m) = &record.mask {
+                        let (mh, mw) = m.size();
+                        mask = Some(read_channel(&mut data, mh, mw, depth, r.psb)?);
+                    }
+                }
+                // real user mask (-3) and extra spot channels are not used
+                _ => {}
+            }
+        }
+        layers.push(record.into_layer(color, alpha, mask, depth)?);
+    }
+    Ok(layers)
+}
+
+struct Record {
+    top: i32,
+    left: i32,
+    bottom: i32,
+    right: i32,
+    channels: Vec<(i16, usize)>,
+    blend_key: [u8; 4],
+    opacity: u8,
+    clipping: bool,
+    visible: bool,
+    mask: Option<MaskRecord>,
+    name: String,
+    kind: PsdLayerKind,
+}
+
+struct MaskRecord {
+    top: i32,
+    left: i32,
+    bottom: i32,
+    right: i32,
+    default_color: u8,
+    disabled: bool,
+}
+
+impl MaskRecord {
+    fn size(&self) -> (usize, usize) {
+        (span(self.top, self.bottom), span(self.left, self.right))
+    }
+}
+
+fn span(a: i32, b: i32) -> usize {
+    (b as i64 - a as i64).max(0) as usize
+}
+
+impl Record {
+    fn size(&self) -> (usize, usize) {
+        (span(self.top, self.bottom), span(self.left, self.right))
+    }
+
+    fn into_layer(
+        self,
+        color: Vec<Option<Vec<f32>>>,
+        alpha: Option<Vec<f32>>,
+        mask: Option<Vec<f32>>,
+        depth: u16,
+    ) -> Result<PsdLayer, DecodeError> {
+        let (h, w) = self.size();
+        let colors = color.len();
+        let mut data = Vec::with_capacity(h * w * (colors + 1));
+        for i in 0..h * w {
+            for plane in &color {
+                data.push(plane.as_ref().map_or(0.0, |p| p[i]));
+            }
+            data.push(alpha.as_ref().map_or(1.0, |p| p[i]));
+        }
+        let image = if h > 0 && w > 0 {
+            let mut image = SVec::try_new(Shape::new(h, w, Some(colors + 1)), ImgData::F32(data))?;
+            match depth {
+                8 => image.as_u8(),
+                16 => image.as_u16(),
+                _ => {}
+            }
+            Some(image)
+        } else {
+            None
+        };
+        let mask = match (self.mask, mask) {
+            (Some(m), Some(data)) if !data.is_empty() => {
+                let (mh, mw) = m.size();
+                let mut image = SVec::try_new(Shape::new(mh, mw, None), ImgData::F32(data))?;
+                image.as_u8();
+                Some(PsdMask {
+                    top: m.top,
+                    left: m.left,
+                    bottom: m.bottom,
+                    right: m.right,
+                    default_color: m.default_color,
+                    disabled: m.disabled,
+                    image,
+                })
+            }
+            _ => None,
+        };
+        Ok(PsdLayer {
+            name: self.name,
+            top: self.top,
+            left: self.left,
+            bottom: self.bottom,
+            right: self.right,
+            opacity: self.opacity,
+            blend_mode: blend_mode(self.blend_key),
+            visible: self.visible,
+            clipping: self.clipping,
+            kind: self.kind,
+            mask,
+            image,
+        })
+    }
+}
+
+fn read_record(r: &mut Reader) -> Result<Record, DecodeError> {
+    let top = r.i32()?;
+    let left = r.i32()?;
+    let bottom = r.i32()?;
+    let right = r.i32()?;
+    let channel_count = r.u16()? as usize;
+    let mut channels = Vec::with_capacity(channel_count);
+    for _ in 0..channel_count {
+        let id = r.i16()?;
+        let len = r.len()?;
+        channels.push((id, len));
+    }
+    if r.take(4)? != b"8BIM" {
+        return Err(PsdDecodingError("Bad blend mode signature".to_string()));
+    }
+    let mut blend_key = r.key()?;
+    let opacity = r.u8()?;
+    let clipping = r.u8()? != 0;
+    let flags = r.u8()?;
+    r.skip(1)?;
+    let extra_len = r.u32()? as usize;
+    let mut extra = r.sub(extra_len)?;
+
+    let mask_len = extra.u32()? as usize;
+    let mut mask_data = extra.sub(mask_len)?;
+    let mask = if mask_len >= 18 {
+        let top = mask_data.i32()?;
+        let left = mask_data.i32()?;
+        let bottom = mask_data.i32()?;
+        let right = mask_data.i32()?;
+        let default_color = mask_data.u8()?;
+        let flags = mask_data.u8()?;
+        Some(MaskRecord {
+            top,
+            left,
+            bottom,
+            right,
+            default_color,
+            disabled: flags & 2 != 0,
+        })
+    } else {
+        None
+    };
+    let ranges = extra.u32()? as usize;
+    extra.skip(ranges)?;
+    let name_len = extra.u8()? as usize;
+    let mut name = String::from_utf8_lossy(extra.take(name_len)?).into_owned();
+    extra.skip((4 - (name_len + 1) % 4) % 4)?;
+
+    let mut kind = PsdLayerKind::Pixel;
+    while extra.remaining() >= 12 {
+        let Some((key, mut data)) = extra.tagged_block(2)? else {
+            break;
+        };
+        match &key {
+            b"luni" => {
+                let chars = data.u32()? as usize;
+                let units = (0..chars).map(|_| data.u16()).collect::<Result<Vec<_>, _>>()?;
+                name = String::from_utf16_lossy(&units).trim_end_matches('\0').to_string();
+            }
+            b"lsct" | b"lsdk" => {
+                kind = match data.u32()? {
+                    1 => PsdLayerKind::Group { open: true },
+                    2 => PsdLayerKind::Group { open: false },
+                    3 => PsdLayerKind::GroupEnd,
+                    _ => PsdLayerKind::Pixel,
+                };
+                // groups keep their real blend mode (usually pass through) here
+                if data.remaining() >= 8 && data.take(4)? == b"8BIM" {
+                    blend_key = data.key()?;
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok(Record {
+        top,
+        left,
+        bottom,
+        right,
+        channels,
+        blend_key,
+        opacity,
+        clipping,
+        visible: flags & 2 == 0,
+        mask,
+        name,
+        kind,
+    })
+}
+
+/// Decode one channel to normalised `f32`, `h` rows of `w` samples.
+fn read_channel(r: &mut Reader, h: usize, w: usize, depth: u16, psb: bool) -> Result<Vec<f32>, DecodeError> {
+    if h == 0 || w == 0 {
+        return Ok(Vec::new());
+    }
+    let bytes_per = (depth / 8).max(1) as usize;
+    let row_bytes = w * bytes_per;
+    let compression = r.u16()?;
+    let mut raw = match compression {
+        0 => r.take(h * row_bytes)?.to_vec(),
+        1 => {
+            let mut counts = Vec::with_capacity(h);
+            for _ in 0..h {
+                counts.push(if psb { r.u32()? as usize } else { r.u16()? as usize });
+            }
+            let mut raw = Vec::with_capacity(h * row_bytes);
+            for count in counts {
+                unpack_bits(r.take(count)?, row_bytes, &mut raw)?;
+            }
+            raw
+        }
+        2 | 3 => {
+            let mut raw = Vec::with_capacity(h * row_bytes);
+            ZlibDecoder::new(r.take(r.remaining())?)
+                .read_to_end(&mut raw)
+                .map_err(|e| PsdDecodingError(format!("{:?}", e)))?;
+            raw
+        }
+        c => return Err(PsdDecodingError(format!("Unknown channel compression {}", c))),
+    };
+    if raw.len() < h * row_bytes {
+        return Err(PsdDecodingError("Channel data is truncated".to_string()));
+    }
+    if compression == 3 {
+        unpredict(&mut raw[..h * row_bytes], w, depth);
+    }
+    Ok(match depth {
+        8 => raw[..h * w].iter().map(|&v| v as f32 / 255.0).collect(),
+        16 => raw[..h * row_bytes]
+            .chunks_exact(2)
+            .map(|b| u16::from_be_bytes([b[0], b[1]]) as f32 / 65535.0)
+            .collect(),
+        _ => raw[..h * row_bytes]
+            .chunks_exact(4)
+            .map(|b| f32::from_be_bytes([b[0], b[1], b[2], b[3]]))
+            .collect(),
+    })
+}
+
+/// PackBits, one row at a time.
+fn unpack_bits(mut src: &[u8], row_bytes: usize, out: &mut Vec<u8>) -> Result<(), DecodeError> {
+    let end = out.len() + row_bytes;
+    while out.len() < end && !src.is_empty() {
+        let n = src[0] as i8;
+        src = &src[1..];
+        if n >= 0 {
+            let n = n as usize + 1;
+            if src.len() < n {
+                return Err(PsdDecodingError("RLE literal run is truncated".to_string()));
+            }
+            out.extend_from_slice(&src[..n]);
+            src = &src[n..];
+        } else if n != -128 {
+            let Some(&v) = src.first() else {
+                return Err(PsdDecodingError("RLE repeat run is truncated".to_string()));
+            };
+            out.extend(std::iter::repeat_n(v, 1 - n as isize as usize));
+            src = &src[1..];
+        }
+    }
+    out.resize(end, 0);
+    Ok(())
+}
+
+/// Undo the per-row delta of "zip with prediction".
+fn unpredict(raw: &mut [u8], w: usize, depth: u16) {
+    match depth {
+        8 => {
+            for row in raw.chunks_exact_mut(w) {
+                for x in 1..w {
+                    row[x] = row[x].wrapping_add(row[x - 1]);
+                }
+            }
+        }
+        16 => {
+            for row in raw.chunks_exact_mut(w * 2) {
+                for x in 1..w {
+                    let prev = u16::from_be_bytes([row[2 * x - 2], row[2 * x - 1]]);
+                    let cur = u16::from_be_bytes([row[2 * x], row[2 * x + 1]]).wrapping_add(prev);
+                    row[2 * x..2 * x + 2].copy_from_slice(&cur.to_be_bytes());
+                }
+            }
+        }
+        _ => {
+            // 32-bit rows are delta coded bytewise, then split into byte planes
+            for row in raw.chunks_exact_mut(w * 4) {
+                for x in 1..row.len() {
+                    row[x] = row[x].wrapping_add(row[x - 1]);
+                }
+                let planes = row.to_vec();
+                for x in 0..w {
+                    for b in 0..4 {
+                        row[x * 4 + b] = planes[b * w + x];
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn blend_mode(key: [u8; 4]) -> PsdBlendMode {
+    match &key {
+        b"pass" => PsdBlendMode::PassThrough,
+        b"norm" => PsdBlendMode::Normal,
+        b"diss" => PsdBlendMode::Dissolve,
+        b"dark" => PsdBlendMode::Darken,
+        b"mul " => PsdBlendMode::Multiply,
+        b"idiv" => PsdBlendMode::ColorBurn,
+        b"lbrn" => PsdBlendMode::LinearBurn,
+        b"dkCl" => PsdBlendMode::DarkerColor,
+        b"lite" => PsdBlendMode::Lighten,
+        b"scrn" => PsdBlendMode::Screen,
+        b"div " => PsdBlendMode::ColorDodge,
+        b"lddg" => PsdBlendMode::LinearDodge,
+        b"lgCl" => PsdBlendMode::LighterColor,
+        b"over" => PsdBlendMode::Overlay,
+        b"sLit" => PsdBlendMode::SoftLight,
+        b"hLit" => PsdBlendMode::HardLight,
+        b"vLit" => PsdBlendMode::VividLight,
+        b"lLit" => PsdBlendMode::LinearLight,
+        b"pLit" => PsdBlendMode::PinLight,
+        b"hMix" => PsdBlendMode::HardMix,
+        b"diff" => PsdBlendMode::Difference,
+        b"smud" => PsdBlendMode::Exclusion,
+        b"fsub" => PsdBlendMode::Subtract,
+        b"fdiv" => PsdBlendMode::Divide,
+        b"hue " => PsdBlendMode::Hue,
+        b"sat " => PsdBlendMode::Saturation,
+        b"colr" => PsdBlendMode::Color,
+        b"lum " => PsdBlendMode::Luminosity,
+        _ => PsdBlendMode::Other(key),
+    }
+}
+
+impl PsdLayers {
+    /// Flatten the layers for which `select(index, layer)` returns `true`, in document order.
+    ///
+    /// `select` decides alone, hidden layers included: pass `|_, layer| layer.visible` to get
+    /// what Photoshop shows. A group rejected by `select` hides its children, and group opacity
+    /// multiplies into them. Groups are composited as pass-through
+    /// whatever their own blend mode, and `Dissolve` is treated as `Normal`. Clipped layers are
+    /// limited to the alpha of their base layer.
+    ///
+    /// Returns a GRAYA or RGBA image in the document's pixel type.
+    pub fn composite<F: Fn(usize, &PsdLayer) -> bool>(&self, select: F) -> Result<SVec, DecodeError> {
+        let (h, w) = (self.height, self.width);
+        let colors = self.color_channels;
+        let channels = colors + 1;
+        let shown = self.effective_opacity(&select);
+        let mut canvas = vec![0f32; h * w * channels];
+        // alpha of the current clipping base on the canvas, `None` while that base is hidden
+        let mut clip_base: Option<Vec<f32>> = None;
+        for (i, layer) in self.layers.iter().enumerate() {
+            if layer.kind != PsdLayerKind::Pixel {
+                continue;
+            }
+            let Some(opacity) = shown[i] else {
+                if !layer.clipping {
+                    clip_base = None;
+                }
+                continue;
+            };
+            if layer.clipping && clip_base.is_none() {
+                continue;
+            }
+            let Some(image) = &layer.image else {
+                if !layer.clipping {
+                    clip_base = Some(vec![0f32; h * w]);
+                }
+                continue;
+            };
+            let mut image = image.clone();
+            image.as_f32();
+            let src = image.get_data::<f32>()?;
+            let mask = layer.mask.as_ref().filter(|m| !m.disabled).map(|m| {
+                let mut image = m.image.clone();
+                image.as_f32();
+                (m, image)
+            });
+            let mask = mask
+                .as_ref()
+                .map(|(m, image)| image.get_data::<f32>().map(|values| (*m, values)))
+                .transpose()?;
+            let mut base = (!layer.clipping).then(|| vec![0f32; h * w]);
+            let lw = span(layer.left, layer.right);
+            let (y0, y1) = (layer.top.max(0), layer.bottom.min(h as i32));
+            let (x0, x1) = (layer.left.max(0), layer.right.min(w as i32));
+            for y in y0..y1 {
+                for x in x0..x1 {
+                    let li = ((y - layer.top) as usize * lw + (x - layer.left) as usize) * channels;
+                    let ci = y as usize * w + x as usize;
+                    let mut alpha = src[li + colors];
+                    if let Some((m, values)) = mask {
+                        alpha *= mask_value(m, values, y, x);
+                    }
+                    if let Some(base) = &mut base {
+                        base[ci] = alpha;
+                    } else if let Some(base) = &clip_base {
+                        alpha *= base[ci];
+                    }
+                    blend_pixel(
+                        &mut canvas[ci * channels..(ci + 1) * channels],
+                        &src[li..li + colors],
+                        alpha * opacity,
+                        layer.blend_mode,
+                    );
+                }
+            }
+            if base.is_some() {
+                clip_base = base;
+            }
+        }
+        let mut out = SVec::try_new(Shape::try_new(h, w, Some(channels))?, ImgData::F32(canvas))?;
+        out.as_pixel_type(self.pixel_type);
+        Ok(out)
+    }
+
+    /// Per-layer opacity after folding in enclosing groups, `None` when the layer is not drawn.
+    fn effective_opacity<F: Fn(usize, &PsdLayer) -> bool>(&self, select: &F) -> Vec<Option<f32>> {
+        let mut out = vec![None; self.layers.len()];
+        let mut groups: Vec<Option<f32>> = Vec::new();
+        // records run bottom to top, so walk down to meet each group before its children
+        for (i, layer) in self.layers.iter().enumerate().rev() {
+            let parent = groups.last().copied().unwrap_or(Some(1.0));
+            let own = select(i, layer).then_some(layer.opacity as f32 / 255.0);
+            let opacity = parent.zip(own).map(|(p, o)| p * o);
+            match layer.kind {
+                PsdLayerKind::Group { .. } => groups.push(opacity),
+                PsdLayerKind::GroupEnd => {
+                    groups.pop();
+                }
+                PsdLayerKind::Pixel => out[i] = opacity,
+            }
+        }
+        out
+    }
+}
+
+fn mask_value(mask: &PsdMask, values: &[f32], y: i32, x: i32) -> f32 {
+    if y < mask.top || y >= mask.bottom || x < mask.left || x >= mask.right {
+        return mask.default_color as f32 / 255.0;
+    }
+    let mw = span(mask.left, mask.right);
+    values[(y - mask.top) as usize * mw + (x - mask.left) as usize]
+}
+
+/// Source-over with a blend function, straight (non-premultiplied) color in `dst`.
+fn blend_pixel(dst: &mut [f32], src: &[f32], src_alpha: f32, mode: PsdBlendMode) {
+    if src_alpha <= 0.0 {
+        return;
+    }
+    let colors = src.len();
+    let dst_alpha = dst[colors];
+    let mut mixed = [0f32; 3];
+    blend_colors(&dst[..colors], src, mode, &mut mixed[..colors]);
+    let out_alpha = src_alpha + dst_alpha * (1.0 - src_alpha);
+    for c in 0..colors {
+        let s = (1.0 - dst_alpha) * src[c] + dst_alpha * mixed[c];
+        dst[c] = (src_alpha * s + dst_alpha * dst[c] * (1.0 - src_alpha)) / out_alpha;
+    }
+    dst[colors] = out_alpha;
+}
+
+fn blend_colors(b: &[f32], s: &[f32], mode: PsdBlendMode, out: &mut [f32]) {
+    use PsdBlendMode::*;
+    match mode {
+        Hue | Saturation | Color | Luminosity | DarkerColor | LighterColor => {
+            if b.len() == 3 {
+                let (b, s) = ([b[0], b[1], b[2]], [s[0], s[1], s[2]]);
+                let rgb = match mode {
+                    Hue => set_lum(set_sat(s, sat(b)), lum(b)),
+                    Saturation => set_lum(set_sat(b, sat(s)), lum(b)),
+                    Color => set_lum(s, lum(b)),
+                    Luminosity => set_lum(b, lum(s)),
+                    DarkerColor if lum(s) < lum(b) => s,
+                    LighterColor if lum(s) > lum(b) => s,
+                    _ => b,
+                };
+                out.copy_from_slice(&rgb);
+            } else {
+                // a single gray channel has no hue or saturation to take
+                out[0] = match mode {
+                    Luminosity => s[0],
+                    DarkerColor => b[0].min(s[0]),
+                    LighterColor => b[0].max(s[0]),
+                    _ => b[0],
+                };
+            }
+        }
+        _ => {
+            for c in 0..b.len() {
+                out[c] = blend_channel(b[c], s[c], mode);
+            }
+        }
+    }
+}
+
+fn blend_channel(b: f32, s: f32, mode: PsdBlendMode) -> f32 {
+    use PsdBlendMode::*;
+    match mode {
+        Multiply => b * s,
+        Screen => b + s - b * s,
+        Overlay => hard_light(s, b),
+        Darken => b.min(s),
+        Lighten => b.max(s),
+        ColorDodge => color_dodge(b, s),
+        ColorBurn => color_burn(b, s),
+        HardLight => hard_light(b, s),
+        SoftLight => {
+            if s <= 0.5 {
+                b - (1.0 - 2.0 * s) * b * (1.0 - b)
+            } else {
+                let d = if b <= 0.25 {
+                    ((16.0 * b - 12.0) * b + 4.0) * b
+                } else {
+                    b.sqrt()
+                };
+                b + (2.0 * s - 1.0) * (d - b)
+            }
+        }
+        Difference => (b - s).abs(),
+        Exclusion => b + s - 2.0 * b * s,
+        LinearBurn => (b + s - 1.0).max(0.0),
+        LinearDodge => (b + s).min(1.0),
+        VividLight => {
+            if s <= 0.5 {
+                color_burn(b, 2.0 * s)
+            } else {
+                color_dodge(b, 2.0 * s - 1.0)
+            }
+        }
+        LinearLight => (b + 2.0 * s - 1.0).clamp(0.0, 1.0),
+        PinLight => {
+            if s <= 0.5 {
+                b.min(2.0 * s)
+            } else {
+                b.max(2.0 * s - 1.0)
+            }
+        }
+        HardMix => {
+            if b + s >= 1.0 {
+                1.0
+            } else {
+                0.0
+            }
+        }
+        Subtract => (b - s).max(0.0),
+        Divide => {
+            if s <= 0.0 {
+                if b > 0.0 { 1.0 } else { 0.0 }
+            } else {
+                (b / s).min(1.0)
+            }
+        }
+        _ => s,
+    }
+}
+
+fn hard_light(b: f32, s: f32) -> f32 {
+    if s <= 0.5 {
+        b * 2.0 * s
+    } else {
+        let s = 2.0 * s - 1.0;
+        b + s - b * s
+    }
+}
+
+fn color_dodge(b: f32, s: f32) -> f32 {
+    if b <= 0.0 {
+        0.0
+    } else if s >= 1.0 {
+        1.0
+    } else {
+        (b / (1.0 - s)).min(1.0)
+    }
+}
+
+fn color_burn(b: f32, s: f32) -> f32 {
+    if b >= 1.0 {
+        1.0
+    } else if s <= 0.0 {
+        0.0
+    } else {
+        1.0 - ((1.0 - b) / s).min(1.0)
+    }
+}
+
+fn lum(c: [f32; 3]) -> f32 {
+    0.3 * c[0] + 0.59 * c[1] + 0.11 * c[2]
+}
+
+fn set_lum(c: [f32; 3], l: f32) -> [f32; 3] {
+    let d = l - lum(c);
+    let c = c.map(|v| v + d);
+    let l = lum(c);
+    let n = c[0].min(c[1]).min(c[2]);
+    let x = c[0].max(c[1]).max(c[2]);
+    c.map(|v| {
+        let v = if n < 0.0 { l + (v - l) * l / (l - n) } else { v };
+        if x > 1.0 { l + (v - l) * (1.0 - l) / (x - l) } else { v }
+    })
+}
+
+fn sat(c: [f32; 3]) -> f32 {
+    c[0].max(c[1]).max(c[2]) - c[0].min(c[1]).min(c[2])
+}
+
+fn set_sat(c: [f32; 3], s: f32) -> [f32; 3] {
+    let n = c[0].min(c[1]).min(c[2]);
+    let x = c[0].max(c[1]).max(c[2]);
+    if x > n { c.map(|v| (v - n) * s / (x - n)) } else { [0.0; 3] }
+}
+
+/// Big-endian cursor over a bounded slice of the file.
+struct Reader<'a> {
+    buf: &'a [u8],
+    pos: usize,
+    psb: bool,
+}
+
+impl<'a> Reader<'a> {
+    fn new(buf: &'a [u8], psb: bool) -> Self {
+        Self { buf, pos: 0, psb }
+    }
+
+    fn remaining(&self) -> usize {
+        self.buf.len() - self.pos
+    }
+
+    fn take(&mut self, n: usize) -> Result<&'a [u8], DecodeError> {
+        if n > self.remaining() {
+            return Err(PsdDecodingError(format!(
+                "Unexpected end of data: need {} bytes at {}, have {}",
+                n,
+                self.pos,
+                self.remaining()
+            )));
+        }
+        let out = &self.buf[self.pos..self.pos + n];
+        self.pos += n;
+        Ok(out)
+    }
+
+    fn skip(&mut self, n: usize) -> Result<(), DecodeError> {
+        self.take(n).map(|_| ())
+    }
+
+    fn sub(&mut self, n: usize) -> Result<Reader<'a>, DecodeError> {
+        Ok(Reader::new(self.take(n)?, self.psb))
+    }
+
+    fn key(&mut self) -> Result<[u8; 4], DecodeError> {
+        Ok(self.take(4)?.try_into().unwrap())
+    }
+
+    fn u8(&mut self) -> Result<u8, DecodeError> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u16(&mut self) -> Result<u16, DecodeError> {
+        Ok(u16::from_be_bytes(self.take(2)?.try_into().unwrap()))
+    }
+
+    fn i16(&mut self) -> Result<i16, DecodeError> {
+        Ok(i16::from_be_bytes(self.take(2)?.try_into().unwrap()))
+    }
+
+    fn u32(&mut self) -> Result<u32, DecodeError> {
+        Ok(u32::from_be_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    fn i32(&mut self) -> Result<i32, DecodeError> {
+        Ok(i32::from_be_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    /// Section length: 4 bytes in PSD, 8 in PSB.
+    fn len(&mut self) -> Result<usize, DecodeError> {
+        if self.psb {
+            Ok(u64::from_be_bytes(self.take(8)?.try_into().unwrap()) as usize)
+        } else {
+            Ok(self.u32()? as usize)
+        }
+    }
+
+    /// `8BIM`/`8B64` tagged block, data padded to `align`. `None` on an unknown signature.
+    fn tagged_block(&mut self, align: usize) -> Result<Option<([u8; 4], Reader<'a>)>, DecodeError> {
+        let sig = self.key()?;
+        if &sig != b"8BIM" && &sig != b"8B64" {
+            return Ok(None);
+        }
+        let key = self.key()?;
+        // PSB widens the length of these blocks to 8 bytes
+        let wide = self.psb
+            && matches!(
+                &key,
+                b"LMsk"
+                    | b"Lr16"
+                    | b"Lr32"
+                    | b"Layr"
+                    | b"Mt16"
+                    | b"Mt32"
+                    | b"Mtrn"
+                    | b"Alph"
+                    | b"FMsk"
+                    | b"lnk2"
+                    | b"FEid"
+                    | b"FXid"
+                    | b"PxSD"
+            );
+        let len = if wide { self.len()? } else { self.u32()? as usize };
+        let data = self.sub(len)?;
+        let pad = (align - len % align) % align;
+        self.skip(pad.min(self.remaining()))?;
+        Ok(Some((key, data)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct TestLayer {
+        name: &'static str,
+        rect: (i32, i32, i32, i32),
+        key: &'static [u8; 4],
+        opacity: u8,
+        hidden: bool,
+        section: Option<u32>,
+        /// RGBA pixels, row-major.
+        pixels: Vec<[u8; 4]>,
+    }
+
+    fn layer(name: &'static str, rect: (i32, i32, i32, i32), key: &'static [u8; 4], color: [u8; 4]) -> TestLayer {
+        let n = ((rect.2 - rect.0) * (rect.3 - rect.1)) as usize;
+        TestLayer {
+            name,
+            rect,
+            key,
+            opacity: 255,
+            hidden: false,
+            section: None,
+            pixels: vec![color; n],
+        }
+    }
+
+    /// PackBits one row as a single literal run.
+    fn rle_row(row: &[u8]) -> Vec<u8> {
+        let mut out = vec![(row.len() - 1) as u8];
+        out.extend_from_slice(row);
+        out
+    }
+
+    /// Minimal 8-bit RGB PSD; alpha goes through RLE, color channels are raw.
+    fn build_psd(h: u32, w: u32, layers: &[TestLayer]) -> Vec<u8> {
+        let mut info = Vec::new();
+        info.extend_from_slice(&(layers.len() as i16).to_be_bytes());
+        let mut data = Vec::new();
+        for l in layers {
+            let (t, lf, b, r) = l.rect;
+            let lw = (r - lf) as usize;
+            let mut channels = Vec::new();
+            for (id, c) in [(-1i16, 3usize), (0, 0), (1, 1), (2, 2)] {
+                let plane: Vec<u8> = l.pixels.iter().map(|p| p[c]).collect();
+                let mut bytes = Vec::new();
+                if id == -1 {
+                    bytes.extend_from_slice(&1u16.to_be_bytes());
+                    let rows: Vec<Vec<u8>> = plane.chunks(lw.max(1)).map(rle_row).collect();
+                    for row in &rows {
+                        bytes.extend_from_slice(&(row.len() as u16).to_be_bytes());
+                    }
+                    rows.iter().for_each(|row| bytes.extend_from_slice(row));
+                } else {
+                    bytes.extend_from_slice(&0u16.to_be_bytes());
+                    bytes.extend_from_slice(&plane);
+                }
+                channels.push((id, bytes));
+            }
+            for v in [t, lf, b, r] {
+                info.extend_from_slice(&v.to_be_bytes());
+            }
+            info.extend_from_slice(&(channels.len() as u16).to_be_bytes());
+            for (id, bytes) in &channels {
+                info.extend_from_slice(&id.to_be_bytes());
+                info.extend_from_slice(&(bytes.len() as u32).to_be_bytes());
+                data.extend_from_slice(bytes);
+            }
+            info.extend_from_slice(b"8BIM");
+            info.extend_from_slice(l.key);
+            info.extend_from_slice(&[l.opacity, 0, if l.hidden { 2 } else { 0 }, 0]);
+            let mut extra = vec![0u8; 8];
+            let mut name = vec![l.name.len() as u8];
+            name.extend_from_slice(l.name.as_bytes());
+            while name.len() % 4 != 0 {
+                name.push(0);
+            }
+            extra.extend_from_slice(&name);
+            if let Some(kind) = l.section {
+                extra.extend_from_slice(b"8BIMlsct");
+                extra.extend_from_slice(&4u32.to_be_bytes());
+                extra.extend_from_slice(&kind.to_be_bytes());
+            }
+            info.extend_from_slice(&(extra.len() as u32).to_be_bytes());
+            info.extend_from_slice(&extra);
+        }
+        info.extend_from_slice(&data);
+
+        let mut out = b"8BPS".to_vec();
+        out.extend_from_slice(&1u16.to_be_bytes());
+        out.extend_from_slice(&[0; 6]);
+        out.extend_from_slice(&4u16.to_be_bytes());
+        out.extend_from_slice(&h.to_be_bytes());
+        out.extend_from_slice(&w.to_be_bytes());
+        out.extend_from_slice(&8u16.to_be_bytes());
+        out.extend_from_slice(&3u16.to_be_bytes());
+        out.extend_from_slice(&[0; 8]);
+        out.extend_from_slice(&(info.len() as u32 + 8).to_be_bytes());
+        out.extend_from_slice(&(info.len() as u32).to_be_bytes());
+        out.extend_from_slice(&info);
+        out.extend_from_slice(&0u32.to_be_bytes());
+        out
+    }
+
+    fn pixel(img: &SVec, y: usize, x: usize) -> Vec<u8> {
+        let (_, w, c) = img.shape();
+        let c = c.unwrap();
+        img.get_data::<u8>().unwrap()[(y * w + x) * c..(y * w + x + 1) * c].to_vec()
+    }
+
+    #[test]
+    fn test_read_layers_and_composite_subset() {
+        let mut top = layer("dot", (1, 1, 3, 3), b"norm", [0, 0, 255, 255]);
+        top.pixels[0] = [0, 255, 0, 255];
+        let mut hidden = layer("hidden", (0, 0, 4, 4), b"norm", [255, 255, 255, 255]);
+        hidden.hidden = true;
+        let layers = [layer("bg", (0, 0, 4, 4), b"norm", [255, 0, 0, 255]), top, hidden];
+        let psd = read_psd_layers(&build_psd(4, 4, &layers)).unwrap();
+
+        assert_eq!((psd.height, psd.width, psd.pixel_type), (4, 4, PixelType::U8));
+        assert_eq!(psd.layers.len(), 3);
+        let dot = &psd.layers[1];
+        assert_eq!(dot.name, "dot");
+        assert_eq!(dot.bounds(), (1, 1, 3, 3));
+        assert_eq!(dot.blend_mode, PsdBlendMode::Normal);
+        let image = dot.image.as_ref().unwrap();
+        assert_eq!(image.shape(), (2, 2, Some(4)));
+        assert_eq!(pixel(image, 0, 0), vec![0, 255, 0, 255]);
+        assert!(!psd.layers[2].visible);
+
+        let visible = psd.composite(|_, l| l.visible).unwrap();
+        assert_eq!(visible.shape(), (4, 4, Some(4)));
+        assert_eq!(pixel(&visible, 0, 0), vec![255, 0, 0, 255]);
+        assert_eq!(pixel(&visible, 1, 1), vec![0, 255, 0, 255]);
+        assert_eq!(pixel(&visible, 2, 2), vec![0, 0, 255, 255]);
+
+        let bg_only = psd.composite(|_, l| l.visible && l.name != "dot").unwrap();
+        assert_eq!(pixel(&bg_only, 2, 2), vec![255, 0, 0, 255]);
+
+        // `select` can bring hidden layers back
+        let all = psd.composite(|_, _| true).unwrap();
+        assert_eq!(pixel(&all, 2, 2), vec![255, 255, 255, 255]);
+    }
+
+    #[test]
+    fn test_composite_blend_and_groups() {
+        let mut half = layer("half", (0, 0, 2, 2), b"mul ", [128, 255, 255, 255]);
+        half.opacity = 255;
+        let mut group = layer("group", (0, 0, 0, 0), b"pass", [0; 4]);
+        group.section = Some(1);
+        group.opacity = 0;
+        let end = TestLayer {
+            section: Some(3),
+            ..layer("</Layer group>", (0, 0, 0, 0), b"norm", [0; 4])
+        };
+        let layers = [
+            layer("bg", (0, 0, 2, 2), b"norm", [200, 100, 50, 255]),
+            half,
+            end,
+            layer("inside", (0, 0, 2, 2), b"norm", [0, 0, 0, 255]),
+            group,
+        ];
+        let psd = read_psd_layers(&build_psd(2, 2, &layers)).unwrap();
+        assert_eq!(psd.layers[1].blend_mode, PsdBlendMode::Multiply);
+        assert_eq!(psd.layers[2].kind, PsdLayerKind::GroupEnd);
+        assert_eq!(psd.layers[4].kind, PsdLayerKind::Group { open: true });
+        assert!(psd.layers[4].image.is_none());
+
+        // the group has zero opacity, so its child never shows
+        let flat = psd.composite(|_, _| true).unwrap();
+        assert_eq!(pixel(&flat, 0, 0), vec![100, 100, 50, 255]);
+        let no_multiply = psd.composite(|i, _| i != 1).unwrap();
+        assert_eq!(pixel(&no_multiply, 1, 1), vec![200, 100, 50, 255]);
+    }
+}
@@ -1,7 +1,8 @@
 //! Module providing high-level API to read image or PSD files/buffers into `SVec`.
 //!
 //! This module exports two functions: `read_in_path` and `read_in_buffer`, supporting various image color modes
-//! (dynamic, gray, rgb, rgba, gray+alpha) and automatically detecting PSD format via magic bytes.
+//! (dynamic, gray, rgb, rgba, gray+alpha) and automatically detecting PSD, OpenEXR and JPEG XL formats via magic bytes.
+//! `read_in_path_with` and `read_in_buffer_with` also apply EXIF orientation and return or apply the ICC profile.
 //!
 //! # Examples
 //!
@@ -24,18 +25,32 @@
 
 use crate::enums::ImgColor;
 use crate::errors::DecodeError;
-use crate::errors::DecodeError::FileOpenError;
+use crate::errors::DecodeError::{FileOpenError, ImgDecodingError};
 use crate::ops::read::decode::{
-    img_din_decode, img_gray_decode, img_graya_decode, img_rgb_decode, img_rgba_decode, psd_din_decode, psd_gray_decode,
-    psd_graya_decode, psd_rgb_decode, psd_rgba_decode,
+    exr_decode, img_decode_with, img_din_decode, img_gray_decode, img_graya_decode, img_rgb_decode, img_rgba_decode, jxl_decode,
+    jxl_decode_with, psd_din_decode, psd_gray_decode, psd_graya_decode, psd_rgb_decode, psd_rgba_decode,
 };
+use crate::ops::read::metadata::{icc_to_srgb, psd_metadata};
+pub use crate::ops::read::options::{ImageMetadata, ReadOptions};
 use filebuffer::FileBuffer;
 use pepecore_array::SVec;
 use std::fmt::Debug;
 use std::path::Path;
+
+/// JPEG XL bare codestream (`FF 0A`) or ISO-BMFF container (`JXL ` signature box).
+pub(crate) fn is_jxl(buffer: &[u8]) -> bool {
+    buffer.starts_with(&[0xff, 0x0a]) || buffer.starts_with(&[0, 0, 0, 0x0c, b'J', b'X', b'L', b' ', 0x0d, 0x0a, 0x87, 0x0a])
+}
+
+fn too_short() -> DecodeError {
+    ImgDecodingError("Image data is shorter than a format signature".to_string())
+}
+
 /// Read image from file path into `SVec`, choosing decoder by `ImgColor` and format.
 ///
-/// Automatically detects PSD files by magic bytes `56 66 80 83`.
+/// Automatically detects PSD files by magic bytes `56 66 80 83` and OpenEXR files by `76 2f 31 01`
+/// (always decoded to `f32`). JPEG XL is recognised by its codestream or container signature and
+/// decoded at its stored depth (`u8`, `u16` or `f32`).
 /// For non-PSD, delegates to common image decoders.
 ///
 /// # Parameters
@@ -59,7 +74,7 @@ use std::path::Path;
 
 pub fn read_in_path<P: Debug + AsRef<Path> + ?Sized>(path: &P, img_color: ImgColor) -> Result<SVec, DecodeError> {
     let img_buffer = FileBuffer::open(path).map_err(|e| FileOpenError(format!("Path: {:?} FileBuffer error: {:?}", path, e)))?;
-    Ok(match &img_buffer[..4] {
+    Ok(match img_buffer.get(..4).ok_or_else(too_short)? {
         [56, 66, 80, 83] => match img_color {
             ImgColor::DYNAMIC => psd_din_decode(&img_buffer)?,
             ImgColor::GRAY => psd_gray_decode(&img_buffer)?,
@@ -67,6 +82,8 @@ pub fn read_in_path<P: Debug + AsRef<Path> + ?Sized>(path: &P, img_color: ImgCol
             ImgColor::RGBA => psd_rgba_decode(&img_buffer)?,
             ImgColor::GRAYA => psd_graya_decode(&img_buffer)?,
         },
+        [0x76, 0x2f, 0x31, 0x01] => exr_decode(&img_buffer, img_color)?,
+        _ if is_jxl(&img_buffer) => jxl_decode(&img_buffer, img_color)?,
         _ => match img_color {
             ImgColor::DYNAMIC => img_din_decode(&img_buffer)?,
             ImgColor::GRAY => img_gray_decode(&img_buffer)?,
@@ -100,7 +117,7 @@ pub fn read_in_path<P: Debug + AsRef<Path> + ?Sized>(path: &P, img_color: ImgCol
 /// assert_eq!(gray.shape.get_channels(), None);
 /// ```
 pub fn read_in_buffer(img_buffer: &[u8], img_color: ImgColor) -> Result<SVec, DecodeError> {
-    Ok(match &img_buffer[..4] {
+    Ok(match img_buffer.get(..4).ok_or_else(too_short)? {
         [56, 66, 80, 83] => match img_color {
             ImgColor::DYNAMIC => psd_din_decode(img_buffer)?,
             ImgColor::GRAY => psd_gray_decode(img_buffer)?,
@@ -108,6 +125,8 @@ pub fn read_in_buffer(img_buffer: &[u8], img_color: ImgColor) -> Result<SVec, De
             ImgColor::RGBA => psd_rgba_decode(img_buffer)?,
             ImgColor::GRAYA => psd_graya_decode(img_buffer)?,
         },
+        [0x76, 0x2f, 0x31, 0x01] => exr_decode(img_buffer, img_color)?,
+        _ if is_jxl(img_buffer) => jxl_decode(img_buffer, img_color)?,
         _ => match img_color {
             ImgColor::DYNAMIC => img_din_decode(img_buffer)?,
             ImgColor::GRAY => img_gray_decode(img_buffer)?,
@@ -117,3 +136,130 @@ pub fn read_in_buffer(img_buffer: &[u8], img_color: ImgColor) -> Result<SVec, De
         },
     })
 }
+
+/// Read an image file together with its ICC profile and EXIF, see [`read_in_buffer_with`].
+pub fn read_in_path_with<P: Debug + AsRef<Path> + ?Sized>(
+    path: &P,
+    img_color: ImgColor,
+    options: ReadOptions,
+) -> Result<(SVec, ImageMetadata), DecodeError> {
+    let img_buffer = FileBuffer::open(path).map_err(|e| FileOpenError(format!("Path: {:?} FileBuffer error: {:?}", path, e)))?;
+    read_in_buffer_with(&img_buffer, img_color, options)
+}
+
+/// Decode an image buffer like [`read_in_buffer`], honouring EXIF orientation and the ICC profile.
+///
+/// With `options.apply_orientation` the pixels are rotated/flipped upright and the orientation
+/// tag in the returned EXIF is reset to 1, so saving it back doesn't rotate the image twice.
+/// With `options.icc_to_srgb` the pixels are converted to sRGB and the profile is dropped from the
+/// returned metadata; profiles that don't match the decoded layout are returned untouched.
+///
+/// PSD files report the ICC profile and EXIF from their image resources; OpenEXR carries neither.
+/// JPEG XL is always returned upright, see [`jxl_decode_with`](crate::ops::read::decode::jxl_decode_with).
+///
+/// # Errors
+///
+/// Same as [`read_in_buffer`], plus `IccError` for a profile that can't be applied.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use pepecore::enums::ImgColor;
+/// use pepecore::read::{ReadOptions, read_in_path_with};
+///
+/// let options = ReadOptions { icc_to_srgb: true, ..Default::default() };
+/// let (img, metadata) = read_in_path_with("phone.jpg", ImgColor::RGB, options).unwrap();
+/// assert!(metadata.icc_profile.is_none());
+/// ```
+pub fn read_in_buffer_with(
+    img_buffer: &[u8],
+    img_color: ImgColor,
+    options: ReadOptions,
+) -> Result<(SVec, ImageMetadata), DecodeError> {
+    match img_buffer.get(..4).ok_or_else(too_short)? {
+        [56, 66, 80, 83] => {
+            let mut img = read_in_buffer(img_buffer, img_color)?;
+            let (icc_profile, exif) = psd_metadata(img_buffer);
+            let mut metadata = ImageMetadata { icc_profile, exif };
+            if options.icc_to_srgb {
+                if let Some(icc) = &metadata.icc_profile {
+                    if icc_to_srgb(&mut img, icc)? {
+                        metadata.icc_profile = None;
+                    }
+                }
+            }
+            Ok((img, metadata))
+        }
+        [0x76, 0x2f, 0x31, 0x01] => Ok((exr_decode(img_buffer, img_color)?, ImageMetadata::default())),
+        _ if is_jxl(img_buffer) => jxl_decode_with(img_buffer, img_color, options),
+        _ => img_decode_with(img_buffer, img_color, options),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::enums::ImageFormat;
+    use crate::save::{SaveOptions, svec_encode};
+    use pepecore_array::{ImgData, PixelType, Shape};
+
+    fn jxl(img: &SVec) -> Vec<u8> {
+        svec_encode(img, ImageFormat::Jxl, SaveOptions::default()).unwrap()
+    }
+
+    #[test]
+    fn test_read_jxl_depths_and_alpha() {
+        let rgb: Vec<u8> = (0..6 * 5 * 3).map(|i| (i * 7) as u8).collect();
+        let bytes = jxl(&SVec::new(Shape::new(6, 5, Some(3)), ImgData::U8(rgb.clone())));
+        let back = read_in_buffer(&bytes, ImgColor::DYNAMIC).unwrap();
+        assert_eq!(back.shape(), (6, 5, Some(3)));
+        assert_eq!(back.get_data::<u8>().unwrap(), rgb);
+
+        let wide: Vec<u16> = (0..6 * 5).map(|i| (i * 2111) as u16).collect();
+        let bytes = jxl(&SVec::new(Shape::new(6, 5, None), ImgData::U16(wide.clone())));
+        let back = read_in_buffer(&bytes, ImgColor::DYNAMIC).unwrap();
+        assert_eq!((back.shape(), back.pixel_type()), ((6, 5, None), PixelType::U16));
+        assert_eq!(back.get_data::<u16>().unwrap(), wide);
+
+        let graya: Vec<u8> = (0..6 * 5 * 2).map(|i| (i * 11) as u8).collect();
+        let bytes = jxl(&SVec::new(Shape::new(6, 5, Some(2)), ImgData::U8(graya.clone())));
+        assert_eq!(
+            read_in_buffer(&bytes, ImgColor::GRAYA).unwrap().get_data::<u8>().unwrap(),
+            graya
+        );
+    }
+
+    #[test]
+    fn test_read_jxl_img_color() {
+        let graya: Vec<u8> = (0..4 * 4 * 2).map(|i| (i * 9) as u8).collect();
+        let bytes = jxl(&SVec::new(Shape::new(4, 4, Some(2)), ImgData::U8(graya.clone())));
+
+        let rgba = read_in_buffer(&bytes, ImgColor::RGBA).unwrap();
+        assert_eq!(rgba.shape(), (4, 4, Some(4)));
+        assert_eq!(rgba.get_data::<u8>().unwrap()[..4], [graya[0], graya[0], graya[0], graya[1]]);
+        let gray = read_in_buffer(&bytes, ImgColor::GRAY).unwrap();
+        assert_eq!(gray.shape(), (4, 4, None));
+        assert_eq!(gray.get_data::<u8>().unwrap()[1], graya[2]);
+        assert_eq!(read_in_buffer(&bytes, ImgColor::RGB).unwrap().shape(), (4, 4, Some(3)));
+
+        let (img, metadata) = read_in_buffer_with(&bytes, ImgColor::DYNAMIC, ReadOptions::default()).unwrap();
+        assert_eq!(img.get_data::<u8>().unwrap(), graya);
+        assert_eq!(metadata, ImageMetadata::default());
+
+        assert!(matches!(
+            read_in_buffer(&bytes[..bytes.len() / 2], ImgColor::RGB),
+            Err(crate::errors::DecodeError::JxlDecodingError(_))
+        ));
+    }
+
+    #[test]
+    fn test_read_short_buffer() {
+        for buffer in [&[][..], &[0xff, 0x0a], b"8BP"] {
+            assert!(matches!(read_in_buffer(buffer, ImgColor::RGB), Err(ImgDecodingError(_))));
+            assert!(matches!(
+                read_in_buffer_with(buffer, ImgColor::RGB, ReadOptions::default()),
+                Err(ImgDecodingError(_))
+            ));
+        }
+    }
+}
@@ -0,0 +1,573 @@
+//! Decoding part of an image without holding all of it.
+//!
+//! [`read_region`] decodes a rectangle, [`read_scaled`] a reduced-size image and [`read_tiles`]
+//! walks a tile grid one band of rows at a time. What is actually skipped depends on the codec:
+//!
+//! - **TIFF**, striped or tiled, chunky or planar: only the strips/tiles overlapping the request
+//!   are decompressed.
+//! - **PNG**, non-interlaced: rows are streamed, a region stops after its last row and tiles hold
+//!   one band.
+//! - **JPEG**: [`read_scaled`] decodes at 1/2, 1/4 or 1/8 through DCT scaling.
+//! - everything else is decoded in full and then cropped or resized.
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//! use pepecore::enums::ImgColor;
+//! use pepecore::region::{Region, read_region_path, read_tiles_path};
+//!
+//! let corner = read_region_path("scan.tif", Region::new(0, 0, 1024, 1024), ImgColor::RGB).unwrap();
+//! for tile in read_tiles_path("scan.tif", 512, 512, ImgColor::RGB).unwrap() {
+//!     let tile = tile.unwrap();
+//!     println!("{:?} {:?}", tile.region, tile.image.shape());
+//! }
+//! ```
+
+use crate::enums::ImgColor;
+use crate::errors::DecodeError;
+use crate::errors::DecodeError::{FileOpenError, ImgDecodingError};
+use crate::ops::read::decode::{RawSamples, img_color_from, raw_to_dynamic, tiff_channels, tiff_err, tiff_samples};
+use crate::ops::read::read::read_in_buffer;
+pub use crate::ops::region::{Region, Tile};
+use crate::ops::svec_ops::resize::fir::ResizeSVec;
+use fast_image_resize::{FilterType, ResizeAlg};
+use filebuffer::FileBuffer;
+use pepecore_array::SVec;
+use pepecore_array::error::Error;
+use std::collections::VecDeque;
+use std::fmt::Debug;
+use std::io::{BufRead, Cursor, Read, Seek};
+use std::path::Path;
+use tiff::decoder::Decoder as TiffDecoder;
+use tiff::tags::Tag;
+
+/// Decode a rectangle of an image file, see [`read_region`].
+pub fn read_region_path<P: Debug + AsRef<Path> + ?Sized>(
+    path: &P,
+    region: Region,
+    img_color: ImgColor,
+) -> Result<SVec, DecodeError> {
+    let buffer = FileBuffer::open(path).map_err(|e| FileOpenError(format!("Path: {:?} FileBuffer error: {:?}", path, e)))?;
+    read_region(&buffer, region, img_color)
+}
+
+/// Decode only `region` of an image buffer.
+///
+/// # Errors
+///
+/// Returns `SVecError(OutOfBounds)` when the region doesn't fit the image and
+/// `SVecError(ZeroDimension)` when it is empty, otherwise the errors
+/// of [`read_in_buffer`].
+pub fn read_region(buffer: &[u8], region: Region, img_color: ImgColor) -> Result<SVec, DecodeError> {
+    match Source::open(buffer, img_color)? {
+        Source::Tiff(mut decoder) => {
+            let (width, height) = decoder.dimensions().map_err(tiff_err)?;
+            check(region, height as usize, width as usize)?;
+            tiff_region(&mut decoder, region, img_color)
+        }
+        Source::Png(mut rows) => {
+            check(region, rows.height, rows.width)?;
+            rows.skip_rows(region.top)?;
+            rows.read(region.height, region.left, region.width, img_color)
+        }
+        Source::Full(img) => {
+            let (height, width, _) = img.shape();
+            check(region, height, width)?;
+            Ok(img
+                .view()
+                .crop(region.left, region.top, region.width, region.height)?
+                .to_svec())
+        }
+    }
+}
+
+/// Decode an image file at reduced size, see [`read_scaled`].
+pub fn read_scaled_path<P: Debug + AsRef<Path> + ?Sized>(
+    path: &P,
+    denominator: usize,
+    img_color: ImgColor,
+) -> Result<SVec, DecodeError> {
+    let buffer = FileBuffer::open(path).map_err(|e| FileOpenError(format!("Path: {:?} FileBuffer error: {:?}", path, e)))?;
+    read_scaled(&buffer, denominator, img_color)
+}
+
+/// Decode an image at `1 / denominator` of its size (rounded up), `denominator` being 1, 2, 4
+/// or 8.
+///
+/// Baseline 8-bit JPEGs are scaled inside the IDCT and never exist at full size; other files
+/// are decoded in full and box-filtered down.
+///
+/// # Errors
+///
+/// Returns `ImgDecodingError` for other denominators, otherwise the errors of [`read_in_buffer`].
+pub fn read_scaled(buffer: &[u8], denominator: usize, img_color: ImgColor) -> Result<SVec, DecodeError> {
+    if !matches!(denominator, 1 | 2 | 4 | 8) {
+        return Err(ImgDecodingError(format!(
+            "Scale denominator must be 1, 2, 4 or 8, got {}",
+            denominator
+        )));
+    }
+    if let [0xff, 0xd8, ..] = buffer {
+        if let Some(img) = jpeg_scaled(buffer, denominator, img_color)? {
+            return Ok(img);
+        }
+    }
+    let mut img = read_in_buffer(buffer, img_color)?;
+    if denominator > 1 {
+        let (height, width, _) = img.shape();
+        let alg = ResizeAlg::Convolution(FilterType::Box);
+        img.resize(height.div_ceil(denominator), width.div_ceil(denominator), alg, false);
+    }
+    Ok(img)
+}
+
+/// `None` when the JPEG is not 8-bit gray or RGB.
+fn jpeg_scaled(buffer: &[u8], denominator: usize, img_color: ImgColor) -> Result<Option<SVec>, DecodeError> {
+    let err = |e| ImgDecodingError(format!("{:?}", e));
+    let mut decoder = jpeg_decoder::Decoder::new(Cursor::new(buffer));
+    decoder.read_info().map_err(err)?;
+    let Some(info) = decoder.info() else {
+        return Ok(None);
+    };
+    let channels = match info.pixel_format {
+        jpeg_decoder::PixelFormat::L8 => 1,
+        jpeg_decoder::PixelFormat::RGB24 => 3,
+        _ => return Ok(None),
+    };
+    let (width, height) = decoder
+        .scale(
+            info.width.div_ceil(denominator as u16),
+            info.height.div_ceil(denominator as u16),
+        )
+        .map_err(err)?;
+    let data = decoder.decode().map_err(err)?;
+    let img = raw_to_dynamic(width as usize, height as usize, channels, RawSamples::U8(data))?;
+    Ok(Some(img_color_from(&img, img_color)?))
+}
+
+/// Tiles in row-major order, see [`read_tiles`].
+pub struct Tiles<'a> {
+    source: Source<'a>,
+    img_color: ImgColor,
+    height: usize,
+    width: usize,
+    channels: Option<usize>,
+    tile_height: usize,
+    tile_width: usize,
+    band_top: usize,
+    band: VecDeque<Tile>,
+    failed: bool,
+}
+
+impl Tiles<'_> {
+    /// `(height, width, channels)` of the whole image as the tiles are decoded.
+    pub fn shape(&self) -> (usize, usize, Option<usize>) {
+        (self.height, self.width, self.channels)
+    }
+
+    /// Decode the next band of rows and cut it into tiles.
+    fn next_band(&mut self) -> Result<(), DecodeError> {
+        let rows = self.tile_height.min(self.height - self.band_top);
+        let band = Region::new(self.band_top, 0, rows, self.width);
+        let image = match &mut self.source {
+            Source::Tiff(decoder) => tiff_region(decoder, band, self.img_color)?,
+            Source::Png(png) => png.read(rows, 0, self.width, self.img_color)?,
+            Source::Full(img) => img.view().crop(0, band.top, band.width, band.height)?.to_svec(),
+        };
+        self.channels = image.shape().2;
+        for left in (0..self.width).step_by(self.tile_width) {
+            let width = self.tile_width.min(self.width - left);
+            self.band.push_back(Tile {
+                region: Region::new(band.top, left, rows, width),
+                image: image.view().crop(left, 0, width, rows)?.to_svec(),
+            });
+        }
+        self.band_top += rows;
+        Ok(())
+    }
+}
+
+impl Iterator for Tiles<'_> {
+    type Item = Result<Tile, DecodeError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.band.is_empty() && !self.failed && self.band_top < self.height {
+            if let Err(e) = self.next_band() {
+                self.failed = true;
+                return Some(Err(e));
+            }
+        }
+        self.band.pop_front().map(Ok)
+    }
+}
+
+/// Tile an image file, see [`read_tiles`].
+///
+/// The file stays memory-mapped while the iterator is alive.
+pub fn read_tiles_path<P: Debug + AsRef<Path> + ?Sized>(
+    path: &P,
+    tile_height: usize,
+    tile_width: usize,
+    img_color: ImgColor,
+) -> Result<Tiles<'static>, DecodeError> {
+    let buffer = FileBuffer::open(path).map_err(|e| FileOpenError(format!("Path: {:?} FileBuffer error: {:?}", path, e)))?;
+    tiles(Source::open(buffer, img_color)?, tile_height, tile_width, img_color)
+}
+
+/// Iterate over `tile_height` x `tile_width` tiles, left to right and top to bottom.
+///
+/// Tiles on the bottom and right edges are cut to the image. Only the current band of rows is
+/// decoded and held (see the module docs for the formats decoded in full instead).
+///
+/// # Errors
+///
+/// Fails up front on unreadable headers and zero tile sizes; a band that fails to decode ends
+/// the iteration with its error.
+pub fn read_tiles(buffer: &[u8], tile_height: usize, tile_width: usize, img_color: ImgColor) -> Result<Tiles<'_>, DecodeError> {
+    tiles(Source::open(buffer, img_color)?, tile_height, tile_width, img_color)
+}
+
+fn tiles(mut source: Source<'_>, tile_height: usize, tile_width: usize, img_color: ImgColor) -> Result<Tiles<'_>, DecodeError> {
+    if tile_height == 0 || tile_width == 0 {
+        return Err(Error::ZeroDimension.into());
+    }
+    let (height, width) = match &mut source {
+        Source::Tiff(decoder) => {
+            let (width, height) = decoder.dimensions().map_err(tiff_err)?;
+            (height as usize, width as usize)
+        }
+        Source::Png(png) => (png.height, png.width),
+        Source::Full(img) => {
+            let (height, width, _) = img.shape();
+            (height, width)
+        }
+    };
+    let mut tiles = Tiles {
+        source,
+        img_color,
+        height,
+        width,
+        channels: None,
+        tile_height,
+        tile_width,
+        band_top: 0,
+        band: VecDeque::new(),
+        failed: false,
+    };
+    // the first band tells the channel count for `shape`
+    if height > 0 && width > 0 {
+        tiles.next_band()?;
+    }
+    Ok(tiles)
+}
+
+fn check(region: Region, height: usize, width: usize) -> Result<(), DecodeError> {
+    if region.height == 0 || region.width == 0 {
+        return Err(Error::ZeroDimension.into());
+    }
+    if region.bottom() > height || region.right() > width {
+        return Err(Error::OutOfBounds.into());
+    }
+    Ok(())
+}
+
+/// Where region and band reads come from.
+enum Source<'a> {
+    Tiff(Box<TiffDecoder<Cursor<Bytes<'a>>>>),
+    Png(Box<PngRows<Cursor<Bytes<'a>>>>),
+    /// Formats without partial decoding, decoded once in full.
+    Full(SVec),
+}
+
+impl<'a> Source<'a> {
+    fn open<B: AsRef<[u8]> + Send + 'a>(buffer: B, img_color: ImgColor) -> Result<Self, DecodeError> {
+        let data = buffer.as_ref();
+        let tiff = matches!(data, [b'I', b'I', 42 | 43, 0, ..] | [b'M', b'M', 0, 42 | 43, ..]);
+        // interlaced PNG rows come in passes, byte 28 is the IHDR interlace method
+        let png = matches!(data, [0x89, b'P', b'N', b'G', ..]) && data.get(28) == Some(&0);
+        if !tiff && !png {
+            return Ok(Source::Full(read_in_buffer(data, img_color)?));
+        }
+        let reader = Cursor::new(Bytes(Box::new(buffer)));
+        if tiff {
+            Ok(Source::Tiff(Box::new(TiffDecoder::new(reader).map_err(tiff_err)?)))
+        } else {
+            Ok(Source::Png(Box::new(PngRows::new(reader)?)))
+        }
+    }
+}
+
+/// Owned or borrowed encoded bytes.
+struct Bytes<'a>(Box<dyn AsRef<[u8]> + Send + 'a>);
+
+impl AsRef<[u8]> for Bytes<'_> {
+    fn as_ref(&self) -> &[u8] {
+        self.0.as_ref().as_ref()
+    }
+}
+
+/// Copies `count` pixels of one chunk into the interleaved output. A chunky chunk carries every
+/// channel, a planar one (`PlanarConfiguration = 2`) a single plane written at channel `plane`.
+fn paste_pixels<T: Copy>(out: &mut [T], data: &[T], src: usize, dst: usize, count: usize, channels: usize, plane: Option<usize>) {
+    match plane {
+        None => out[dst * channels..(dst + count) * channels].copy_from_slice(&data[src * channels..(src + count) * channels]),
+        Some(plane) => {
+            for (out, &value) in out[dst * channels..(dst + count) * channels]
+                .chunks_exact_mut(channels)
+                .zip(&data[src..src + count])
+            {
+                out[plane] = value;
+            }
+        }
+    }
+}
+
+/// Decode `region` from the strips or tiles of the current TIFF page that overlap it.
+fn tiff_region<R: Read + Seek>(decoder: &mut TiffDecoder<R>, region: Region, img_color: ImgColor) -> Result<SVec, DecodeError> {
+    let channels = tiff_channels(decoder.colortype().map_err(tiff_err)?)?;
+    let (width, height) = decoder.dimensions().map_err(tiff_err)?;
+    let planar = decoder
+        .find_tag_unsigned::<u16>(Tag::PlanarConfiguration)
+        .map_err(tiff_err)?
+        .is_some_and(|config| config == 2);
+    let (chunk_width, chunk_height) = decoder.chunk_dimensions();
+    let (chunk_width, chunk_height) = (chunk_width as usize, chunk_height as usize);
+    let across = (width as usize).div_ceil(chunk_width);
+    let per_plane = across * (height as usize).div_ceil(chunk_height);
+    let len = region.height * region.width * channels;
+    let mut samples = None;
+    for plane in 0..if planar { channels } else { 1 } {
+        let target = planar.then_some(plane);
+        for row in region.top / chunk_height..region.bottom().div_ceil(chunk_height) {
+            for col in region.left / chunk_width..region.right().div_ceil(chunk_width) {
+                // padding is only reported right for the first plane, later planes share its layout
+                let (data_width, data_height) = decoder.chunk_data_dimensions((row * across + col) as u32);
+                let index = (plane * per_plane + row * across + col) as u32;
+                let chunk = tiff_samples(decoder.read_chunk(index).map_err(tiff_err)?)?;
+                let (top, left) = (row * chunk_height, col * chunk_width);
+                let rows = region.top.max(top)..region.bottom().min(top + data_height as usize);
+                let cols = region.left.max(left)..region.right().min(left + data_width as usize);
+                let paste = |y: usize| {
+                    let src = (y - top) * data_width as usize + cols.start - left;
+                    let dst = (y - region.top) * region.width + cols.start - region.left;
+                    (src, dst)
+                };
+                let out = samples.get_or_insert_with(|| match chunk {
+                    RawSamples::U8(_) => RawSamples::U8(vec![0; len]),
+                    RawSamples::U16(_) => RawSamples::U16(vec![0; len]),
+                    RawSamples::F32(_) => RawSamples::F32(vec![0.0; len]),
+                });
+                match (out, &chunk) {
+                    (RawSamples::U8(out), RawSamples::U8(data)) => rows.for_each(|y| {
+                        let (src, dst) = paste(y);
+                        paste_pixels(out, data, src, dst, cols.len(), channels, target);
+                    }),
+                    (RawSamples::U16(out), RawSamples::U16(data)) => rows.for_each(|y| {
+                        let (src, dst) = paste(y);
+                        paste_pixels(out, data, src, dst, cols.len(), channels, target);
+                    }),
+                    (RawSamples::F32(out), RawSamples::F32(data)) => rows.for_each(|y| {
+                        let (src, dst) = paste(y);
+                        paste_pixels(out, data, src, dst, cols.len(), channels, target);
+                    }),
+                    _ => return Err(ImgDecodingError("TIFF chunks differ in sample type".to_string())),
+                }
+            }
+        }
+    }
+    let samples = samples.ok_or(Error::ZeroDimension)?;
+    img_color_from(&raw_to_dynamic(region.width, region.height, channels, samples)?, img_color)
+}
+
+/// Row-by-row reader over a non-interlaced PNG.
+struct PngRows<R: BufRead + Seek> {
+    reader: png::Reader<R>,
+    height: usize,
+    width: usize,
+    channels: usize,
+    sixteen: bool,
+}
+
+impl<R: BufRead + Seek> PngRows<R> {
+    fn new(reader: R) -> Result<Self, DecodeError> {
+        let mut decoder = png::Decoder::new(reader);
+        decoder.set_transformations(png::Transformations::EXPAND);
+        let reader = decoder.read_info().map_err(png_err)?;
+        let (color, depth) = reader.output_color_type();
+        let info = reader.info();
+        let (height, width) = (info.height as usize, info.width as usize);
+        Ok(Self {
+            reader,
+            height,
+            width,
+            channels: color.samples(),
+            sixteen: depth == png::BitDepth::Sixteen,
+        })
+    }
+
+    fn skip_rows(&mut self, rows: usize) -> Result<(), DecodeError> {
+        for _ in 0..rows {
+            self.reader.next_row().map_err(png_err)?;
+        }
+        Ok(())
+    }
+
+    /// Decode the next `rows` rows, keeping columns `left..left + width`.
+    fn read(&mut self, rows: usize, left: usize, width: usize, img_color: ImgColor) -> Result<SVec, DecodeError> {
+        let channels = self.channels;
+        let (start, end) = (left * channels, (left + width) * channels);
+        let mut bytes = Vec::with_capacity(rows * width * channels * if self.sixteen { 2 } else { 1 });
+        for _ in 0..rows {
+            let row = self
+                .reader
+                .next_row()
+                .map_err(png_err)?
+                .ok_or_else(|| ImgDecodingError("Image data is truncated".to_string()))?;
+            let data = row.data();
+            if self.sixteen {
+                bytes.extend_from_slice(&data[start * 2..end * 2]);
+            } else {
+                bytes.extend_from_slice(&data[start..end]);
+            }
+        }
+        let samples = if self.sixteen {
+            RawSamples::U16(bytes.chunks_exact(2).map(|b| u16::from_be_bytes([b[0], b[1]])).collect())
+        } else {
+            RawSamples::U8(bytes)
+        };
+        img_color_from(&raw_to_dynamic(width, rows, channels, samples)?, img_color)
+    }
+}
+
+fn png_err(e: png::DecodingError) -> DecodeError {
+    ImgDecodingError(format!("{:?}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::enums::ImageFormat;
+    use crate::save::{SaveOptions, svec_encode};
+    use pepecore_array::{ImgData, Shape};
+    use tiff::encoder::TiffEncoder;
+    use tiff::encoder::colortype::RGB8;
+
+    fn gradient() -> SVec {
+        let data = (0..7 * 10 * 3).map(|i| i as u8).collect();
+        SVec::new(Shape::new(7, 10, Some(3)), ImgData::U8(data))
+    }
+
+    fn striped_tiff(img: &SVec) -> Vec<u8> {
+        let mut tiff = Cursor::new(Vec::new());
+        let mut encoder = TiffEncoder::new(&mut tiff).unwrap();
+        let mut image = encoder.new_image::<RGB8>(10, 7).unwrap();
+        image.rows_per_strip(2).unwrap();
+        image.write_data(img.get_data::<u8>().unwrap()).unwrap();
+        tiff.into_inner()
+    }
+
+    /// Uncompressed little-endian RGB8 TIFF with one set of strips per plane
+    /// (`PlanarConfiguration = 2`), which the encoder cannot write.
+    fn planar_tiff(img: &SVec, rows_per_strip: usize) -> Vec<u8> {
+        let (h, w, _) = img.shape();
+        let data = img.get_data::<u8>().unwrap();
+        let strips = h.div_ceil(rows_per_strip);
+        let mut pixels = Vec::new();
+        let mut offsets = Vec::new();
+        let mut counts = Vec::new();
+        for plane in 0..3 {
+            for strip in 0..strips {
+                let rows = strip * rows_per_strip..h.min((strip + 1) * rows_per_strip);
+                offsets.push(8 + pixels.len() as u32);
+                counts.push((rows.len() * w) as u32);
+                pixels.extend(
+                    rows.flat_map(|y| (0..w).map(move |x| (y, x)))
+                        .map(|(y, x)| data[(y * w + x) * 3 + plane]),
+                );
+            }
+        }
+        let bits = 8 + pixels.len() as u32;
+        let offsets_at = bits + 6;
+        let counts_at = offsets_at + 4 * strips as u32 * 3;
+        let ifd_at = counts_at + 4 * strips as u32 * 3;
+        let mut tiff = b"II*\0".to_vec();
+        tiff.extend(ifd_at.to_le_bytes());
+        tiff.extend(pixels);
+        tiff.extend([8u16, 8, 8].iter().flat_map(|b| b.to_le_bytes()));
+        tiff.extend(offsets.iter().chain(&counts).flat_map(|v| v.to_le_bytes()));
+        let count = (strips * 3) as u32;
+        let entries: [(u16, u16, u32, u32); 10] = [
+            (256, 3, 1, w as u32),
+            (257, 3, 1, h as u32),
+            (258, 3, 3, bits),
+            (259, 3, 1, 1),
+            (262, 3, 1, 2),
+            (273, 4, count, offsets_at),
+            (277, 3, 1, 3),
+            (278, 3, 1, rows_per_strip as u32),
+            (279, 4, count, counts_at),
+            (284, 3, 1, 2),
+        ];
+        tiff.extend((entries.len() as u16).to_le_bytes());
+        for (tag, kind, count, value) in entries {
+            tiff.extend(tag.to_le_bytes());
+            tiff.extend(kind.to_le_bytes());
+            tiff.extend(count.to_le_bytes());
+            tiff.extend(value.to_le_bytes());
+        }
+        tiff.extend(0u32.to_le_bytes());
+        tiff
+    }
+
+    #[test]
+    fn test_region_matches_crop() {
+        let img = gradient();
+        let png = svec_encode(&img, ImageFormat::Png, SaveOptions::default()).unwrap();
+        let webp = svec_encode(&img, ImageFormat::WebP, SaveOptions::default()).unwrap();
+        let region = Region::new(3, 2, 3, 5);
+        let expected = img.view().crop(2, 3, 5, 3).unwrap().to_svec();
+        for buffer in [striped_tiff(&img), planar_tiff(&img, 2), png, webp] {
+            let read = read_region(&buffer, region, ImgColor::RGB).unwrap();
+            assert_eq!(read.shape(), (3, 5, Some(3)));
+            assert_eq!(read.get_data::<u8>().unwrap(), expected.get_data::<u8>().unwrap());
+        }
+        assert!(read_region(&striped_tiff(&img), Region::new(5, 0, 3, 10), ImgColor::RGB).is_err());
+    }
+
+    #[test]
+    fn test_tiles_cover_image() {
+        let img = gradient();
+        let png = svec_encode(&img, ImageFormat::Png, SaveOptions::default()).unwrap();
+        for buffer in [striped_tiff(&img), planar_tiff(&img, 3), png] {
+            let tiles = read_tiles(&buffer, 3, 4, ImgColor::RGB).unwrap();
+            assert_eq!(tiles.shape(), (7, 10, Some(3)));
+            let tiles: Vec<Tile> = tiles.collect::<Result<_, _>>().unwrap();
+            assert_eq!(tiles.len(), 9);
+            assert_eq!(tiles[8].region, Region::new(6, 8, 1, 2));
+            for tile in tiles {
+                let Region {
+                    top,
+                    left,
+                    height,
+                    width,
+                } = tile.region;
+                let expected = img.view().crop(left, top, width, height).unwrap().to_svec();
+                assert_eq!(tile.image.get_data::<u8>().unwrap(), expected.get_data::<u8>().unwrap());
+            }
+        }
+        assert!(read_tiles(&striped_tiff(&img), 0, 4, ImgColor::RGB).is_err());
+    }
+
+    #[test]
+    fn test_scaled() {
+        let img = SVec::new(Shape::new(33, 20, Some(3)), ImgData::U8(vec![128; 33 * 20 * 3]));
+        let jpeg = svec_encode(&img, ImageFormat::Jpeg, SaveOptions::default()).unwrap();
+        let png = svec_encode(&img, ImageFormat::Png, SaveOptions::default()).unwrap();
+        for denominator in [1, 2, 4, 8] {
+            let expected = (33usize.div_ceil(denominator), 20usize.div_ceil(denominator), Some(3));
+            assert_eq!(read_scaled(&jpeg, denominator, ImgColor::RGB).unwrap().shape(), expected);
+            assert_eq!(read_scaled(&png, denominator, ImgColor::RGB).unwrap().shape(), expected);
+        }
+        assert!(read_scaled(&png, 3, ImgColor::RGB).is_err());
+    }
+}
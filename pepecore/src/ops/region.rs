@@ -0,0 +1,39 @@
+//! Pixel rectangles and tiles shared by partial decoding ([`crate::region`]) and tiled
+//! processing ([`crate::tile`]).
+use pepecore_array::SVec;
+
+/// Pixel rectangle, `top`/`left` of the first row and column.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct Region {
+    pub top: usize,
+    pub left: usize,
+    pub height: usize,
+    pub width: usize,
+}
+
+impl Region {
+    pub fn new(top: usize, left: usize, height: usize, width: usize) -> Self {
+        Self {
+            top,
+            left,
+            height,
+            width,
+        }
+    }
+
+    pub(crate) fn bottom(&self) -> usize {
+        self.top + self.height
+    }
+
+    pub(crate) fn right(&self) -> usize {
+        self.left + self.width
+    }
+}
+
+/// One tile and the rectangle of the image it covers, from [`read_tiles`](crate::region::read_tiles)
+/// or [`Tiler::split`](crate::tile::Tiler::split).
+#[derive(Debug, Clone)]
+pub struct Tile {
+    pub region: Region,
+    pub image: SVec,
+}
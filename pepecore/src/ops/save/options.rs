@@ -0,0 +1,88 @@
+//! Per-format encoder settings for [`svec_save_with`](crate::ops::save::save::svec_save_with).
+//!
+//! Only the block matching the output format is used, the rest are ignored.
+//! `SaveOptions::default()` reproduces what [`svec_save`](crate::ops::save::save::svec_save) writes.
+
+use crate::enums::{PngCompression, PngFilter, TiffCompression, YCbCrRatio};
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct SaveOptions {
+    pub jpeg: JpegOptions,
+    pub png: PngOptions,
+    pub webp: WebpOptions,
+    pub tiff: TiffOptions,
+    pub jxl: JxlOptions,
+    /// Used by [`svec_save_frames`](crate::ops::save::save::svec_save_frames) only.
+    pub animation: AnimationOptions,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct JpegOptions {
+    /// 1..=100
+    pub quality: u8,
+    /// Chroma subsampling, only used for colour images and only with the `encode` feature.
+    pub subsampling: YCbCrRatio,
+}
+
+impl Default for JpegOptions {
+    fn default() -> Self {
+        Self {
+            quality: 75,
+            subsampling: YCbCrRatio::R420,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct PngOptions {
+    pub compression: PngCompression,
+    pub filter: PngFilter,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WebpOptions {
+    pub lossless: bool,
+    /// 0..=100, quality for lossy output and effort for lossless output.
+    pub quality: f32,
+}
+
+impl Default for WebpOptions {
+    fn default() -> Self {
+        Self {
+            lossless: true,
+            quality: 75.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct TiffOptions {
+    pub compression: TiffCompression,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct JxlOptions {
+    /// 1..=9, higher is slower and smaller.
+    pub effort: u8,
+    /// `0.0..=25.0`, `0.0` is lossless.
+    ///
+    /// The bundled encoder is modular-only, so instead of a butteraugli target this is a
+    /// near-lossless bound: every sample stays within `distance` 8-bit code values of the
+    /// source, and larger values give smaller files.
+    pub distance: f32,
+}
+
+impl Default for JxlOptions {
+    fn default() -> Self {
+        Self {
+            effort: 4,
+            distance: 0.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct AnimationOptions {
+    /// How many times the animation plays, `0` loops forever.
+    pub loop_count: u16,
+}
@@ -1,7 +1,13 @@
 //! Module providing functionality to save `SVec` images to disk using the `image` crate.
 //!
+//! [`svec_save_with`] takes a [`SaveOptions`] with per-format encoder settings (JPEG quality and
+//! subsampling, PNG compression and filter, lossy WebP, TIFF compression, JPEG XL effort and distance).
+//!
 //! Supports saving single-channel (gray), two-channel (gray+alpha), three-channel (RGB), and four-channel (RGBA)
-//! images of various bit depths (u8, u16, f32), with proper error handling for unsupported formats.
+//! images of various bit depths (u8, u16, f32; i16 and f16 are widened to f32 first), with proper error
+//! handling for unsupported formats. Samples are written at their own depth: `u16` as 16-bit PNG/TIFF/JXL,
+//! `f32` as 32-bit float TIFF or EXR (EXR also takes `u8`/`u16`, stored as normalised float); other
+//! combinations, `u32` and `f64` included, are rejected rather than quantised.
 //!
 //! # Examples
 //!
@@ -17,14 +23,38 @@
 //!                                                                  77,255,9]));
 //!
 //! // Save as PNG with automatic channel mapping
-//! svec_save(svec, "output.png").expect("Failed to save image");
+//! svec_save(svec, &std::env::temp_dir().join("output.png")).expect("Failed to save image");
 //! ```
 
+#[cfg(feature = "encode")]
+use crate::enums::YCbCrRatio;
+use crate::enums::{Disposal, ImageFormat, PngCompression, PngFilter, TiffCompression};
 use crate::errors::SaveError;
-use crate::errors::SaveError::{GraySaveError, JxlSaveError, RGBSaveError, UnsupportedChannelSaveError};
-use image::{ImageBuffer, Luma, LumaA, Rgb, Rgba};
-use pepecore_array::{ImgData, SVec};
+use crate::errors::SaveError::{
+    AnimationSaveError, ExrSaveError, GifSaveError, GraySaveError, JpegSaveError, JxlSaveError, PngSaveError, RGBSaveError,
+    TiffSaveError, UnsupportedChannelSaveError, UnsupportedPixelTypeSaveError, WebpSaveError,
+};
+pub use crate::ops::read::frames::Frame;
+pub use crate::ops::read::options::ImageMetadata;
+pub use crate::ops::save::options::{
+    AnimationOptions, JpegOptions, JxlOptions, PngOptions, SaveOptions, TiffOptions, WebpOptions,
+};
+use exr::prelude::{AnyChannel, AnyChannels, Encoding, FlatSamples, Image, Layer, LayerAttributes, WritableImage};
+use flate2::Crc;
+use image::codecs::png::{CompressionType, FilterType, PngEncoder};
+use image::{ExtendedColorType, ImageBuffer, ImageEncoder, Luma, LumaA, Rgb, Rgba};
+#[cfg(feature = "encode")]
+use jpeg_encoder::{ColorType as JpegColorType, Encoder as JpegEncoder, SamplingFactor};
+use pepecore_array::{ImgData, Pixel, PixelType, SVec};
+use std::io::{Cursor, Seek, Write};
 use std::path::Path;
+use std::time::Duration;
+use tiff::TiffResult;
+use tiff::encoder::colortype::ColorType as TiffColorType;
+use tiff::encoder::colortype::{Gray8, Gray16, Gray32Float, RGB8, RGB16, RGB32Float, RGBA8, RGBA16, RGBA32Float};
+use tiff::encoder::compression::{Compression, Deflate, DeflateLevel, Lzw, Packbits, Uncompressed};
+use tiff::encoder::{TiffEncoder, TiffValue};
+use tiff::tags::Tag;
 use zune_core::bit_depth::BitDepth;
 use zune_core::colorspace::ColorSpace;
 use zune_core::options::EncoderOptions;
@@ -50,6 +80,7 @@ use zune_jpegxl::JxlSimpleEncoder;
 /// - `GraySaveError` for errors saving grayscale or gray+alpha images.
 /// - `RGBSaveError` for errors saving RGB or RGBA images.
 /// - `UnsupportedChannelSaveError` if the channel count is not 0–4.
+/// - `UnsupportedPixelTypeSaveError` if the format can't hold the pixel type (e.g. `f32` as PNG).
 ///
 /// # Examples
 ///
@@ -58,30 +89,237 @@ use zune_jpegxl::JxlSimpleEncoder;
 /// use pepecore_array::{SVec,Shape,PixelType,ImgData};
 /// // Create or decode an SVec with 3 channels, u8 data
 /// let svec: SVec = SVec::new(Shape::new(1,1,Some(3)),ImgData::U8(vec![0,128,255]));
-/// svec_save(svec, "photo_out.jpg").unwrap();
+/// svec_save(svec, &std::env::temp_dir().join("photo_out.jpg")).unwrap();
 /// ```
 pub fn svec_save<P: AsRef<Path> + ?Sized>(img: SVec, path: &P) -> Result<(), SaveError> {
+    svec_save_with(img, path, SaveOptions::default())
+}
+
+/// Save an `SVec` image like [`svec_save`], using the encoder settings in `options`.
+///
+/// JPEG, PNG, WebP, TIFF and JPEG XL honour their block of [`SaveOptions`]; other extensions are
+/// written with the `image` crate defaults.
+///
+/// # Errors
+///
+/// Besides the errors of [`svec_save`], each format reports encoder failures and unsupported
+/// combinations (e.g. 16-bit JPEG, `f32` JPEG XL) through its own `SaveError` variant.
+///
+/// # Examples
+///
+/// ```rust
+/// use pepecore::enums::YCbCrRatio;
+/// use pepecore::save::{svec_save_with, JpegOptions, SaveOptions};
+/// use pepecore_array::{ImgData, SVec, Shape};
+///
+/// let svec = SVec::new(Shape::new(1, 1, Some(3)), ImgData::U8(vec![0, 128, 255]));
+/// let options = SaveOptions {
+///     jpeg: JpegOptions { quality: 95, subsampling: YCbCrRatio::R444 },
+///     ..Default::default()
+/// };
+/// svec_save_with(svec, "photo_q95.jpg", options).unwrap();
+/// # std::fs::remove_file("photo_q95.jpg").unwrap();
+/// ```
+pub fn svec_save_with<P: AsRef<Path> + ?Sized>(img: SVec, path: &P, options: SaveOptions) -> Result<(), SaveError> {
+    svec_save_with_metadata(img, path, options, &ImageMetadata::default())
+}
+
+/// Save an `SVec` image like [`svec_save_with`], embedding the ICC profile and EXIF returned by
+/// [`read_in_path_with`](crate::ops::read::read::read_in_path_with).
+///
+/// JPEG, PNG and WebP keep both, TIFF keeps the ICC profile; EXR, JPEG XL and the formats
+/// written through the `image` fallback drop them.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use pepecore::enums::ImgColor;
+/// use pepecore::read::{read_in_path_with, ReadOptions};
+/// use pepecore::save::{svec_save_with_metadata, SaveOptions};
+///
+/// let (img, metadata) = read_in_path_with("phone.jpg", ImgColor::RGB, ReadOptions::default()).unwrap();
+/// svec_save_with_metadata(img, "phone.png", SaveOptions::default(), &metadata).unwrap();
+/// ```
+pub fn svec_save_with_metadata<P: AsRef<Path> + ?Sized>(
+    img: SVec,
+    path: &P,
+    options: SaveOptions,
+    metadata: &ImageMetadata,
+) -> Result<(), SaveError> {
     let ext = path
         .as_ref()
         .extension()
         .and_then(|e| e.to_str())
         .unwrap_or("")
         .to_ascii_lowercase();
-    if ext == "jxl" {
-        return save_jxl(img, path);
+    let format = match ext.as_str() {
+        "jxl" => ImageFormat::Jxl,
+        "jpg" | "jpeg" => ImageFormat::Jpeg,
+        "png" => ImageFormat::Png,
+        "webp" => ImageFormat::WebP,
+        "tif" | "tiff" => ImageFormat::Tiff,
+        "exr" => ImageFormat::Exr,
+        "gif" => ImageFormat::Gif,
+        _ => return save_image(to_saveable(img, &ext)?, path),
+    };
+    // encode fully before touching the file, a failed encode leaves nothing behind
+    let mut out = Vec::new();
+    let img = to_saveable(img, &format!("{:?}", format))?;
+    encode(img, format, options, metadata, &mut out)?;
+    std::fs::write(path, out)?;
+    Ok(())
+}
+
+/// Encode an `SVec` into an in-memory buffer, the counterpart of
+/// [`read_in_buffer`](crate::ops::read::read::read_in_buffer).
+///
+/// Channel handling and pixel type conversion are the same as for [`svec_save`].
+///
+/// # Examples
+///
+/// ```rust
+/// use pepecore::enums::ImageFormat;
+/// use pepecore::save::{svec_encode, SaveOptions};
+/// use pepecore_array::{ImgData, SVec, Shape};
+///
+/// let svec = SVec::new(Shape::new(1, 2, Some(3)), ImgData::U8(vec![0, 128, 255, 255, 128, 0]));
+/// let png = svec_encode(&svec, ImageFormat::Png, SaveOptions::default()).unwrap();
+/// assert_eq!(&png[1..4], b"PNG");
+/// ```
+pub fn svec_encode(img: &SVec, format: ImageFormat, options: SaveOptions) -> Result<Vec<u8>, SaveError> {
+    let mut out = Vec::new();
+    svec_encode_to(img, format, options, &mut out)?;
+    Ok(out)
+}
+
+/// Encode an `SVec` like [`svec_encode`], writing the result into `out`.
+///
+/// `out` is not buffered or flushed, wrap sockets and files in a `BufWriter`.
+pub fn svec_encode_to<W: Write>(img: &SVec, format: ImageFormat, options: SaveOptions, out: W) -> Result<(), SaveError> {
+    let img = to_saveable(img.clone(), &format!("{:?}", format))?;
+    encode(img, format, options, &ImageMetadata::default(), out)
+}
+
+/// Encode an `SVec` like [`svec_encode`], embedding the ICC profile and EXIF from `metadata`.
+///
+/// JPEG, PNG and WebP keep both, TIFF keeps the ICC profile; EXR and JPEG XL drop them.
+pub fn svec_encode_with_metadata(
+    img: &SVec,
+    format: ImageFormat,
+    options: SaveOptions,
+    metadata: &ImageMetadata,
+) -> Result<Vec<u8>, SaveError> {
+    let mut out = Vec::new();
+    let img = to_saveable(img.clone(), &format!("{:?}", format))?;
+    encode(img, format, options, metadata, &mut out)?;
+    Ok(out)
+}
+
+/// Save a sequence of frames as an animated GIF, WebP or PNG (APNG), picked by the extension.
+///
+/// Frames are written full-canvas at position `(0, 0)` and replace the previous one, so the
+/// composited frames from [`read_frames`](crate::ops::read::frames::read_frames) round-trip.
+/// `options.animation` sets the loop count; WebP and PNG frames use `options.webp` and
+/// `options.png`.
+///
+/// # Errors
+///
+/// Returns `AnimationSaveError` for an empty sequence, frames of different shapes or another
+/// extension. GIF and WebP take `u8` frames, APNG `u8` or `u16`.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use pepecore::enums::ImgColor;
+/// use pepecore::frames::read_frames_path;
+/// use pepecore::save::{svec_save_frames, SaveOptions};
+///
+/// let frames: Vec<_> = read_frames_path("anim.gif", ImgColor::RGBA)
+///     .unwrap()
+///     .collect::<Result<_, _>>()
+///     .unwrap();
+/// svec_save_frames(&frames, "anim.webp", SaveOptions::default()).unwrap();
+/// ```
+pub fn svec_save_frames<P: AsRef<Path> + ?Sized>(frames: &[Frame], path: &P, options: SaveOptions) -> Result<(), SaveError> {
+    let ext = path
+        .as_ref()
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or("")
+        .to_ascii_lowercase();
+    let format = match ext.as_str() {
+        "gif" => ImageFormat::Gif,
+        "png" | "apng" => ImageFormat::Png,
+        "webp" => ImageFormat::WebP,
+        _ => return Err(AnimationSaveError(format!("{} can't hold an animation", ext))),
+    };
+    std::fs::write(path, svec_encode_frames(frames, format, options)?)?;
+    Ok(())
+}
+
+/// Encode a sequence of frames into an in-memory animated GIF, WebP or PNG, see
+/// [`svec_save_frames`].
+pub fn svec_encode_frames(frames: &[Frame], format: ImageFormat, options: SaveOptions) -> Result<Vec<u8>, SaveError> {
+    let Some(first) = frames.first() else {
+        return Err(AnimationSaveError("no frames to encode".to_string()));
+    };
+    let shape = first.image.shape();
+    let frames = frames
+        .iter()
+        .enumerate()
+        .map(|(i, frame)| {
+            if frame.image.shape() != shape {
+                return Err(AnimationSaveError(format!(
+                    "frame {} has shape {:?}, the first frame {:?}",
+                    i,
+                    frame.image.shape(),
+                    shape
+                )));
+            }
+            Ok(Frame {
+                image: to_saveable(frame.image.clone(), &format!("{:?}", format))?,
+                ..*frame
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let loop_count = options.animation.loop_count;
+    let mut out = Vec::new();
+    match format {
+        ImageFormat::Gif => encode_gif(frames, &mut out, loop_count)?,
+        ImageFormat::Png => encode_apng(frames, &mut out, &options.png, loop_count)?,
+        ImageFormat::WebP => encode_webp_frames(frames, &mut out, &options.webp, loop_count)?,
+        _ => return Err(AnimationSaveError(format!("{:?} can't hold an animation", format))),
+    }
+    Ok(out)
+}
+
+fn encode<W: Write>(
+    img: SVec,
+    format: ImageFormat,
+    options: SaveOptions,
+    metadata: &ImageMetadata,
+    out: W,
+) -> Result<(), SaveError> {
+    match format {
+        ImageFormat::Png => encode_png(img, out, &options.png, metadata),
+        ImageFormat::Jpeg => encode_jpeg(img, out, &options.jpeg, metadata),
+        ImageFormat::WebP => encode_webp(img, out, &options.webp, metadata),
+        ImageFormat::Tiff => encode_tiff(img, out, &options.tiff, metadata.icc_profile.as_deref()),
+        ImageFormat::Jxl => encode_jxl(img, out, &options.jxl),
+        ImageFormat::Exr => encode_exr(img, out),
+        ImageFormat::Gif => encode_gif(vec![Frame::new(img)], out, 1),
     }
+}
 
+/// Fallback for formats without their own options, saved through `ImageBuffer::save`.
+fn save_image<P: AsRef<Path> + ?Sized>(img: SVec, path: &P) -> Result<(), SaveError> {
+    if img.pixel_type() == PixelType::F32 {
+        let ext = path.as_ref().extension().and_then(|e| e.to_str()).unwrap_or("");
+        return Err(unsupported(ext, PixelType::F32));
+    }
     let (height, width, channel) = img.shape();
     match channel {
         Some(1) | None => match img.data {
-            ImgData::F32(data) => {
-                let img: ImageBuffer<Luma<u8>, Vec<u8>> = ImageBuffer::from_fn(width as u32, height as u32, |x, y| {
-                    let idx = (y * width as u32 + x) as usize;
-                    let value = (data[idx] * 255.0) as u8;
-                    Luma([value])
-                });
-                img.save(path).map_err(|e| GraySaveError(format!("{:?}", e)))?
-            }
             ImgData::U16(data) => {
                 let img: ImageBuffer<Luma<u16>, Vec<u16>> = ImageBuffer::from_raw(width as u32, height as u32, data).unwrap();
                 img.save(path).map_err(|e| GraySaveError(format!("{:?}", e)))?
@@ -90,17 +328,9 @@ pub fn svec_save<P: AsRef<Path> + ?Sized>(img: SVec, path: &P) -> Result<(), Sav
                 let img: ImageBuffer<Luma<u8>, Vec<u8>> = ImageBuffer::from_raw(width as u32, height as u32, data).unwrap();
                 img.save(path).map_err(|e| GraySaveError(format!("{:?}", e)))?
             }
+            _ => unreachable!(),
         },
         Some(2) => match img.data {
-            ImgData::F32(data) => {
-                let img: ImageBuffer<LumaA<u8>, Vec<u8>> = ImageBuffer::from_fn(width as u32, height as u32, |x, y| {
-                    let idx = ((y * width as u32 + x) * 2) as usize;
-                    let value = (data[idx] * 255.0) as u8;
-                    let alpha = (data[idx + 1] * 255.0) as u8;
-                    LumaA([value, alpha])
-                });
-                img.save(path).map_err(|e| GraySaveError(format!("{:?}", e)))?
-            }
             ImgData::U16(data) => {
                 let img: ImageBuffer<LumaA<u16>, Vec<u16>> = ImageBuffer::from_raw(width as u32, height as u32, data).unwrap();
                 img.save(path).map_err(|e| GraySaveError(format!("{:?}", e)))?
@@ -109,18 +339,9 @@ pub fn svec_save<P: AsRef<Path> + ?Sized>(img: SVec, path: &P) -> Result<(), Sav
                 let img: ImageBuffer<LumaA<u8>, Vec<u8>> = ImageBuffer::from_raw(width as u32, height as u32, data).unwrap();
                 img.save(path).map_err(|e| GraySaveError(format!("{:?}", e)))?
             }
+            _ => unreachable!(),
         },
         Some(3) => match img.data {
-            ImgData::F32(data) => {
-                let img: ImageBuffer<Rgb<u8>, Vec<u8>> = ImageBuffer::from_fn(width as u32, height as u32, |x, y| {
-                    let idx = ((y * width as u32 + x) * 3) as usize;
-                    let r = (data[idx] * 255.0) as u8;
-                    let g = (data[idx + 1] * 255.0) as u8;
-                    let b = (data[idx + 2] * 255.0) as u8;
-                    Rgb([r, g, b])
-                });
-                img.save(path).map_err(|e| RGBSaveError(format!("{:?}", e)))?
-            }
             ImgData::U16(data) => {
                 let img: ImageBuffer<Rgb<u16>, Vec<u16>> = ImageBuffer::from_raw(width as u32, height as u32, data).unwrap();
                 img.save(path).map_err(|e| RGBSaveError(format!("{:?}", e)))?
@@ -129,19 +350,9 @@ pub fn svec_save<P: AsRef<Path> + ?Sized>(img: SVec, path: &P) -> Result<(), Sav
                 let img: ImageBuffer<Rgb<u8>, Vec<u8>> = ImageBuffer::from_raw(width as u32, height as u32, data).unwrap();
                 img.save(path).map_err(|e| RGBSaveError(format!("{:?}", e)))?
             }
+            _ => unreachable!(),
         },
         Some(4) => match img.data {
-            ImgData::F32(data) => {
-                let img: ImageBuffer<Rgba<u8>, Vec<u8>> = ImageBuffer::from_fn(width as u32, height as u32, |x, y| {
-                    let idx = ((y * width as u32 + x) * 4) as usize;
-                    let r = (data[idx] * 255.0) as u8;
-                    let g = (data[idx + 1] * 255.0) as u8;
-                    let b = (data[idx + 2] * 255.0) as u8;
-                    let a = (data[idx + 3] * 255.0) as u8;
-                    Rgba([r, g, b, a])
-                });
-                img.save(path).map_err(|e| RGBSaveError(format!("{:?}", e)))?
-            }
             ImgData::U16(data) => {
                 let img: ImageBuffer<Rgba<u16>, Vec<u16>> = ImageBuffer::from_raw(width as u32, height as u32, data).unwrap();
                 img.save(path).map_err(|e| RGBSaveError(format!("{:?}", e)))?
@@ -150,6 +361,7 @@ pub fn svec_save<P: AsRef<Path> + ?Sized>(img: SVec, path: &P) -> Result<(), Sav
                 let img: ImageBuffer<Rgba<u8>, Vec<u8>> = ImageBuffer::from_raw(width as u32, height as u32, data).unwrap();
                 img.save(path).map_err(|e| RGBSaveError(format!("{:?}", e)))?
             }
+            _ => unreachable!(),
         },
 
         _ => return Err(UnsupportedChannelSaveError(format!("{:?}", channel))),
@@ -157,7 +369,36 @@ pub fn svec_save<P: AsRef<Path> + ?Sized>(img: SVec, path: &P) -> Result<(), Sav
     Ok(())
 }
 
-fn save_jxl<P: AsRef<Path> + ?Sized>(img: SVec, path: &P) -> Result<(), SaveError> {
+/// Snap every sample to the clamped-gradient prediction the modular encoder makes from the
+/// already snapped neighbours whenever it is within `tolerance` code values of the source.
+///
+/// Those residuals become zero and are run-length coded, which turns the lossless encoder into
+/// a near-lossless one with a bounded per-sample error.
+fn jxl_near_lossless<T: Pixel>(data: &mut [T], width: usize, channels: usize, tolerance: f64) {
+    let row = width * channels;
+    for i in 0..data.len() {
+        let (y, x) = (i / row, i % row / channels);
+        let at = |i: usize| data[i].to_f64();
+        let left = if x > 0 {
+            at(i - channels)
+        } else if y > 0 {
+            at(i - row)
+        } else {
+            0.0
+        };
+        let top = if y > 0 { at(i - row) } else { left };
+        let top_left = if x > 0 && y > 0 { at(i - row - channels) } else { top };
+        let prediction = (left + top - top_left).clamp(left.min(top), left.max(top));
+        if (at(i) - prediction).abs() <= tolerance {
+            data[i] = T::from_f64(prediction);
+        }
+    }
+}
+
+fn encode_jxl<W: Write>(img: SVec, mut out: W, jxl: &JxlOptions) -> Result<(), SaveError> {
+    if !(0.0..=25.0).contains(&jxl.distance) {
+        return Err(JxlSaveError(format!("distance {} is outside 0.0..=25.0", jxl.distance)));
+    }
     let (height, width, channel) = img.shape();
     let colorspace = match channel {
         Some(1) | None => ColorSpace::Luma,
@@ -166,40 +407,758 @@ fn save_jxl<P: AsRef<Path> + ?Sized>(img: SVec, path: &P) -> Result<(), SaveErro
         Some(4) => ColorSpace::RGBA,
         _ => return Err(UnsupportedChannelSaveError(format!("{:?}", channel))),
     };
-    // fixme: open file stream and write to stream
     match img.data {
-        ImgData::U8(data) => {
-            let options = EncoderOptions::new(width, height, colorspace, BitDepth::Eight);
+        ImgData::U8(mut data) => {
+            if jxl.distance > 0.0 {
+                jxl_near_lossless(&mut data, width, colorspace.num_components(), jxl.distance as f64);
+            }
+            let options = EncoderOptions::new(width, height, colorspace, BitDepth::Eight).set_effort(jxl.effort);
             let encoder = JxlSimpleEncoder::new(&data, options);
-            let mut out = Vec::new();
-            encoder.encode(&mut out).map_err(|e| JxlSaveError(format!("{:?}", e)))?;
-            std::fs::write(path, out).map_err(|e| JxlSaveError(format!("{:?}", e)))?;
+            let mut encoded = Vec::new();
+            encoder.encode(&mut encoded).map_err(|e| JxlSaveError(format!("{:?}", e)))?;
+            out.write_all(&encoded)?;
             Ok(())
         }
-        ImgData::U16(data) => {
+        ImgData::U16(mut data) => {
+            if jxl.distance > 0.0 {
+                jxl_near_lossless(&mut data, width, colorspace.num_components(), jxl.distance as f64 * 257.0);
+            }
             let mut bytes = Vec::with_capacity(data.len() * 2);
             for v in data {
                 bytes.extend_from_slice(&v.to_ne_bytes());
             }
-            let options = EncoderOptions::new(width, height, colorspace, BitDepth::Sixteen);
+            let options = EncoderOptions::new(width, height, colorspace, BitDepth::Sixteen).set_effort(jxl.effort);
             let encoder = JxlSimpleEncoder::new(&bytes, options);
-            let mut out = Vec::new();
-            encoder.encode(&mut out).map_err(|e| JxlSaveError(format!("{:?}", e)))?;
-            std::fs::write(path, out).map_err(|e| JxlSaveError(format!("{:?}", e)))?;
+            let mut encoded = Vec::new();
+            encoder.encode(&mut encoded).map_err(|e| JxlSaveError(format!("{:?}", e)))?;
+            out.write_all(&encoded)?;
             Ok(())
         }
-        ImgData::F32(data) => {
-            let mut bytes = Vec::with_capacity(data.len());
-            for v in data {
-                let val = (v.clamp(0.0, 1.0) * 255.0) as u8; // fixme: slow
-                bytes.push(val);
+        _ => Err(unsupported("jxl", img.pixel_type())),
+    }
+}
+
+/// Interleaved samples as the encoders take them.
+enum Samples {
+    U8(Vec<u8>),
+    U16(Vec<u16>),
+    F32(Vec<f32>),
+}
+
+fn samples(img: SVec) -> Samples {
+    match img.data {
+        ImgData::U8(data) => Samples::U8(data),
+        ImgData::U16(data) => Samples::U16(data),
+        ImgData::F32(data) => Samples::F32(data),
+        _ => unreachable!(),
+    }
+}
+
+fn unsupported(format: &str, pixel_type: PixelType) -> SaveError {
+    UnsupportedPixelTypeSaveError {
+        format: format.to_ascii_lowercase(),
+        pixel_type,
+    }
+}
+
+#[cfg(feature = "encode")]
+fn encode_jpeg<W: Write>(img: SVec, out: W, jpeg: &JpegOptions, metadata: &ImageMetadata) -> Result<(), SaveError> {
+    let (height, width, channel) = img.shape();
+    let color = match channel {
+        Some(1) | None => JpegColorType::Luma,
+        Some(3) => JpegColorType::Rgb,
+        Some(4) => JpegColorType::Rgba,
+        _ => return Err(UnsupportedChannelSaveError(format!("{:?}", channel))),
+    };
+    let pixel_type = img.pixel_type();
+    let Samples::U8(data) = samples(img) else {
+        return Err(unsupported("jpeg", pixel_type));
+    };
+    let (width, height) = match (u16::try_from(width), u16::try_from(height)) {
+        (Ok(w), Ok(h)) => (w, h),
+        _ => return Err(JpegSaveError(format!("{}x{} exceeds the 65535px limit", width, height))),
+    };
+    let mut encoder = JpegEncoder::new(out, jpeg.quality.clamp(1, 100));
+    encoder.set_sampling_factor(sampling_factor(jpeg.subsampling));
+    if let Some(exif) = &metadata.exif {
+        let segment = [&b"Exif\0\0"[..], exif].concat();
+        encoder
+            .add_app_segment(1, &segment)
+            .map_err(|e| JpegSaveError(format!("{:?}", e)))?;
+    }
+    if let Some(icc) = &metadata.icc_profile {
+        encoder.add_icc_profile(icc).map_err(|e| JpegSaveError(format!("{:?}", e)))?;
+    }
+    encoder
+        .encode(&data, width, height, color)
+        .map_err(|e| JpegSaveError(format!("{:?}", e)))
+}
+
+#[cfg(feature = "encode")]
+fn sampling_factor(ratio: YCbCrRatio) -> SamplingFactor {
+    match ratio {
+        YCbCrRatio::R444 => SamplingFactor::R_4_4_4,
+        YCbCrRatio::R440 => SamplingFactor::R_4_4_0,
+        YCbCrRatio::R441 => SamplingFactor::R_4_4_1,
+        YCbCrRatio::R422 => SamplingFactor::R_4_2_2,
+        YCbCrRatio::R420 => SamplingFactor::R_4_2_0,
+        YCbCrRatio::R411 => SamplingFactor::R_4_1_1,
+        YCbCrRatio::R410 => SamplingFactor::R_4_1_0,
+    }
+}
+
+/// JPEG through the `image` encoder when the `encode` feature (and with it `jpeg-encoder`) is off.
+///
+/// Alpha is dropped and `subsampling` is ignored, the `image` encoder has a fixed chroma layout.
+#[cfg(not(feature = "encode"))]
+fn encode_jpeg<W: Write>(mut img: SVec, mut out: W, jpeg: &JpegOptions, metadata: &ImageMetadata) -> Result<(), SaveError> {
+    use image::codecs::jpeg::JpegEncoder;
+
+    if img.shape.get_channels() == Some(4) {
+        img.drop_alpha().map_err(|e| JpegSaveError(e.to_string()))?;
+    }
+    let (height, width, channel) = img.shape();
+    let color = match channel {
+        Some(1) | None => ExtendedColorType::L8,
+        Some(3) => ExtendedColorType::Rgb8,
+        _ => return Err(UnsupportedChannelSaveError(format!("{:?}", channel))),
+    };
+    let pixel_type = img.pixel_type();
+    let Samples::U8(data) = samples(img) else {
+        return Err(unsupported("jpeg", pixel_type));
+    };
+    let mut encoded = Vec::new();
+    let mut encoder = JpegEncoder::new_with_quality(&mut encoded, jpeg.quality.clamp(1, 100));
+    if let Some(icc) = &metadata.icc_profile {
+        encoder
+            .set_icc_profile(icc.clone())
+            .map_err(|e| JpegSaveError(e.to_string()))?;
+    }
+    encoder
+        .write_image(&data, width as u32, height as u32, color)
+        .map_err(|e| JpegSaveError(e.to_string()))?;
+    // the APP1 segment goes right after SOI
+    out.write_all(&encoded[..2])?;
+    if let Some(exif) = &metadata.exif {
+        let len = u16::try_from(exif.len() + 8).map_err(|_| JpegSaveError("EXIF block exceeds 64 KiB".into()))?;
+        out.write_all(&[0xFF, 0xE1])?;
+        out.write_all(&len.to_be_bytes())?;
+        out.write_all(b"Exif\0\0")?;
+        out.write_all(exif)?;
+    }
+    out.write_all(&encoded[2..])?;
+    Ok(())
+}
+
+fn encode_png<W: Write>(img: SVec, mut out: W, png: &PngOptions, metadata: &ImageMetadata) -> Result<(), SaveError> {
+    let (height, width, channel) = img.shape();
+    let (bytes, color) = match (samples(img), channel) {
+        (Samples::U8(data), Some(1) | None) => (data, ExtendedColorType::L8),
+        (Samples::U8(data), Some(2)) => (data, ExtendedColorType::La8),
+        (Samples::U8(data), Some(3)) => (data, ExtendedColorType::Rgb8),
+        (Samples::U8(data), Some(4)) => (data, ExtendedColorType::Rgba8),
+        (Samples::U16(data), Some(1) | None) => (ne_bytes(&data), ExtendedColorType::L16),
+        (Samples::U16(data), Some(2)) => (ne_bytes(&data), ExtendedColorType::La16),
+        (Samples::U16(data), Some(3)) => (ne_bytes(&data), ExtendedColorType::Rgb16),
+        (Samples::U16(data), Some(4)) => (ne_bytes(&data), ExtendedColorType::Rgba16),
+        (Samples::F32(_), _) => return Err(unsupported("png", PixelType::F32)),
+        _ => return Err(UnsupportedChannelSaveError(format!("{:?}", channel))),
+    };
+    let compression = match png.compression {
+        PngCompression::Fast => CompressionType::Fast,
+        PngCompression::Default => CompressionType::Default,
+        PngCompression::Best => CompressionType::Best,
+    };
+    let filter = match png.filter {
+        PngFilter::NoFilter => FilterType::NoFilter,
+        PngFilter::Sub => FilterType::Sub,
+        PngFilter::Up => FilterType::Up,
+        PngFilter::Avg => FilterType::Avg,
+        PngFilter::Paeth => FilterType::Paeth,
+        PngFilter::Adaptive => FilterType::Adaptive,
+    };
+    let mut encoded = Vec::new();
+    let mut encoder = PngEncoder::new_with_quality(&mut encoded, compression, filter);
+    if let Some(icc) = &metadata.icc_profile {
+        encoder
+            .set_icc_profile(icc.clone())
+            .map_err(|e| PngSaveError(format!("{:?}", e)))?;
+    }
+    encoder
+        .write_image(&bytes, width as u32, height as u32, color)
+        .map_err(|e| PngSaveError(format!("{:?}", e)))?;
+    if let Some(exif) = &metadata.exif {
+        // eXIf goes right after IHDR (8-byte signature + 25-byte IHDR chunk)
+        let mut chunk = Vec::with_capacity(exif.len() + 12);
+        chunk.extend_from_slice(&(exif.len() as u32).to_be_bytes());
+        chunk.extend_from_slice(b"eXIf");
+        chunk.extend_from_slice(exif);
+        let mut crc = Crc::new();
+        crc.update(&chunk[4..]);
+        chunk.extend_from_slice(&crc.sum().to_be_bytes());
+        encoded.splice(33..33, chunk);
+    }
+    out.write_all(&encoded)?;
+    Ok(())
+}
+
+fn ne_bytes(data: &[u16]) -> Vec<u8> {
+    data.iter().flat_map(|v| v.to_ne_bytes()).collect()
+}
+
+fn encode_webp<W: Write>(img: SVec, mut out: W, webp: &WebpOptions, metadata: &ImageMetadata) -> Result<(), SaveError> {
+    let (height, width, channel) = img.shape();
+    let pixel_type = img.pixel_type();
+    let Samples::U8(data) = samples(img) else {
+        return Err(unsupported("webp", pixel_type));
+    };
+    // libwebp only takes RGB(A), gray is expanded
+    let (data, alpha) = match channel {
+        Some(1) | None => (data.iter().flat_map(|&v| [v, v, v]).collect(), false),
+        Some(2) => (data.chunks_exact(2).flat_map(|p| [p[0], p[0], p[0], p[1]]).collect(), true),
+        Some(3) => (data, false),
+        Some(4) => (data, true),
+        _ => return Err(UnsupportedChannelSaveError(format!("{:?}", channel))),
+    };
+    let encoder = if alpha {
+        webp::Encoder::from_rgba(&data, width as u32, height as u32)
+    } else {
+        webp::Encoder::from_rgb(&data, width as u32, height as u32)
+    };
+    let encoded = encoder
+        .encode_simple(webp.lossless, webp.quality.clamp(0.0, 100.0))
+        .map_err(|e| WebpSaveError(format!("{:?}", e)))?;
+    if metadata.icc_profile.is_none() && metadata.exif.is_none() {
+        out.write_all(&encoded)?;
+    } else {
+        out.write_all(&webp_with_metadata(&encoded, width, height, alpha, metadata))?;
+    }
+    Ok(())
+}
+
+/// Rewrite a simple-format WebP as extended (`VP8X`) with `ICCP` and `EXIF` chunks.
+fn webp_with_metadata(encoded: &[u8], width: usize, height: usize, alpha: bool, metadata: &ImageMetadata) -> Vec<u8> {
+    let mut flags = if alpha { 0x10 } else { 0 };
+    flags |= if metadata.icc_profile.is_some() { 0x20 } else { 0 };
+    flags |= if metadata.exif.is_some() { 0x08 } else { 0 };
+    let mut body = b"WEBP".to_vec();
+    riff_chunk(&mut body, b"VP8X", &vp8x_header(flags, width, height));
+    if let Some(icc) = &metadata.icc_profile {
+        riff_chunk(&mut body, b"ICCP", icc);
+    }
+    webp_image_chunks(encoded, &mut body);
+    if let Some(exif) = &metadata.exif {
+        riff_chunk(&mut body, b"EXIF", exif);
+    }
+    riff(&body)
+}
+
+fn vp8x_header(flags: u8, width: usize, height: usize) -> Vec<u8> {
+    let mut header = vec![flags, 0, 0, 0];
+    header.extend_from_slice(&(width as u32 - 1).to_le_bytes()[..3]);
+    header.extend_from_slice(&(height as u32 - 1).to_le_bytes()[..3]);
+    header
+}
+
+fn riff_chunk(out: &mut Vec<u8>, fourcc: &[u8], data: &[u8]) {
+    out.extend_from_slice(fourcc);
+    out.extend_from_slice(&(data.len() as u32).to_le_bytes());
+    out.extend_from_slice(data);
+    if data.len() % 2 == 1 {
+        out.push(0);
+    }
+}
+
+fn riff(body: &[u8]) -> Vec<u8> {
+    let mut out = b"RIFF".to_vec();
+    out.extend_from_slice(&(body.len() as u32).to_le_bytes());
+    out.extend_from_slice(body);
+    out
+}
+
+/// Copy the image chunks (ALPH, VP8, VP8L) of a libwebp file, dropping its own VP8X.
+fn webp_image_chunks(encoded: &[u8], out: &mut Vec<u8>) {
+    let mut at = 12;
+    while at + 8 <= encoded.len() {
+        let len = u32::from_le_bytes([encoded[at + 4], encoded[at + 5], encoded[at + 6], encoded[at + 7]]) as usize;
+        let end = (at + 8 + len + (len & 1)).min(encoded.len());
+        if &encoded[at..at + 4] != b"VP8X" {
+            out.extend_from_slice(&encoded[at..end]);
+        }
+        at = end;
+    }
+}
+
+/// Delay in `unit`ths of a second, saturating at `max`.
+fn delay_in(delay: Duration, unit: u128, max: u128) -> u128 {
+    (delay.as_millis() * unit / 1000).min(max)
+}
+
+fn rgba8(img: SVec, format: &str) -> Result<Vec<u8>, SaveError> {
+    let channel = img.shape.get_channels();
+    let pixel_type = img.pixel_type();
+    let Samples::U8(data) = samples(img) else {
+        return Err(unsupported(format, pixel_type));
+    };
+    Ok(match channel {
+        Some(1) | None => data.iter().flat_map(|&v| [v, v, v, 255]).collect(),
+        Some(2) => data.chunks_exact(2).flat_map(|p| [p[0], p[0], p[0], p[1]]).collect(),
+        Some(3) => data.chunks_exact(3).flat_map(|p| [p[0], p[1], p[2], 255]).collect(),
+        Some(4) => data,
+        _ => return Err(UnsupportedChannelSaveError(format!("{:?}", channel))),
+    })
+}
+
+fn encode_gif<W: Write>(frames: Vec<Frame>, out: W, loop_count: u16) -> Result<(), SaveError> {
+    let (height, width, _) = frames[0].image.shape();
+    let (Ok(width), Ok(height)) = (u16::try_from(width), u16::try_from(height)) else {
+        return Err(GifSaveError(format!("{}x{} is larger than GIF allows", width, height)));
+    };
+    let err = |e| GifSaveError(format!("{:?}", e));
+    let mut encoder = gif::Encoder::new(out, width, height, &[]).map_err(err)?;
+    // without the NETSCAPE extension a GIF plays once, its count is repeats after the first play
+    match loop_count {
+        0 => encoder.set_repeat(gif::Repeat::Infinite).map_err(err)?,
+        1 => {}
+        n => encoder.set_repeat(gif::Repeat::Finite(n - 1)).map_err(err)?,
+    }
+    for frame in frames {
+        let mut rgba = rgba8(frame.image, "gif")?;
+        let mut gif_frame = gif::Frame::from_rgba_speed(width, height, &mut rgba, 10);
+        gif_frame.delay = delay_in(frame.delay, 100, u16::MAX as u128) as u16;
+        gif_frame.dispose = match frame.disposal {
+            Disposal::Keep => gif::DisposalMethod::Keep,
+            Disposal::Background => gif::DisposalMethod::Background,
+            Disposal::Previous => gif::DisposalMethod::Previous,
+        };
+        encoder.write_frame(&gif_frame).map_err(err)?;
+    }
+    Ok(())
+}
+
+fn encode_apng<W: Write>(frames: Vec<Frame>, out: W, png: &PngOptions, loop_count: u16) -> Result<(), SaveError> {
+    let (height, width, channel) = frames[0].image.shape();
+    let color = match channel {
+        Some(1) | None => png::ColorType::Grayscale,
+        Some(2) => png::ColorType::GrayscaleAlpha,
+        Some(3) => png::ColorType::Rgb,
+        Some(4) => png::ColorType::Rgba,
+        _ => return Err(UnsupportedChannelSaveError(format!("{:?}", channel))),
+    };
+    let depth = match frames[0].image.pixel_type() {
+        PixelType::U8 => png::BitDepth::Eight,
+        PixelType::U16 => png::BitDepth::Sixteen,
+        pixel_type => return Err(unsupported("png", pixel_type)),
+    };
+    let err = |e| PngSaveError(format!("{:?}", e));
+    let mut encoder = png::Encoder::new(out, width as u32, height as u32);
+    encoder.set_color(color);
+    encoder.set_depth(depth);
+    encoder.set_compression(match png.compression {
+        PngCompression::Fast => png::Compression::Fast,
+        PngCompression::Default => png::Compression::Default,
+        PngCompression::Best => png::Compression::Best,
+    });
+    match png.filter {
+        PngFilter::NoFilter => encoder.set_filter(png::FilterType::NoFilter),
+        PngFilter::Sub => encoder.set_filter(png::FilterType::Sub),
+        PngFilter::Up => encoder.set_filter(png::FilterType::Up),
+        PngFilter::Avg => encoder.set_filter(png::FilterType::Avg),
+        PngFilter::Paeth => encoder.set_filter(png::FilterType::Paeth),
+        PngFilter::Adaptive => encoder.set_adaptive_filter(png::AdaptiveFilterType::Adaptive),
+    }
+    encoder.set_animated(frames.len() as u32, loop_count as u32).map_err(err)?;
+    let mut writer = encoder.write_header().map_err(err)?;
+    for frame in frames {
+        // milliseconds while they fit the 16-bit numerator, centiseconds beyond
+        if frame.delay.as_millis() <= u16::MAX as u128 {
+            writer.set_frame_delay(frame.delay.as_millis() as u16, 1000).map_err(err)?;
+        } else {
+            writer
+                .set_frame_delay(delay_in(frame.delay, 100, u16::MAX as u128) as u16, 100)
+                .map_err(err)?;
+        }
+        writer
+            .set_dispose_op(match frame.disposal {
+                Disposal::Keep => png::DisposeOp::None,
+                Disposal::Background => png::DisposeOp::Background,
+                Disposal::Previous => png::DisposeOp::Previous,
+            })
+            .map_err(err)?;
+        let bytes = match samples(frame.image) {
+            Samples::U8(data) => data,
+            Samples::U16(data) => data.iter().flat_map(|v| v.to_be_bytes()).collect(),
+            Samples::F32(_) => unreachable!(),
+        };
+        writer.write_image_data(&bytes).map_err(err)?;
+    }
+    writer.finish().map_err(err)
+}
+
+/// Animated WebP: every frame goes through [`encode_webp`] and its image chunks are wrapped in
+/// an `ANMF` chunk. WebP has no "restore previous", `Disposal::Previous` is written as `Keep`.
+fn encode_webp_frames<W: Write>(frames: Vec<Frame>, mut out: W, webp: &WebpOptions, loop_count: u16) -> Result<(), SaveError> {
+    let (height, width, channel) = frames[0].image.shape();
+    let flags = if matches!(channel, Some(2) | Some(4)) { 0x12 } else { 0x02 };
+    let mut body = b"WEBP".to_vec();
+    riff_chunk(&mut body, b"VP8X", &vp8x_header(flags, width, height));
+    // transparent background, then the loop count
+    let mut anim = vec![0u8; 4];
+    anim.extend_from_slice(&loop_count.to_le_bytes());
+    riff_chunk(&mut body, b"ANIM", &anim);
+    for frame in frames {
+        let mut encoded = Vec::new();
+        encode_webp(frame.image, &mut encoded, webp, &ImageMetadata::default())?;
+        // offset (0, 0), canvas size, duration, then "don't blend" and the dispose bit
+        let mut anmf = vec![0u8; 6];
+        anmf.extend_from_slice(&vp8x_header(0, width, height)[4..]);
+        anmf.extend_from_slice(&(delay_in(frame.delay, 1000, 0xff_ffff) as u32).to_le_bytes()[..3]);
+        anmf.push(0x02 | (frame.disposal == Disposal::Background) as u8);
+        webp_image_chunks(&encoded, &mut anmf);
+        riff_chunk(&mut body, b"ANMF", &anmf);
+    }
+    out.write_all(&riff(&body))?;
+    Ok(())
+}
+
+fn encode_tiff<W: Write>(img: SVec, mut out: W, tiff: &TiffOptions, icc: Option<&[u8]>) -> Result<(), SaveError> {
+    // the tiff encoder seeks back to patch offsets, so it writes into memory first
+    let mut encoded = Cursor::new(Vec::new());
+    match tiff.compression {
+        TiffCompression::None => write_tiff(&mut encoded, img, Uncompressed, icc),
+        TiffCompression::Lzw => write_tiff(&mut encoded, img, Lzw, icc),
+        TiffCompression::Deflate => write_tiff(&mut encoded, img, Deflate::with_level(DeflateLevel::Balanced), icc),
+        TiffCompression::PackBits => write_tiff(&mut encoded, img, Packbits, icc),
+    }?;
+    out.write_all(encoded.get_ref())?;
+    Ok(())
+}
+
+fn write_tiff<W: Write + Seek, D: Compression>(out: W, img: SVec, compression: D, icc: Option<&[u8]>) -> Result<(), SaveError> {
+    let (height, width, channel) = img.shape();
+    let (width, height) = (width as u32, height as u32);
+    let mut encoder = TiffEncoder::new(out).map_err(|e| TiffSaveError(format!("{:?}", e)))?;
+    match (samples(img), channel) {
+        (Samples::U8(data), Some(1) | None) => tiff_image::<_, Gray8, D>(&mut encoder, width, height, compression, &data, icc),
+        (Samples::U8(data), Some(3)) => tiff_image::<_, RGB8, D>(&mut encoder, width, height, compression, &data, icc),
+        (Samples::U8(data), Some(4)) => tiff_image::<_, RGBA8, D>(&mut encoder, width, height, compression, &data, icc),
+        (Samples::U16(data), Some(1) | None) => tiff_image::<_, Gray16, D>(&mut encoder, width, height, compression, &data, icc),
+        (Samples::U16(data), Some(3)) => tiff_image::<_, RGB16, D>(&mut encoder, width, height, compression, &data, icc),
+        (Samples::U16(data), Some(4)) => tiff_image::<_, RGBA16, D>(&mut encoder, width, height, compression, &data, icc),
+        (Samples::F32(data), Some(1) | None) => {
+            tiff_image::<_, Gray32Float, D>(&mut encoder, width, height, compression, &data, icc)
+        }
+        (Samples::F32(data), Some(3)) => tiff_image::<_, RGB32Float, D>(&mut encoder, width, height, compression, &data, icc),
+        (Samples::F32(data), Some(4)) => tiff_image::<_, RGBA32Float, D>(&mut encoder, width, height, compression, &data, icc),
+        _ => return Err(UnsupportedChannelSaveError(format!("{:?}", channel))),
+    }
+    .map_err(|e| TiffSaveError(format!("{:?}", e)))
+}
+
+/// `InterColorProfile`, not named by the `tiff` crate.
+const ICC_PROFILE_TAG: u16 = 34675;
+
+fn tiff_image<W: Write + Seek, C: TiffColorType, D: Compression>(
+    encoder: &mut TiffEncoder<W>,
+    width: u32,
+    height: u32,
+    compression: D,
+    data: &[C::Inner],
+    icc: Option<&[u8]>,
+) -> TiffResult<()>
+where
+    [C::Inner]: TiffValue,
+{
+    let mut image = encoder.new_image_with_compression::<C, D>(width, height, compression)?;
+    if let Some(icc) = icc {
+        image.encoder().write_tag(Tag::Unknown(ICC_PROFILE_TAG), icc)?;
+    }
+    image.write_data(data)
+}
+
+fn encode_exr<W: Write>(img: SVec, mut out: W) -> Result<(), SaveError> {
+    let (height, width, channel) = img.shape();
+    let names: &[&str] = match channel {
+        Some(1) | None => &["Y"],
+        Some(2) => &["Y", "A"],
+        Some(3) => &["R", "G", "B"],
+        Some(4) => &["R", "G", "B", "A"],
+        _ => return Err(UnsupportedChannelSaveError(format!("{:?}", channel))),
+    };
+    // exr has no integer colour samples, normalised f32 holds 8 and 16-bit values exactly
+    let data = match samples(img) {
+        Samples::F32(data) => data,
+        Samples::U16(data) => data.into_iter().map(|v| v as f32 / u16::MAX as f32).collect(),
+        Samples::U8(data) => data.into_iter().map(|v| v as f32 / u8::MAX as f32).collect(),
+    };
+    // exr stores every channel as its own plane
+    let channels = names
+        .iter()
+        .enumerate()
+        .map(|(c, name)| {
+            let plane = data.iter().skip(c).step_by(names.len()).copied().collect();
+            AnyChannel::new(*name, FlatSamples::F32(plane))
+        })
+        .collect();
+    let layer = Layer::new(
+        (width, height),
+        LayerAttributes::default(),
+        Encoding::FAST_LOSSLESS,
+        AnyChannels::sort(channels),
+    );
+    let mut encoded = Cursor::new(Vec::new());
+    Image::from_layer(layer)
+        .write()
+        .to_buffered(&mut encoded)
+        .map_err(|e| ExrSaveError(format!("{:?}", e)))?;
+    out.write_all(encoded.get_ref())?;
+    Ok(())
+}
+
+/// Widen `i16` and `f16` to `f32`, which holds them exactly. `u32` and `f64` have no lossless
+/// target and return `UnsupportedPixelTypeSaveError`, as does a format that can't hold the
+/// resulting type: encoders never quantise. Planar images are interleaved first, encoders expect HWC.
+fn to_saveable(mut img: SVec, format: &str) -> Result<SVec, SaveError> {
+    img.to_interleaved();
+    match img.pixel_type() {
+        PixelType::U8 | PixelType::U16 | PixelType::F32 => {}
+        PixelType::I16 | PixelType::F16 => img.as_f32(),
+        pixel_type @ (PixelType::U32 | PixelType::F64) => return Err(unsupported(format, pixel_type)),
+    }
+    Ok(img)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pepecore_array::Shape;
+
+    fn noise() -> SVec {
+        let mut state = 0x9E37_79B9u32;
+        let data = (0..64 * 64 * 3)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 17;
+                state ^= state << 5;
+                (state >> 24) as u8
+            })
+            .collect();
+        SVec::new(Shape::new(64, 64, Some(3)), ImgData::U8(data))
+    }
+
+    fn tmp(name: &str) -> std::path::PathBuf {
+        std::env::temp_dir().join(format!("pepecore_save_{}_{}", std::process::id(), name))
+    }
+
+    #[test]
+    fn test_save_with_options_roundtrip() {
+        let cases = [
+            (
+                "a.png",
+                SaveOptions {
+                    png: PngOptions {
+                        compression: PngCompression::Best,
+                        filter: PngFilter::Paeth,
+                    },
+                    ..Default::default()
+                },
+            ),
+            (
+                "a.tiff",
+                SaveOptions {
+                    tiff: TiffOptions {
+                        compression: TiffCompression::Lzw,
+                    },
+                    ..Default::default()
+                },
+            ),
+            ("a.webp", SaveOptions::default()),
+        ];
+        for (name, options) in cases {
+            let path = tmp(name);
+            svec_save_with(noise(), &path, options).unwrap();
+            let back = image::open(&path).unwrap().to_rgb8().into_raw();
+            std::fs::remove_file(&path).unwrap();
+            assert_eq!(back, noise().get_data::<u8>().unwrap(), "{name}");
+        }
+    }
+
+    #[test]
+    fn test_save_with_quality_changes_size() {
+        use crate::enums::YCbCrRatio;
+
+        let size = |options: SaveOptions, name: &str| {
+            let path = tmp(name);
+            svec_save_with(noise(), &path, options).unwrap();
+            let len = std::fs::metadata(&path).unwrap().len();
+            std::fs::remove_file(&path).unwrap();
+            len
+        };
+        let jpeg = |quality, subsampling| SaveOptions {
+            jpeg: JpegOptions { quality, subsampling },
+            ..Default::default()
+        };
+        assert!(size(jpeg(95, YCbCrRatio::R444), "hi.jpg") > size(jpeg(20, YCbCrRatio::R420), "lo.jpg"));
+
+        let lossy = SaveOptions {
+            webp: WebpOptions {
+                lossless: false,
+                quality: 10.0,
+            },
+            ..Default::default()
+        };
+        assert!(size(lossy, "lossy.webp") < size(SaveOptions::default(), "lossless.webp"));
+    }
+
+    #[test]
+    fn test_encode_to_buffer() {
+        use crate::enums::ImgColor;
+        use crate::read::read_in_buffer;
+
+        for format in [ImageFormat::Png, ImageFormat::WebP, ImageFormat::Tiff] {
+            let bytes = svec_encode(&noise(), format, SaveOptions::default()).unwrap();
+            let back = read_in_buffer(&bytes, ImgColor::RGB).unwrap();
+            assert_eq!(
+                back.get_data::<u8>().unwrap(),
+                noise().get_data::<u8>().unwrap(),
+                "{format:?}"
+            );
+        }
+        let jpeg = svec_encode(&noise(), ImageFormat::Jpeg, SaveOptions::default()).unwrap();
+        assert_eq!(read_in_buffer(&jpeg, ImgColor::RGB).unwrap().shape(), (64, 64, Some(3)));
+
+        let mut sink = Vec::new();
+        svec_encode_to(&noise(), ImageFormat::Jxl, SaveOptions::default(), &mut sink).unwrap();
+        assert_eq!(&sink[..2], &[0xFF, 0x0A]);
+        assert_eq!(
+            read_in_buffer(&sink, ImgColor::RGB).unwrap().get_data::<u8>().unwrap(),
+            noise().get_data::<u8>().unwrap()
+        );
+    }
+
+    #[test]
+    fn test_encode_keeps_depth() {
+        let wide: Vec<u16> = (0..64 * 64 * 3).map(|i| (i * 13) as u16).collect();
+        let img = SVec::new(Shape::new(64, 64, Some(3)), ImgData::U16(wide.clone()));
+        let png = svec_encode(&img, ImageFormat::Png, SaveOptions::default()).unwrap();
+        assert_eq!(image::load_from_memory(&png).unwrap().to_rgb16().into_raw(), wide);
+
+        let hdr: Vec<f32> = (0..64 * 64 * 3).map(|i| i as f32 / 1000.0 - 2.0).collect();
+        let img = SVec::new(Shape::new(64, 64, Some(3)), ImgData::F32(hdr.clone()));
+        let exr = svec_encode(&img, ImageFormat::Exr, SaveOptions::default()).unwrap();
+        assert_eq!(image::load_from_memory(&exr).unwrap().to_rgb32f().into_raw(), hdr);
+        // image can't read float tiff, go through the tiff decoder
+        let tiff = svec_encode(&img, ImageFormat::Tiff, SaveOptions::default()).unwrap();
+        let back = tiff::decoder::Decoder::new(Cursor::new(tiff)).unwrap().read_image().unwrap();
+        assert!(matches!(back, tiff::decoder::DecodingResult::F32(back) if back == hdr));
+    }
+
+    #[test]
+    fn test_encode_rejects_quantising() {
+        let img = SVec::new(Shape::new(2, 2, Some(3)), ImgData::F32(vec![0.5; 12]));
+        for format in [ImageFormat::Png, ImageFormat::Jpeg, ImageFormat::WebP, ImageFormat::Jxl] {
+            let err = svec_encode(&img, format, SaveOptions::default()).unwrap_err();
+            assert!(
+                matches!(
+                    err,
+                    UnsupportedPixelTypeSaveError {
+                        pixel_type: PixelType::F32,
+                        ..
+                    }
+                ),
+                "{format:?}"
+            );
+        }
+        let img = SVec::new(Shape::new(2, 2, Some(3)), ImgData::U16(vec![1; 12]));
+        assert!(matches!(
+            svec_encode(&img, ImageFormat::Jpeg, SaveOptions::default()),
+            Err(UnsupportedPixelTypeSaveError {
+                pixel_type: PixelType::U16,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_encode_rejects_narrowing() {
+        use crate::enums::ImgColor;
+        use crate::read::read_in_buffer;
+        for data in [ImgData::U32(vec![u32::MAX; 12]), ImgData::F64(vec![0.5; 12])] {
+            let img = SVec::new(Shape::new(2, 2, Some(3)), data);
+            let pixel_type = img.pixel_type();
+            for format in [ImageFormat::Png, ImageFormat::Jpeg] {
+                let err = svec_encode(&img, format, SaveOptions::default()).unwrap_err();
+                assert!(
+                    matches!(err, UnsupportedPixelTypeSaveError { pixel_type: p, .. } if p == pixel_type),
+                    "{format:?}"
+                );
             }
-            let options = EncoderOptions::new(width, height, colorspace, BitDepth::Eight);
-            let encoder = JxlSimpleEncoder::new(&bytes, options);
-            let mut out = Vec::new();
-            encoder.encode(&mut out).map_err(|e| JxlSaveError(format!("{:?}", e)))?;
-            std::fs::write(path, out).map_err(|e| JxlSaveError(format!("{:?}", e)))?;
-            Ok(())
+            let path = tmp("narrow.png");
+            assert!(svec_save(img, &path).is_err());
+            assert!(!path.exists());
         }
+        let img = SVec::new(Shape::new(2, 2, None), ImgData::I16(vec![0, 8192, 16384, 32767]));
+        let exr = svec_encode(&img, ImageFormat::Exr, SaveOptions::default()).unwrap();
+        let back = read_in_buffer(&exr, ImgColor::GRAY).unwrap();
+        assert_eq!(back.get_data::<f32>().unwrap()[3], 1.0);
+    }
+
+    #[test]
+    fn test_save_jxl_effort() {
+        let fast = svec_encode(&noise(), ImageFormat::Jxl, SaveOptions::default()).unwrap();
+        let options = SaveOptions {
+            jxl: JxlOptions {
+                effort: 9,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let path = tmp("a.jxl");
+        svec_save_with(noise(), &path, options).unwrap();
+        let slow = std::fs::read(&path).unwrap();
+        assert_eq!(&slow[..2], &[0xFF, 0x0A]);
+        assert!(slow.len() <= fast.len());
+    }
+
+    #[test]
+    fn test_save_jxl_distance() {
+        use crate::enums::ImgColor;
+        use crate::read::read_in_buffer;
+
+        // smooth gradients with a little grain, like a photo
+        let grain = noise();
+        let data = grain
+            .get_data::<u8>()
+            .unwrap()
+            .iter()
+            .enumerate()
+            .map(|(i, g)| ((i / 3 % 64) * 2 + i / 192 + (i % 3) * 20) as u8 + g % 4)
+            .collect();
+        let src = SVec::new(Shape::new(64, 64, Some(3)), ImgData::U8(data));
+        let lossless = svec_encode(&src, ImageFormat::Jxl, SaveOptions::default()).unwrap();
+        let lossy = |distance| SaveOptions {
+            jxl: JxlOptions {
+                distance,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let near = svec_encode(&src, ImageFormat::Jxl, lossy(1.0)).unwrap();
+        let far = svec_encode(&src, ImageFormat::Jxl, lossy(4.0)).unwrap();
+        assert!(far.len() < near.len() && near.len() < lossless.len());
+
+        let back = read_in_buffer(&near, ImgColor::RGB).unwrap();
+        let (a, b) = (back.get_data::<u8>().unwrap(), src.get_data::<u8>().unwrap());
+        assert!(a.iter().zip(b).all(|(a, b)| a.abs_diff(*b) <= 1));
+        assert_eq!(read_in_buffer(&lossless, ImgColor::RGB).unwrap().get_data::<u8>().unwrap(), b);
+
+        assert!(matches!(
+            svec_encode(&src, ImageFormat::Jxl, lossy(-1.0)),
+            Err(JxlSaveError(_))
+        ));
+        assert!(matches!(
+            svec_encode(&src, ImageFormat::Jxl, lossy(f32::NAN)),
+            Err(JxlSaveError(_))
+        ));
     }
 }
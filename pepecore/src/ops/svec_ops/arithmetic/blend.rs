@@ -0,0 +1,97 @@
+//! Porter-Duff compositing of images with straight (non-premultiplied) alpha.
+use crate::errors::ArithmeticError;
+use crate::ops::svec_ops::arithmetic::utils::{check_same_type, interleaved, zip_pixels};
+use pepecore_array::{Pixel, PixelType, SVec, f16};
+
+fn over_t<T: Pixel>(dst: &mut [T], dst_c: usize, src: &[T], src_c: usize) {
+    // destination without alpha is treated as fully opaque
+    let dst_alpha = dst_c == src_c;
+    let colors = src_c - 1;
+    zip_pixels(dst, dst_c, src, src_c, |d, s| {
+        let sa = s[colors].to_f32_normalized();
+        let da = if dst_alpha { d[colors].to_f32_normalized() } else { 1.0 };
+        let k = da * (1.0 - sa);
+        let oa = sa + k;
+        if oa <= 0.0 {
+            for v in d.iter_mut() {
+                *v = T::from_f32_normalized(0.0);
+            }
+            return;
+        }
+        for i in 0..colors {
+            let sc = s[i].to_f32_normalized();
+            let dc = d[i].to_f32_normalized();
+            d[i] = T::from_f32_normalized((sc * sa + dc * k) / oa);
+        }
+        if dst_alpha {
+            d[colors] = T::from_f32_normalized(oa);
+        }
+    });
+}
+
+/// Composite `src` over `dst` in place (Porter-Duff `over`).
+///
+/// `src` must be GRAYA or RGBA. `dst` may have the same channel layout or lack the
+/// alpha channel (GRAY / RGB), in which case it is treated as opaque. Both images must
+/// share pixel type, height and width.
+pub fn over(dst: &mut SVec, src: &SVec) -> Result<(), ArithmeticError> {
+    check_same_type(dst, src)?;
+    let (h, w, c) = dst.shape();
+    let (sh, sw, sc) = src.shape();
+    if h != sh || w != sw {
+        return Err(ArithmeticError::ShapeMismatch((h, w, c), (sh, sw, sc)));
+    }
+    let dst_c = c.unwrap_or(1);
+    let src_c = sc.unwrap_or(1);
+    if !matches!(src_c, 2 | 4) || (dst_c != src_c && dst_c != src_c - 1) {
+        return Err(ArithmeticError::UnsupportedChannels { src: src_c, dst: dst_c });
+    }
+    let src = interleaved(src);
+    dst.with_interleaved(|dst| {
+        match dst.pixel_type() {
+            PixelType::U8 => over_t(dst.get_data_mut::<u8>()?, dst_c, src.get_data::<u8>()?, src_c),
+            PixelType::U16 => over_t(dst.get_data_mut::<u16>()?, dst_c, src.get_data::<u16>()?, src_c),
+            PixelType::I16 => over_t(dst.get_data_mut::<i16>()?, dst_c, src.get_data::<i16>()?, src_c),
+            PixelType::U32 => over_t(dst.get_data_mut::<u32>()?, dst_c, src.get_data::<u32>()?, src_c),
+            PixelType::F16 => over_t(dst.get_data_mut::<f16>()?, dst_c, src.get_data::<f16>()?, src_c),
+            PixelType::F32 => over_t(dst.get_data_mut::<f32>()?, dst_c, src.get_data::<f32>()?, src_c),
+            PixelType::F64 => over_t(dst.get_data_mut::<f64>()?, dst_c, src.get_data::<f64>()?, src_c),
+        }
+        Ok(())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pepecore_array::{ImgData, Shape};
+
+    #[test]
+    fn over_rgba_and_rgb() {
+        let src = SVec::new(
+            Shape::new(1, 2, Some(4)),
+            ImgData::from(vec![1.0f32, 0.0, 0.0, 0.5, 0.0, 1.0, 0.0, 0.0]),
+        );
+        let mut dst = SVec::new(
+            Shape::new(1, 2, Some(4)),
+            ImgData::from(vec![0.0f32, 0.0, 1.0, 1.0, 0.0, 0.0, 1.0, 0.5]),
+        );
+        over(&mut dst, &src).unwrap();
+        assert_eq!(dst.get_data::<f32>().unwrap(), &[0.5, 0.0, 0.5, 1.0, 0.0, 0.0, 1.0, 0.5]);
+
+        let mut rgb = SVec::new(Shape::new(1, 2, Some(3)), ImgData::from(vec![0u8, 0, 255, 0, 0, 255]));
+        let src = SVec::new(Shape::new(1, 2, Some(4)), ImgData::from(vec![255u8, 0, 0, 255, 255, 0, 0, 0]));
+        over(&mut rgb, &src).unwrap();
+        assert_eq!(rgb.get_data::<u8>().unwrap(), &[255, 0, 0, 0, 0, 255]);
+    }
+
+    #[test]
+    fn over_rejects_opaque_source() {
+        let src = SVec::new(Shape::new(1, 1, Some(3)), ImgData::from(vec![0u8; 3]));
+        let mut dst = src.clone();
+        assert!(matches!(
+            over(&mut dst, &src),
+            Err(ArithmeticError::UnsupportedChannels { src: 3, dst: 3 })
+        ));
+    }
+}
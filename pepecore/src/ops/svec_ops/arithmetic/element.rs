@@ -0,0 +1,156 @@
+//! Element-wise arithmetic between two images or an image and a scalar.
+//!
+//! Values are combined in their native range (`u8` stays `0..=255`, `f32` stays as is).
+//! Integer results saturate at the type bounds, division by zero yields the type
+//! maximum (or zero for `0 / 0`). Float results are never clamped.
+//!
+//! The same semantics back the `+ - * /` operators on `&SVec`; the functions here work in
+//! place and honour the rayon mode.
+use crate::enums::ArithOp;
+use crate::errors::ArithmeticError;
+use crate::global_params::rayon_get_mode;
+use crate::ops::svec_ops::arithmetic::utils::{check_same_type, interleaved, zip_pixels};
+use pepecore_array::{Pixel, PixelType, SVec, f16};
+use rayon::prelude::*;
+
+#[inline(always)]
+fn apply(a: f64, b: f64, op: ArithOp) -> f64 {
+    match op {
+        ArithOp::Add => a + b,
+        ArithOp::Sub => a - b,
+        ArithOp::Mul => a * b,
+        ArithOp::Div => a / b,
+    }
+}
+
+fn arithmetic_t<T: Pixel>(dst: &mut [T], dst_c: usize, src: &[T], src_c: usize, op: ArithOp) {
+    if src_c == dst_c {
+        zip_pixels(dst, dst_c, src, src_c, |d, s| {
+            for (d, s) in d.iter_mut().zip(s) {
+                *d = T::from_f64(apply(d.to_f64(), s.to_f64(), op));
+            }
+        });
+    } else {
+        // single channel broadcast over every channel of the pixel
+        zip_pixels(dst, dst_c, src, src_c, |d, s| {
+            let s = s[0].to_f64();
+            for d in d.iter_mut() {
+                *d = T::from_f64(apply(d.to_f64(), s, op));
+            }
+        });
+    }
+}
+
+fn arithmetic_scalar_t<T: Pixel>(dst: &mut [T], value: f64, op: ArithOp) {
+    let f = |d: &mut T| *d = T::from_f64(apply(d.to_f64(), value, op));
+    if rayon_get_mode() {
+        dst.par_iter_mut().for_each(f);
+    } else {
+        dst.iter_mut().for_each(f);
+    }
+}
+
+/// Apply `op` element-wise as `img = img <op> other`.
+///
+/// Both images must share pixel type, height and width. `other` must have the same
+/// channel count as `img` or a single channel, which is then broadcast over all channels.
+///
+/// # Example
+/// ```rust
+/// use pepecore::enums::ArithOp;
+/// use pepecore::ops::svec_ops::arithmetic::element::arithmetic;
+/// use pepecore_array::{ImgData, SVec, Shape};
+///
+/// let mut img = SVec::new(Shape::new(1, 2, Some(3)), ImgData::from(vec![250u8, 10, 20, 30, 40, 50]));
+/// let mask = SVec::new(Shape::new(1, 2, None), ImgData::from(vec![10u8, 1]));
+/// arithmetic(&mut img, &mask, ArithOp::Add).unwrap();
+/// assert_eq!(img.get_data::<u8>().unwrap(), &[255, 20, 30, 31, 41, 51]);
+/// ```
+pub fn arithmetic(img: &mut SVec, other: &SVec, op: ArithOp) -> Result<(), ArithmeticError> {
+    check_same_type(img, other)?;
+    let (h, w, c) = img.shape();
+    let (oh, ow, oc) = other.shape();
+    let dst_c = c.unwrap_or(1);
+    let src_c = oc.unwrap_or(1);
+    if h != oh || w != ow || (src_c != dst_c && src_c != 1) {
+        return Err(ArithmeticError::ShapeMismatch((h, w, c), (oh, ow, oc)));
+    }
+    let other = interleaved(other);
+    img.with_interleaved(|img| {
+        match img.pixel_type() {
+            PixelType::U8 => arithmetic_t(img.get_data_mut::<u8>()?, dst_c, other.get_data::<u8>()?, src_c, op),
+            PixelType::U16 => arithmetic_t(img.get_data_mut::<u16>()?, dst_c, other.get_data::<u16>()?, src_c, op),
+            PixelType::I16 => arithmetic_t(img.get_data_mut::<i16>()?, dst_c, other.get_data::<i16>()?, src_c, op),
+            PixelType::U32 => arithmetic_t(img.get_data_mut::<u32>()?, dst_c, other.get_data::<u32>()?, src_c, op),
+            PixelType::F16 => arithmetic_t(img.get_data_mut::<f16>()?, dst_c, other.get_data::<f16>()?, src_c, op),
+            PixelType::F32 => arithmetic_t(img.get_data_mut::<f32>()?, dst_c, other.get_data::<f32>()?, src_c, op),
+            PixelType::F64 => arithmetic_t(img.get_data_mut::<f64>()?, dst_c, other.get_data::<f64>()?, src_c, op),
+        }
+        Ok(())
+    })
+}
+
+/// Apply `op` between every sample of `img` and `value`, e.g. `img = img * value`.
+///
+/// `value` is in the native range of the pixel type, integer results saturate.
+pub fn arithmetic_scalar(img: &mut SVec, value: f64, op: ArithOp) -> Result<(), ArithmeticError> {
+    match img.pixel_type() {
+        PixelType::U8 => arithmetic_scalar_t(img.get_data_mut::<u8>()?, value, op),
+        PixelType::U16 => arithmetic_scalar_t(img.get_data_mut::<u16>()?, value, op),
+        PixelType::I16 => arithmetic_scalar_t(img.get_data_mut::<i16>()?, value, op),
+        PixelType::U32 => arithmetic_scalar_t(img.get_data_mut::<u32>()?, value, op),
+        PixelType::F16 => arithmetic_scalar_t(img.get_data_mut::<f16>()?, value, op),
+        PixelType::F32 => arithmetic_scalar_t(img.get_data_mut::<f32>()?, value, op),
+        PixelType::F64 => arithmetic_scalar_t(img.get_data_mut::<f64>()?, value, op),
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pepecore_array::{ImgData, Shape};
+
+    #[test]
+    fn saturating_u8() {
+        let mut a = SVec::new(Shape::new(1, 3, None), ImgData::from(vec![200u8, 5, 0]));
+        let b = SVec::new(Shape::new(1, 3, None), ImgData::from(vec![100u8, 10, 0]));
+        let mut sub = a.clone();
+        arithmetic(&mut a, &b, ArithOp::Add).unwrap();
+        assert_eq!(a.get_data::<u8>().unwrap(), &[255, 15, 0]);
+        arithmetic(&mut sub, &b, ArithOp::Sub).unwrap();
+        assert_eq!(sub.get_data::<u8>().unwrap(), &[100, 0, 0]);
+        let mut div = SVec::new(Shape::new(1, 3, None), ImgData::from(vec![200u8, 5, 0]));
+        arithmetic(&mut div, &b, ArithOp::Div).unwrap();
+        assert_eq!(div.get_data::<u8>().unwrap(), &[2, 1, 0]);
+    }
+
+    #[test]
+    fn scalar_and_float() {
+        let mut img = SVec::new(Shape::new(1, 2, Some(2)), ImgData::from(vec![0.5f32, 1.0, 2.0, -1.0]));
+        arithmetic_scalar(&mut img, 2.0, ArithOp::Mul).unwrap();
+        assert_eq!(img.get_data::<f32>().unwrap(), &[1.0, 2.0, 4.0, -2.0]);
+
+        let mut img = SVec::new(Shape::new(1, 2, None), ImgData::from(vec![60000u16, 3]));
+        arithmetic_scalar(&mut img, 10000.0, ArithOp::Add).unwrap();
+        assert_eq!(img.get_data::<u16>().unwrap(), &[65535, 10003]);
+    }
+
+    #[test]
+    fn planar_broadcast_and_mismatch() {
+        let mut img = SVec::new(Shape::new(1, 2, Some(3)), ImgData::from(vec![1u8, 2, 3, 4, 5, 6]));
+        img.to_planar();
+        let mask = SVec::new(Shape::new(1, 2, None), ImgData::from(vec![2u8, 3]));
+        arithmetic(&mut img, &mask, ArithOp::Mul).unwrap();
+        img.to_interleaved();
+        assert_eq!(img.get_data::<u8>().unwrap(), &[2, 4, 6, 12, 15, 18]);
+
+        let wrong = SVec::new(Shape::new(2, 1, None), ImgData::from(vec![1u8, 1]));
+        assert!(matches!(
+            arithmetic(&mut img, &wrong, ArithOp::Add),
+            Err(ArithmeticError::ShapeMismatch(..))
+        ));
+        let wrong = SVec::new(Shape::new(1, 2, None), ImgData::from(vec![1.0f32, 1.0]));
+        assert!(arithmetic(&mut img, &wrong, ArithOp::Add).is_err());
+    }
+}
@@ -0,0 +1,3 @@
+pub mod blend;
+pub mod element;
+mod utils;
@@ -0,0 +1,42 @@
+use crate::errors::ArithmeticError;
+use crate::global_params::rayon_get_mode;
+use pepecore_array::{Layout, SVec};
+use rayon::prelude::*;
+use std::borrow::Cow;
+
+/// Borrow `img` as HWC, copying only when it is planar.
+pub(crate) fn interleaved(img: &SVec) -> Cow<'_, SVec> {
+    if img.shape.get_layout() == Layout::CHW {
+        let mut img = img.clone();
+        img.to_interleaved();
+        Cow::Owned(img)
+    } else {
+        Cow::Borrowed(img)
+    }
+}
+
+/// Walk `dst` and `src` pixel by pixel, `dst_c` and `src_c` samples at a time.
+pub(crate) fn zip_pixels<T, F>(dst: &mut [T], dst_c: usize, src: &[T], src_c: usize, f: F)
+where
+    T: Send + Sync,
+    F: Fn(&mut [T], &[T]) + Send + Sync,
+{
+    if rayon_get_mode() {
+        dst.par_chunks_mut(dst_c)
+            .zip(src.par_chunks(src_c))
+            .for_each(|(d, s)| f(d, s));
+    } else {
+        dst.chunks_mut(dst_c).zip(src.chunks(src_c)).for_each(|(d, s)| f(d, s));
+    }
+}
+
+pub(crate) fn check_same_type(img: &SVec, other: &SVec) -> Result<(), ArithmeticError> {
+    if img.pixel_type() != other.pixel_type() {
+        return Err(pepecore_array::error::Error::TypeMismatch {
+            expected: img.pixel_type().type_name(),
+            actual: other.pixel_type().type_name(),
+        }
+        .into());
+    }
+    Ok(())
+}
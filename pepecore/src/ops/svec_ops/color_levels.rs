@@ -1,3 +1,5 @@
+use pepecore_array::{Pixel, PixelType, SVecViewMut, f16};
+
 pub fn f32_color_level(img_vec: &mut [f32], in_low: f32, in_high: f32, out_low: f32, out_high: f32, gamma: f32) {
     let in_range = in_high - in_low;
     let out_range = out_high - out_low;
@@ -12,3 +14,41 @@ pub fn f32_color_level(img_vec: &mut [f32], in_low: f32, in_high: f32, out_low:
             .for_each(|i| *i = ((*i - in_low) / in_range * out_range + out_low).clamp(0.0, 1.0).powf(gamma));
     }
 }
+
+fn apply_color_level_view<T: Pixel>(img: &mut SVecViewMut, in_low: f32, in_high: f32, out_low: f32, out_high: f32, gamma: f32) {
+    let (h, w, c) = img.shape();
+    let c = c.unwrap_or(1);
+    let strides = img.strides();
+    let data = img.get_data_mut::<T>().unwrap();
+    let in_range = in_high - in_low;
+    let out_range = out_high - out_low;
+
+    for y in 0..h {
+        for x in 0..w {
+            for ch in 0..c {
+                let value = &mut data[strides.index(y, x, ch)];
+                let mut v = ((value.to_f32_normalized() - in_low) / in_range * out_range + out_low).clamp(0.0, 1.0);
+                if gamma != 1.0 {
+                    v = v.powf(gamma);
+                }
+                *value = T::from_f32_normalized(v);
+            }
+        }
+    }
+}
+
+/// Apply color levels to a view in-place.
+///
+/// Levels are given in the normalized `0.0..=1.0` range regardless of pixel type,
+/// integer samples are converted on the fly.
+pub fn color_levels_view(img: &mut SVecViewMut, in_low: f32, in_high: f32, out_low: f32, out_high: f32, gamma: f32) {
+    match img.pixel_type() {
+        PixelType::F32 => apply_color_level_view::<f32>(img, in_low, in_high, out_low, out_high, gamma),
+        PixelType::U8 => apply_color_level_view::<u8>(img, in_low, in_high, out_low, out_high, gamma),
+        PixelType::U16 => apply_color_level_view::<u16>(img, in_low, in_high, out_low, out_high, gamma),
+        PixelType::I16 => apply_color_level_view::<i16>(img, in_low, in_high, out_low, out_high, gamma),
+        PixelType::U32 => apply_color_level_view::<u32>(img, in_low, in_high, out_low, out_high, gamma),
+        PixelType::F16 => apply_color_level_view::<f16>(img, in_low, in_high, out_low, out_high, gamma),
+        PixelType::F64 => apply_color_level_view::<f64>(img, in_low, in_high, out_low, out_high, gamma),
+    }
+}
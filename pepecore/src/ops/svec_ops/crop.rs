@@ -1,5 +1,19 @@
 use pepecore_array::error::Error;
-use pepecore_array::{PixelType, SVec, Shape};
+use pepecore_array::{Layout, PixelType, SVec, Shape, f16};
+
+fn crop_vec<T: Copy>(data: &mut Vec<T>, img_w: usize, c: usize, x: usize, y: usize, w: usize, h: usize) {
+    let mut write_index = 0;
+
+    for row in 0..h {
+        let read_start = ((y + row) * img_w + x) * c;
+        let read_end = read_start + w * c;
+
+        data.copy_within(read_start..read_end, write_index);
+        write_index += w * c;
+    }
+
+    data.truncate(w * h * c);
+}
 
 pub fn crop(img: &mut SVec, x: usize, y: usize, w: usize, h: usize) -> Result<(), Error> {
     let (img_h, img_w, opt_c) = img.shape.get_shape();
@@ -8,56 +22,23 @@ pub fn crop(img: &mut SVec, x: usize, y: usize, w: usize, h: usize) -> Result<()
     if x + w > img_w || y + h > img_h {
         return Err(Error::OutOfBounds);
     }
+    if img.shape.get_layout() == Layout::CHW {
+        *img = img.view().crop(x, y, w, h)?.to_svec();
+        return Ok(());
+    }
+    let new_shape = Shape::try_new(h, w, Some(c))?;
 
     match img.pixel_type() {
-        PixelType::F32 => {
-            let data = img.get_mut_vec::<f32>()?;
-
-            let mut write_index = 0;
-
-            for row in 0..h {
-                let read_start = ((y + row) * img_w + x) * c;
-                let read_end = read_start + w * c;
-
-                data.copy_within(read_start..read_end, write_index);
-                write_index += w * c;
-            }
-
-            data.truncate(w * h * c);
-        }
-        PixelType::U16 => {
-            let data = img.get_mut_vec::<u16>()?;
-
-            let mut write_index = 0;
-
-            for row in 0..h {
-                let read_start = ((y + row) * img_w + x) * c;
-                let read_end = read_start + w * c;
-
-                data.copy_within(read_start..read_end, write_index);
-                write_index += w * c;
-            }
-
-            data.truncate(w * h * c);
-        }
-        PixelType::U8 => {
-            let data = img.get_mut_vec::<u8>()?;
-
-            let mut write_index = 0;
-
-            for row in 0..h {
-                let read_start = ((y + row) * img_w + x) * c;
-                let read_end = read_start + w * c;
-
-                data.copy_within(read_start..read_end, write_index);
-                write_index += w * c;
-            }
-
-            data.truncate(w * h * c);
-        }
+        PixelType::F32 => crop_vec(img.get_mut_vec::<f32>()?, img_w, c, x, y, w, h),
+        PixelType::U16 => crop_vec(img.get_mut_vec::<u16>()?, img_w, c, x, y, w, h),
+        PixelType::U8 => crop_vec(img.get_mut_vec::<u8>()?, img_w, c, x, y, w, h),
+        PixelType::I16 => crop_vec(img.get_mut_vec::<i16>()?, img_w, c, x, y, w, h),
+        PixelType::U32 => crop_vec(img.get_mut_vec::<u32>()?, img_w, c, x, y, w, h),
+        PixelType::F16 => crop_vec(img.get_mut_vec::<f16>()?, img_w, c, x, y, w, h),
+        PixelType::F64 => crop_vec(img.get_mut_vec::<f64>()?, img_w, c, x, y, w, h),
     }
 
-    img.shape = Shape::new(h, w, Some(c));
+    img.shape = new_shape;
 
     Ok(())
 }
@@ -78,4 +59,21 @@ mod tests {
 
         svec_save(img, "temp.png").expect("save failed");
     }
+
+    #[test]
+    fn test_crop_planar_matches_interleaved() {
+        use pepecore_array::{ImgData, Layout, SVec, Shape};
+
+        let data: Vec<u8> = (0..4 * 5 * 3).map(|v| v as u8).collect();
+        let mut hwc = SVec::new(Shape::new(4, 5, Some(3)), ImgData::from(data));
+        let mut chw = hwc.clone();
+        chw.to_planar();
+
+        super::crop(&mut hwc, 1, 1, 3, 2).unwrap();
+        super::crop(&mut chw, 1, 1, 3, 2).unwrap();
+
+        assert_eq!(chw.shape.get_layout(), Layout::CHW);
+        chw.to_interleaved();
+        assert_eq!(chw.get_data::<u8>().unwrap(), hwc.get_data::<u8>().unwrap());
+    }
 }
@@ -1,4 +1,4 @@
-use pepecore_array::{ImgData, PixelType, SVec, Shape};
+use pepecore_array::{ImgData, PixelType, SVec, Shape, f16};
 
 fn rgb_swap<T: Copy>(ptr: *mut T, len: usize) {
     unsafe {
@@ -27,6 +27,22 @@ pub fn rgb_to_bgr(img: &mut SVec) {
             let ptr: *mut u16 = img.get_mut_ptr::<u16>().unwrap();
             rgb_swap(ptr, len)
         }
+        PixelType::I16 => {
+            let ptr: *mut i16 = img.get_mut_ptr::<i16>().unwrap();
+            rgb_swap(ptr, len)
+        }
+        PixelType::U32 => {
+            let ptr: *mut u32 = img.get_mut_ptr::<u32>().unwrap();
+            rgb_swap(ptr, len)
+        }
+        PixelType::F16 => {
+            let ptr: *mut f16 = img.get_mut_ptr::<f16>().unwrap();
+            rgb_swap(ptr, len)
+        }
+        PixelType::F64 => {
+            let ptr: *mut f64 = img.get_mut_ptr::<f64>().unwrap();
+            rgb_swap(ptr, len)
+        }
     }
 }
 
@@ -53,6 +69,30 @@ pub fn gray_to_rgb(img: &mut SVec) {
                 vec_img.extend([vec_img[index], vec_img[index], vec_img[index]]);
             }
         }
+        PixelType::I16 => {
+            let vec_img = img.get_mut_vec::<i16>().unwrap();
+            for index in 0..h * w {
+                vec_img.extend([vec_img[index], vec_img[index], vec_img[index]]);
+            }
+        }
+        PixelType::U32 => {
+            let vec_img = img.get_mut_vec::<u32>().unwrap();
+            for index in 0..h * w {
+                vec_img.extend([vec_img[index], vec_img[index], vec_img[index]]);
+            }
+        }
+        PixelType::F16 => {
+            let vec_img = img.get_mut_vec::<f16>().unwrap();
+            for index in 0..h * w {
+                vec_img.extend([vec_img[index], vec_img[index], vec_img[index]]);
+            }
+        }
+        PixelType::F64 => {
+            let vec_img = img.get_mut_vec::<f64>().unwrap();
+            for index in 0..h * w {
+                vec_img.extend([vec_img[index], vec_img[index], vec_img[index]]);
+            }
+        }
     }
     img.drain(0..h * w).unwrap()
 }
@@ -96,6 +136,22 @@ pub fn rgb_to_bayer_2x2(img: &mut SVec, pattern: [usize; 4]) {
             let ptr: *mut u16 = img.get_mut_ptr::<u16>().unwrap();
             process_bayer_line(ptr, w, h, pattern);
         }
+        PixelType::I16 => {
+            let ptr: *mut i16 = img.get_mut_ptr::<i16>().unwrap();
+            process_bayer_line(ptr, w, h, pattern);
+        }
+        PixelType::U32 => {
+            let ptr: *mut u32 = img.get_mut_ptr::<u32>().unwrap();
+            process_bayer_line(ptr, w, h, pattern);
+        }
+        PixelType::F16 => {
+            let ptr: *mut f16 = img.get_mut_ptr::<f16>().unwrap();
+            process_bayer_line(ptr, w, h, pattern);
+        }
+        PixelType::F64 => {
+            let ptr: *mut f64 = img.get_mut_ptr::<f64>().unwrap();
+            process_bayer_line(ptr, w, h, pattern);
+        }
     }
 
     img.truncate(h * w).unwrap()
@@ -153,6 +209,34 @@ pub fn bayer_to_rgb(img: &mut SVec, pattern: [usize; 4]) {
             bayer_to_rgb_line(src, dst, w, h, pattern);
             img.data = ImgData::U16(rgb_buf)
         }
+        PixelType::I16 => {
+            let src: *const i16 = img.get_mut_ptr::<i16>().unwrap();
+            let mut rgb_buf = vec![0i16; total_pixels * 3];
+            let dst: *mut i16 = rgb_buf.as_mut_ptr();
+            bayer_to_rgb_line(src, dst, w, h, pattern);
+            img.data = ImgData::I16(rgb_buf)
+        }
+        PixelType::U32 => {
+            let src: *const u32 = img.get_mut_ptr::<u32>().unwrap();
+            let mut rgb_buf = vec![0u32; total_pixels * 3];
+            let dst: *mut u32 = rgb_buf.as_mut_ptr();
+            bayer_to_rgb_line(src, dst, w, h, pattern);
+            img.data = ImgData::U32(rgb_buf)
+        }
+        PixelType::F16 => {
+            let src: *const f16 = img.get_mut_ptr::<f16>().unwrap();
+            let mut rgb_buf = vec![f16::ZERO; total_pixels * 3];
+            let dst: *mut f16 = rgb_buf.as_mut_ptr();
+            bayer_to_rgb_line(src, dst, w, h, pattern);
+            img.data = ImgData::F16(rgb_buf)
+        }
+        PixelType::F64 => {
+            let src: *const f64 = img.get_mut_ptr::<f64>().unwrap();
+            let mut rgb_buf = vec![0f64; total_pixels * 3];
+            let dst: *mut f64 = rgb_buf.as_mut_ptr();
+            bayer_to_rgb_line(src, dst, w, h, pattern);
+            img.data = ImgData::F64(rgb_buf)
+        }
     }
 
     img.shape = Shape::new(h, w, Some(3)); // Теперь 3 канала RGB
@@ -2,7 +2,7 @@
 //!
 //! Provides a single function `cvt_color` that applies various color transformations
 //! (e.g., RGB⇄Gray, RGB⇄YCbCr, RGB⇄CMYK, channel swaps) on an `SVec` in-place, supporting
-//! different pixel types (u8, u16, f32).
+//! different pixel types (u8, u16, f32; i16, u32, f16 and f64 go through f32).
 //!
 //! # Examples
 //!
@@ -19,12 +19,14 @@
 //! assert!(svec.shape.get_channels().is_none()); // now single-channel
 //! ```
 use crate::enums::CVTColor;
+use crate::errors::CvtColorError;
 use crate::ops::svec_ops::cvtcolor::constants::*;
 use crate::ops::svec_ops::cvtcolor::cvt::{bayer_to_rgb, gray_to_rgb, rgb_to_bayer_2x2, rgb_to_bgr};
 use crate::ops::svec_ops::cvtcolor::cvt_f32::*;
 use crate::ops::svec_ops::cvtcolor::cvt_u8::*;
 use crate::ops::svec_ops::cvtcolor::cvt_u16::*;
-use pepecore_array::{PixelType, SVec};
+use crate::ops::svec_ops::cvtcolor::cvt_view::{rgb_to_bgr_view, rgb_to_ycbcr_view, ycbcr_to_rgb_view};
+use pepecore_array::{Pixel, PixelType, SVec, SVecViewMut, f16};
 
 /// Convert color space of `SVec` in-place according to `cvt_type`.
 ///
@@ -62,6 +64,10 @@ use pepecore_array::{PixelType, SVec};
 /// assert_eq!(svec.shape.get_ndims(), 3);
 /// ```
 pub fn cvt_color(img: &mut SVec, cvt_type: CVTColor) {
+    img.with_interleaved(|img| apply_cvt_color(img, cvt_type))
+}
+
+fn apply_cvt_color(img: &mut SVec, cvt_type: CVTColor) {
     match img.pixel_type() {
         PixelType::F32 => match cvt_type {
             CVTColor::RGB2Gray_601 => rgb_to_gray_f32(img, KR_601, KG_601, KB_601),
@@ -135,5 +141,91 @@ pub fn cvt_color(img: &mut SVec, cvt_type: CVTColor) {
             CVTColor::Bayer2RGB_GBRG => bayer_to_rgb(img, [1, 2, 0, 1]),
             CVTColor::Bayer2RGB_GRBG => bayer_to_rgb(img, [1, 0, 2, 1]),
         },
+        // Channel shuffles work on any type, colour maths goes through f32 and back
+        PixelType::I16 | PixelType::U32 | PixelType::F16 | PixelType::F64 => match cvt_type {
+            CVTColor::BGR2RGB | CVTColor::RGB2BGR => rgb_to_bgr(img),
+            CVTColor::Gray2RGB => gray_to_rgb(img),
+            CVTColor::RGB2Bayer_RGGB => rgb_to_bayer_2x2(img, [0, 1, 1, 2]),
+            CVTColor::RGB2Bayer_BGGR => rgb_to_bayer_2x2(img, [2, 1, 1, 0]),
+            CVTColor::RGB2Bayer_GBRG => rgb_to_bayer_2x2(img, [1, 2, 0, 1]),
+            CVTColor::RGB2Bayer_GRBG => rgb_to_bayer_2x2(img, [1, 0, 2, 1]),
+            CVTColor::Bayer2RGB_BGGR => bayer_to_rgb(img, [2, 1, 1, 0]),
+            CVTColor::Bayer2RGB_RGGB => bayer_to_rgb(img, [0, 1, 1, 2]),
+            CVTColor::Bayer2RGB_GBRG => bayer_to_rgb(img, [1, 2, 0, 1]),
+            CVTColor::Bayer2RGB_GRBG => bayer_to_rgb(img, [1, 0, 2, 1]),
+            _ => {
+                let pixel_type = img.pixel_type();
+                img.as_f32();
+                apply_cvt_color(img, cvt_type);
+                img.as_pixel_type(pixel_type);
+            }
+        },
+    }
+}
+
+fn apply_cvt_color_view<T: Pixel>(img: &mut SVecViewMut, cvt_type: CVTColor) {
+    match cvt_type {
+        CVTColor::RGB2YCbCR_601 => rgb_to_ycbcr_view::<T>(img, KR_601, KG_601, KB_601),
+        CVTColor::RGB2YCbCR_709 => rgb_to_ycbcr_view::<T>(img, KR_709, KG_709, KB_709),
+        CVTColor::RGB2YCbCR_2020 => rgb_to_ycbcr_view::<T>(img, KR_2020, KG_2020, KB_2020),
+        CVTColor::YCbCR2RGB_601 => ycbcr_to_rgb_view::<T>(img, KR_601, KG_601, KB_601),
+        CVTColor::YCbCR2RGB_709 => ycbcr_to_rgb_view::<T>(img, KR_709, KG_709, KB_709),
+        CVTColor::YCbCR2RGB_2020 => ycbcr_to_rgb_view::<T>(img, KR_2020, KG_2020, KB_2020),
+        CVTColor::RGB2BGR | CVTColor::BGR2RGB => rgb_to_bgr_view::<T>(img),
+        _ => unreachable!(),
+    }
+}
+
+/// Convert color space of a view in-place.
+///
+/// Only conversions that keep the channel count (RGB ⇄ YCbCr, RGB ⇄ BGR) can be applied to a view,
+/// since the view cannot be reshaped. Integer samples are converted through normalized `f32`.
+///
+/// # Errors
+///
+/// - `UnsupportedOnView` for conversions that change the channel count.
+/// - `ChannelMismatch` if the view does not have 3 channels.
+///
+/// # Examples
+///
+/// ```rust
+/// use pepecore::cvt_color::cvt_color_view;
+/// use pepecore_array::{SVec,Shape,ImgData};
+/// use pepecore::enums::{CVTColor};
+///
+/// let mut svec = SVec::new(Shape::new(2, 2, Some(3)), ImgData::U8(vec![10, 20, 30, 40, 50, 60, 70, 80, 90, 100, 110, 120]));
+/// // Swap channels in the right column only
+/// let mut column = svec.view_mut().crop(1, 0, 1, 2).unwrap();
+/// cvt_color_view(&mut column, CVTColor::RGB2BGR).unwrap();
+/// assert_eq!(svec.get_data::<u8>().unwrap(), &[10, 20, 30, 60, 50, 40, 70, 80, 90, 120, 110, 100]);
+/// ```
+pub fn cvt_color_view(img: &mut SVecViewMut, cvt_type: CVTColor) -> Result<(), CvtColorError> {
+    match cvt_type {
+        CVTColor::RGB2YCbCR_601
+        | CVTColor::RGB2YCbCR_709
+        | CVTColor::RGB2YCbCR_2020
+        | CVTColor::YCbCR2RGB_601
+        | CVTColor::YCbCR2RGB_709
+        | CVTColor::YCbCR2RGB_2020
+        | CVTColor::RGB2BGR
+        | CVTColor::BGR2RGB => {}
+        _ => return Err(CvtColorError::UnsupportedOnView(cvt_type)),
+    }
+    let channels = img.shape().2.unwrap_or(1);
+    if channels != 3 {
+        return Err(CvtColorError::ChannelMismatch {
+            expected: 3,
+            actual: channels,
+        });
+    }
+    match img.pixel_type() {
+        PixelType::F32 => apply_cvt_color_view::<f32>(img, cvt_type),
+        PixelType::U8 => apply_cvt_color_view::<u8>(img, cvt_type),
+        PixelType::U16 => apply_cvt_color_view::<u16>(img, cvt_type),
+        PixelType::I16 => apply_cvt_color_view::<i16>(img, cvt_type),
+        PixelType::U32 => apply_cvt_color_view::<u32>(img, cvt_type),
+        PixelType::F16 => apply_cvt_color_view::<f16>(img, cvt_type),
+        PixelType::F64 => apply_cvt_color_view::<f64>(img, cvt_type),
     }
+    Ok(())
 }
@@ -0,0 +1,51 @@
+use crate::ops::svec_ops::cvtcolor::cvt_f32::get_crg_cbg;
+use pepecore_array::{Pixel, SVecViewMut};
+
+pub fn rgb_to_ycbcr_view<T: Pixel>(img: &mut SVecViewMut, r: f32, g: f32, b: f32) {
+    let (h, w, _) = img.shape();
+    let strides = img.strides();
+    let data = img.get_data_mut::<T>().unwrap();
+    let ke: f32 = 1.0 / ((1_f32 - r) / 0.5);
+    let kd: f32 = 1.0 / ((1_f32 - b) / 0.5);
+    for y in 0..h {
+        for x in 0..w {
+            let (i_r, i_g, i_b) = (strides.index(y, x, 0), strides.index(y, x, 1), strides.index(y, x, 2));
+            let vr = data[i_r].to_f32_normalized();
+            let vg = data[i_g].to_f32_normalized();
+            let vb = data[i_b].to_f32_normalized();
+            let luma = vr.mul_add(r, vg.mul_add(g, vb * b));
+            data[i_r] = T::from_f32_normalized(luma);
+            data[i_g] = T::from_f32_normalized((vb - luma).mul_add(kd, 0.5));
+            data[i_b] = T::from_f32_normalized((vr - luma).mul_add(ke, 0.5));
+        }
+    }
+}
+
+pub fn ycbcr_to_rgb_view<T: Pixel>(img: &mut SVecViewMut, r: f32, g: f32, b: f32) {
+    let (h, w, _) = img.shape();
+    let strides = img.strides();
+    let data = img.get_data_mut::<T>().unwrap();
+    let (ke, kd, crg, cbg) = get_crg_cbg(r, g, b);
+    for y in 0..h {
+        for x in 0..w {
+            let (i_y, i_cb, i_cr) = (strides.index(y, x, 0), strides.index(y, x, 1), strides.index(y, x, 2));
+            let luma = data[i_y].to_f32_normalized();
+            let cb = data[i_cb].to_f32_normalized() - 0.5;
+            let cr = data[i_cr].to_f32_normalized() - 0.5;
+            data[i_y] = T::from_f32_normalized(cr.mul_add(ke, luma));
+            data[i_cb] = T::from_f32_normalized(cb.mul_add(cbg, cr.mul_add(crg, luma)));
+            data[i_cr] = T::from_f32_normalized(cb.mul_add(kd, luma));
+        }
+    }
+}
+
+pub fn rgb_to_bgr_view<T: Pixel>(img: &mut SVecViewMut) {
+    let (h, w, _) = img.shape();
+    let strides = img.strides();
+    let data = img.get_data_mut::<T>().unwrap();
+    for y in 0..h {
+        for x in 0..w {
+            data.swap(strides.index(y, x, 0), strides.index(y, x, 2));
+        }
+    }
+}
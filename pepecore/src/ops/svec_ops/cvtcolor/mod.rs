@@ -4,4 +4,5 @@ pub mod cvt_color;
 mod cvt_f32;
 mod cvt_u16;
 mod cvt_u8;
+mod cvt_view;
 mod lut;
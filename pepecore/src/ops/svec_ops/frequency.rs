@@ -0,0 +1,103 @@
+//! Frequency-domain filtering built on [`pepecore_array::fft`].
+use crate::enums::FreqFilter;
+use pepecore_array::error::Error;
+use pepecore_array::fft::{FftPlan, Spectrum};
+use pepecore_array::{Layout, SVec};
+
+#[inline(always)]
+fn gaussian(d2: f32, sigma: f32) -> f32 {
+    (-d2 / (2.0 * sigma * sigma)).exp()
+}
+
+/// Gain of `filter` at frequency `(fy, fx)`.
+fn gain(filter: &FreqFilter, fy: f32, fx: f32) -> f32 {
+    let r2 = fy * fy + fx * fx;
+    match filter {
+        FreqFilter::LowPass(cutoff) => gaussian(r2, *cutoff),
+        FreqFilter::HighPass(cutoff) => 1.0 - gaussian(r2, *cutoff),
+        FreqFilter::BandPass { low, high } => (1.0 - gaussian(r2, *low)) * gaussian(r2, *high),
+        FreqFilter::Notch { points, radius } => points
+            .iter()
+            .map(|&(py, px)| {
+                let d1 = (fy - py).powi(2) + (fx - px).powi(2);
+                let d2 = (fy + py).powi(2) + (fx + px).powi(2);
+                (1.0 - gaussian(d1, *radius)) * (1.0 - gaussian(d2, *radius))
+            })
+            .product(),
+    }
+}
+
+/// Apply `filter` to every spectrum in place.
+pub fn filter_spectra(spectra: &mut [Spectrum], filter: &FreqFilter) {
+    for spectrum in spectra {
+        spectrum.apply_gain(|fy, fx| gain(filter, fy, fx));
+    }
+}
+
+/// Filter every channel of `img` in the frequency domain.
+///
+/// The pixel type and layout of `img` are kept; integer results are clamped to the type range.
+///
+/// # Example
+/// ```rust
+/// use pepecore::enums::FreqFilter;
+/// use pepecore::ops::svec_ops::frequency::frequency_filter;
+/// use pepecore_array::{ImgData, SVec, Shape};
+///
+/// let mut img = SVec::new(Shape::new(8, 8, None), ImgData::from(vec![128u8; 64]));
+/// frequency_filter(&mut img, &FreqFilter::LowPass(0.1)).unwrap();
+/// assert_eq!(img.get_data::<u8>().unwrap()[0], 128);
+/// ```
+pub fn frequency_filter(img: &mut SVec, filter: &FreqFilter) -> Result<(), Error> {
+    let mut plan = FftPlan::new();
+    let mut spectra = plan.fft2(img)?;
+    filter_spectra(&mut spectra, filter);
+    let mut out = plan.ifft2(&spectra)?;
+    out.shape = img.shape.with_layout(Layout::HWC);
+    out.as_pixel_type(img.pixel_type());
+    if img.shape.get_layout() == Layout::CHW {
+        out.to_planar();
+    }
+    *img = out;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pepecore_array::{ImgData, Shape};
+    use std::f32::consts::PI;
+
+    fn stripes(h: usize, w: usize, period: f32) -> Vec<f32> {
+        (0..h * w)
+            .map(|i| 0.5 + 0.25 * (2.0 * PI * (i % w) as f32 / period).cos())
+            .collect()
+    }
+
+    #[test]
+    fn notch_removes_periodic_pattern() {
+        let (h, w) = (32, 32);
+        let mut img = SVec::new(Shape::new(h, w, None), ImgData::from(stripes(h, w, 4.0)));
+        let filter = FreqFilter::Notch { points: vec![(0.0, 0.25)], radius: 0.02 };
+        frequency_filter(&mut img, &filter).unwrap();
+        for v in img.get_data::<f32>().unwrap() {
+            assert!((v - 0.5).abs() < 1e-3, "{v}");
+        }
+    }
+
+    #[test]
+    fn low_and_high_pass_split_signal() {
+        let (h, w) = (16, 16);
+        let src = SVec::new(Shape::new(h, w, Some(1)), ImgData::from(stripes(h, w, 2.0)));
+        let mut low = src.clone();
+        frequency_filter(&mut low, &FreqFilter::LowPass(0.05)).unwrap();
+        assert_eq!(low.shape(), (h, w, Some(1)));
+        for v in low.get_data::<f32>().unwrap() {
+            assert!((v - 0.5).abs() < 1e-3);
+        }
+        let mut high = src.clone();
+        frequency_filter(&mut high, &FreqFilter::HighPass(0.05)).unwrap();
+        let mean = high.get_data::<f32>().unwrap().iter().sum::<f32>() / (h * w) as f32;
+        assert!(mean.abs() < 1e-3);
+    }
+}
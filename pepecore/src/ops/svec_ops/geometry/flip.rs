@@ -0,0 +1,212 @@
+//! Lossless flips, quarter-turn rotations and transposition.
+//!
+//! Every function works on any pixel type, channel count and layout and replaces the image in
+//! place; flips and [`rot180`] swap pixels without allocating. Quarter turns are
+//! counter-clockwise, matching [`rotate`](super::rotate::rotate) with a positive angle.
+use pepecore_array::error::Error;
+use pepecore_array::{PixelType, SVec, Shape, f16};
+
+fn flip_h_t<T>(data: &mut [T], w: usize, c: usize) {
+    for row in data.chunks_exact_mut(w * c) {
+        for x in 0..w / 2 {
+            for k in 0..c {
+                row.swap(x * c + k, (w - 1 - x) * c + k);
+            }
+        }
+    }
+}
+
+fn flip_v_t<T>(data: &mut [T], h: usize, w: usize, c: usize) {
+    let stride = w * c;
+    for y in 0..h / 2 {
+        let (top, bottom) = data.split_at_mut((h - 1 - y) * stride);
+        top[y * stride..(y + 1) * stride].swap_with_slice(&mut bottom[..stride]);
+    }
+}
+
+fn rot180_t<T>(data: &mut [T], c: usize) {
+    let n = data.len() / c;
+    for i in 0..n / 2 {
+        for k in 0..c {
+            data.swap(i * c + k, (n - 1 - i) * c + k);
+        }
+    }
+}
+
+/// Rebuild `data` as an `out_h` x `out_w` image where pixel `(y, x)` comes from `index(y, x)`.
+fn permute_t<T: Copy>(
+    data: &mut Vec<T>,
+    w: usize,
+    c: usize,
+    out_h: usize,
+    out_w: usize,
+    index: impl Fn(usize, usize) -> (usize, usize),
+) {
+    let mut out = Vec::with_capacity(data.len());
+    for y in 0..out_h {
+        for x in 0..out_w {
+            let (sy, sx) = index(y, x);
+            let start = (sy * w + sx) * c;
+            out.extend_from_slice(&data[start..start + c]);
+        }
+    }
+    *data = out;
+}
+
+/// Rebuild `img` as a `width` x `height` image, see [`permute_t`].
+fn permute(img: &mut SVec, index: impl Fn(usize, usize) -> (usize, usize)) -> Result<(), Error> {
+    let (h, w, c) = img.shape();
+    let (out_h, out_w) = (w, h);
+    let cn = c.unwrap_or(1);
+    img.with_interleaved(|img| {
+        match img.pixel_type() {
+            PixelType::U8 => permute_t(img.get_mut_vec::<u8>()?, w, cn, out_h, out_w, index),
+            PixelType::U16 => permute_t(img.get_mut_vec::<u16>()?, w, cn, out_h, out_w, index),
+            PixelType::I16 => permute_t(img.get_mut_vec::<i16>()?, w, cn, out_h, out_w, index),
+            PixelType::U32 => permute_t(img.get_mut_vec::<u32>()?, w, cn, out_h, out_w, index),
+            PixelType::F16 => permute_t(img.get_mut_vec::<f16>()?, w, cn, out_h, out_w, index),
+            PixelType::F32 => permute_t(img.get_mut_vec::<f32>()?, w, cn, out_h, out_w, index),
+            PixelType::F64 => permute_t(img.get_mut_vec::<f64>()?, w, cn, out_h, out_w, index),
+        }
+        img.shape = Shape::try_new(out_h, out_w, c)?;
+        Ok(())
+    })
+}
+
+/// Mirror `img` left to right.
+///
+/// # Errors
+///
+/// `ZeroDimension` when `img` is empty.
+pub fn flip_h(img: &mut SVec) -> Result<(), Error> {
+    let (h, w, c) = img.shape();
+    if h == 0 || w == 0 {
+        return Err(Error::ZeroDimension);
+    }
+    let c = c.unwrap_or(1);
+    img.with_interleaved(|img| {
+        match img.pixel_type() {
+            PixelType::U8 => flip_h_t(img.get_data_mut::<u8>()?, w, c),
+            PixelType::U16 => flip_h_t(img.get_data_mut::<u16>()?, w, c),
+            PixelType::I16 => flip_h_t(img.get_data_mut::<i16>()?, w, c),
+            PixelType::U32 => flip_h_t(img.get_data_mut::<u32>()?, w, c),
+            PixelType::F16 => flip_h_t(img.get_data_mut::<f16>()?, w, c),
+            PixelType::F32 => flip_h_t(img.get_data_mut::<f32>()?, w, c),
+            PixelType::F64 => flip_h_t(img.get_data_mut::<f64>()?, w, c),
+        }
+        Ok(())
+    })
+}
+
+/// Mirror `img` top to bottom.
+pub fn flip_v(img: &mut SVec) -> Result<(), Error> {
+    let (h, w, c) = img.shape();
+    let c = c.unwrap_or(1);
+    img.with_interleaved(|img| {
+        match img.pixel_type() {
+            PixelType::U8 => flip_v_t(img.get_data_mut::<u8>()?, h, w, c),
+            PixelType::U16 => flip_v_t(img.get_data_mut::<u16>()?, h, w, c),
+            PixelType::I16 => flip_v_t(img.get_data_mut::<i16>()?, h, w, c),
+            PixelType::U32 => flip_v_t(img.get_data_mut::<u32>()?, h, w, c),
+            PixelType::F16 => flip_v_t(img.get_data_mut::<f16>()?, h, w, c),
+            PixelType::F32 => flip_v_t(img.get_data_mut::<f32>()?, h, w, c),
+            PixelType::F64 => flip_v_t(img.get_data_mut::<f64>()?, h, w, c),
+        }
+        Ok(())
+    })
+}
+
+/// Rotate `img` by half a turn.
+pub fn rot180(img: &mut SVec) -> Result<(), Error> {
+    let c = img.shape().2.unwrap_or(1);
+    img.with_interleaved(|img| {
+        match img.pixel_type() {
+            PixelType::U8 => rot180_t(img.get_data_mut::<u8>()?, c),
+            PixelType::U16 => rot180_t(img.get_data_mut::<u16>()?, c),
+            PixelType::I16 => rot180_t(img.get_data_mut::<i16>()?, c),
+            PixelType::U32 => rot180_t(img.get_data_mut::<u32>()?, c),
+            PixelType::F16 => rot180_t(img.get_data_mut::<f16>()?, c),
+            PixelType::F32 => rot180_t(img.get_data_mut::<f32>()?, c),
+            PixelType::F64 => rot180_t(img.get_data_mut::<f64>()?, c),
+        }
+        Ok(())
+    })
+}
+
+/// Swap rows and columns, the output is `width` x `height`.
+pub fn transpose(img: &mut SVec) -> Result<(), Error> {
+    permute(img, |y, x| (x, y))
+}
+
+/// Rotate `img` a quarter turn counter-clockwise, the output is `width` x `height`.
+pub fn rot90(img: &mut SVec) -> Result<(), Error> {
+    let w = img.shape().1;
+    permute(img, |y, x| (x, w - 1 - y))
+}
+
+/// Rotate `img` a quarter turn clockwise, the output is `width` x `height`.
+pub fn rot270(img: &mut SVec) -> Result<(), Error> {
+    let h = img.shape().0;
+    permute(img, |y, x| (h - 1 - x, y))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pepecore_array::{ImgData, Layout};
+
+    // 2 x 3 image of two-channel pixels, channel 0 is the pixel index
+    fn img() -> SVec {
+        let data: Vec<u16> = (0..6).flat_map(|i| [i, 100 + i]).collect();
+        SVec::new(Shape::new(2, 3, Some(2)), ImgData::from(data))
+    }
+
+    fn first_channel(img: &SVec) -> Vec<u16> {
+        img.get_data::<u16>().unwrap().iter().step_by(2).copied().collect()
+    }
+
+    type Op = fn(&mut SVec) -> Result<(), Error>;
+
+    #[test]
+    fn test_flips_and_quarter_turns() {
+        let cases: [(Op, (usize, usize), [u16; 6]); 6] = [
+            (flip_h, (2, 3), [2, 1, 0, 5, 4, 3]),
+            (flip_v, (2, 3), [3, 4, 5, 0, 1, 2]),
+            (rot180, (2, 3), [5, 4, 3, 2, 1, 0]),
+            (transpose, (3, 2), [0, 3, 1, 4, 2, 5]),
+            (rot90, (3, 2), [2, 5, 1, 4, 0, 3]),
+            (rot270, (3, 2), [3, 0, 4, 1, 5, 2]),
+        ];
+        for (op, (h, w), expected) in cases {
+            let mut out = img();
+            op(&mut out).unwrap();
+            assert_eq!(out.shape(), (h, w, Some(2)));
+            assert_eq!(first_channel(&out), expected);
+            // channels travel with their pixel
+            assert!(out.get_data::<u16>().unwrap().chunks(2).all(|p| p[1] == p[0] + 100));
+        }
+    }
+
+    #[test]
+    fn test_planar_matches_interleaved() {
+        let mut hwc = img();
+        let mut chw = img();
+        chw.to_planar();
+        rot90(&mut hwc).unwrap();
+        rot90(&mut chw).unwrap();
+        assert_eq!(chw.shape.get_layout(), Layout::CHW);
+        chw.to_interleaved();
+        assert_eq!(chw.get_data::<u16>().unwrap(), hwc.get_data::<u16>().unwrap());
+
+        let mut gray = SVec::new(Shape::new(1, 3, None), ImgData::from(vec![1.0f32, 2.0, 3.0]));
+        rot270(&mut gray).unwrap();
+        assert_eq!(gray.shape(), (3, 1, None));
+        assert_eq!(gray.get_data::<f32>().unwrap(), &[1.0, 2.0, 3.0]);
+    }
+
+    #[test]
+    fn test_flip_h_empty() {
+        let mut empty = SVec::new(Shape::new(2, 0, Some(3)), ImgData::from(Vec::<u8>::new()));
+        assert!(matches!(flip_h(&mut empty), Err(Error::ZeroDimension)));
+    }
+}
@@ -0,0 +1,5 @@
+pub mod flip;
+pub mod pad;
+pub mod rotate;
+pub(crate) mod sample;
+pub mod warp;
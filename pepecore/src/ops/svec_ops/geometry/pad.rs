@@ -0,0 +1,190 @@
+//! Padding with border extension.
+use crate::enums::Border;
+use crate::ops::svec_ops::crop::crop;
+use crate::ops::svec_ops::geometry::sample::{border_index, fill_color};
+use pepecore_array::error::Error;
+use pepecore_array::{Pixel, PixelType, SVec, Shape, f16};
+
+/// Pixels added on each side by [`pad`] or [`pad_to_multiple`].
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Default)]
+pub struct Padding {
+    pub top: usize,
+    pub bottom: usize,
+    pub left: usize,
+    pub right: usize,
+}
+
+impl Padding {
+    /// Crop the padding back off `img`.
+    pub fn undo(&self, img: &mut SVec) -> Result<(), Error> {
+        let (h, w, _) = img.shape();
+        let height = h.checked_sub(self.top + self.bottom).ok_or(Error::OutOfBounds)?;
+        let width = w.checked_sub(self.left + self.right).ok_or(Error::OutOfBounds)?;
+        crop(img, self.left, self.top, width, height)
+    }
+}
+
+fn pad_t<T: Pixel>(data: &mut Vec<T>, h: usize, w: usize, c: usize, padding: Padding, border: &Border, fill: &[f32]) {
+    let fill: Vec<T> = fill.iter().map(|&v| T::from_f32_normalized(v)).collect();
+    let out_w = w + padding.left + padding.right;
+    let mut out = Vec::with_capacity((h + padding.top + padding.bottom) * out_w * c);
+    let push = |out: &mut Vec<T>, src: Option<usize>, x: isize| match (src, border_index(x, w, border)) {
+        (Some(row), Some(x)) => out.extend_from_slice(&data[(row * w + x) * c..(row * w + x + 1) * c]),
+        _ => out.extend_from_slice(&fill),
+    };
+    for y in 0..h + padding.top + padding.bottom {
+        let row = border_index(y as isize - padding.top as isize, h, border);
+        for x in 0..padding.left {
+            push(&mut out, row, x as isize - padding.left as isize);
+        }
+        match row {
+            Some(row) => out.extend_from_slice(&data[row * w * c..(row + 1) * w * c]),
+            None => (0..w).for_each(|_| out.extend_from_slice(&fill)),
+        }
+        for x in w..w + padding.right {
+            push(&mut out, row, x as isize);
+        }
+    }
+    *data = out;
+}
+
+/// Grow `img` by `top`, `bottom`, `left` and `right` pixels filled according to `border`.
+///
+/// Works for every pixel type, channel count and layout. Reflecting and wrapping borders repeat
+/// as often as needed when the padding is wider than the image.
+///
+/// # Errors
+///
+/// `ZeroDimension` when `img` is empty, `LengthMismatch` when a constant border color has
+/// neither zero, one nor `channels` values.
+///
+/// # Example
+/// ```rust
+/// use pepecore::enums::Border;
+/// use pepecore::ops::svec_ops::geometry::pad::pad;
+/// use pepecore_array::{ImgData, SVec, Shape};
+///
+/// let mut img = SVec::new(Shape::new(1, 3, None), ImgData::from(vec![1u8, 2, 3]));
+/// pad(&mut img, 0, 0, 2, 2, Border::Reflect101).unwrap();
+/// assert_eq!(img.get_data::<u8>().unwrap(), &[3, 2, 1, 2, 3, 2, 1]);
+/// ```
+pub fn pad(img: &mut SVec, top: usize, bottom: usize, left: usize, right: usize, border: Border) -> Result<(), Error> {
+    let padding = Padding {
+        top,
+        bottom,
+        left,
+        right,
+    };
+    let (h, w, c) = img.shape();
+    if h == 0 || w == 0 {
+        return Err(Error::ZeroDimension);
+    }
+    let cn = c.unwrap_or(1);
+    let fill = match &border {
+        Border::Constant(fill) => fill_color(fill, cn)?,
+        _ => Vec::new(),
+    };
+    let shape = Shape::try_new(h + top + bottom, w + left + right, c)?;
+    img.with_interleaved(|img| {
+        match img.pixel_type() {
+            PixelType::U8 => pad_t(img.get_mut_vec::<u8>()?, h, w, cn, padding, &border, &fill),
+            PixelType::U16 => pad_t(img.get_mut_vec::<u16>()?, h, w, cn, padding, &border, &fill),
+            PixelType::I16 => pad_t(img.get_mut_vec::<i16>()?, h, w, cn, padding, &border, &fill),
+            PixelType::U32 => pad_t(img.get_mut_vec::<u32>()?, h, w, cn, padding, &border, &fill),
+            PixelType::F16 => pad_t(img.get_mut_vec::<f16>()?, h, w, cn, padding, &border, &fill),
+            PixelType::F32 => pad_t(img.get_mut_vec::<f32>()?, h, w, cn, padding, &border, &fill),
+            PixelType::F64 => pad_t(img.get_mut_vec::<f64>()?, h, w, cn, padding, &border, &fill),
+        }
+        img.shape = shape;
+        Ok(())
+    })
+}
+
+/// Pad the bottom and right of `img` up to the next multiple of `n` in both dimensions.
+///
+/// Returns the padding added, [`Padding::undo`] (or `crop(img, 0, 0, w, h)`) restores the
+/// original size.
+///
+/// # Errors
+///
+/// `ZeroDimension` for `n == 0`, otherwise the errors of [`pad`].
+pub fn pad_to_multiple(img: &mut SVec, n: usize, border: Border) -> Result<Padding, Error> {
+    if n == 0 {
+        return Err(Error::ZeroDimension);
+    }
+    let (h, w, _) = img.shape();
+    let padding = Padding {
+        bottom: h.next_multiple_of(n) - h,
+        right: w.next_multiple_of(n) - w,
+        ..Default::default()
+    };
+    if padding != Padding::default() {
+        pad(img, 0, padding.bottom, 0, padding.right, border)?;
+    }
+    Ok(padding)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pepecore_array::ImgData;
+
+    fn column(img: &SVec, x: usize) -> Vec<u8> {
+        let (_, w, _) = img.shape();
+        img.get_data::<u8>().unwrap().iter().skip(x).step_by(w).copied().collect()
+    }
+
+    #[test]
+    fn test_pad_modes() {
+        let img = SVec::new(Shape::new(3, 2, None), ImgData::from(vec![1u8, 2, 3, 4, 5, 6]));
+        let cases = [
+            (Border::Constant(vec![1.0]), [255, 255, 1, 3, 5, 255]),
+            (Border::Replicate, [1, 1, 1, 3, 5, 5]),
+            (Border::Reflect, [3, 1, 1, 3, 5, 5]),
+            (Border::Reflect101, [5, 3, 1, 3, 5, 3]),
+            (Border::Wrap, [3, 5, 1, 3, 5, 1]),
+        ];
+        for (border, expected) in cases {
+            let mut out = img.clone();
+            pad(&mut out, 2, 1, 1, 0, border.clone()).unwrap();
+            assert_eq!(out.shape(), (6, 3, None));
+            assert_eq!(column(&out, 1), expected, "{:?}", border);
+        }
+    }
+
+    #[test]
+    fn test_pad_to_multiple_roundtrip() {
+        let data: Vec<f32> = (0..5 * 7 * 3).map(|v| v as f32).collect();
+        let img = SVec::new(Shape::new(5, 7, Some(3)), ImgData::from(data));
+        let mut planar = img.clone();
+        planar.to_planar();
+        for mut padded in [img.clone(), planar] {
+            let padding = pad_to_multiple(&mut padded, 4, Border::Constant(vec![0.5, 0.25, 0.0])).unwrap();
+            assert_eq!((padding.bottom, padding.right), (3, 1));
+            assert_eq!(padded.shape(), (8, 8, Some(3)));
+            padding.undo(&mut padded).unwrap();
+            padded.to_interleaved();
+            assert_eq!(padded.get_data::<f32>().unwrap(), img.get_data::<f32>().unwrap());
+        }
+
+        let mut img = img;
+        assert_eq!(pad_to_multiple(&mut img, 1, Border::Wrap).unwrap(), Padding::default());
+        assert!(pad_to_multiple(&mut img, 0, Border::Wrap).is_err());
+        assert!(pad(&mut img, 1, 1, 1, 1, Border::Constant(vec![0.0, 0.0])).is_err());
+    }
+
+    #[test]
+    fn test_pad_empty() {
+        let empty = SVec::new(Shape::new(0, 3, None), ImgData::from(Vec::<u8>::new()));
+        for border in [
+            Border::Constant(vec![]),
+            Border::Replicate,
+            Border::Reflect,
+            Border::Reflect101,
+            Border::Wrap,
+        ] {
+            let mut img = empty.clone();
+            assert!(matches!(pad(&mut img, 1, 1, 1, 1, border), Err(Error::ZeroDimension)));
+        }
+    }
+}
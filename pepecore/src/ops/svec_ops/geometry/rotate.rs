@@ -0,0 +1,115 @@
+//! Rotation by an arbitrary angle.
+use crate::enums::{Border, Interpolation};
+use crate::ops::svec_ops::geometry::flip::{rot90, rot180, rot270};
+use crate::ops::svec_ops::geometry::sample::remap;
+use pepecore_array::SVec;
+use pepecore_array::error::Error;
+
+/// Rotate `img` counter-clockwise by `angle` degrees around its center.
+///
+/// With `expand` the canvas grows to hold the whole rotated image, otherwise the size is kept and
+/// the corners are cut. Uncovered pixels take `fill`: normalized values (`0.0..=1.0` for unsigned
+/// types) given per channel, as a single value for all channels, or empty for zero. Quarter turns
+/// that keep the canvas are done losslessly through [`rot90`], [`rot180`] and [`rot270`].
+///
+/// # Errors
+///
+/// `ZeroDimension` when `img` is empty, `LengthMismatch` when `fill` has neither zero, one nor
+/// `channels` values.
+///
+/// # Example
+/// ```rust
+/// use pepecore::enums::Interpolation;
+/// use pepecore::ops::svec_ops::geometry::rotate::rotate;
+/// use pepecore_array::{ImgData, SVec, Shape};
+///
+/// let mut img = SVec::new(Shape::new(10, 10, Some(3)), ImgData::from(vec![255u8; 300]));
+/// rotate(&mut img, 45.0, Interpolation::Bilinear, true, &[0.0, 1.0, 0.0]).unwrap();
+/// assert_eq!(img.shape(), (15, 15, Some(3)));
+/// assert_eq!(&img.get_data::<u8>().unwrap()[..3], &[0, 255, 0]);
+/// ```
+pub fn rotate(img: &mut SVec, angle: f32, interpolation: Interpolation, expand: bool, fill: &[f32]) -> Result<(), Error> {
+    let (h, w, _) = img.shape();
+    if h == 0 || w == 0 {
+        return Err(Error::ZeroDimension);
+    }
+    let turn = angle.rem_euclid(360.0);
+    let square = expand || h == w;
+    match turn {
+        0.0 => return Ok(()),
+        90.0 if square => return rot90(img),
+        180.0 => return rot180(img),
+        270.0 if square => return rot270(img),
+        _ => {}
+    }
+
+    let (sin, cos) = turn.to_radians().sin_cos();
+    let (out_h, out_w) = if expand {
+        // the epsilon keeps float noise from adding a row or column
+        let out_h = (w as f32 * sin.abs() + h as f32 * cos.abs() - 1e-3).ceil() as usize;
+        let out_w = (w as f32 * cos.abs() + h as f32 * sin.abs() - 1e-3).ceil() as usize;
+        (out_h, out_w)
+    } else {
+        (h, w)
+    };
+    let (cx, cy) = ((w as f32 - 1.0) / 2.0, (h as f32 - 1.0) / 2.0);
+    let (out_cx, out_cy) = ((out_w as f32 - 1.0) / 2.0, (out_h as f32 - 1.0) / 2.0);
+    *img = remap(img, out_h, out_w, interpolation, &Border::Constant(fill.to_vec()), |x, y| {
+        let (dx, dy) = (x - out_cx, y - out_cy);
+        (cos * dx - sin * dy + cx, sin * dx + cos * dy + cy)
+    })?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pepecore_array::{ImgData, Shape};
+
+    fn square() -> SVec {
+        SVec::new(
+            Shape::new(5, 5, None),
+            ImgData::from((0..25).map(|v| v as u8).collect::<Vec<u8>>()),
+        )
+    }
+
+    #[test]
+    fn test_rotate_matches_quarter_turn() {
+        let mut exact = square();
+        rot90(&mut exact).unwrap();
+        // off the lossless path, nearest sampling lands on the same pixels
+        for interpolation in [
+            Interpolation::Nearest,
+            Interpolation::Bilinear,
+            Interpolation::Bicubic,
+            Interpolation::Lanczos3,
+        ] {
+            let mut img = square();
+            rotate(&mut img, 90.0001, interpolation, false, &[]).unwrap();
+            assert_eq!(
+                img.get_data::<u8>().unwrap(),
+                exact.get_data::<u8>().unwrap(),
+                "{:?}",
+                interpolation
+            );
+        }
+
+        let mut wide = SVec::new(Shape::new(2, 4, Some(2)), ImgData::from(vec![0.5f32; 16]));
+        rotate(&mut wide, -90.0, Interpolation::Bilinear, true, &[]).unwrap();
+        assert_eq!(wide.shape(), (4, 2, Some(2)));
+    }
+
+    #[test]
+    fn test_rotate_fill_and_canvas() {
+        let mut img = SVec::new(Shape::new(6, 8, Some(2)), ImgData::from(vec![1000u16; 96]));
+        rotate(&mut img, 30.0, Interpolation::Bicubic, false, &[0.0, 1.0]).unwrap();
+        assert_eq!(img.shape(), (6, 8, Some(2)));
+        let data = img.get_data::<u16>().unwrap();
+        assert_eq!(&data[..2], &[0, 65535]);
+        // the center stays untouched by the border
+        let center = (3 * 8 + 4) * 2;
+        assert_eq!(&data[center..center + 2], &[1000, 1000]);
+
+        assert!(rotate(&mut img, 10.0, Interpolation::Nearest, false, &[0.0, 0.0, 0.0]).is_err());
+    }
+}
@@ -0,0 +1,264 @@
+//! Resampling shared by the geometric transforms.
+//!
+//! Coordinates are in pixel units with the center of pixel `i` at `i`. Nearest copies source
+//! samples unchanged; the other filters interpolate in the normalized range of [`Pixel`], so
+//! their integer outputs are rounded and clamped.
+use crate::enums::{Border, Interpolation};
+use crate::global_params::rayon_get_mode;
+use pepecore_array::error::Error;
+use pepecore_array::{ImgData, Layout, Pixel, PixelType, SVec, Shape, f16};
+use rayon::prelude::*;
+use std::borrow::Cow;
+use std::f32::consts::PI;
+
+/// Expand a fill color to `c` normalized values: empty means zero, one value is broadcast.
+pub(crate) fn fill_color(fill: &[f32], c: usize) -> Result<Vec<f32>, Error> {
+    match fill.len() {
+        0 => Ok(vec![0.0; c]),
+        1 => Ok(vec![fill[0]; c]),
+        n if n == c => Ok(fill.to_vec()),
+        n => Err(Error::LengthMismatch { expected: c, actual: n }),
+    }
+}
+
+/// Index inside `0..n` that position `i` of a row or column reads under `border`, `None` when
+/// it takes the constant color. `n` must not be zero, callers reject empty images up front.
+#[inline(always)]
+pub(crate) fn border_index(i: isize, n: usize, border: &Border) -> Option<usize> {
+    let len = n as isize;
+    if (0..len).contains(&i) {
+        return Some(i as usize);
+    }
+    let i = match border {
+        Border::Constant(_) => return None,
+        Border::Replicate => i.clamp(0, len - 1),
+        Border::Reflect => {
+            let i = i.rem_euclid(2 * len);
+            if i < len { i } else { 2 * len - 1 - i }
+        }
+        Border::Reflect101 if n == 1 => 0,
+        Border::Reflect101 => {
+            let period = 2 * len - 2;
+            let i = i.rem_euclid(period);
+            if i < len { i } else { period - i }
+        }
+        Border::Wrap => i.rem_euclid(len),
+    };
+    Some(i as usize)
+}
+
+#[inline(always)]
+fn catmull_rom(t: f32) -> [f32; 4] {
+    [
+        ((-0.5 * t + 1.0) * t - 0.5) * t,
+        (1.5 * t - 2.5) * t * t + 1.0,
+        ((-1.5 * t + 2.0) * t + 0.5) * t,
+        (0.5 * t - 0.5) * t * t,
+    ]
+}
+
+/// Lanczos weights for taps `-2..=3` around `t` in `0.0..1.0`, normalized to sum to one.
+#[inline(always)]
+fn lanczos3(t: f32) -> [f32; 6] {
+    let mut weights = [0.0; 6];
+    for (i, w) in weights.iter_mut().enumerate() {
+        let d = t - (i as f32 - 2.0);
+        *w = if d.abs() < 1e-6 {
+            1.0
+        } else {
+            let p = PI * d;
+            3.0 * p.sin() * (p / 3.0).sin() / (p * p)
+        };
+    }
+    let sum: f32 = weights.iter().sum();
+    weights.map(|w| w / sum)
+}
+
+struct Sampler<'a, T> {
+    data: &'a [T],
+    h: usize,
+    w: usize,
+    c: usize,
+    border: &'a Border,
+    fill: &'a [f32],
+}
+
+impl<T: Pixel> Sampler<'_, T> {
+    #[inline(always)]
+    fn fetch(&self, x: isize, y: isize, k: usize) -> f32 {
+        match (border_index(x, self.w, self.border), border_index(y, self.h, self.border)) {
+            (Some(x), Some(y)) => self.data[(y * self.w + x) * self.c + k].to_f32_normalized(),
+            _ => self.fill[k],
+        }
+    }
+
+    /// Separable filter with `N` taps per axis starting at `(x0, y0)`.
+    #[inline(always)]
+    fn separable<const N: usize>(&self, x0: isize, y0: isize, wx: [f32; N], wy: [f32; N], out: &mut [T]) {
+        for (k, out) in out.iter_mut().enumerate() {
+            let mut v = 0.0;
+            for (j, wy) in wy.iter().enumerate() {
+                let row: f32 = wx
+                    .iter()
+                    .enumerate()
+                    .map(|(i, wx)| wx * self.fetch(x0 + i as isize, y0 + j as isize, k))
+                    .sum();
+                v += wy * row;
+            }
+            *out = T::from_f32_normalized(v);
+        }
+    }
+
+    fn sample(&self, x: f32, y: f32, interpolation: Interpolation, out: &mut [T]) {
+        // far outside (or undefined, e.g. behind a perspective horizon) reads the border
+        let (x, y) = (
+            if x.is_nan() { -1e6 } else { x.clamp(-1e6, 1e6) },
+            if y.is_nan() { -1e6 } else { y.clamp(-1e6, 1e6) },
+        );
+        let (x0, y0) = (x.floor(), y.floor());
+        let (tx, ty) = (x - x0, y - y0);
+        let (x0, y0) = (x0 as isize, y0 as isize);
+        match interpolation {
+            Interpolation::Nearest => {
+                let (x, y) = (x0 + (tx >= 0.5) as isize, y0 + (ty >= 0.5) as isize);
+                match (border_index(x, self.w, self.border), border_index(y, self.h, self.border)) {
+                    (Some(x), Some(y)) => {
+                        let start = (y * self.w + x) * self.c;
+                        out.copy_from_slice(&self.data[start..start + self.c]);
+                    }
+                    _ => {
+                        for (out, &fill) in out.iter_mut().zip(self.fill) {
+                            *out = T::from_f32_normalized(fill);
+                        }
+                    }
+                }
+            }
+            Interpolation::Bilinear => self.separable(x0, y0, [1.0 - tx, tx], [1.0 - ty, ty], out),
+            Interpolation::Bicubic => self.separable(x0 - 1, y0 - 1, catmull_rom(tx), catmull_rom(ty), out),
+            Interpolation::Lanczos3 => self.separable(x0 - 2, y0 - 2, lanczos3(tx), lanczos3(ty), out),
+        }
+    }
+}
+
+/// Sampling of an `h` x `w` x `c` source into an `out_h` x `out_w` image.
+struct Remap<'a, M> {
+    h: usize,
+    w: usize,
+    c: usize,
+    out_h: usize,
+    out_w: usize,
+    interpolation: Interpolation,
+    border: &'a Border,
+    fill: &'a [f32],
+    map: M,
+}
+
+impl<M: Fn(f32, f32) -> (f32, f32) + Sync> Remap<'_, M> {
+    fn apply<T: Pixel>(&self, data: &[T]) -> Vec<T> {
+        let (c, out_w) = (self.c, self.out_w);
+        let sampler = Sampler {
+            data,
+            h: self.h,
+            w: self.w,
+            c,
+            border: self.border,
+            fill: self.fill,
+        };
+        let mut out = vec![T::MIN_VALUE; self.out_h * out_w * c];
+        let row = |(y, row): (usize, &mut [T])| {
+            for (x, px) in row.chunks_exact_mut(c).enumerate() {
+                let (sx, sy) = (self.map)(x as f32, y as f32);
+                sampler.sample(sx, sy, self.interpolation, px);
+            }
+        };
+        if rayon_get_mode() {
+            out.par_chunks_mut(out_w * c).enumerate().for_each(row);
+        } else {
+            out.chunks_mut(out_w * c).enumerate().for_each(row);
+        }
+        out
+    }
+}
+
+/// Sample an `out_h` x `out_w` image whose pixel `(x, y)` is read from `img` at `map(x, y)`.
+///
+/// The result keeps the pixel type, channel count and layout of `img`.
+pub(crate) fn remap(
+    img: &SVec,
+    out_h: usize,
+    out_w: usize,
+    interpolation: Interpolation,
+    border: &Border,
+    map: impl Fn(f32, f32) -> (f32, f32) + Sync,
+) -> Result<SVec, Error> {
+    let (h, w, c) = img.shape();
+    if h == 0 || w == 0 {
+        return Err(Error::ZeroDimension);
+    }
+    let fill = match border {
+        Border::Constant(fill) => fill_color(fill, c.unwrap_or(1))?,
+        _ => Vec::new(),
+    };
+    let shape = Shape::try_new(out_h, out_w, c)?;
+    let planar = img.shape.get_layout() == Layout::CHW;
+    let src = if planar {
+        let mut img = img.clone();
+        img.to_interleaved();
+        Cow::Owned(img)
+    } else {
+        Cow::Borrowed(img)
+    };
+    let remap = Remap {
+        h,
+        w,
+        c: c.unwrap_or(1),
+        out_h,
+        out_w,
+        interpolation,
+        border,
+        fill: &fill,
+        map,
+    };
+    let data = match src.pixel_type() {
+        PixelType::U8 => ImgData::from(remap.apply(src.get_data::<u8>()?)),
+        PixelType::U16 => ImgData::from(remap.apply(src.get_data::<u16>()?)),
+        PixelType::I16 => ImgData::from(remap.apply(src.get_data::<i16>()?)),
+        PixelType::U32 => ImgData::from(remap.apply(src.get_data::<u32>()?)),
+        PixelType::F16 => ImgData::from(remap.apply(src.get_data::<f16>()?)),
+        PixelType::F32 => ImgData::from(remap.apply(src.get_data::<f32>()?)),
+        PixelType::F64 => ImgData::from(remap.apply(src.get_data::<f64>()?)),
+    };
+    let mut out = SVec::try_new(shape, data)?;
+    if planar {
+        out.to_planar();
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_border_index() {
+        let row = |border: &Border| -> Vec<Option<usize>> { (-3..7).map(|i| border_index(i, 4, border)).collect() };
+        let some = |v: [usize; 10]| v.map(Some).to_vec();
+        assert_eq!(row(&Border::Replicate), some([0, 0, 0, 0, 1, 2, 3, 3, 3, 3]));
+        assert_eq!(row(&Border::Reflect), some([2, 1, 0, 0, 1, 2, 3, 3, 2, 1]));
+        assert_eq!(row(&Border::Reflect101), some([3, 2, 1, 0, 1, 2, 3, 2, 1, 0]));
+        assert_eq!(row(&Border::Wrap), some([1, 2, 3, 0, 1, 2, 3, 0, 1, 2]));
+        assert_eq!(border_index(-1, 4, &Border::Constant(vec![])), None);
+        assert_eq!(border_index(5, 1, &Border::Reflect101), Some(0));
+    }
+
+    #[test]
+    fn test_nearest_copies_samples() {
+        // neither survives a round trip through normalized f32
+        let img = SVec::new(Shape::new(1, 2, None), ImgData::from(vec![16_777_217u32, 4_000_000_001]));
+        let out = remap(&img, 1, 2, Interpolation::Nearest, &Border::Replicate, |x, y| (1.0 - x, y)).unwrap();
+        assert_eq!(out.get_data::<u32>().unwrap(), &[4_000_000_001, 16_777_217]);
+        let img = SVec::new(Shape::new(1, 1, None), ImgData::from(vec![0.1f64]));
+        let out = remap(&img, 1, 2, Interpolation::Nearest, &Border::Constant(vec![]), |x, y| (x, y)).unwrap();
+        assert_eq!(out.get_data::<f64>().unwrap(), &[0.1, 0.0]);
+    }
+}
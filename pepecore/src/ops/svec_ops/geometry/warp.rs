@@ -0,0 +1,208 @@
+//! Affine and perspective warping.
+//!
+//! Matrices map source coordinates to output coordinates (pixel centers at integers, as in
+//! OpenCV) and are inverted internally, so every output pixel is sampled once from the source.
+use crate::enums::{Border, Interpolation};
+use crate::errors::GeometryError;
+use crate::ops::svec_ops::geometry::sample::remap;
+use pepecore_array::SVec;
+
+/// Inverse of the 3x3 row-major matrix `m`.
+fn invert3(m: [f64; 9]) -> Result<[f64; 9], GeometryError> {
+    let cof = [
+        m[4] * m[8] - m[5] * m[7],
+        m[5] * m[6] - m[3] * m[8],
+        m[3] * m[7] - m[4] * m[6],
+    ];
+    let det = m[0] * cof[0] + m[1] * cof[1] + m[2] * cof[2];
+    if det.abs() < 1e-12 || !det.is_finite() {
+        return Err(GeometryError::SingularMatrix);
+    }
+    Ok([
+        cof[0] / det,
+        (m[2] * m[7] - m[1] * m[8]) / det,
+        (m[1] * m[5] - m[2] * m[4]) / det,
+        cof[1] / det,
+        (m[0] * m[8] - m[2] * m[6]) / det,
+        (m[2] * m[3] - m[0] * m[5]) / det,
+        cof[2] / det,
+        (m[1] * m[6] - m[0] * m[7]) / det,
+        (m[0] * m[4] - m[1] * m[3]) / det,
+    ])
+}
+
+/// Warp `img` by the affine `matrix` `[a, b, c, d, e, f]`, mapping source `(x, y)` to
+/// `(a*x + b*y + c, d*x + e*y + f)`, into an image of `out_shape` `(height, width)`.
+///
+/// The result keeps the pixel type, channel count and layout of `img`.
+///
+/// # Errors
+///
+/// `SingularMatrix` when `matrix` can't be inverted, `SVecError` for an empty `img`, a zero
+/// `out_shape` or a constant border whose color doesn't match the channel count.
+///
+/// # Example
+/// ```rust
+/// use pepecore::enums::{Border, Interpolation};
+/// use pepecore::ops::svec_ops::geometry::warp::warp_affine;
+/// use pepecore_array::{ImgData, SVec, Shape};
+///
+/// let img = SVec::new(Shape::new(1, 3, None), ImgData::from(vec![10u8, 20, 30]));
+/// let shifted = warp_affine(&img, [1.0, 0.0, 1.0, 0.0, 1.0, 0.0], (1, 3), Interpolation::Nearest, Border::Replicate).unwrap();
+/// assert_eq!(shifted.get_data::<u8>().unwrap(), &[10, 10, 20]);
+/// ```
+pub fn warp_affine(
+    img: &SVec,
+    matrix: [f32; 6],
+    out_shape: (usize, usize),
+    interpolation: Interpolation,
+    border: Border,
+) -> Result<SVec, GeometryError> {
+    let m = matrix.map(|v| v as f64);
+    let inv = invert3([m[0], m[1], m[2], m[3], m[4], m[5], 0.0, 0.0, 1.0])?;
+    let inv = inv.map(|v| v as f32);
+    Ok(remap(img, out_shape.0, out_shape.1, interpolation, &border, |x, y| {
+        (inv[0] * x + inv[1] * y + inv[2], inv[3] * x + inv[4] * y + inv[5])
+    })?)
+}
+
+/// Warp `img` by the row-major 3x3 `homography`, mapping source `(x, y)` to
+/// `((h0*x + h1*y + h2) / w, (h3*x + h4*y + h5) / w)` with `w = h6*x + h7*y + h8`.
+///
+/// Output pixels whose source lies on the horizon read the border. See [`warp_affine`] for the
+/// other arguments and errors.
+pub fn warp_perspective(
+    img: &SVec,
+    homography: [f32; 9],
+    out_shape: (usize, usize),
+    interpolation: Interpolation,
+    border: Border,
+) -> Result<SVec, GeometryError> {
+    let inv = invert3(homography.map(|v| v as f64))?.map(|v| v as f32);
+    Ok(remap(img, out_shape.0, out_shape.1, interpolation, &border, |x, y| {
+        let w = inv[6] * x + inv[7] * y + inv[8];
+        if w.abs() < 1e-9 {
+            return (f32::NAN, f32::NAN);
+        }
+        ((inv[0] * x + inv[1] * y + inv[2]) / w, (inv[3] * x + inv[4] * y + inv[5]) / w)
+    })?)
+}
+
+/// Homography for [`warp_perspective`] taking the four `src` points onto the four `dst` points,
+/// e.g. the corners of a photographed page onto an upright rectangle.
+///
+/// # Errors
+///
+/// `SingularMatrix` when three of the points are collinear.
+pub fn perspective_transform(src: [(f32, f32); 4], dst: [(f32, f32); 4]) -> Result<[f32; 9], GeometryError> {
+    // eight equations in h0..h7 with h8 = 1, solved by Gaussian elimination
+    let mut a = [[0.0f64; 9]; 8];
+    for (i, ((x, y), (u, v))) in src.iter().zip(&dst).enumerate() {
+        let (x, y, u, v) = (*x as f64, *y as f64, *u as f64, *v as f64);
+        a[2 * i] = [x, y, 1.0, 0.0, 0.0, 0.0, -x * u, -y * u, u];
+        a[2 * i + 1] = [0.0, 0.0, 0.0, x, y, 1.0, -x * v, -y * v, v];
+    }
+    for col in 0..8 {
+        let pivot = (col..8)
+            .max_by(|&i, &j| a[i][col].abs().total_cmp(&a[j][col].abs()))
+            .unwrap_or(col);
+        if a[pivot][col].abs() < 1e-12 {
+            return Err(GeometryError::SingularMatrix);
+        }
+        a.swap(col, pivot);
+        for row in 0..8 {
+            if row != col {
+                let f = a[row][col] / a[col][col];
+                for k in col..9 {
+                    a[row][k] -= f * a[col][k];
+                }
+            }
+        }
+    }
+    let mut h = [1.0f32; 9];
+    for (i, h) in h.iter_mut().take(8).enumerate() {
+        *h = (a[i][8] / a[i][i]) as f32;
+    }
+    Ok(h)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pepecore_array::{ImgData, Shape};
+
+    fn ramp() -> SVec {
+        SVec::new(
+            Shape::new(4, 5, Some(2)),
+            ImgData::from((0..40).map(|v| v as u8 * 5).collect::<Vec<u8>>()),
+        )
+    }
+
+    #[test]
+    fn test_identity_and_shift() {
+        let img = ramp();
+        let interpolations = [
+            Interpolation::Nearest,
+            Interpolation::Bilinear,
+            Interpolation::Bicubic,
+            Interpolation::Lanczos3,
+        ];
+        for interpolation in interpolations {
+            let same = warp_affine(&img, [1.0, 0.0, 0.0, 0.0, 1.0, 0.0], (4, 5), interpolation, Border::Replicate).unwrap();
+            assert_eq!(
+                same.get_data::<u8>().unwrap(),
+                img.get_data::<u8>().unwrap(),
+                "{:?}",
+                interpolation
+            );
+        }
+
+        // a full-width shift under wrap lands on the same pixels
+        let wrapped = warp_affine(
+            &img,
+            [1.0, 0.0, 5.0, 0.0, 1.0, 0.0],
+            (4, 5),
+            Interpolation::Bilinear,
+            Border::Wrap,
+        )
+        .unwrap();
+        assert_eq!(wrapped.get_data::<u8>().unwrap(), img.get_data::<u8>().unwrap());
+
+        let down = warp_affine(
+            &img,
+            [1.0, 0.0, 0.0, 0.0, 1.0, 1.0],
+            (4, 5),
+            Interpolation::Nearest,
+            Border::Constant(vec![1.0, 0.0]),
+        )
+        .unwrap();
+        let data = down.get_data::<u8>().unwrap();
+        assert!(data[..10].chunks(2).all(|p| p == [255, 0]));
+        assert_eq!(&data[10..], &img.get_data::<u8>().unwrap()[..30]);
+
+        let singular = [1.0, 2.0, 0.0, 2.0, 4.0, 0.0];
+        assert!(matches!(
+            warp_affine(&img, singular, (4, 5), Interpolation::Nearest, Border::Replicate),
+            Err(GeometryError::SingularMatrix)
+        ));
+    }
+
+    #[test]
+    fn test_perspective() {
+        let img = ramp();
+        let identity = [1.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0];
+        let same = warp_perspective(&img, identity, (4, 5), Interpolation::Bicubic, Border::Reflect101).unwrap();
+        assert_eq!(same.get_data::<u8>().unwrap(), img.get_data::<u8>().unwrap());
+
+        let src = [(0.0, 0.0), (4.0, 0.0), (4.0, 3.0), (0.0, 3.0)];
+        let dst = [(1.0, 0.0), (3.0, 1.0), (3.0, 2.0), (0.0, 3.0)];
+        let h = perspective_transform(src, dst).unwrap();
+        for ((x, y), (u, v)) in src.iter().zip(dst) {
+            let w = h[6] * x + h[7] * y + h[8];
+            assert!(((h[0] * x + h[1] * y + h[2]) / w - u).abs() < 1e-4);
+            assert!(((h[3] * x + h[4] * y + h[5]) / w - v).abs() < 1e-4);
+        }
+        let collinear = [(0.0, 0.0), (1.0, 1.0), (2.0, 2.0), (3.0, 3.0)];
+        assert!(perspective_transform(collinear, dst).is_err());
+    }
+}
@@ -0,0 +1,284 @@
+//! Descreening: estimate the halftone/screentone lattice of a scan and suppress it.
+//!
+//! A dot screen shows up in the spectrum as a lattice of sharp peaks spanned by two orthogonal
+//! fundamentals; a line screen has a single one. The fundamental is the strongest spectral peak
+//! among those that stand out against the mean magnitude at the same radius, which keeps ordinary
+//! image content (whose energy falls off smoothly with frequency) from being mistaken for a screen.
+use crate::enums::{DescreenMode, FreqFilter};
+use crate::ops::svec_ops::frequency::frequency_filter;
+use pepecore_array::SVec;
+use pepecore_array::error::Error;
+use pepecore_array::fft::{FftPlan, Spectrum};
+
+/// Peaks weaker than this multiple of the radial mean are not considered part of a screen.
+const MIN_PROMINENCE: f32 = 8.0;
+
+/// Screen lattice found by [`estimate_screen`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScreenEstimate {
+    /// Distance between neighbouring dots (or lines) in pixels.
+    pub period: f32,
+    /// Direction of the first lattice vector in degrees, `0..90` for dot screens and `0..180`
+    /// for line screens.
+    pub angle: f32,
+    /// Fundamental frequencies `(fy, fx)` in cycles per pixel; two for dot screens, one for
+    /// line screens.
+    pub fundamentals: Vec<(f32, f32)>,
+}
+
+impl ScreenEstimate {
+    /// `dot_size` for [`screentone`](crate::screentone)/[`rotate_screentone`](crate::rotate_screentone)
+    /// producing this screen.
+    ///
+    /// Their dot matrix is a checkerboard of `dot_size` cells, so the dot lattice is the cell
+    /// diagonal (`dot_size * √2`) turned by 45°.
+    pub fn dot_size(&self) -> usize {
+        (self.period / std::f32::consts::SQRT_2).round().max(1.0) as usize
+    }
+}
+
+/// Average of the colour channels (alpha is ignored) as a single-channel `f32` image.
+fn luminance(img: &SVec) -> Result<SVec, Error> {
+    let mut img = img.clone();
+    img.as_f32();
+    let c = img.shape.get_channels().unwrap_or(1);
+    let colors = if c == 2 || c == 4 { c - 1 } else { c };
+    if c == 1 {
+        return Ok(img);
+    }
+    let mut planes = img.split_channels();
+    planes.truncate(colors);
+    let mut gray = planes.pop().ok_or(Error::NoChannelsError)?;
+    let out = gray.get_data_mut::<f32>()?;
+    for plane in &planes {
+        for (o, v) in out.iter_mut().zip(plane.get_data::<f32>()?) {
+            *o += v;
+        }
+    }
+    for o in out.iter_mut() {
+        *o /= colors as f32;
+    }
+    Ok(gray)
+}
+
+/// Mean magnitude per integer radius bin (radius measured in bins of the shorter side).
+fn radial_mean(spectrum: &Spectrum, bins: usize) -> Vec<f32> {
+    let (h, w) = (spectrum.height(), spectrum.width());
+    let n = h.min(w) as f32;
+    let mut sum = vec![0.0f32; bins + 1];
+    let mut count = vec![0u32; bins + 1];
+    for y in 0..h {
+        for x in 0..w {
+            let (fy, fx) = spectrum.frequency(y, x);
+            let r = ((fy * fy + fx * fx).sqrt() * n).round() as usize;
+            if r <= bins {
+                sum[r] += spectrum.data()[y * w + x].norm();
+                count[r] += 1;
+            }
+        }
+    }
+    sum.iter()
+        .zip(&count)
+        .map(|(s, &c)| if c > 0 { s / c as f32 } else { 0.0 })
+        .collect()
+}
+
+/// Strongest local maximum that stands out at least `min_score` times above its radial mean,
+/// within `r_min..=0.5` cycles per pixel.
+///
+/// Only the half-plane `fx > 0 || (fx == 0 && fy > 0)` is searched, the spectrum of a real
+/// image is symmetric. With `near`, only bins within `tolerance` of that frequency are searched.
+fn find_peak(
+    spectrum: &Spectrum,
+    radial: &[f32],
+    r_min: f32,
+    min_score: f32,
+    near: Option<((f32, f32), f32)>,
+) -> Option<(f32, f32)> {
+    let (h, w) = (spectrum.height(), spectrum.width());
+    let n = h.min(w) as f32;
+    let data = spectrum.data();
+    let mut best: Option<((f32, f32), f32)> = None;
+    for y in 0..h {
+        for x in 0..w {
+            let (fy, fx) = spectrum.frequency(y, x);
+            if fx < 0.0 || (fx == 0.0 && fy <= 0.0) {
+                continue;
+            }
+            let r = (fy * fy + fx * fx).sqrt();
+            if r < r_min || r > 0.5 {
+                continue;
+            }
+            if let Some(((ny, nx), tol)) = near {
+                // the expected peak may sit in either half-plane
+                let d = ((fy - ny).powi(2) + (fx - nx).powi(2)).min((fy + ny).powi(2) + (fx + nx).powi(2));
+                if d > tol * tol {
+                    continue;
+                }
+            }
+            let m = data[y * w + x].norm();
+            let is_max = (0..3).all(|dy| (0..3).all(|dx| data[((y + h + dy - 1) % h) * w + (x + w + dx - 1) % w].norm() <= m));
+            let mean = radial[((r * n).round() as usize).min(radial.len() - 1)];
+            if !is_max || m < min_score * mean {
+                continue;
+            }
+            if best.is_none_or(|(_, b)| m > b) {
+                best = Some(((fy, fx), m));
+            }
+        }
+    }
+    best.map(|(f, _)| f)
+}
+
+/// Multiply `img` by a separable Hann window to keep the image borders out of the spectrum.
+fn hann_window(img: &mut SVec) -> Result<(), Error> {
+    let (h, w, _) = img.shape();
+    let window = |i: usize, n: usize| 0.5 - 0.5 * (2.0 * std::f32::consts::PI * i as f32 / n as f32).cos();
+    let wx = (0..w).map(|x| window(x, w)).collect::<Vec<_>>();
+    let data = img.get_data_mut::<f32>()?;
+    for y in 0..h {
+        let wy = window(y, h);
+        for (v, wx) in data[y * w..(y + 1) * w].iter_mut().zip(&wx) {
+            *v *= wy * wx;
+        }
+    }
+    Ok(())
+}
+
+/// Estimate the screen lattice of `img`, or `None` when no periodic screen stands out.
+pub fn estimate_screen(img: &SVec) -> Result<Option<ScreenEstimate>, Error> {
+    let mut gray = luminance(img)?;
+    hann_window(&mut gray)?;
+    let spectrum = FftPlan::new().fft2_channel(&gray, 0)?;
+    let n = spectrum.height().min(spectrum.width());
+    // periods longer than 64 px (or a quarter of the image) are treated as content
+    let r_min = (4.0 / n as f32).max(1.0 / 64.0);
+    let radial = radial_mean(&spectrum, n);
+
+    let Some((fy, fx)) = find_peak(&spectrum, &radial, r_min, MIN_PROMINENCE, None) else {
+        return Ok(None);
+    };
+    let f0 = (fy * fy + fx * fx).sqrt();
+    let mut fundamentals = vec![(fy, fx)];
+    // a dot screen has a second, orthogonal fundamental of the same length
+    let rotated = (fx, -fy);
+    if let Some(second) = find_peak(&spectrum, &radial, r_min, MIN_PROMINENCE / 2.0, Some((rotated, f0 * 0.15))) {
+        fundamentals.push(second);
+    }
+    let mut angle = fy.atan2(fx).to_degrees();
+    let wrap = if fundamentals.len() == 2 { 90.0 } else { 180.0 };
+    angle = angle.rem_euclid(wrap);
+    Ok(Some(ScreenEstimate {
+        period: 1.0 / f0,
+        angle,
+        fundamentals,
+    }))
+}
+
+/// Lattice points `m * f1 + n * f2` inside the Nyquist square, one of each mirrored pair.
+fn lattice_points(fundamentals: &[(f32, f32)]) -> Vec<(f32, f32)> {
+    let (f1, f2) = (fundamentals[0], fundamentals.get(1).copied().unwrap_or((0.0, 0.0)));
+    let f0 = (f1.0 * f1.0 + f1.1 * f1.1).sqrt();
+    let k = (0.5 / f0).ceil() as i32 + 1;
+    let mut points = Vec::new();
+    for m in 0..=k {
+        let n_range = if fundamentals.len() == 2 { -k..=k } else { 0..=0 };
+        for n in n_range {
+            if m == 0 && n <= 0 {
+                continue;
+            }
+            let p = (m as f32 * f1.0 + n as f32 * f2.0, m as f32 * f1.1 + n as f32 * f2.1);
+            if p.0.abs() <= 0.5 && p.1.abs() <= 0.5 {
+                points.push(p);
+            }
+        }
+    }
+    points
+}
+
+/// Remove the halftone/screentone pattern from `img` in place.
+///
+/// Returns the detected screen, or `None` (leaving `img` untouched) when none was found.
+/// The pixel type and layout of `img` are kept.
+pub fn descreen(img: &mut SVec, mode: DescreenMode) -> Result<Option<ScreenEstimate>, Error> {
+    let Some(screen) = estimate_screen(img)? else {
+        return Ok(None);
+    };
+    let f0 = 1.0 / screen.period;
+    let filter = match mode {
+        DescreenMode::Smooth => FreqFilter::LowPass(f0 / 3.0),
+        DescreenMode::PreserveLines => FreqFilter::Notch {
+            points: lattice_points(&screen.fundamentals),
+            radius: f0 * 0.12,
+        },
+    };
+    frequency_filter(img, &filter)?;
+    Ok(Some(screen))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::enums::DotType;
+    use crate::ops::svec_ops::halftone::screentone::{rotate_screentone, screentone};
+    use pepecore_array::{ImgData, Shape};
+    use std::f32::consts::PI;
+
+    fn flat(h: usize, w: usize, v: f32) -> SVec {
+        SVec::new(Shape::new(h, w, None), ImgData::from(vec![v; h * w]))
+    }
+
+    #[test]
+    fn estimates_screentone_lattice() {
+        let mut img = flat(128, 128, 0.5);
+        screentone(&mut img, 6, &DotType::CIRCLE).unwrap();
+        let screen = estimate_screen(&img).unwrap().expect("screen");
+        assert_eq!(screen.fundamentals.len(), 2);
+        assert_eq!(screen.dot_size(), 6);
+        assert!((screen.angle - 45.0).abs() < 3.0, "{screen:?}");
+
+        let mut rotated = flat(128, 128, 0.5);
+        rotate_screentone(&mut rotated, 6, 15.0, &DotType::CIRCLE).unwrap();
+        let screen = estimate_screen(&rotated).unwrap().expect("screen");
+        assert_eq!(screen.dot_size(), 6);
+        assert!(
+            (screen.angle - 30.0).abs() < 3.0 || (screen.angle - 60.0).abs() < 3.0,
+            "{screen:?}"
+        );
+    }
+
+    #[test]
+    fn no_screen_on_smooth_image() {
+        let data = (0..64 * 64)
+            .map(|i| {
+                let (y, x) = ((i / 64) as f32 - 32.0, (i % 64) as f32 - 20.0);
+                (-(x * x + y * y) / 200.0).exp()
+            })
+            .collect::<Vec<_>>();
+        let img = SVec::new(Shape::new(64, 64, None), ImgData::from(data));
+        assert!(estimate_screen(&img).unwrap().is_none());
+        let mut copy = img.clone();
+        assert!(descreen(&mut copy, DescreenMode::Smooth).unwrap().is_none());
+        assert_eq!(copy.get_data::<f32>().unwrap(), img.get_data::<f32>().unwrap());
+    }
+
+    #[test]
+    fn descreen_flattens_dot_pattern() {
+        let (h, w) = (96, 96);
+        let data = (0..h * w)
+            .map(|i| {
+                let (y, x) = ((i / w) as f32, (i % w) as f32);
+                0.5 + 0.2 * (2.0 * PI * x / 6.0).cos() * (2.0 * PI * y / 6.0).cos()
+            })
+            .collect::<Vec<_>>();
+        let src = SVec::new(Shape::new(h, w, None), ImgData::from(data));
+        for mode in [DescreenMode::Smooth, DescreenMode::PreserveLines] {
+            let mut img = src.clone();
+            let screen = descreen(&mut img, mode).unwrap().expect("screen");
+            assert!((screen.period - 6.0 / std::f32::consts::SQRT_2).abs() < 0.3, "{screen:?}");
+            for v in img.get_data::<f32>().unwrap() {
+                assert!((v - 0.5).abs() < 0.02, "{mode:?}: {v}");
+            }
+        }
+    }
+}
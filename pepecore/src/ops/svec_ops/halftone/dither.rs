@@ -0,0 +1,263 @@
+//! Error-diffusion dithering to a number of levels or to a fixed palette.
+use crate::enums::DitherKernel;
+use crate::errors::HalftoneError;
+use crate::ops::svec_ops::halftone::utils::{from_unit, to_unit};
+use pepecore_array::{Pixel, PixelType, SVec, f16};
+
+/// `(dx, dy, weight)` taps to the right of / below the current pixel and their divisor.
+fn kernel_taps(kernel: DitherKernel) -> (&'static [(isize, usize, f32)], f32) {
+    match kernel {
+        DitherKernel::FloydSteinberg => (&[(1, 0, 7.0), (-1, 1, 3.0), (0, 1, 5.0), (1, 1, 1.0)], 16.0),
+        // Atkinson spreads only 6/8 of the error, which keeps highlights and shadows clean
+        DitherKernel::Atkinson => (
+            &[(1, 0, 1.0), (2, 0, 1.0), (-1, 1, 1.0), (0, 1, 1.0), (1, 1, 1.0), (0, 2, 1.0)],
+            8.0,
+        ),
+        DitherKernel::JarvisJudiceNinke => (
+            &[
+                (1, 0, 7.0),
+                (2, 0, 5.0),
+                (-2, 1, 3.0),
+                (-1, 1, 5.0),
+                (0, 1, 7.0),
+                (1, 1, 5.0),
+                (2, 1, 3.0),
+                (-2, 2, 1.0),
+                (-1, 2, 3.0),
+                (0, 2, 5.0),
+                (1, 2, 3.0),
+                (2, 2, 1.0),
+            ],
+            48.0,
+        ),
+        DitherKernel::Stucki => (
+            &[
+                (1, 0, 8.0),
+                (2, 0, 4.0),
+                (-2, 1, 2.0),
+                (-1, 1, 4.0),
+                (0, 1, 8.0),
+                (1, 1, 4.0),
+                (2, 1, 2.0),
+                (-2, 2, 1.0),
+                (-1, 2, 2.0),
+                (0, 2, 4.0),
+                (1, 2, 2.0),
+                (2, 2, 1.0),
+            ],
+            42.0,
+        ),
+        DitherKernel::Sierra => (
+            &[
+                (1, 0, 5.0),
+                (2, 0, 3.0),
+                (-2, 1, 2.0),
+                (-1, 1, 4.0),
+                (0, 1, 5.0),
+                (1, 1, 4.0),
+                (2, 1, 2.0),
+                (-1, 2, 2.0),
+                (0, 2, 3.0),
+                (1, 2, 2.0),
+            ],
+            32.0,
+        ),
+        DitherKernel::TwoRowSierra => (
+            &[
+                (1, 0, 4.0),
+                (2, 0, 3.0),
+                (-2, 1, 1.0),
+                (-1, 1, 2.0),
+                (0, 1, 3.0),
+                (1, 1, 2.0),
+                (2, 1, 1.0),
+            ],
+            16.0,
+        ),
+        DitherKernel::SierraLite => (&[(1, 0, 2.0), (-1, 1, 1.0), (0, 1, 1.0)], 4.0),
+    }
+}
+
+/// Diffuse quantization error over an interleaved `h x w x c` buffer of unit-range samples.
+///
+/// `quantize` maps the error-corrected pixel (all `c` samples) to its output in place.
+fn diffuse(
+    data: &mut [f32],
+    h: usize,
+    w: usize,
+    c: usize,
+    kernel: DitherKernel,
+    serpentine: bool,
+    quantize: impl Fn(&mut [f32]),
+) {
+    let (taps, divisor) = kernel_taps(kernel);
+    let mut wanted = vec![0.0f32; c];
+    for y in 0..h {
+        let reverse = serpentine && y % 2 == 1;
+        for i in 0..w {
+            let x = if reverse { w - 1 - i } else { i };
+            let base = (y * w + x) * c;
+            wanted.copy_from_slice(&data[base..base + c]);
+            quantize(&mut data[base..base + c]);
+            for ch in 0..c {
+                let err = (wanted[ch] - data[base + ch]) / divisor;
+                if err == 0.0 {
+                    continue;
+                }
+                for &(dx, dy, weight) in taps {
+                    // mirror the kernel on right-to-left rows
+                    let dx = if reverse { -dx } else { dx };
+                    let (nx, ny) = (x as isize + dx, y + dy);
+                    if nx < 0 || nx >= w as isize || ny >= h {
+                        continue;
+                    }
+                    data[(ny * w + nx as usize) * c + ch] += err * weight;
+                }
+            }
+        }
+    }
+}
+
+fn dither_t<T: Pixel>(img: &mut SVec, kernel: DitherKernel, serpentine: bool, quantize: impl Fn(&mut [f32])) {
+    let (h, w, c) = img.shape();
+    let c = c.unwrap_or(1);
+    let data = img.get_data_mut::<T>().unwrap();
+    let mut unit: Vec<f32> = data.iter().map(|&v| to_unit(v)).collect();
+    diffuse(&mut unit, h, w, c, kernel, serpentine, quantize);
+    for (dst, &v) in data.iter_mut().zip(&unit) {
+        *dst = from_unit(v);
+    }
+}
+
+fn dispatch(img: &mut SVec, kernel: DitherKernel, serpentine: bool, quantize: impl Fn(&mut [f32])) {
+    img.with_interleaved(|img| match img.pixel_type() {
+        PixelType::F32 => dither_t::<f32>(img, kernel, serpentine, quantize),
+        PixelType::U8 => dither_t::<u8>(img, kernel, serpentine, quantize),
+        PixelType::U16 => dither_t::<u16>(img, kernel, serpentine, quantize),
+        PixelType::I16 => dither_t::<i16>(img, kernel, serpentine, quantize),
+        PixelType::U32 => dither_t::<u32>(img, kernel, serpentine, quantize),
+        PixelType::F16 => dither_t::<f16>(img, kernel, serpentine, quantize),
+        PixelType::F64 => dither_t::<f64>(img, kernel, serpentine, quantize),
+    })
+}
+
+/// Dither every channel to `levels` evenly spaced values, e.g. `2` for 1-bit output or
+/// `1 << bits` for a given bit depth.
+///
+/// With `serpentine`, odd rows are scanned right to left, which breaks up the directional
+/// "worm" artifacts of plain raster order.
+///
+/// # Example
+/// ```rust
+/// use pepecore::enums::DitherKernel;
+/// use pepecore::ops::svec_ops::halftone::dither::dither;
+/// use pepecore_array::{ImgData, SVec, Shape};
+///
+/// let mut img = SVec::new(Shape::new(4, 4, None), ImgData::from(vec![128u8; 16]));
+/// dither(&mut img, 2, DitherKernel::FloydSteinberg, true).unwrap();
+/// assert!(img.get_data::<u8>().unwrap().iter().all(|&v| v == 0 || v == 255));
+/// ```
+pub fn dither(img: &mut SVec, levels: usize, kernel: DitherKernel, serpentine: bool) -> Result<(), HalftoneError> {
+    if levels < 2 {
+        return Err(HalftoneError::InvalidLevels(levels));
+    }
+    let steps = (levels - 1) as f32;
+    dispatch(img, kernel, serpentine, |px| {
+        for v in px {
+            *v = (v.clamp(0.0, 1.0) * steps).round() / steps;
+        }
+    });
+    Ok(())
+}
+
+/// Dither an RGB image to the nearest colours of `palette`.
+///
+/// `palette` holds RGB triplets on the `0..=255` scale, as returned by
+/// [`svec_to_palette`](crate::ops::svec_ops::palette_gen::gen_palette::svec_to_palette).
+pub fn dither_palette(img: &mut SVec, palette: &[f32], kernel: DitherKernel, serpentine: bool) -> Result<(), HalftoneError> {
+    let channels = img.shape.get_channels().unwrap_or(1);
+    if channels != 3 || palette.is_empty() || palette.len() % 3 != 0 {
+        return Err(HalftoneError::PaletteMismatch {
+            channels,
+            len: palette.len(),
+        });
+    }
+    let colors: Vec<[f32; 3]> = palette
+        .chunks_exact(3)
+        .map(|p| [p[0] / 255.0, p[1] / 255.0, p[2] / 255.0])
+        .collect();
+    dispatch(img, kernel, serpentine, |px| {
+        let nearest = colors
+            .iter()
+            .min_by(|a, b| {
+                let da: f32 = (0..3).map(|i| (a[i] - px[i]).powi(2)).sum();
+                let db: f32 = (0..3).map(|i| (b[i] - px[i]).powi(2)).sum();
+                da.total_cmp(&db)
+            })
+            .unwrap();
+        px.copy_from_slice(nearest);
+    });
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pepecore_array::{ImgData, Shape};
+
+    #[test]
+    fn preserves_mean_tone() {
+        let kernels = [
+            DitherKernel::FloydSteinberg,
+            DitherKernel::JarvisJudiceNinke,
+            DitherKernel::Stucki,
+            DitherKernel::Sierra,
+            DitherKernel::TwoRowSierra,
+            DitherKernel::SierraLite,
+        ];
+        for kernel in kernels {
+            for serpentine in [false, true] {
+                let mut img = SVec::new(Shape::new(32, 32, None), ImgData::from(vec![0.25f32; 1024]));
+                dither(&mut img, 2, kernel, serpentine).unwrap();
+                let data = img.get_data::<f32>().unwrap();
+                assert!(data.iter().all(|&v| v == 0.0 || v == 1.0));
+                let mean = data.iter().sum::<f32>() / 1024.0;
+                assert!((mean - 0.25).abs() < 0.02, "{kernel:?}: {mean}");
+            }
+        }
+    }
+
+    #[test]
+    fn levels_and_types() {
+        let mut img = SVec::new(Shape::new(8, 8, Some(3)), ImgData::from(vec![1000u16; 192]));
+        dither(&mut img, 4, DitherKernel::Atkinson, false).unwrap();
+        let allowed = [0u16, 21845, 43690, 65535];
+        assert!(img.get_data::<u16>().unwrap().iter().all(|v| allowed.contains(v)));
+        assert!(matches!(
+            dither(&mut img, 1, DitherKernel::Atkinson, false),
+            Err(HalftoneError::InvalidLevels(1))
+        ));
+    }
+
+    #[test]
+    fn signed_levels_span_full_range() {
+        let mut img = SVec::new(Shape::new(8, 8, None), ImgData::from(vec![-16384i16; 64]));
+        dither(&mut img, 2, DitherKernel::FloydSteinberg, false).unwrap();
+        let data = img.get_data::<i16>().unwrap();
+        assert!(data.iter().all(|&v| v == -i16::MAX || v == i16::MAX));
+        assert!(data.iter().filter(|&&v| v < 0).count() > 32);
+    }
+
+    #[test]
+    fn palette_dither_uses_palette_colors() {
+        let palette = [0.0, 0.0, 0.0, 255.0, 0.0, 0.0, 255.0, 255.0, 255.0];
+        let data: Vec<u8> = (0..16 * 16).flat_map(|i| [(i % 256) as u8, 40, 40]).collect();
+        let mut img = SVec::new(Shape::new(16, 16, Some(3)), ImgData::from(data));
+        dither_palette(&mut img, &palette, DitherKernel::FloydSteinberg, true).unwrap();
+        for px in img.get_data::<u8>().unwrap().chunks(3) {
+            assert!(matches!(px, [0, 0, 0] | [255, 0, 0] | [255, 255, 255]), "{px:?}");
+        }
+        let mut gray = SVec::new(Shape::new(2, 2, None), ImgData::from(vec![0u8; 4]));
+        assert!(dither_palette(&mut gray, &palette, DitherKernel::FloydSteinberg, true).is_err());
+    }
+}
@@ -1,13 +1,14 @@
 use crate::enums::DotType;
+use pepecore_array::error::Error;
 use pepecore_array::{ImgData, SVec, Shape};
 
-fn create_mask(dot: &SVec, dot_inv: &SVec) -> SVec {
+fn create_mask(dot: &SVec, dot_inv: &SVec) -> Result<SVec, Error> {
     let (h, w, c) = dot.shape();
     let (h2, w2) = (h * 2, w * 2);
-    let mut new_dot = SVec::new(Shape::new(h2, w2, c), ImgData::F32(vec![0.0; h2 * w2]));
-    let new_dot_data = new_dot.get_data_mut::<f32>().unwrap();
-    let dot_data = dot.get_data::<f32>().unwrap();
-    let dot_inv_data = dot_inv.get_data::<f32>().unwrap();
+    let mut new_dot = SVec::try_new(Shape::try_new(h2, w2, c)?, ImgData::F32(vec![0.0; h2 * w2]))?;
+    let new_dot_data = new_dot.get_data_mut::<f32>()?;
+    let dot_data = dot.get_data::<f32>()?;
+    let dot_inv_data = dot_inv.get_data::<f32>()?;
 
     for y in 0..h2 {
         let (y_base, y_src) = if y < h { (0, y) } else { (h, y - h) };
@@ -20,7 +21,7 @@ fn create_mask(dot: &SVec, dot_inv: &SVec) -> SVec {
         }
     }
 
-    new_dot
+    Ok(new_dot)
 }
 const X: f32 = 0.1;
 const Y: f32 = 0.15;
@@ -104,7 +105,7 @@ fn circle_cordinates(dot_size: usize) -> Vec<(usize, usize, f32)> {
         .collect();
     coordinates
 }
-pub fn dot_create(dot_size: usize, dot_type: &DotType) -> SVec {
+pub fn dot_create(dot_size: usize, dot_type: &DotType) -> Result<SVec, Error> {
     let mut mut_dot = vec![0.0; dot_size * dot_size];
     let mut mut_inv_dot = vec![0.0; dot_size * dot_size];
     let step = (1.0 - 0.5) / ((dot_size as f32).powi(2) - 1.0);
@@ -123,7 +124,7 @@ pub fn dot_create(dot_size: usize, dot_type: &DotType) -> SVec {
         mut_inv_dot[i * dot_size + j] = 0.503 - value;
     }
 
-    let dot = SVec::new(Shape::new(dot_size, dot_size, None), ImgData::F32(mut_dot));
-    let inv_dot = SVec::new(Shape::new(dot_size, dot_size, None), ImgData::F32(mut_inv_dot));
+    let dot = SVec::try_new(Shape::try_new(dot_size, dot_size, None)?, ImgData::F32(mut_dot))?;
+    let inv_dot = SVec::try_new(Shape::try_new(dot_size, dot_size, None)?, ImgData::F32(mut_inv_dot))?;
     create_mask(&dot, &inv_dot)
 }
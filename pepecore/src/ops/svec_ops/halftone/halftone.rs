@@ -25,10 +25,10 @@ use crate::enums::DotType;
 use crate::errors::HalftoneError;
 use crate::global_params::rayon_get_mode;
 use crate::ops::svec_ops::halftone::dot::dot_create;
-use crate::ops::svec_ops::halftone::utils::{HalftonePixel, compute_cos_sin, rotate_pixel_coordinates, wrap_index};
+use crate::ops::svec_ops::halftone::utils::{compute_cos_sin, prepare_dot_matrix, rotate_pixel_coordinates, wrap_index};
 use crate::ops::svec_ops::resize::fir::ResizeSVec;
 use fast_image_resize::ResizeAlg;
-use pepecore_array::{PixelType, SVec};
+use pepecore_array::{Pixel, PixelType, SVec, f16};
 use rayon::prelude::*;
 use std::fmt::Debug;
 
@@ -41,7 +41,8 @@ use std::fmt::Debug;
 /// # Parameters
 ///
 /// - `img`: mutable reference to the `SVec` image.
-/// - `dot_sizes`: array of dot sizes per channel (length must match channel count).
+/// - `dot_sizes`: array of dot sizes per channel (length must match channel count, an image
+///   without a channel axis counts as one channel).
 /// - `dot_type`: array of `DotType` specifying dot shape per channel.
 ///
 /// # Errors
@@ -52,12 +53,12 @@ use std::fmt::Debug;
 ///
 fn apply_halftone<T>(img: &mut SVec, dot_sizes: &[usize], dot_type: &[DotType]) -> Result<(), HalftoneError>
 where
-    T: HalftonePixel + Debug,
+    T: Pixel + Debug,
 {
     // Retrieve image shape and data buffer
     let (height, width, channels_opt) = img.shape();
     let data = img.get_data_mut::<T>()?;
-    let channels = channels_opt.ok_or(pepecore_array::error::Error::NoChannelsError)?;
+    let channels = channels_opt.unwrap_or(1);
 
     // Ensure that dot_sizes matches number of channels
     if dot_sizes.len() < channels || dot_type.len() < channels {
@@ -74,9 +75,9 @@ where
         let bias = size / 2;
         let doubled = size * 2;
         let matrix = if size > 0 {
-            let kernel = dot_create(size, &dot_type[index]);
+            let kernel = dot_create(size, &dot_type[index])?;
             let kernel_data = kernel.get_data::<f32>()?;
-            T::prepare_dot_matrix(kernel_data)
+            prepare_dot_matrix::<T>(kernel_data)
         } else {
             Vec::new()
         };
@@ -118,12 +119,12 @@ fn apply_ssaa_halftone<T>(
     disable_auto_dot: bool,
 ) -> Result<(), HalftoneError>
 where
-    T: HalftonePixel + Debug + std::marker::Send + std::marker::Sync,
+    T: Pixel + Debug + std::marker::Send + std::marker::Sync,
 {
     // Retrieve image shape and data buffer
     let (height, width, channels_opt) = img.shape();
     let data = img.get_mut_vec::<T>()?;
-    let channels = channels_opt.ok_or(pepecore_array::error::Error::NoChannelsError)?;
+    let channels = channels_opt.unwrap_or(1);
 
     // Ensure that dot_sizes matches number of channels
     if dot_sizes.len() < channels || dot_type.len() < channels {
@@ -136,13 +137,17 @@ where
     let mut dot_matrices = Vec::with_capacity(channels);
 
     for index in 0..channels {
-        let size = if disable_auto_dot {dot_sizes[index]} else{(dot_sizes[index] as f32 * scale) as usize};
+        let size = if disable_auto_dot {
+            dot_sizes[index]
+        } else {
+            (dot_sizes[index] as f32 * scale) as usize
+        };
         let bias = size / 2;
         let doubled = size * 2;
         let matrix = if size > 0 {
-            let kernel = dot_create(size, &dot_type[index]);
+            let kernel = dot_create(size, &dot_type[index])?;
             let kernel_data = kernel.get_data::<f32>()?;
-            T::prepare_dot_matrix(kernel_data)
+            prepare_dot_matrix::<T>(kernel_data)
         } else {
             Vec::new()
         };
@@ -253,12 +258,12 @@ fn apply_rotate_halftone<T>(
     dot_type: &[DotType],
 ) -> Result<(), HalftoneError>
 where
-    T: HalftonePixel + Debug,
+    T: Pixel + Debug,
 {
     // Retrieve image shape and data buffer
     let (height, width, channels_opt) = img.shape();
     let data = img.get_data_mut::<T>()?;
-    let channels = channels_opt.ok_or(pepecore_array::error::Error::NoChannelsError)?;
+    let channels = channels_opt.unwrap_or(1);
 
     // Ensure dot_sizes and angles arrays match number of channels
     if dot_sizes.len() < channels || angles.len() < channels || dot_type.len() < channels {
@@ -276,9 +281,9 @@ where
         let size = dot_sizes[i];
         let doubled = size * 2;
         let matrix = if size > 0 {
-            let kernel = dot_create(size, &dot_type[i]);
+            let kernel = dot_create(size, &dot_type[i])?;
             let kernel_data = kernel.get_data::<f32>()?;
-            T::prepare_dot_matrix(kernel_data)
+            prepare_dot_matrix::<T>(kernel_data)
         } else {
             Vec::new()
         };
@@ -330,12 +335,12 @@ fn apply_ssaa_rotate_halftone<T>(
     disable_auto_dot: bool,
 ) -> Result<(), HalftoneError>
 where
-    T: HalftonePixel + Debug + std::marker::Send + std::marker::Sync,
+    T: Pixel + Debug + std::marker::Send + std::marker::Sync,
 {
     // Retrieve image shape and data buffer
     let (height, width, channels_opt) = img.shape();
     let data = img.get_mut_vec::<T>()?;
-    let channels = channels_opt.ok_or(pepecore_array::error::Error::NoChannelsError)?;
+    let channels = channels_opt.unwrap_or(1);
     let (scale_height, scale_width) = ((height as f32 * scale) as usize, (width as f32 * scale) as usize);
     // Ensure dot_sizes and angles arrays match number of channels
     if dot_sizes.len() < channels || angles.len() < channels || dot_type.len() < channels {
@@ -350,12 +355,16 @@ where
 
     // Precompute matrices and rotation sin/cos for each channel
     for i in 0..channels {
-        let size = if disable_auto_dot {dot_sizes[i]} else{(dot_sizes[i] as f32 * scale) as usize};
+        let size = if disable_auto_dot {
+            dot_sizes[i]
+        } else {
+            (dot_sizes[i] as f32 * scale) as usize
+        };
         let doubled = size * 2;
         let matrix = if size > 0 {
-            let kernel = dot_create(size, &dot_type[i]);
+            let kernel = dot_create(size, &dot_type[i])?;
             let kernel_data = kernel.get_data::<f32>()?;
-            T::prepare_dot_matrix(kernel_data)
+            prepare_dot_matrix::<T>(kernel_data)
         } else {
             Vec::new()
         };
@@ -442,14 +451,21 @@ where
 }
 /// Apply non-rotated halftone to `img` dispatching by pixel type.
 ///
+/// Single-channel planes such as those from [`SVec::split_channels`] are accepted too, so
+/// channels can be screened one by one and put back with [`SVec::merge_channels`].
+///
 /// # See
 /// - `apply_halftone` for detailed behavior.
 pub fn halftone(img: &mut SVec, dot_sizes: &[usize], dot_type: &[DotType]) -> Result<(), HalftoneError> {
-    match img.pixel_type() {
+    img.with_interleaved(|img| match img.pixel_type() {
         PixelType::F32 => apply_halftone::<f32>(img, dot_sizes, dot_type),
         PixelType::U8 => apply_halftone::<u8>(img, dot_sizes, dot_type),
         PixelType::U16 => apply_halftone::<u16>(img, dot_sizes, dot_type),
-    }
+        PixelType::I16 => apply_halftone::<i16>(img, dot_sizes, dot_type),
+        PixelType::U32 => apply_halftone::<u32>(img, dot_sizes, dot_type),
+        PixelType::F16 => apply_halftone::<f16>(img, dot_sizes, dot_type),
+        PixelType::F64 => apply_halftone::<f64>(img, dot_sizes, dot_type),
+    })
 }
 pub fn ssaa_halftone(
     img: &mut SVec,
@@ -459,22 +475,30 @@ pub fn ssaa_halftone(
     resize_alg: ResizeAlg,
     disable_auto_dot: bool,
 ) -> Result<(), HalftoneError> {
-    match img.pixel_type() {
+    img.with_interleaved(|img| match img.pixel_type() {
         PixelType::F32 => apply_ssaa_halftone::<f32>(img, dot_sizes, dot_type, scale, resize_alg, disable_auto_dot),
         PixelType::U8 => apply_ssaa_halftone::<u8>(img, dot_sizes, dot_type, scale, resize_alg, disable_auto_dot),
         PixelType::U16 => apply_ssaa_halftone::<u16>(img, dot_sizes, dot_type, scale, resize_alg, disable_auto_dot),
-    }
+        PixelType::I16 => apply_ssaa_halftone::<i16>(img, dot_sizes, dot_type, scale, resize_alg, disable_auto_dot),
+        PixelType::U32 => apply_ssaa_halftone::<u32>(img, dot_sizes, dot_type, scale, resize_alg, disable_auto_dot),
+        PixelType::F16 => apply_ssaa_halftone::<f16>(img, dot_sizes, dot_type, scale, resize_alg, disable_auto_dot),
+        PixelType::F64 => apply_ssaa_halftone::<f64>(img, dot_sizes, dot_type, scale, resize_alg, disable_auto_dot),
+    })
 }
 /// Apply rotated halftone to `img` dispatching by pixel type.
 ///
 /// # See
 /// - `apply_rotate_halftone` for detailed behavior.
 pub fn rotate_halftone(img: &mut SVec, dot_sizes: &[usize], angles: &[f32], dot_type: &[DotType]) -> Result<(), HalftoneError> {
-    match img.pixel_type() {
+    img.with_interleaved(|img| match img.pixel_type() {
         PixelType::F32 => apply_rotate_halftone::<f32>(img, dot_sizes, angles, dot_type),
         PixelType::U8 => apply_rotate_halftone::<u8>(img, dot_sizes, angles, dot_type),
         PixelType::U16 => apply_rotate_halftone::<u16>(img, dot_sizes, angles, dot_type),
-    }
+        PixelType::I16 => apply_rotate_halftone::<i16>(img, dot_sizes, angles, dot_type),
+        PixelType::U32 => apply_rotate_halftone::<u32>(img, dot_sizes, angles, dot_type),
+        PixelType::F16 => apply_rotate_halftone::<f16>(img, dot_sizes, angles, dot_type),
+        PixelType::F64 => apply_rotate_halftone::<f64>(img, dot_sizes, angles, dot_type),
+    })
 }
 pub fn ssaa_rotate_halftone(
     img: &mut SVec,
@@ -485,9 +509,47 @@ pub fn ssaa_rotate_halftone(
     resize_alg: ResizeAlg,
     disable_auto_dot: bool,
 ) -> Result<(), HalftoneError> {
-    match img.pixel_type() {
-        PixelType::F32 => apply_ssaa_rotate_halftone::<f32>(img, dot_sizes, angles, dot_type, scale, resize_alg, disable_auto_dot),
+    img.with_interleaved(|img| match img.pixel_type() {
+        PixelType::F32 => {
+            apply_ssaa_rotate_halftone::<f32>(img, dot_sizes, angles, dot_type, scale, resize_alg, disable_auto_dot)
+        }
         PixelType::U8 => apply_ssaa_rotate_halftone::<u8>(img, dot_sizes, angles, dot_type, scale, resize_alg, disable_auto_dot),
-        PixelType::U16 => apply_ssaa_rotate_halftone::<u16>(img, dot_sizes, angles, dot_type, scale, resize_alg, disable_auto_dot),
+        PixelType::U16 => {
+            apply_ssaa_rotate_halftone::<u16>(img, dot_sizes, angles, dot_type, scale, resize_alg, disable_auto_dot)
+        }
+        PixelType::I16 => {
+            apply_ssaa_rotate_halftone::<i16>(img, dot_sizes, angles, dot_type, scale, resize_alg, disable_auto_dot)
+        }
+        PixelType::U32 => {
+            apply_ssaa_rotate_halftone::<u32>(img, dot_sizes, angles, dot_type, scale, resize_alg, disable_auto_dot)
+        }
+        PixelType::F16 => {
+            apply_ssaa_rotate_halftone::<f16>(img, dot_sizes, angles, dot_type, scale, resize_alg, disable_auto_dot)
+        }
+        PixelType::F64 => {
+            apply_ssaa_rotate_halftone::<f64>(img, dot_sizes, angles, dot_type, scale, resize_alg, disable_auto_dot)
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pepecore_array::{ImgData, Shape};
+
+    #[test]
+    fn per_channel_planes_match_whole_image() {
+        let data: Vec<u8> = (0..16 * 16 * 3).map(|i| (i * 7 % 256) as u8).collect();
+        let mut img = SVec::new(Shape::new(16, 16, Some(3)), ImgData::from(data));
+        let sizes = [3, 4, 5];
+        let types = [DotType::CIRCLE, DotType::CROSS, DotType::LINE];
+
+        let mut planes = img.split_channels();
+        for (plane, (size, dot)) in planes.iter_mut().zip(sizes.iter().zip(&types)) {
+            halftone(plane, &[*size], std::slice::from_ref(dot)).unwrap();
+        }
+        halftone(&mut img, &sizes, &types).unwrap();
+        let merged = SVec::merge_channels(&planes).unwrap();
+        assert_eq!(merged.get_data::<u8>().unwrap(), img.get_data::<u8>().unwrap());
     }
 }
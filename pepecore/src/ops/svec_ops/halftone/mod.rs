@@ -1,3 +1,5 @@
+pub mod descreen;
+pub mod dither;
 pub mod dot;
 pub mod halftone;
 pub mod screentone;
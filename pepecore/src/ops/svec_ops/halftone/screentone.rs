@@ -12,21 +12,22 @@
 //! // Create a grayscale SVec (2x2, u8)
 //! let mut img = SVec::new(Shape::new(2, 2, None), ImgData::U8(vec![50, 200, 150, 100]));
 //! // Apply screentone with dot size 4 and circular dots
-//! screentone(&mut img, 4, &DotType::CIRCLE);
+//! screentone(&mut img, 4, &DotType::CIRCLE).unwrap();
 //!
 //! // Apply rotated screentone at 45 degrees on f32 image
 //! let mut img_f32 = SVec::new(Shape::new(3, 3, None), ImgData::F32(vec![0.2; 9]));
-//! rotate_screentone(&mut img_f32, 5, 45.0, &DotType::ELLIPSE);
+//! rotate_screentone(&mut img_f32, 5, 45.0, &DotType::ELLIPSE).unwrap();
 //! ```
 
 use crate::array::Shape;
 use crate::enums::DotType;
+use crate::errors::HalftoneError;
 use crate::global_params::rayon_get_mode;
 use crate::ops::svec_ops::halftone::dot::dot_create;
-use crate::ops::svec_ops::halftone::utils::{HalftonePixel, compute_cos_sin, rotate_pixel_coordinates, wrap_index};
+use crate::ops::svec_ops::halftone::utils::{compute_cos_sin, prepare_dot_matrix, rotate_pixel_coordinates, wrap_index};
 use crate::ops::svec_ops::resize::fir::ResizeSVec;
 use fast_image_resize::ResizeAlg;
-use pepecore_array::{PixelType, SVec};
+use pepecore_array::{Pixel, PixelType, SVec, SVecViewMut, f16};
 use rayon::iter::ParallelIterator;
 use rayon::prelude::IntoParallelIterator;
 
@@ -39,26 +40,27 @@ use rayon::prelude::IntoParallelIterator;
 ///
 /// # Parameters
 ///
-/// - `img`: Mutable reference to a single-channel `SVec`.
+/// - `img`: Mutable single-channel view.
 /// - `dot_size`: Radius of dot pattern (matrix will be `2*dot_size`).
 /// - `dot_type`: `DotType` enum specifying shape of dots.
-fn apply_screentone<T: HalftonePixel>(img: &mut SVec, dot_size: usize, dot_type: &DotType) {
+fn apply_screentone<T: Pixel>(img: &mut SVecViewMut, dot_size: usize, dot_type: &DotType) -> Result<(), HalftoneError> {
     let (h, w, _) = img.shape();
-    let mut_img = img.get_data_mut::<T>().unwrap();
+    let strides = img.strides();
+    let mut_img = img.get_data_mut::<T>()?;
 
-    let dot_matrix = dot_create(dot_size, dot_type);
-    let dot_matrix_data = dot_matrix.get_data::<f32>().unwrap();
+    let dot_matrix = dot_create(dot_size, dot_type)?;
+    let dot_matrix_data = dot_matrix.get_data::<f32>()?;
     let lx_bias = dot_size / 2;
     let ly_bias = dot_size / 2;
     let dot_size = dot_size * 2;
 
-    let dot_matrix_converted = T::prepare_dot_matrix(dot_matrix_data);
+    let dot_matrix_converted = prepare_dot_matrix::<T>(dot_matrix_data);
 
     for ly in 0..h {
         let ly2 = (ly + ly_bias) % dot_size;
 
         for lx in 0..w {
-            let idx = ly * w + lx;
+            let idx = strides.index(ly, lx, 0);
             let dot_idx = (lx + lx_bias) % dot_size + ly2 * dot_size;
 
             mut_img[idx] = if mut_img[idx] < dot_matrix_converted[dot_idx] {
@@ -68,6 +70,7 @@ fn apply_screentone<T: HalftonePixel>(img: &mut SVec, dot_size: usize, dot_type:
             };
         }
     }
+    Ok(())
 }
 
 /// Apply rotated screentone effect to a single-channel image in-place.
@@ -76,17 +79,23 @@ fn apply_screentone<T: HalftonePixel>(img: &mut SVec, dot_size: usize, dot_type:
 ///
 /// # Parameters
 ///
-/// - `img`: Mutable reference to a single-channel `SVec`.
+/// - `img`: Mutable single-channel view.
 /// - `dot_size`: Radius of dot pattern.
 /// - `angle`: Rotation angle in degrees.
 /// - `dot_type`: `DotType` specifying shape.
-fn apply_rotate_screentone<T: HalftonePixel>(img: &mut SVec, dot_size: usize, angle: f32, dot_type: &DotType) {
+fn apply_rotate_screentone<T: Pixel>(
+    img: &mut SVecViewMut,
+    dot_size: usize,
+    angle: f32,
+    dot_type: &DotType,
+) -> Result<(), HalftoneError> {
     let (h, w, _) = img.shape();
-    let mut_img = img.get_data_mut::<T>().unwrap();
+    let strides = img.strides();
+    let mut_img = img.get_data_mut::<T>()?;
     let cos_sin = compute_cos_sin(angle.to_radians());
-    let dot_matrix = dot_create(dot_size, dot_type);
-    let dot_matrix_data = dot_matrix.get_data::<f32>().unwrap();
-    let new_dot_matrix_data = T::prepare_dot_matrix(dot_matrix_data);
+    let dot_matrix = dot_create(dot_size, dot_type)?;
+    let dot_matrix_data = dot_matrix.get_data::<f32>()?;
+    let new_dot_matrix_data = prepare_dot_matrix::<T>(dot_matrix_data);
     let lx_bias = dot_size / 2;
     let ly_bias = dot_size / 2;
     let dot_size = dot_size * 2;
@@ -96,7 +105,7 @@ fn apply_rotate_screentone<T: HalftonePixel>(img: &mut SVec, dot_size: usize, an
 
         for lx in 0..w {
             let lx2 = lx + lx_bias;
-            let value = &mut mut_img[ly * w + lx];
+            let value = &mut mut_img[strides.index(ly, lx, 0)];
             let rot = rotate_pixel_coordinates(lx2 as f32, ly2 as f32, w as f32, h as f32, cos_sin[0], cos_sin[1]);
             let dx = wrap_index(rot.0.round() as i32, dot_size);
             let dy = wrap_index(rot.1.round() as i32, dot_size);
@@ -107,24 +116,29 @@ fn apply_rotate_screentone<T: HalftonePixel>(img: &mut SVec, dot_size: usize, an
             };
         }
     }
+    Ok(())
 }
-fn apply_ssaa_screentone<T: HalftonePixel + std::marker::Send + std::marker::Sync>(
+fn apply_ssaa_screentone<T: Pixel + std::marker::Send + std::marker::Sync>(
     img: &mut SVec,
     dot_size: usize,
     dot_type: &DotType,
     scale: f32,
     resize_alg: ResizeAlg,
     disable_auto_dot: bool,
-) {
+) -> Result<(), HalftoneError> {
     let (h, w, _) = img.shape();
-    let mut_img = img.get_mut_vec::<T>().unwrap();
-    let dot_size = if disable_auto_dot {dot_size} else {(dot_size as f32 * scale).round() as usize};
-    let dot_matrix = dot_create(dot_size, dot_type);
-    let dot_matrix_data = dot_matrix.get_data::<f32>().unwrap();
+    let mut_img = img.get_mut_vec::<T>()?;
+    let dot_size = if disable_auto_dot {
+        dot_size
+    } else {
+        (dot_size as f32 * scale).round() as usize
+    };
+    let dot_matrix = dot_create(dot_size, dot_type)?;
+    let dot_matrix_data = dot_matrix.get_data::<f32>()?;
     let lx_bias = dot_size / 2;
     let ly_bias = dot_size / 2;
     let dot_size = dot_size * 2;
-    let dot_matrix_converted = T::prepare_dot_matrix(dot_matrix_data);
+    let dot_matrix_converted = prepare_dot_matrix::<T>(dot_matrix_data);
     let x_in_tab: Vec<usize> = (0..(w as f32 * scale) as usize)
         .map(|x| ((x as f32 / scale).floor() as usize).min(w - 1))
         .collect();
@@ -176,26 +190,31 @@ fn apply_ssaa_screentone<T: HalftonePixel + std::marker::Send + std::marker::Syn
         img.shape = Shape::new((h as f32 * scale) as usize, (w as f32 * scale) as usize, None);
         img.resize(h, w, resize_alg, false);
     }
+    Ok(())
 }
-fn apply_rotate_ssaa_screentone<T: HalftonePixel + std::marker::Send + std::marker::Sync>(
+fn apply_rotate_ssaa_screentone<T: Pixel + std::marker::Send + std::marker::Sync>(
     img: &mut SVec,
     dot_size: usize,
     angle: f32,
     dot_type: &DotType,
     scale: f32,
     resize_alg: ResizeAlg,
-    disable_auto_dot: bool
-) {
+    disable_auto_dot: bool,
+) -> Result<(), HalftoneError> {
     let (h, w, _) = img.shape();
-    let mut_img = img.get_mut_vec::<T>().unwrap();
-    let dot_size = if disable_auto_dot {dot_size} else {(dot_size as f32 * scale).round() as usize};
-    let dot_matrix = dot_create(dot_size, dot_type);
-    let dot_matrix_data = dot_matrix.get_data::<f32>().unwrap();
+    let mut_img = img.get_mut_vec::<T>()?;
+    let dot_size = if disable_auto_dot {
+        dot_size
+    } else {
+        (dot_size as f32 * scale).round() as usize
+    };
+    let dot_matrix = dot_create(dot_size, dot_type)?;
+    let dot_matrix_data = dot_matrix.get_data::<f32>()?;
     let (s_h, s_w) = ((h as f32 * scale) as usize, (w as f32 * scale) as usize);
     let lx_bias = dot_size / 2;
     let ly_bias = dot_size / 2;
     let dot_size = dot_size * 2;
-    let dot_matrix_converted = T::prepare_dot_matrix(dot_matrix_data);
+    let dot_matrix_converted = prepare_dot_matrix::<T>(dot_matrix_data);
     let cos_sin = compute_cos_sin(angle.to_radians());
 
     let x_in_tab: Vec<usize> = (0..(w as f32 * scale) as usize)
@@ -258,29 +277,66 @@ fn apply_rotate_ssaa_screentone<T: HalftonePixel + std::marker::Send + std::mark
         img.shape = Shape::new(s_h, s_w, None);
         img.resize(h, w, resize_alg, false);
     }
+    Ok(())
 }
 /// Public API: apply non-rotated screentone, dispatching by pixel type.
-pub fn screentone(img: &mut SVec, dot_size: usize, dot_type: &DotType) {
+pub fn screentone(img: &mut SVec, dot_size: usize, dot_type: &DotType) -> Result<(), HalftoneError> {
+    screentone_view(&mut img.view_mut(), dot_size, dot_type)
+}
+/// Public API: apply non-rotated screentone to a single-channel view (e.g. a region of a page) in-place.
+///
+/// The dot grid is anchored at the top-left corner of the view.
+pub fn screentone_view(img: &mut SVecViewMut, dot_size: usize, dot_type: &DotType) -> Result<(), HalftoneError> {
     match img.pixel_type() {
         PixelType::F32 => apply_screentone::<f32>(img, dot_size, dot_type),
         PixelType::U8 => apply_screentone::<u8>(img, dot_size, dot_type),
         PixelType::U16 => apply_screentone::<u16>(img, dot_size, dot_type),
+        PixelType::I16 => apply_screentone::<i16>(img, dot_size, dot_type),
+        PixelType::U32 => apply_screentone::<u32>(img, dot_size, dot_type),
+        PixelType::F16 => apply_screentone::<f16>(img, dot_size, dot_type),
+        PixelType::F64 => apply_screentone::<f64>(img, dot_size, dot_type),
     }
 }
 /// Public API: apply SSAA non-rotated screentone, dispatching by pixel type.
-pub fn ssaa_screentone(img: &mut SVec, dot_size: usize, dot_type: &DotType, scale: f32, resize_alg: ResizeAlg, disable_auto_dot: bool) {
-    match img.pixel_type() {
+pub fn ssaa_screentone(
+    img: &mut SVec,
+    dot_size: usize,
+    dot_type: &DotType,
+    scale: f32,
+    resize_alg: ResizeAlg,
+    disable_auto_dot: bool,
+) -> Result<(), HalftoneError> {
+    img.with_interleaved(|img| match img.pixel_type() {
         PixelType::F32 => apply_ssaa_screentone::<f32>(img, dot_size, dot_type, scale, resize_alg, disable_auto_dot),
         PixelType::U8 => apply_ssaa_screentone::<u8>(img, dot_size, dot_type, scale, resize_alg, disable_auto_dot),
         PixelType::U16 => apply_ssaa_screentone::<u16>(img, dot_size, dot_type, scale, resize_alg, disable_auto_dot),
-    }
+        PixelType::I16 => apply_ssaa_screentone::<i16>(img, dot_size, dot_type, scale, resize_alg, disable_auto_dot),
+        PixelType::U32 => apply_ssaa_screentone::<u32>(img, dot_size, dot_type, scale, resize_alg, disable_auto_dot),
+        PixelType::F16 => apply_ssaa_screentone::<f16>(img, dot_size, dot_type, scale, resize_alg, disable_auto_dot),
+        PixelType::F64 => apply_ssaa_screentone::<f64>(img, dot_size, dot_type, scale, resize_alg, disable_auto_dot),
+    })
 }
 /// Public API: apply rotated screentone, dispatching by pixel type.
-pub fn rotate_screentone(img: &mut SVec, dot_size: usize, angle: f32, dot_type: &DotType) {
+pub fn rotate_screentone(img: &mut SVec, dot_size: usize, angle: f32, dot_type: &DotType) -> Result<(), HalftoneError> {
+    rotate_screentone_view(&mut img.view_mut(), dot_size, angle, dot_type)
+}
+/// Public API: apply rotated screentone to a single-channel view in-place.
+///
+/// The rotation is performed around the center of the view.
+pub fn rotate_screentone_view(
+    img: &mut SVecViewMut,
+    dot_size: usize,
+    angle: f32,
+    dot_type: &DotType,
+) -> Result<(), HalftoneError> {
     match img.pixel_type() {
         PixelType::F32 => apply_rotate_screentone::<f32>(img, dot_size, angle, dot_type),
         PixelType::U8 => apply_rotate_screentone::<u8>(img, dot_size, angle, dot_type),
         PixelType::U16 => apply_rotate_screentone::<u16>(img, dot_size, angle, dot_type),
+        PixelType::I16 => apply_rotate_screentone::<i16>(img, dot_size, angle, dot_type),
+        PixelType::U32 => apply_rotate_screentone::<u32>(img, dot_size, angle, dot_type),
+        PixelType::F16 => apply_rotate_screentone::<f16>(img, dot_size, angle, dot_type),
+        PixelType::F64 => apply_rotate_screentone::<f64>(img, dot_size, angle, dot_type),
     }
 }
 /// Public API: apply SSAA non-rotated screentone, dispatching by pixel type.
@@ -291,11 +347,27 @@ pub fn ssaa_rotate_screentone(
     dot_type: &DotType,
     scale: f32,
     resize_alg: ResizeAlg,
-    disable_auto_dot: bool
-) {
-    match img.pixel_type() {
-        PixelType::F32 => apply_rotate_ssaa_screentone::<f32>(img, dot_size, angle, dot_type, scale, resize_alg, disable_auto_dot),
+    disable_auto_dot: bool,
+) -> Result<(), HalftoneError> {
+    img.with_interleaved(|img| match img.pixel_type() {
+        PixelType::F32 => {
+            apply_rotate_ssaa_screentone::<f32>(img, dot_size, angle, dot_type, scale, resize_alg, disable_auto_dot)
+        }
         PixelType::U8 => apply_rotate_ssaa_screentone::<u8>(img, dot_size, angle, dot_type, scale, resize_alg, disable_auto_dot),
-        PixelType::U16 => apply_rotate_ssaa_screentone::<u16>(img, dot_size, angle, dot_type, scale, resize_alg, disable_auto_dot),
-    }
+        PixelType::U16 => {
+            apply_rotate_ssaa_screentone::<u16>(img, dot_size, angle, dot_type, scale, resize_alg, disable_auto_dot)
+        }
+        PixelType::I16 => {
+            apply_rotate_ssaa_screentone::<i16>(img, dot_size, angle, dot_type, scale, resize_alg, disable_auto_dot)
+        }
+        PixelType::U32 => {
+            apply_rotate_ssaa_screentone::<u32>(img, dot_size, angle, dot_type, scale, resize_alg, disable_auto_dot)
+        }
+        PixelType::F16 => {
+            apply_rotate_ssaa_screentone::<f16>(img, dot_size, angle, dot_type, scale, resize_alg, disable_auto_dot)
+        }
+        PixelType::F64 => {
+            apply_rotate_ssaa_screentone::<f64>(img, dot_size, angle, dot_type, scale, resize_alg, disable_auto_dot)
+        }
+    })
 }
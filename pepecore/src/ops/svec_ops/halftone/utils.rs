@@ -1,3 +1,5 @@
+use pepecore_array::Pixel;
+
 pub fn rotate_pixel_coordinates(x: f32, y: f32, w: f32, h: f32, cos: f32, sin: f32) -> (f32, f32) {
     let cx = w / 2.0;
     let cy = h / 2.0;
@@ -19,62 +21,18 @@ pub fn compute_cos_sin(theta: f32) -> [f32; 2] {
     let sin_theta = theta.sin();
     [cos_theta, sin_theta]
 }
-pub trait HalftonePixel: Sized + Copy + PartialOrd + 'static {
-    const MIN_VALUE: Self;
-    const MAX_VALUE: Self;
-
-    fn prepare_dot_matrix(matrix: &[f32]) -> Vec<Self>;
+/// Sample on the `0.0..=1.0` scale spanned by `T::MIN_VALUE..=T::MAX_VALUE`, so signed types
+/// use their negative half too. Used for thresholds and error diffusion.
+pub fn to_unit<T: Pixel>(value: T) -> f32 {
+    let (min, max) = (T::MIN_VALUE.to_f32_normalized(), T::MAX_VALUE.to_f32_normalized());
+    ((value.to_f32_normalized() - min) / (max - min)).clamp(0.0, 1.0)
 }
-
-impl HalftonePixel for f32 {
-    const MIN_VALUE: Self = 0.0;
-    const MAX_VALUE: Self = 1.0;
-
-    fn prepare_dot_matrix(matrix: &[f32]) -> Vec<Self> {
-        matrix.into()
-    }
+/// Inverse of [`to_unit`].
+pub fn from_unit<T: Pixel>(value: f32) -> T {
+    let (min, max) = (T::MIN_VALUE.to_f32_normalized(), T::MAX_VALUE.to_f32_normalized());
+    T::from_f32_normalized(min + value.clamp(0.0, 1.0) * (max - min))
 }
-
-impl HalftonePixel for u8 {
-    const MIN_VALUE: Self = u8::MIN;
-    const MAX_VALUE: Self = u8::MAX;
-
-    fn prepare_dot_matrix(matrix: &[f32]) -> Vec<Self> {
-        let mut new_dot_matrix_data = Vec::with_capacity(matrix.len());
-
-        unsafe {
-            let src_ptr = matrix.as_ptr();
-            let dst_ptr: *mut u8 = new_dot_matrix_data.as_mut_ptr();
-
-            for i in 0..matrix.len() {
-                *dst_ptr.add(i) = (*src_ptr.add(i) * 255.0).min(255.0) as u8;
-            }
-
-            new_dot_matrix_data.set_len(matrix.len());
-        }
-
-        new_dot_matrix_data
-    }
-}
-
-impl HalftonePixel for u16 {
-    const MIN_VALUE: Self = u16::MIN;
-    const MAX_VALUE: Self = u16::MAX;
-
-    fn prepare_dot_matrix(matrix: &[f32]) -> Vec<Self> {
-        let mut new_dot_matrix_data = Vec::with_capacity(matrix.len());
-
-        unsafe {
-            let src_ptr = matrix.as_ptr();
-            let dst_ptr: *mut u16 = new_dot_matrix_data.as_mut_ptr();
-
-            for i in 0..matrix.len() {
-                *dst_ptr.add(i) = (*src_ptr.add(i) * u16::MAX as f32).min(u16::MAX as f32) as u16;
-            }
-
-            new_dot_matrix_data.set_len(matrix.len());
-        }
-
-        new_dot_matrix_data
-    }
+/// Dot matrix thresholds, given on the `0.0..=1.0` scale, in the pixel type of the image.
+pub fn prepare_dot_matrix<T: Pixel>(matrix: &[f32]) -> Vec<T> {
+    matrix.iter().map(|&v| from_unit(v)).collect()
 }
@@ -65,6 +65,9 @@ pub fn jpeg_compress_one_ch(data: &mut [u8], h: usize, w: usize, q: &Quantizatio
     }
 }
 pub fn jpeg_compress(img: &mut SVec, quality: u8, qt: &QuantizationTableType, yuv: &YCbCrRatio) {
+    img.with_interleaved(|img| jpeg_compress_interleaved(img, quality, qt, yuv))
+}
+fn jpeg_compress_interleaved(img: &mut SVec, quality: u8, qt: &QuantizationTableType, yuv: &YCbCrRatio) {
     img.as_u8();
     let (h, w, c) = img.shape();
     let data = img.get_data::<u8>().unwrap();
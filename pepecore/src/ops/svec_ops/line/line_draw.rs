@@ -50,7 +50,7 @@ mod tests {
             let pixel = (min(1000 - 1, pixel.0), min(1000 - 1, pixel.1));
             img_data[pixel.1 * 1000 + pixel.0] = 255
         }
-        svec_save(img, "test.png");
+        svec_save(img, &std::env::temp_dir().join("pepecore_line_draw.png")).unwrap();
         // println!("{:?}", pixels);
     }
 }
@@ -1,6 +1,9 @@
+pub mod arithmetic;
 pub mod color_levels;
 pub mod crop;
 pub mod cvtcolor;
+pub mod frequency;
+pub mod geometry;
 pub mod halftone;
 pub mod jpeg;
 pub mod line;
@@ -9,3 +12,4 @@ pub mod normalize;
 pub mod palette_gen;
 pub mod real_size;
 pub mod resize;
+pub mod tile;
@@ -1,11 +1,13 @@
 use biski64::Biski64Rng;
 use fastnoise_lite::*;
+use pepecore_array::error::Error;
 use pepecore_array::{ImgData, SVec, Shape};
 use rand::{RngCore, SeedableRng};
 
-pub fn create_noise_2d(shape: Shape, octaves: usize, amplitudes: &[f32], frequency: &[f32], noise_types: &[NoiseType]) -> SVec {
-    let (h, w, c) = shape.get_shape();
-    let mut data: Vec<f32> = vec![0.0; h * w * c.unwrap_or(1)];
+pub fn create_noise_2d(shape: Shape, octaves: usize, amplitudes: &[f32], frequency: &[f32], noise_types: &[NoiseType]) -> Result<SVec, Error> {
+    let len = shape.checked_len()?;
+    let (h, w, _) = shape.get_shape();
+    let mut data: Vec<f32> = vec![0.0; len];
     let mut noise_func = vec![];
     let mut rng = Biski64Rng::from_os_rng();
     let mut amp: Vec<f32> = Vec::with_capacity(octaves);
@@ -30,12 +32,13 @@ pub fn create_noise_2d(shape: Shape, octaves: usize, amplitudes: &[f32], frequen
             }
         }
     }
-    SVec::new(shape, ImgData::F32(data))
+    SVec::try_new(shape, ImgData::F32(data))
 }
-pub fn create_noise_3d(shape: Shape, octaves: usize, amplitudes: &[f32], frequency: &[f32], noise_types: &[NoiseType]) -> SVec {
+pub fn create_noise_3d(shape: Shape, octaves: usize, amplitudes: &[f32], frequency: &[f32], noise_types: &[NoiseType]) -> Result<SVec, Error> {
+    let len = shape.checked_len()?;
     let (h, w, c) = shape.get_shape();
     let c = c.unwrap_or(1);
-    let mut data: Vec<f32> = vec![0.0; h * w * c];
+    let mut data: Vec<f32> = vec![0.0; len];
     let mut noise_func = vec![];
     let mut rng = Biski64Rng::from_os_rng();
     let mut amp: Vec<f32> = Vec::with_capacity(octaves);
@@ -63,5 +66,5 @@ pub fn create_noise_3d(shape: Shape, octaves: usize, amplitudes: &[f32], frequen
             }
         }
     }
-    SVec::new(shape, ImgData::F32(data))
+    SVec::try_new(shape, ImgData::F32(data))
 }
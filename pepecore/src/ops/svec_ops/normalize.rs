@@ -1,5 +1,5 @@
 use crate::global_params::rayon_get_mode;
-use pepecore_array::{PixelType, SVec};
+use pepecore_array::{Pixel, PixelType, SVec, SVecViewMut, f16};
 use rayon::iter::{IntoParallelRefIterator, IntoParallelRefMutIterator};
 use rayon::prelude::*;
 use std::usize;
@@ -7,76 +7,9 @@ use std::usize;
 pub trait NormalizeSVec {
     fn normalize(&mut self, scale: f32);
 }
-pub trait SVecPixel: Sized + Copy + PartialOrd + 'static {
-    const MIN_VALUE: Self;
-    const MAX_VALUE: Self;
-    fn min(&mut self, value: Self);
-    fn max(&mut self, value: Self);
-    fn as_f32(&self) -> f32;
-    fn from_f32(value: f32) -> Self;
-}
-impl SVecPixel for f32 {
-    const MIN_VALUE: Self = 0.0;
-    const MAX_VALUE: Self = 1.0;
-    fn min(&mut self, value: Self) {
-        if *self > value {
-            *self = value
-        }
-    }
-    fn max(&mut self, value: Self) {
-        if *self < value {
-            *self = value
-        }
-    }
-    fn as_f32(&self) -> f32 {
-        *self
-    }
-    fn from_f32(value: f32) -> Self {
-        value
-    }
-}
-impl SVecPixel for u8 {
-    const MIN_VALUE: Self = u8::MIN;
-    const MAX_VALUE: Self = u8::MAX;
-    fn min(&mut self, value: Self) {
-        if *self > value {
-            *self = value
-        }
-    }
-    fn max(&mut self, value: Self) {
-        if *self < value {
-            *self = value
-        }
-    }
-    fn as_f32(&self) -> f32 {
-        *self as f32
-    }
-    fn from_f32(value: f32) -> Self {
-        (value * 255.0) as u8
-    }
-}
-impl SVecPixel for u16 {
-    const MIN_VALUE: Self = u16::MIN;
-    const MAX_VALUE: Self = u16::MAX;
-    fn min(&mut self, value: Self) {
-        if *self > value {
-            *self = value
-        }
-    }
-    fn max(&mut self, value: Self) {
-        if *self < value {
-            *self = value
-        }
-    }
-    fn as_f32(&self) -> f32 {
-        *self as f32
-    }
-    fn from_f32(value: f32) -> Self {
-        (value * 65535.0) as u16
-    }
-}
-fn normalize<T: SVecPixel>(img: &mut SVec, scale: f32) {
+fn normalize<T: Pixel>(img: &mut SVecViewMut, scale: f32) {
     let (h, w, c) = img.shape();
+    let strides = img.strides();
     let data = img.get_data_mut::<T>().unwrap();
     let mut min = T::MAX_VALUE;
     let mut max = T::MIN_VALUE;
@@ -96,26 +29,38 @@ fn normalize<T: SVecPixel>(img: &mut SVec, scale: f32) {
     for y in y_in_tab {
         for x in x_in_tab.iter() {
             for z in 0..c {
-                let value = &data[(y * w + x) * c + z];
-                min.min(*value);
-                max.max(*value);
+                let value = data[strides.index(y, *x, z)];
+                if value < min {
+                    min = value;
+                }
+                if value > max {
+                    max = value;
+                }
             }
         }
     }
     if min == max {
         return;
     }
-    let min: f32 = min.as_f32();
-    let div: f32 = max.as_f32() - min;
-    for val in data.iter_mut() {
-        *val = T::from_f32((val.as_f32() - min) / div)
+    let min: f32 = min.to_f32_normalized();
+    let div: f32 = max.to_f32_normalized() - min;
+    for y in 0..h {
+        for x in 0..w {
+            for z in 0..c {
+                let val = &mut data[strides.index(y, x, z)];
+                *val = T::from_f32_normalized((val.to_f32_normalized() - min) / div)
+            }
+        }
     }
 }
-fn rayon_normalize<T>(img: &mut SVec, scale: f32)
+fn rayon_normalize<T>(img: &mut SVecViewMut, scale: f32)
 where
-    T: SVecPixel + Send + Sync,
+    T: Pixel,
 {
     let (h, w, c_opt) = img.shape();
+    let strides = img.strides();
+    let contiguous = img.is_contiguous();
+    let len = img.get_len();
     let data = img.get_data_mut::<T>().unwrap();
     let c = c_opt.unwrap_or(1);
 
@@ -135,7 +80,11 @@ where
     // Плоский вектор индексов для выборки
     let sample_indices: Vec<usize> = y_in_tab
         .iter()
-        .flat_map(|&y| x_in_tab.iter().flat_map(move |&x| (0..c).map(move |z| (y * w + x) * c + z)))
+        .flat_map(|&y| {
+            x_in_tab
+                .iter()
+                .flat_map(move |&x| (0..c).map(move |z| strides.index(y, x, z)))
+        })
         .collect();
 
     // Параллельно находим (min, max) по выборке
@@ -147,13 +96,7 @@ where
         })
         .reduce(
             || (T::MAX_VALUE, T::MIN_VALUE),
-            |(min_v, max_v), (mn, mx)| {
-                let mut min_acc = min_v;
-                let mut max_acc = max_v;
-                min_acc.min(mn);
-                max_acc.max(mx);
-                (min_acc, max_acc)
-            },
+            |(min_v, max_v), (mn, mx)| (if mn < min_v { mn } else { min_v }, if mx > max_v { mx } else { max_v }),
         );
 
     // Если все пиксели одинаковы — выходим
@@ -161,17 +104,34 @@ where
         return;
     }
 
-    let min_f = min_pixel.as_f32();
-    let div = max_pixel.as_f32() - min_f;
+    let min_f = min_pixel.to_f32_normalized();
+    let div = max_pixel.to_f32_normalized() - min_f;
 
     // Параллельно нормализуем весь буфер
-    data.par_iter_mut().for_each(|val| {
-        let normalized = (val.as_f32() - min_f) / div;
-        *val = T::from_f32(normalized);
-    });
+    if contiguous {
+        data[strides.offset..strides.offset + len].par_iter_mut().for_each(|val| {
+            let normalized = (val.to_f32_normalized() - min_f) / div;
+            *val = T::from_f32_normalized(normalized);
+        });
+    } else {
+        for y in 0..h {
+            for x in 0..w {
+                for z in 0..c {
+                    let val = &mut data[strides.index(y, x, z)];
+                    *val = T::from_f32_normalized((val.to_f32_normalized() - min_f) / div);
+                }
+            }
+        }
+    }
 }
 
 impl NormalizeSVec for SVec {
+    fn normalize(&mut self, scale: f32) {
+        self.view_mut().normalize(scale)
+    }
+}
+
+impl NormalizeSVec for SVecViewMut<'_> {
     fn normalize(&mut self, scale: f32) {
         match self.pixel_type() {
             PixelType::F32 => {
@@ -195,6 +155,34 @@ impl NormalizeSVec for SVec {
                     normalize::<u16>(self, scale);
                 }
             }
+            PixelType::I16 => {
+                if rayon_get_mode() {
+                    rayon_normalize::<i16>(self, scale)
+                } else {
+                    normalize::<i16>(self, scale);
+                }
+            }
+            PixelType::U32 => {
+                if rayon_get_mode() {
+                    rayon_normalize::<u32>(self, scale)
+                } else {
+                    normalize::<u32>(self, scale);
+                }
+            }
+            PixelType::F16 => {
+                if rayon_get_mode() {
+                    rayon_normalize::<f16>(self, scale)
+                } else {
+                    normalize::<f16>(self, scale);
+                }
+            }
+            PixelType::F64 => {
+                if rayon_get_mode() {
+                    rayon_normalize::<f64>(self, scale)
+                } else {
+                    normalize::<f64>(self, scale);
+                }
+            }
         }
     }
 }
@@ -6,6 +6,7 @@ use crate::ops::svec_ops::palette_gen::wu_color::{MAXCOLOR, WuQuantizer};
 use pepecore_array::SVec;
 pub fn svec_to_palette(img: &mut SVec, num_color: usize, p_a: PaletteAlg) -> Vec<f32> {
     img.as_u8();
+    img.to_interleaved();
     let data = img.get_data::<u8>().unwrap();
     match p_a {
         PaletteAlg::OcTree => {
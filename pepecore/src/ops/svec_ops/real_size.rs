@@ -1,4 +1,22 @@
 use pepecore_array::SVec;
+use pepecore_array::dct::{DctMode, DctPlan};
+use std::cell::RefCell;
+
+thread_local! {
+    // plans are cached per thread so scanning many images of the same size does not replan
+    static DCT_PLAN: RefCell<DctPlan> = RefCell::new(DctPlan::new());
+}
+
+/// Absolute DCT-II coefficients of `img` as an interleaved `f32` image, read straight from `img`.
+fn dct_magnitude(img: &SVec) -> SVec {
+    let mut dct_i = DCT_PLAN
+        .with(|plan| plan.borrow_mut().forward_view(&img.view(), DctMode::Full))
+        .unwrap();
+    for v in dct_i.get_data_mut::<f32>().unwrap() {
+        *v = v.abs();
+    }
+    dct_i
+}
 
 #[inline(always)]
 fn sum_ch(dct: &[f32], base: usize, c: usize) -> f32 {
@@ -19,8 +37,7 @@ fn sum_ch(dct: &[f32], base: usize, c: usize) -> f32 {
 }
 
 pub fn get_full_original_size(img: &SVec) -> (usize, usize) {
-    let mut dct_i = img.clone();
-    dct_i.dct2().unwrap();
+    let dct_i = dct_magnitude(img);
 
     let (h, w, c_opt) = img.shape.get_shape();
     let c = c_opt.unwrap_or(1);
@@ -92,8 +109,7 @@ pub fn get_full_original_size(img: &SVec) -> (usize, usize) {
     (index_h, index_w)
 }
 pub fn get_original_height_only(img: &SVec) -> usize {
-    let mut dct_i = img.clone();
-    dct_i.dct2().unwrap();
+    let dct_i = dct_magnitude(img);
 
     let (h, w, c_opt) = img.shape.get_shape();
     let c = c_opt.unwrap_or(1);
@@ -147,8 +163,7 @@ pub fn get_original_height_only(img: &SVec) -> usize {
 }
 
 pub fn get_original_width_only(img: &SVec) -> usize {
-    let mut dct_i = img.clone();
-    dct_i.dct2().unwrap();
+    let dct_i = dct_magnitude(img);
 
     let (h, w, c_opt) = img.shape.get_shape();
     let c = c_opt.unwrap_or(1);
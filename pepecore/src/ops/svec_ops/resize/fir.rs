@@ -1,10 +1,54 @@
-use crate::array::{PixelType, SVec, Shape};
+use crate::array::{ImgData, Pixel, PixelType, SVec, Shape};
+use crate::enums::Anchor;
+use crate::ops::svec_ops::crop::crop;
 use fast_image_resize::images::{Image, ImageRef};
 use fast_image_resize::{ResizeAlg, ResizeOptions, Resizer};
 use image::EncodableLayout;
+use pepecore_array::error::Error;
 
+/// Resizing of an [`SVec`] of any pixel type, channel count and layout.
+///
+/// With `alpha` the last channel is treated as alpha and the others are resized premultiplied by
+/// it, so transparent pixels don't bleed their color into their neighbours. Images with more than
+/// four channels, e.g. feature maps, are resized in groups of up to four channels.
+///
+/// The fallible methods return `ZeroDimension` for an empty image or target size and `Overflow`
+/// when a scaled side doesn't fit in `u32`.
 pub trait ResizeSVec {
+    /// Resize to exactly `h` x `w`.
     fn resize(&mut self, h: usize, w: usize, resize_alg: ResizeAlg, alpha: bool);
+    /// Scale, up or down, to the largest size that fits inside `max_h` x `max_w` with the aspect
+    /// ratio kept.
+    fn resize_to_fit(&mut self, max_h: usize, max_w: usize, resize_alg: ResizeAlg, alpha: bool) -> Result<(), Error>;
+    /// Scale to the smallest size covering `h` x `w` with the aspect ratio kept, then crop to
+    /// `h` x `w` keeping the part at `anchor`.
+    fn resize_to_fill(&mut self, h: usize, w: usize, anchor: Anchor, resize_alg: ResizeAlg, alpha: bool) -> Result<(), Error>;
+    /// Scale both sides by `factor`, rounded to whole pixels.
+    fn resize_scale(&mut self, factor: f64, resize_alg: ResizeAlg, alpha: bool) -> Result<(), Error>;
+    /// Scale so the shorter side is `size` pixels.
+    fn resize_short_side(&mut self, size: usize, resize_alg: ResizeAlg, alpha: bool) -> Result<(), Error>;
+    /// Scale so the longer side is `size` pixels.
+    fn resize_long_side(&mut self, size: usize, resize_alg: ResizeAlg, alpha: bool) -> Result<(), Error>;
+}
+
+/// `(h, w)` scaled by `factor`, each side rounded and at least one pixel.
+fn scaled(h: usize, w: usize, factor: f64) -> Result<(usize, usize), Error> {
+    let side = |n: usize| {
+        let side = (n as f64 * factor).round();
+        if side > u32::MAX as f64 {
+            return Err(Error::Overflow);
+        }
+        Ok((side as usize).max(1))
+    };
+    Ok((side(h)?, side(w)?))
+}
+
+/// `(height, width)` of `img`, `ZeroDimension` when it is empty.
+fn source_size(img: &SVec) -> Result<(usize, usize), Error> {
+    match img.shape() {
+        (0, _, _) | (_, 0, _) => Err(Error::ZeroDimension),
+        (h, w, _) => Ok((h, w)),
+    }
 }
 fn replace_vec_u8_from_bytes(vec: &mut Vec<u8>, bytes: &[u8]) {
     vec.clear();
@@ -36,78 +80,315 @@ fn replace_vec_f32_from_bytes(vec: &mut Vec<f32>, bytes: &[u8]) {
 }
 impl ResizeSVec for SVec {
     fn resize(&mut self, h: usize, w: usize, resize_alg: ResizeAlg, alpha: bool) {
-        let mut resizer = Resizer::new();
-        #[cfg(target_arch = "x86_64")]
-        unsafe {
-            resizer.set_cpu_extensions(fast_image_resize::CpuExtensions::Avx2);
+        self.with_interleaved(|img| resize_interleaved(img, h, w, resize_alg, alpha))
+    }
+
+    fn resize_to_fit(&mut self, max_h: usize, max_w: usize, resize_alg: ResizeAlg, alpha: bool) -> Result<(), Error> {
+        if max_h == 0 || max_w == 0 {
+            return Err(Error::ZeroDimension);
         }
-        match self.pixel_type() {
-            PixelType::F32 => {
-                let (h_s, w_s, c_s) = self.shape.get_shape();
-                let pt = match c_s {
-                    Some(1) | None => fast_image_resize::PixelType::F32,
-                    Some(2) => fast_image_resize::PixelType::F32x2,
-                    Some(3) => fast_image_resize::PixelType::F32x3,
-                    Some(4) => fast_image_resize::PixelType::F32x4,
-                    _ => panic!(),
-                };
-                let src = ImageRef::new(w_s as u32, h_s as u32, self.get_data::<f32>().unwrap().as_bytes(), pt).unwrap();
-                let mut resized = Image::new(w as u32, h as u32, pt);
-                resizer
-                    .resize(
-                        &src,
-                        &mut resized,
-                        &ResizeOptions::new().resize_alg(resize_alg).use_alpha(alpha),
-                    )
-                    .unwrap();
-                let data = self.get_mut_vec::<f32>().unwrap();
-                replace_vec_f32_from_bytes(data, resized.buffer());
-                self.shape = Shape::new(h, w, c_s)
-            }
-            PixelType::U8 => {
-                let (h_s, w_s, c_s) = self.shape.get_shape();
-                let pt = match c_s {
-                    Some(1) | None => fast_image_resize::PixelType::U8,
-                    Some(2) => fast_image_resize::PixelType::U8x2,
-                    Some(3) => fast_image_resize::PixelType::U8x3,
-                    Some(4) => fast_image_resize::PixelType::U8x4,
-                    _ => panic!(),
-                };
-                let src = ImageRef::new(w_s as u32, h_s as u32, self.get_data::<u8>().unwrap(), pt).unwrap();
-                let mut resized = Image::new(w as u32, h as u32, pt);
-                resizer
-                    .resize(
-                        &src,
-                        &mut resized,
-                        &ResizeOptions::new().resize_alg(resize_alg).use_alpha(alpha),
-                    )
-                    .unwrap();
-                let data = self.get_mut_vec::<u8>().unwrap();
-                replace_vec_u8_from_bytes(data, resized.buffer());
-                self.shape = Shape::new(h, w, c_s)
+        let (h, w) = source_size(self)?;
+        let (new_h, new_w) = scaled(h, w, (max_h as f64 / h as f64).min(max_w as f64 / w as f64))?;
+        self.resize(new_h.min(max_h), new_w.min(max_w), resize_alg, alpha);
+        Ok(())
+    }
+
+    fn resize_to_fill(&mut self, h: usize, w: usize, anchor: Anchor, resize_alg: ResizeAlg, alpha: bool) -> Result<(), Error> {
+        if h == 0 || w == 0 {
+            return Err(Error::ZeroDimension);
+        }
+        let (src_h, src_w) = source_size(self)?;
+        let (new_h, new_w) = scaled(src_h, src_w, (h as f64 / src_h as f64).max(w as f64 / src_w as f64))?;
+        let (new_h, new_w) = (new_h.max(h), new_w.max(w));
+        self.resize(new_h, new_w, resize_alg, alpha);
+        let (extra_y, extra_x) = (new_h - h, new_w - w);
+        let y = match anchor {
+            Anchor::TopLeft | Anchor::Top | Anchor::TopRight => 0,
+            Anchor::Left | Anchor::Center | Anchor::Right => extra_y / 2,
+            Anchor::BottomLeft | Anchor::Bottom | Anchor::BottomRight => extra_y,
+        };
+        let x = match anchor {
+            Anchor::TopLeft | Anchor::Left | Anchor::BottomLeft => 0,
+            Anchor::Top | Anchor::Center | Anchor::Bottom => extra_x / 2,
+            Anchor::TopRight | Anchor::Right | Anchor::BottomRight => extra_x,
+        };
+        crop(self, x, y, w, h)
+    }
+
+    fn resize_scale(&mut self, factor: f64, resize_alg: ResizeAlg, alpha: bool) -> Result<(), Error> {
+        let (h, w) = source_size(self)?;
+        if !factor.is_finite() {
+            return Err(Error::Overflow);
+        }
+        if factor <= 0.0 {
+            return Err(Error::ZeroDimension);
+        }
+        let (new_h, new_w) = scaled(h, w, factor)?;
+        self.resize(new_h, new_w, resize_alg, alpha);
+        Ok(())
+    }
+
+    fn resize_short_side(&mut self, size: usize, resize_alg: ResizeAlg, alpha: bool) -> Result<(), Error> {
+        let (h, w, _) = self.shape();
+        self.resize_scale(size as f64 / h.min(w) as f64, resize_alg, alpha)
+    }
+
+    fn resize_long_side(&mut self, size: usize, resize_alg: ResizeAlg, alpha: bool) -> Result<(), Error> {
+        let (h, w, _) = self.shape();
+        self.resize_scale(size as f64 / h.max(w) as f64, resize_alg, alpha)
+    }
+}
+
+/// Multiply the color channels of interleaved pixels by their last, alpha channel, or with
+/// `divide` undo it. Fully transparent pixels come back black.
+fn premultiply(data: &mut [f32], c: usize, divide: bool) {
+    for px in data.chunks_exact_mut(c) {
+        let (color, alpha) = px.split_at_mut(c - 1);
+        let a = alpha[0];
+        match (divide, a > 0.0) {
+            (false, _) => color.iter_mut().for_each(|v| *v *= a),
+            (true, true) => color.iter_mut().for_each(|v| *v /= a),
+            (true, false) => color.fill(0.0),
+        }
+    }
+}
+
+/// Resize each group of up to four channels of an interleaved image on its own.
+fn resize_groups_t<T: Pixel>(img: &mut SVec, h: usize, w: usize, resize_alg: ResizeAlg)
+where
+    ImgData: From<Vec<T>>,
+{
+    let (h_s, w_s, c_s) = img.shape.get_shape();
+    let c = c_s.unwrap_or(1);
+    let src = img.get_data::<T>().unwrap();
+    let mut out = vec![T::MIN_VALUE; h * w * c];
+    for start in (0..c).step_by(4) {
+        let n = (c - start).min(4);
+        let data: Vec<T> = src.chunks_exact(c).flat_map(|px| &px[start..start + n]).copied().collect();
+        let mut group = SVec::new(Shape::new(h_s, w_s, Some(n)), ImgData::from(data));
+        resize_interleaved(&mut group, h, w, resize_alg, false);
+        let group = group.get_data::<T>().unwrap();
+        for (px, v) in out.chunks_exact_mut(c).zip(group.chunks_exact(n)) {
+            px[start..start + n].copy_from_slice(v);
+        }
+    }
+    *img.get_mut_vec::<T>().unwrap() = out;
+    img.shape = Shape::new(h, w, c_s)
+}
+
+/// Resize an interleaved image with more channels than `fast_image_resize` takes.
+fn resize_groups(img: &mut SVec, h: usize, w: usize, resize_alg: ResizeAlg, alpha: bool) {
+    match img.pixel_type() {
+        PixelType::U8 if !alpha => resize_groups_t::<u8>(img, h, w, resize_alg),
+        PixelType::U16 if !alpha => resize_groups_t::<u16>(img, h, w, resize_alg),
+        PixelType::F32 if !alpha => resize_groups_t::<f32>(img, h, w, resize_alg),
+        // premultiplying needs the alpha of the whole pixel, so it's done here in f32
+        pixel_type => {
+            let c = img.shape.get_channels().unwrap_or(1);
+            img.as_f32();
+            if alpha {
+                premultiply(img.get_data_mut::<f32>().unwrap(), c, false);
             }
-            PixelType::U16 => {
-                let (h_s, w_s, c_s) = self.shape.get_shape();
-                let pt = match c_s {
-                    Some(1) | None => fast_image_resize::PixelType::U16,
-                    Some(2) => fast_image_resize::PixelType::U16x2,
-                    Some(3) => fast_image_resize::PixelType::U16x3,
-                    Some(4) => fast_image_resize::PixelType::U16x4,
-                    _ => panic!(),
-                };
-                let src = ImageRef::new(w_s as u32, h_s as u32, self.get_data::<u16>().unwrap().as_bytes(), pt).unwrap();
-                let mut resized = Image::new(w as u32, h as u32, pt);
-                resizer
-                    .resize(
-                        &src,
-                        &mut resized,
-                        &ResizeOptions::new().resize_alg(resize_alg).use_alpha(alpha),
-                    )
-                    .unwrap();
-                let data = self.get_mut_vec::<u16>().unwrap();
-                replace_vec_u16_from_bytes(data, resized.buffer());
-                self.shape = Shape::new(h, w, c_s)
+            resize_groups_t::<f32>(img, h, w, resize_alg);
+            if alpha {
+                premultiply(img.get_data_mut::<f32>().unwrap(), c, true);
             }
+            img.as_pixel_type(pixel_type);
         }
     }
 }
+
+fn resize_interleaved(img: &mut SVec, h: usize, w: usize, resize_alg: ResizeAlg, alpha: bool) {
+    if img.shape.get_channels().is_some_and(|c| c > 4) {
+        return resize_groups(img, h, w, resize_alg, alpha);
+    }
+    let mut resizer = Resizer::new();
+    #[cfg(target_arch = "x86_64")]
+    unsafe {
+        resizer.set_cpu_extensions(fast_image_resize::CpuExtensions::Avx2);
+    }
+    match img.pixel_type() {
+        PixelType::F32 => {
+            let (h_s, w_s, c_s) = img.shape.get_shape();
+            let pt = match c_s {
+                Some(1) | None => fast_image_resize::PixelType::F32,
+                Some(2) => fast_image_resize::PixelType::F32x2,
+                Some(3) => fast_image_resize::PixelType::F32x3,
+                Some(4) => fast_image_resize::PixelType::F32x4,
+                _ => panic!(),
+            };
+            let src = ImageRef::new(w_s as u32, h_s as u32, img.get_data::<f32>().unwrap().as_bytes(), pt).unwrap();
+            let mut resized = Image::new(w as u32, h as u32, pt);
+            resizer
+                .resize(
+                    &src,
+                    &mut resized,
+                    &ResizeOptions::new().resize_alg(resize_alg).use_alpha(alpha),
+                )
+                .unwrap();
+            let data = img.get_mut_vec::<f32>().unwrap();
+            replace_vec_f32_from_bytes(data, resized.buffer());
+            img.shape = Shape::new(h, w, c_s)
+        }
+        PixelType::U8 => {
+            let (h_s, w_s, c_s) = img.shape.get_shape();
+            let pt = match c_s {
+                Some(1) | None => fast_image_resize::PixelType::U8,
+                Some(2) => fast_image_resize::PixelType::U8x2,
+                Some(3) => fast_image_resize::PixelType::U8x3,
+                Some(4) => fast_image_resize::PixelType::U8x4,
+                _ => panic!(),
+            };
+            let src = ImageRef::new(w_s as u32, h_s as u32, img.get_data::<u8>().unwrap(), pt).unwrap();
+            let mut resized = Image::new(w as u32, h as u32, pt);
+            resizer
+                .resize(
+                    &src,
+                    &mut resized,
+                    &ResizeOptions::new().resize_alg(resize_alg).use_alpha(alpha),
+                )
+                .unwrap();
+            let data = img.get_mut_vec::<u8>().unwrap();
+            replace_vec_u8_from_bytes(data, resized.buffer());
+            img.shape = Shape::new(h, w, c_s)
+        }
+        PixelType::U16 => {
+            let (h_s, w_s, c_s) = img.shape.get_shape();
+            let pt = match c_s {
+                Some(1) | None => fast_image_resize::PixelType::U16,
+                Some(2) => fast_image_resize::PixelType::U16x2,
+                Some(3) => fast_image_resize::PixelType::U16x3,
+                Some(4) => fast_image_resize::PixelType::U16x4,
+                _ => panic!(),
+            };
+            let src = ImageRef::new(w_s as u32, h_s as u32, img.get_data::<u16>().unwrap().as_bytes(), pt).unwrap();
+            let mut resized = Image::new(w as u32, h as u32, pt);
+            resizer
+                .resize(
+                    &src,
+                    &mut resized,
+                    &ResizeOptions::new().resize_alg(resize_alg).use_alpha(alpha),
+                )
+                .unwrap();
+            let data = img.get_mut_vec::<u16>().unwrap();
+            replace_vec_u16_from_bytes(data, resized.buffer());
+            img.shape = Shape::new(h, w, c_s)
+        }
+        // fast_image_resize has no such pixel types, resample in f32 and convert back
+        PixelType::I16 | PixelType::U32 | PixelType::F16 | PixelType::F64 => {
+            let pixel_type = img.pixel_type();
+            img.as_f32();
+            resize_interleaved(img, h, w, resize_alg, alpha);
+            img.as_pixel_type(pixel_type);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fast_image_resize::FilterType;
+
+    fn gradient(h: usize, w: usize, c: usize) -> SVec {
+        let data: Vec<f32> = (0..h * w * c).map(|i| (i % (w * c)) as f32 / (w * c) as f32).collect();
+        SVec::new(Shape::new(h, w, Some(c)), ImgData::from(data))
+    }
+
+    #[test]
+    fn test_aspect_sizes() {
+        let img = gradient(40, 100, 3);
+        const ALG: ResizeAlg = ResizeAlg::Convolution(FilterType::Bilinear);
+        type Op = fn(&mut SVec) -> Result<(), Error>;
+        let cases: [(Op, (usize, usize)); 5] = [
+            (|img| img.resize_to_fit(30, 30, ALG, false), (12, 30)),
+            (|img| img.resize_to_fill(30, 30, Anchor::Center, ALG, false), (30, 30)),
+            (|img| img.resize_scale(0.5, ALG, false), (20, 50)),
+            (|img| img.resize_short_side(10, ALG, false), (10, 25)),
+            (|img| img.resize_long_side(50, ALG, false), (20, 50)),
+        ];
+        for (op, (h, w)) in cases {
+            let mut out = img.clone();
+            op(&mut out).unwrap();
+            assert_eq!(out.shape(), (h, w, Some(3)));
+        }
+
+        let mut out = img.clone();
+        assert!(out.resize_to_fit(0, 10, ALG, false).is_err());
+        assert!(out.resize_scale(-1.0, ALG, false).is_err());
+        assert!(matches!(out.resize_scale(1e9, ALG, false), Err(Error::Overflow)));
+        assert_eq!(out.shape(), (40, 100, Some(3)));
+
+        let mut empty = SVec::new(Shape::new(0, 10, Some(3)), ImgData::from(Vec::<f32>::new()));
+        assert!(matches!(empty.resize_to_fit(10, 10, ALG, false), Err(Error::ZeroDimension)));
+        assert!(matches!(
+            empty.resize_to_fill(10, 10, Anchor::Center, ALG, false),
+            Err(Error::ZeroDimension)
+        ));
+        assert!(matches!(empty.resize_scale(2.0, ALG, false), Err(Error::ZeroDimension)));
+        assert!(matches!(empty.resize_short_side(10, ALG, false), Err(Error::ZeroDimension)));
+    }
+
+    #[test]
+    fn test_fill_anchor() {
+        let img = SVec::new(Shape::new(2, 4, None), ImgData::from((0..8).collect::<Vec<u8>>()));
+        for (anchor, expected) in [
+            (Anchor::Left, [0, 1, 4, 5]),
+            (Anchor::Center, [1, 2, 5, 6]),
+            (Anchor::BottomRight, [2, 3, 6, 7]),
+        ] {
+            let mut out = img.clone();
+            out.resize_to_fill(2, 2, anchor, ResizeAlg::Nearest, false).unwrap();
+            assert_eq!(out.get_data::<u8>().unwrap(), &expected, "{:?}", anchor);
+        }
+    }
+
+    #[test]
+    fn test_many_channels() {
+        // six channels resize like the two halves resized on their own
+        let img = gradient(8, 12, 6);
+        let alg = ResizeAlg::Convolution(FilterType::CatmullRom);
+        let mut planar = img.clone();
+        planar.to_planar();
+        planar.resize(5, 7, alg, false);
+        planar.to_interleaved();
+        let data = img.get_data::<f32>().unwrap();
+        for start in [0, 3] {
+            let half: Vec<f32> = data.chunks_exact(6).flat_map(|px| &px[start..start + 3]).copied().collect();
+            let mut half = SVec::new(Shape::new(8, 12, Some(3)), ImgData::from(half));
+            half.resize(5, 7, alg, false);
+            let got: Vec<f32> = planar
+                .get_data::<f32>()
+                .unwrap()
+                .chunks_exact(6)
+                .flat_map(|px| &px[start..start + 3])
+                .copied()
+                .collect();
+            assert_eq!(got, half.get_data::<f32>().unwrap());
+        }
+
+        // transparent red next to opaque green: with alpha no red bleeds into visible pixels
+        let px = |x: usize| {
+            if x < 4 {
+                [1.0, 0.0, 0.5, 0.5, 0.0]
+            } else {
+                [0.0, 1.0, 0.5, 0.5, 1.0]
+            }
+        };
+        let data: Vec<f32> = (0..4 * 8).flat_map(|i| px(i % 8)).collect();
+        let img = SVec::new(Shape::new(4, 8, Some(5)), ImgData::from(data));
+        let mut blended = img.clone();
+        blended.resize(2, 4, ResizeAlg::Convolution(FilterType::Bilinear), true);
+        let out = blended.get_data::<f32>().unwrap();
+        assert!(out.chunks_exact(5).filter(|px| px[4] > 0.0).all(|px| px[0].abs() < 1e-6));
+        assert!(out.chunks_exact(5).any(|px| px[4] > 0.0 && px[4] < 1.0));
+        let mut straight = img;
+        straight.resize(2, 4, ResizeAlg::Convolution(FilterType::Bilinear), false);
+        assert!(
+            straight
+                .get_data::<f32>()
+                .unwrap()
+                .chunks_exact(5)
+                .any(|px| px[4] > 0.0 && px[0] > 0.1)
+        );
+    }
+}
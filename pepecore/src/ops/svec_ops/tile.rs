@@ -0,0 +1,463 @@
+//! Overlapping tiles for processing images piecewise, e.g. running a model on an image too large
+//! to pass at once.
+//!
+//! A [`Tiler`] covers the image with square tiles that overlap their neighbours by at least
+//! `overlap` pixels. The last row and column of tiles are shifted inward, so every tile is full
+//! size and no remainder is dropped. A [`Merger`] reassembles the processed tiles, possibly at an
+//! integer `scale` as produced by an upscaler, and blends the overlaps with a [`Feather`] ramp so
+//! the seams don't show.
+//!
+//! # Example
+//! ```rust
+//! use pepecore::enums::Feather;
+//! use pepecore::tile::{Merger, Tiler};
+//! use pepecore_array::{ImgData, SVec, Shape};
+//!
+//! let img = SVec::new(Shape::new(100, 70, Some(3)), ImgData::from(vec![128u8; 100 * 70 * 3]));
+//! let tiler = Tiler::new(100, 70, 32, 8).unwrap();
+//! let mut merger = Merger::new(&tiler, 1, Feather::Cosine).unwrap();
+//! for tile in tiler.split(&img) {
+//!     let tile = tile.unwrap();
+//!     // process tile.image here
+//!     merger.add(&tile).unwrap();
+//! }
+//! assert_eq!(merger.finish().unwrap().get_data::<u8>().unwrap(), img.get_data::<u8>().unwrap());
+//! ```
+use crate::enums::Feather;
+use crate::errors::TileError;
+use crate::ops::region::{Region, Tile};
+use pepecore_array::error::Error;
+use pepecore_array::{ImgData, Layout, Pixel, PixelType, SVec, Shape, f16};
+use std::borrow::Cow;
+use std::f32::consts::PI;
+
+/// Starts of `tile`-long spans covering `0..n` with at least `overlap` shared, the last ending at `n`.
+fn starts(n: usize, tile: usize, overlap: usize) -> Vec<usize> {
+    if n <= tile {
+        return vec![0];
+    }
+    let mut starts: Vec<usize> = (0..).step_by(tile - overlap).take_while(|s| s + tile < n).collect();
+    starts.push(n - tile);
+    starts
+}
+
+/// Grid of overlapping tiles over a `height` x `width` image, see the [module docs](self).
+#[derive(Debug, Clone)]
+pub struct Tiler {
+    height: usize,
+    width: usize,
+    tile: usize,
+    overlap: usize,
+    tops: Vec<usize>,
+    lefts: Vec<usize>,
+}
+
+impl Tiler {
+    /// Tiles of `tile` x `tile` pixels overlapping by at least `overlap`. Along an axis shorter
+    /// than `tile` there is a single tile as long as the image.
+    ///
+    /// # Errors
+    ///
+    /// `ZeroDimension` for an empty image or `tile == 0`, `InvalidOverlap` when `overlap >= tile`.
+    pub fn new(height: usize, width: usize, tile: usize, overlap: usize) -> Result<Self, TileError> {
+        if height == 0 || width == 0 || tile == 0 {
+            return Err(Error::ZeroDimension.into());
+        }
+        if overlap >= tile {
+            return Err(TileError::InvalidOverlap { overlap, tile });
+        }
+        Ok(Self {
+            height,
+            width,
+            tile,
+            overlap,
+            tops: starts(height, tile, overlap),
+            lefts: starts(width, tile, overlap),
+        })
+    }
+
+    /// `(height, width)` of the tiled image.
+    pub fn shape(&self) -> (usize, usize) {
+        (self.height, self.width)
+    }
+
+    pub fn tile_size(&self) -> usize {
+        self.tile
+    }
+
+    pub fn overlap(&self) -> usize {
+        self.overlap
+    }
+
+    /// Number of tiles, never zero.
+    pub fn len(&self) -> usize {
+        self.tops.len() * self.lefts.len()
+    }
+
+    /// Always `false`, see [`len`](Self::len).
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Tile rectangles in row-major order.
+    pub fn regions(&self) -> impl Iterator<Item = Region> + '_ {
+        let (height, width) = (self.tile.min(self.height), self.tile.min(self.width));
+        self.tops
+            .iter()
+            .flat_map(move |&top| self.lefts.iter().map(move |&left| Region::new(top, left, height, width)))
+    }
+
+    /// Cut `img` into the tiles of [`regions`](Self::regions), each keeping the layout of `img`.
+    ///
+    /// # Errors
+    ///
+    /// Every item is `ShapeMismatch` when `img` isn't the size the tiler was made for.
+    pub fn split<'a>(&'a self, img: &'a SVec) -> impl Iterator<Item = Result<Tile, TileError>> + 'a {
+        let view = img.view();
+        self.regions().map(move |region| {
+            let (h, w, c) = img.shape();
+            if (h, w) != (self.height, self.width) {
+                return Err(TileError::ShapeMismatch {
+                    expected: (self.height, self.width, c),
+                    actual: (h, w, c),
+                });
+            }
+            let image = view.crop(region.left, region.top, region.width, region.height)?.to_svec();
+            Ok(Tile { region, image })
+        })
+    }
+}
+
+/// Reassembles tiles of a [`Tiler`] into one image, see the [module docs](self).
+///
+/// Tiles are accumulated as normalized `f32`, or `f64` for `u32` and `f64` tiles whose precision
+/// `f32` would lose. Each is weighted by a ramp over the overlaps it shares
+/// with its neighbours, falling towards its edge, so every output pixel is a weighted mean of the
+/// tiles that cover it.
+#[derive(Debug, Clone)]
+pub struct Merger {
+    tiler: Tiler,
+    scale: usize,
+    feather: Feather,
+    pixel_type: Option<PixelType>,
+    channels: Option<usize>,
+    values: Sums,
+    weights: Vec<f64>,
+}
+
+/// Weighted sums of normalized samples, wide enough for the pixel type.
+#[derive(Debug, Clone)]
+enum Sums {
+    F32(Vec<f32>),
+    F64(Vec<f64>),
+}
+
+impl Merger {
+    /// Merger for tiles of `tiler` resized by the integer factor `scale`, e.g. `4` for the output
+    /// of a 4x upscaler; the merged image is `scale` times the size of the tiled one.
+    ///
+    /// # Errors
+    ///
+    /// `ZeroDimension` for `scale == 0`.
+    pub fn new(tiler: &Tiler, scale: usize, feather: Feather) -> Result<Self, TileError> {
+        if scale == 0 {
+            return Err(Error::ZeroDimension.into());
+        }
+        Ok(Self {
+            tiler: tiler.clone(),
+            scale,
+            feather,
+            pixel_type: None,
+            channels: None,
+            values: Sums::F32(Vec::new()),
+            weights: Vec::new(),
+        })
+    }
+
+    /// `(height, width)` of the merged image.
+    pub fn shape(&self) -> (usize, usize) {
+        (self.tiler.height * self.scale, self.tiler.width * self.scale)
+    }
+
+    /// Weights along one axis of a tile `n` pixels long, ramping up over the overlap at the
+    /// leading and/or trailing edge. They never reach zero, so every covered pixel has weight.
+    fn ramp(&self, lead: bool, trail: bool, n: usize) -> Vec<f32> {
+        let overlap = (self.tiler.overlap * self.scale) as f32;
+        let edge = |d: usize| {
+            let t = ((d as f32 + 0.5) / overlap).min(1.0);
+            match self.feather {
+                Feather::Linear => t,
+                Feather::Cosine => 0.5 - 0.5 * (PI * t).cos(),
+            }
+        };
+        (0..n)
+            .map(|i| match overlap > 0.0 {
+                true if lead && trail => edge(i).min(edge(n - 1 - i)),
+                true if lead => edge(i),
+                true if trail => edge(n - 1 - i),
+                _ => 1.0,
+            })
+            .collect()
+    }
+
+    fn accumulate<T: Pixel>(&mut self, data: &[T], top: usize, left: usize, wy: &[f32], wx: &[f32]) {
+        let c = self.channels.unwrap_or(1);
+        let (out_w, tile_w) = (self.shape().1, wx.len());
+        for (y, wy) in wy.iter().enumerate() {
+            for (x, wx) in wx.iter().enumerate() {
+                let (o, w) = ((top + y) * out_w + left + x, *wy as f64 * *wx as f64);
+                self.weights[o] += w;
+                let src = &data[(y * tile_w + x) * c..(y * tile_w + x + 1) * c];
+                match &mut self.values {
+                    Sums::F32(values) => {
+                        for (v, s) in values[o * c..(o + 1) * c].iter_mut().zip(src) {
+                            *v += w as f32 * s.to_f32_normalized();
+                        }
+                    }
+                    Sums::F64(values) => {
+                        for (v, s) in values[o * c..(o + 1) * c].iter_mut().zip(src) {
+                            *v += w * s.to_f64_normalized();
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Blend in `tile`, whose region is in the coordinates of the tiled image and whose image is
+    /// `scale` times the region size. Tiles may come in any order and layout; the channel count
+    /// and pixel type are those of the first tile.
+    ///
+    /// # Errors
+    ///
+    /// `OutOfBounds` for a region outside the tiled image, `ShapeMismatch` for a wrongly sized
+    /// tile or a different channel count, `PixelTypeMismatch` for a different pixel type.
+    pub fn add(&mut self, tile: &Tile) -> Result<(), TileError> {
+        let Region {
+            top,
+            left,
+            height,
+            width,
+        } = tile.region;
+        if top + height > self.tiler.height || left + width > self.tiler.width {
+            return Err(Error::OutOfBounds.into());
+        }
+        let (s, (h, w, c)) = (self.scale, tile.image.shape());
+        let expected = (
+            height * s,
+            width * s,
+            if self.pixel_type.is_some() { self.channels } else { c },
+        );
+        if (h, w, c) != expected {
+            return Err(TileError::ShapeMismatch {
+                expected,
+                actual: (h, w, c),
+            });
+        }
+        let pixel_type = tile.image.pixel_type();
+        match self.pixel_type {
+            Some(expected) if expected != pixel_type => {
+                return Err(TileError::PixelTypeMismatch {
+                    expected,
+                    actual: pixel_type,
+                });
+            }
+            Some(_) => {}
+            None => {
+                let (out_h, out_w) = self.shape();
+                self.pixel_type = Some(pixel_type);
+                self.channels = c;
+                let len = out_h * out_w * c.unwrap_or(1);
+                self.values = match pixel_type {
+                    PixelType::U32 | PixelType::F64 => Sums::F64(vec![0.0; len]),
+                    _ => Sums::F32(vec![0.0; len]),
+                };
+                self.weights = vec![0.0; out_h * out_w];
+            }
+        }
+
+        let wy = self.ramp(top > 0, top + height < self.tiler.height, h);
+        let wx = self.ramp(left > 0, left + width < self.tiler.width, w);
+        let image = if tile.image.shape.get_layout() == Layout::CHW {
+            let mut image = tile.image.clone();
+            image.to_interleaved();
+            Cow::Owned(image)
+        } else {
+            Cow::Borrowed(&tile.image)
+        };
+        let (top, left) = (top * s, left * s);
+        match pixel_type {
+            PixelType::U8 => self.accumulate(image.get_data::<u8>()?, top, left, &wy, &wx),
+            PixelType::U16 => self.accumulate(image.get_data::<u16>()?, top, left, &wy, &wx),
+            PixelType::I16 => self.accumulate(image.get_data::<i16>()?, top, left, &wy, &wx),
+            PixelType::U32 => self.accumulate(image.get_data::<u32>()?, top, left, &wy, &wx),
+            PixelType::F16 => self.accumulate(image.get_data::<f16>()?, top, left, &wy, &wx),
+            PixelType::F32 => self.accumulate(image.get_data::<f32>()?, top, left, &wy, &wx),
+            PixelType::F64 => self.accumulate(image.get_data::<f64>()?, top, left, &wy, &wx),
+        }
+        Ok(())
+    }
+
+    /// The merged interleaved image, with the pixel type and channel count of the tiles.
+    ///
+    /// # Errors
+    ///
+    /// `Incomplete` when some pixel isn't covered by any added tile.
+    pub fn finish(self) -> Result<SVec, TileError> {
+        let Some(pixel_type) = self.pixel_type else {
+            return Err(TileError::Incomplete);
+        };
+        if self.weights.contains(&0.0) {
+            return Err(TileError::Incomplete);
+        }
+        let c = self.channels.unwrap_or(1);
+        let data = match &self.values {
+            Sums::F32(values) => {
+                let values = values.iter().enumerate().map(|(i, v)| v / self.weights[i / c] as f32);
+                match pixel_type {
+                    PixelType::U8 => ImgData::from(values.map(u8::from_f32_normalized).collect::<Vec<_>>()),
+                    PixelType::U16 => ImgData::from(values.map(u16::from_f32_normalized).collect::<Vec<_>>()),
+                    PixelType::I16 => ImgData::from(values.map(i16::from_f32_normalized).collect::<Vec<_>>()),
+                    PixelType::F16 => ImgData::from(values.map(f16::from_f32_normalized).collect::<Vec<_>>()),
+                    _ => ImgData::from(values.collect::<Vec<_>>()),
+                }
+            }
+            Sums::F64(values) => {
+                let values = values.iter().enumerate().map(|(i, v)| v / self.weights[i / c]);
+                match pixel_type {
+                    PixelType::U32 => ImgData::from(values.map(u32::from_f64_normalized).collect::<Vec<_>>()),
+                    _ => ImgData::from(values.collect::<Vec<_>>()),
+                }
+            }
+        };
+        let (h, w) = self.shape();
+        Ok(SVec::try_new(Shape::try_new(h, w, self.channels)?, data)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ramp_img(h: usize, w: usize, c: usize) -> SVec {
+        let data: Vec<u8> = (0..h * w * c).map(|v| (v * 7 % 251) as u8).collect();
+        SVec::new(Shape::new(h, w, Some(c)), ImgData::from(data))
+    }
+
+    /// `img` with every pixel repeated `s` x `s` times.
+    fn upscale(img: &SVec, s: usize) -> SVec {
+        let (h, w, c) = img.shape();
+        let cn = c.unwrap_or(1);
+        let data = img.get_data::<f32>().unwrap();
+        let mut out = Vec::with_capacity(h * w * cn * s * s);
+        for y in 0..h * s {
+            for x in 0..w * s {
+                let i = ((y / s) * w + x / s) * cn;
+                out.extend_from_slice(&data[i..i + cn]);
+            }
+        }
+        SVec::new(Shape::new(h * s, w * s, c), ImgData::from(out))
+    }
+
+    #[test]
+    fn test_tiler_covers_image() {
+        let tiler = Tiler::new(100, 70, 32, 8).unwrap();
+        assert_eq!(
+            (tiler.tops.as_slice(), tiler.lefts.as_slice()),
+            (&[0, 24, 48, 68][..], &[0, 24, 38][..])
+        );
+        assert_eq!(tiler.len(), 12);
+        assert!(!tiler.is_empty());
+        assert!(tiler.regions().all(|r| (r.height, r.width) == (32, 32)));
+
+        let small = Tiler::new(10, 50, 32, 0).unwrap();
+        let regions: Vec<Region> = small.regions().collect();
+        assert_eq!(regions, [Region::new(0, 0, 10, 32), Region::new(0, 18, 10, 32)]);
+
+        assert!(matches!(Tiler::new(10, 10, 8, 8), Err(TileError::InvalidOverlap { .. })));
+        assert!(Tiler::new(10, 0, 8, 2).is_err());
+        let img = ramp_img(9, 50, 1);
+        assert!(small.split(&img).all(|t| matches!(t, Err(TileError::ShapeMismatch { .. }))));
+    }
+
+    #[test]
+    fn test_split_merge_roundtrip() {
+        let img = ramp_img(45, 38, 3);
+        let mut planar = img.clone();
+        planar.to_planar();
+        let tiler = Tiler::new(45, 38, 16, 5).unwrap();
+        for feather in [Feather::Linear, Feather::Cosine] {
+            let mut merger = Merger::new(&tiler, 1, feather).unwrap();
+            for tile in tiler.split(&planar) {
+                merger.add(&tile.unwrap()).unwrap();
+            }
+            let out = merger.finish().unwrap();
+            assert_eq!(out.shape(), (45, 38, Some(3)));
+            assert_eq!(out.get_data::<u8>().unwrap(), img.get_data::<u8>().unwrap(), "{:?}", feather);
+        }
+    }
+
+    #[test]
+    fn test_merge_scaled_and_blended() {
+        let mut img = ramp_img(20, 27, 2);
+        img.as_f32();
+        let tiler = Tiler::new(20, 27, 12, 4).unwrap();
+        let mut merger = Merger::new(&tiler, 3, Feather::Cosine).unwrap();
+        for tile in tiler.split(&img) {
+            let tile = tile.unwrap();
+            let image = upscale(&tile.image, 3);
+            merger.add(&Tile { image, ..tile }).unwrap();
+        }
+        let out = merger.finish().unwrap();
+        let expected = upscale(&img, 3);
+        assert_eq!(out.shape(), (60, 81, Some(2)));
+        let diff = out.get_data::<f32>().unwrap().iter().zip(expected.get_data::<f32>().unwrap());
+        assert!(diff.map(|(a, b)| (a - b).abs()).fold(0.0, f32::max) < 1e-5);
+
+        // disagreeing tiles meet in a ramp inside the overlap instead of a hard edge
+        let tiler = Tiler::new(1, 20, 12, 4).unwrap();
+        let mut merger = Merger::new(&tiler, 1, Feather::Linear).unwrap();
+        for (region, v) in tiler.regions().zip([0.0f32, 1.0]) {
+            let image = SVec::new(Shape::new(1, 12, None), ImgData::from(vec![v; 12]));
+            merger.add(&Tile { region, image }).unwrap();
+        }
+        let row = merger.finish().unwrap();
+        let row = row.get_data::<f32>().unwrap();
+        assert!(row[..8].iter().all(|&v| v == 0.0) && row[12..].iter().all(|&v| v == 1.0));
+        assert!(row[8..12].windows(2).all(|p| 0.0 < p[0] && p[0] < p[1] && p[1] < 1.0));
+
+        let mut merger = Merger::new(&tiler, 2, Feather::Linear).unwrap();
+        let region = Region::new(0, 0, 1, 12);
+        let wrong = SVec::new(Shape::new(1, 12, None), ImgData::from(vec![0u8; 12]));
+        assert!(matches!(
+            merger.add(&Tile { region, image: wrong }),
+            Err(TileError::ShapeMismatch { .. })
+        ));
+        let image = SVec::new(Shape::new(2, 24, None), ImgData::from(vec![0u8; 48]));
+        merger.add(&Tile { region, image }).unwrap();
+        assert!(matches!(merger.finish(), Err(TileError::Incomplete)));
+    }
+
+    #[test]
+    fn test_merge_keeps_wide_precision() {
+        // both lose their low bits as normalized f32
+        let data: Vec<u32> = (0..30 * 30).map(|i| 4_000_000_001 - i * 1_000_003).collect();
+        let img = SVec::new(Shape::new(30, 30, None), ImgData::from(data));
+        let mut wide = img.clone();
+        wide.as_f64();
+        for img in [img, wide] {
+            let tiler = Tiler::new(30, 30, 12, 5).unwrap();
+            let mut merger = Merger::new(&tiler, 1, Feather::Cosine).unwrap();
+            for tile in tiler.split(&img) {
+                merger.add(&tile.unwrap()).unwrap();
+            }
+            let mut out = merger.finish().unwrap();
+            assert_eq!(out.pixel_type(), img.pixel_type());
+            out.as_f64();
+            let mut expected = img.clone();
+            expected.as_f64();
+            let diff = out.get_data::<f64>().unwrap().iter().zip(expected.get_data::<f64>().unwrap());
+            assert!(diff.map(|(a, b)| (a - b).abs()).fold(0.0, f64::max) < 1e-12);
+        }
+    }
+}